@@ -24,9 +24,9 @@ fn print_point(point: MountPoint) -> Option<()> {
 	println!(
 		"{:<15} {:>10} {:>10} {:>10} {}",
 		point.mount_source()?,
-		format!("{:.1}", stat.total()?),
-		format!("{:.1}", stat.available()?),
-		format!("{:.1}", stat.used()?),
+		format!("{:#.1}", stat.total()?),
+		format!("{:#.1}", stat.available()?),
+		format!("{:#.1}", stat.used()?),
 		point.mount_point()?
 	);
 
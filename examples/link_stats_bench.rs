@@ -0,0 +1,44 @@
+//! Compares reading the interface counters from sysfs, `/proc/net/dev` and
+//! with a single rtnetlink dump.
+
+use linux_info::network::interfaces::NetDev;
+use linux_info::network::link_stats::LinkStatsReader;
+
+use std::fs;
+use std::time::{Duration, Instant};
+
+const ROUNDS: u32 = 10_000;
+
+fn bench(name: &str, mut f: impl FnMut() -> usize) {
+	let start = Instant::now();
+	let mut values = 0;
+	for _ in 0..ROUNDS {
+		values = f();
+	}
+	let per_read: Duration = start.elapsed() / ROUNDS;
+	println!("{:<14} {:>10?} per read ({} values)", name, per_read, values);
+}
+
+fn main() {
+	bench("sysfs", || {
+		let mut values = 0;
+		for iface in fs::read_dir("/sys/class/net").unwrap() {
+			let stats = iface.unwrap().path().join("statistics");
+			for file in fs::read_dir(stats).unwrap() {
+				let raw = fs::read_to_string(file.unwrap().path()).unwrap();
+				let _: u64 = raw.trim().parse().unwrap();
+				values += 1;
+			}
+		}
+		values
+	});
+
+	let mut dev = NetDev::read().expect("could not read /proc/net/dev");
+	bench("/proc/net/dev", || {
+		dev.reload().unwrap();
+		dev.entries().count() * 16
+	});
+
+	let mut reader = LinkStatsReader::new().expect("could not open netlink");
+	bench("rtnetlink", || reader.read().unwrap().len() * 16);
+}
@@ -0,0 +1,279 @@
+//! Get the default audio output and input with their volume from the
+//! session audio server, using `pactl`.
+//!
+//! This works with PulseAudio and with PipeWire (through `pipewire-pulse`).
+//! Since the audio server runs per user, this needs to be called from the
+//! user session and not as root.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::audio::AudioInfo;
+//!
+//! let info = AudioInfo::read().unwrap().expect("pactl not installed");
+//! if let Some(sink) = info.default_sink {
+//!     println!(
+//!         "{}: {:.0}% muted {}",
+//!         sink.description, sink.volume(), sink.mute
+//!     );
+//! }
+//! ```
+
+use std::io;
+use std::process::Command;
+
+/// The audio server with its default devices.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct AudioInfo {
+	/// The name of the server for example `PulseAudio (on PipeWire 1.0.5)`.
+	pub server_name: String,
+	pub server_version: String,
+	/// The default output.
+	pub default_sink: Option<AudioDevice>,
+	/// The default input.
+	pub default_source: Option<AudioDevice>
+}
+
+impl AudioInfo {
+	/// Queries the audio server.
+	///
+	/// Returns `None` if `pactl` is not installed.
+	pub fn read() -> io::Result<Option<Self>> {
+		let info = match pactl(&["info"])? {
+			Some(i) => i,
+			None => return Ok(None)
+		};
+		let info = parse_info(&info);
+		let value = |key: &str| info.iter()
+			.find(|(k, _)| *k == key)
+			.map(|(_, v)| v.to_string());
+
+		let default_sink = match value("Default Sink") {
+			Some(name) => sinks()?.into_iter().find(|d| d.name == name),
+			None => None
+		};
+		let default_source = match value("Default Source") {
+			Some(name) => sources()?.into_iter().find(|d| d.name == name),
+			None => None
+		};
+
+		Ok(Some(Self {
+			server_name: value("Server Name").unwrap_or_default(),
+			server_version: value("Server Version").unwrap_or_default(),
+			default_sink,
+			default_source
+		}))
+	}
+}
+
+/// Returns every output, empty if `pactl` is not installed.
+pub fn sinks() -> io::Result<Vec<AudioDevice>> {
+	pactl(&["list", "sinks"])
+		.map(|o| o.map(|o| parse_devices(&o)).unwrap_or_default())
+}
+
+/// Returns every input including the monitors of the outputs, empty if
+/// `pactl` is not installed.
+pub fn sources() -> io::Result<Vec<AudioDevice>> {
+	pactl(&["list", "sources"])
+		.map(|o| o.map(|o| parse_devices(&o)).unwrap_or_default())
+}
+
+/// Runs pactl and returns its output, `None` if it is not installed.
+fn pactl(args: &[&str]) -> io::Result<Option<String>> {
+	let output = Command::new("pactl")
+		.args(args)
+		// the labels are translated
+		.env("LC_ALL", "C")
+		.output();
+	let output = match output {
+		Ok(o) => o,
+		Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+		Err(e) => return Err(e)
+	};
+	if !output.status.success() {
+		let msg = String::from_utf8_lossy(&output.stderr);
+		return Err(io::Error::new(io::ErrorKind::Other, msg.trim()))
+	}
+
+	Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
+}
+
+/// A sink (output) or source (input) of the audio server.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct AudioDevice {
+	pub index: u32,
+	/// The name for example `alsa_output.pci-0000_00_1f.3.analog-stereo`.
+	pub name: String,
+	/// A readable name for example `Built-in Audio Analog Stereo`.
+	pub description: String,
+	/// `RUNNING`, `IDLE` or `SUSPENDED`.
+	pub state: String,
+	pub mute: bool,
+	/// The volume of every channel.
+	pub channels: Vec<ChannelVolume>,
+	/// The sink this source records from, if it is a monitor.
+	pub monitor_of: Option<String>
+}
+
+impl AudioDevice {
+	/// The average volume of all channels in percent, 100 is the normal
+	/// volume but it can be higher.
+	pub fn volume(&self) -> f64 {
+		if self.channels.is_empty() {
+			return 0.0
+		}
+		let sum: f64 = self.channels.iter().map(|c| c.percent).sum();
+		sum / self.channels.len() as f64
+	}
+
+	/// Returns true if this source records what a sink plays.
+	pub fn is_monitor(&self) -> bool {
+		self.monitor_of.is_some()
+	}
+}
+
+/// The volume of a channel.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct ChannelVolume {
+	/// The position for example `front-left` or `mono`.
+	pub channel: String,
+	pub percent: f64
+}
+
+/// Parses `key: value` lines.
+fn parse_info(out: &str) -> Vec<(&str, &str)> {
+	out.lines()
+		.filter_map(|l| l.split_once(':'))
+		.map(|(k, v)| (k.trim(), v.trim()))
+		.collect()
+}
+
+/// Parses the output of `pactl list sinks` or `pactl list sources`.
+fn parse_devices(out: &str) -> Vec<AudioDevice> {
+	let mut list: Vec<AudioDevice> = vec![];
+	for line in out.lines() {
+		// every device starts with `Sink #0` or `Source #0`
+		if let Some((_, index)) = line.split_once(" #")
+			.filter(|_| !line.starts_with(char::is_whitespace))
+		{
+			list.push(AudioDevice {
+				index: index.trim().parse().unwrap_or(0),
+				..Default::default()
+			});
+			continue
+		}
+
+		let dev = match list.last_mut() {
+			Some(d) => d,
+			None => continue
+		};
+		let (key, value) = match line.split_once(':') {
+			Some((k, v)) => (k.trim(), v.trim()),
+			None => continue
+		};
+		match key {
+			"Name" => dev.name = value.into(),
+			"Description" => dev.description = value.into(),
+			"State" => dev.state = value.into(),
+			"Mute" => dev.mute = value == "yes",
+			"Volume" => dev.channels = parse_volume(value),
+			"Monitor of Sink" if value != "n/a" => {
+				dev.monitor_of = Some(value.into());
+			},
+			_ => {}
+		}
+	}
+	list
+}
+
+/// Parses a volume like
+/// `front-left: 32768 /  50% / -18.06 dB,   front-right: 32768 /  50% / ...`.
+fn parse_volume(s: &str) -> Vec<ChannelVolume> {
+	s.split(',')
+		.filter_map(|c| {
+			let (channel, value) = c.split_once(':')?;
+			let percent = value.split('/')
+				.nth(1)?
+				.trim()
+				.strip_suffix('%')?
+				.parse().ok()?;
+			Some(ChannelVolume {
+				channel: channel.trim().into(),
+				percent
+			})
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn devices() {
+		let out = "\
+Source #54
+	State: SUSPENDED
+	Name: alsa_output.pci-0000_00_1f.3.analog-stereo.monitor
+	Description: Monitor of Built-in Audio Analog Stereo
+	Driver: PipeWire
+	Sample Specification: s32le 2ch 48000Hz
+	Channel Map: front-left,front-right
+	Owner Module: 4294967295
+	Mute: no
+	Volume: front-left: 65536 / 100% / 0.00 dB,   front-right: 65536 / 100% / 0.00 dB
+	        balance 0.00
+	Base Volume: 65536 / 100% / 0.00 dB
+	Monitor of Sink: alsa_output.pci-0000_00_1f.3.analog-stereo
+	Latency: 0 usec, configured 0 usec
+	Flags: HARDWARE DECIBEL_VOLUME LATENCY
+	Properties:
+		device.description = \"Built-in Audio\"
+
+Source #55
+	State: RUNNING
+	Name: alsa_input.usb-Blue_Yeti-00.analog-stereo
+	Description: Yeti Stereo Microphone Analog Stereo
+	Mute: yes
+	Volume: front-left: 32768 /  50% / -18.06 dB,   front-right: 26214 /  40% / -23.88 dB
+	        balance -0.20
+	Monitor of Sink: n/a
+";
+		let devs = parse_devices(out);
+		assert_eq!(devs.len(), 2);
+
+		let monitor = &devs[0];
+		assert_eq!(monitor.index, 54);
+		assert!(monitor.is_monitor());
+		assert_eq!(
+			monitor.description,
+			"Monitor of Built-in Audio Analog Stereo"
+		);
+		assert_eq!(monitor.volume(), 100.0);
+
+		let mic = &devs[1];
+		assert_eq!(mic.index, 55);
+		assert_eq!(mic.name, "alsa_input.usb-Blue_Yeti-00.analog-stereo");
+		assert_eq!(mic.state, "RUNNING");
+		assert!(mic.mute);
+		assert!(!mic.is_monitor());
+		assert_eq!(mic.channels.len(), 2);
+		assert_eq!(mic.channels[1].channel, "front-right");
+		assert_eq!(mic.volume(), 45.0);
+	}
+}
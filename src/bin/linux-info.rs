@@ -0,0 +1,168 @@
+//! Prints information about the system.
+//!
+//! ```text
+//! linux-info [--json] [cpu|memory|storage|network|bios|all]...
+//! ```
+//!
+//! Without a section everything except bios is printed.
+
+use linux_info::snapshot::{Snapshot, SnapshotOptions};
+
+use std::{env, process};
+
+const USAGE: &str = "\
+usage: linux-info [--json] [cpu|memory|storage|network|bios|all]...
+
+Prints information about the system. Without a section everything except
+bios is printed, reading the bios usually requires root.
+
+options:
+  --json      print the sections as json
+  -h, --help  print this message";
+
+fn main() {
+	let mut json = false;
+	let mut sections = vec![];
+
+	for arg in env::args().skip(1) {
+		match arg.as_str() {
+			"--json" => json = true,
+			"-h" | "--help" => {
+				println!("{}", USAGE);
+				return
+			},
+			"cpu" | "memory" | "storage" | "network" | "bios" | "all" => {
+				sections.push(arg)
+			},
+			_ => {
+				eprintln!("unknown argument {:?}\n\n{}", arg, USAGE);
+				process::exit(2)
+			}
+		}
+	}
+
+	let options = options(&sections);
+	let snapshot = Snapshot::collect(options);
+
+	if json {
+		let s = serde_json::to_string_pretty(&snapshot)
+			.expect("snapshot can always be serialized");
+		println!("{}", s);
+	} else {
+		print_plain(&snapshot);
+	}
+
+	if !snapshot.is_complete() {
+		process::exit(1)
+	}
+}
+
+fn options(sections: &[String]) -> SnapshotOptions {
+	if sections.is_empty() {
+		return SnapshotOptions::default()
+	}
+
+	let has = |name: &str| sections.iter().any(|s| s == name || s == "all");
+	SnapshotOptions {
+		cpu: has("cpu"),
+		memory: has("memory"),
+		uptime: has("memory"),
+		load_avg: has("cpu"),
+		mounts: has("storage"),
+		disks: has("storage"),
+		interfaces: has("network"),
+		bios: has("bios")
+	}
+}
+
+fn print_plain(snapshot: &Snapshot) {
+	if let Some(cpu) = &snapshot.cpu {
+		println!("== cpu");
+		let model = cpu.first().and_then(|c| c.value("model name"));
+		println!("model: {}", model.unwrap_or("unknown"));
+		println!("cores: {}", cpu.len());
+	}
+
+	if let Some(load) = &snapshot.load_avg {
+		println!(
+			"load average: {:.2} {:.2} {:.2}",
+			load.one, load.five, load.fifteen
+		);
+	}
+
+	if let Some(mem) = &snapshot.memory {
+		println!("== memory");
+		let size = |s: Option<_>| s.map(|s| format!("{:#.1}", s))
+			.unwrap_or_else(|| "-".into());
+		println!("total: {}", size(mem.total));
+		println!("free: {}", size(mem.free));
+		println!("available: {}", size(mem.available));
+		println!("swap total: {}", size(mem.swap_total));
+		println!("swap free: {}", size(mem.swap_free));
+	}
+
+	if let Some(uptime) = &snapshot.uptime {
+		println!("uptime: {}s", uptime.as_secs());
+	}
+
+	if let Some(mounts) = &snapshot.mounts {
+		println!("== mounts");
+		for point in mounts {
+			println!(
+				"{:<20} {:<10} {}",
+				point.mount_source.as_deref().unwrap_or("-"),
+				point.filesystem_type.as_deref().unwrap_or("-"),
+				point.mount_point.as_deref().unwrap_or("-")
+			);
+		}
+	}
+
+	if let Some(disks) = &snapshot.disks {
+		println!("== disks");
+		println!("{:<15} {:>10} {:>10}", "name", "read", "written");
+		for disk in disks {
+			println!(
+				"{:<15} {:>10} {:>10}",
+				disk.name,
+				format!("{:#.1}", disk.read()),
+				format!("{:#.1}", disk.written())
+			);
+		}
+	}
+
+	if let Some(interfaces) = &snapshot.interfaces {
+		println!("== network");
+		for iface in interfaces {
+			println!(
+				"{:<15} {:<8} {:<17} {:>10} {:>10}",
+				iface.name,
+				iface.operstate.as_deref().unwrap_or("-"),
+				iface.mac_address.as_deref().unwrap_or("-"),
+				iface.counters.as_ref()
+					.map(|c| format!("{:#.1}", c.received()))
+					.unwrap_or_default(),
+				iface.counters.as_ref()
+					.map(|c| format!("{:#.1}", c.transmitted()))
+					.unwrap_or_default()
+			);
+		}
+	}
+
+	#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+	if let Some(bios) = &snapshot.bios {
+		println!("== bios");
+		if let Some(info) = &bios.bios_info {
+			println!("vendor: {}", info.vendor);
+			println!("version: {}", info.version);
+			println!("release date: {}", info.release_date);
+		}
+		if let Some(info) = &bios.system_info {
+			println!("manufacturer: {}", info.manufacturer);
+			println!("product: {}", info.product_name);
+		}
+	}
+
+	for error in &snapshot.errors {
+		eprintln!("{}: {}", error.section, error.message);
+	}
+}
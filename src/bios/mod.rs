@@ -26,6 +26,11 @@ pub struct Bios {
 }
 
 #[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize),
+	serde(crate = "serde1")
+)]
 pub struct BiosInfo<'a> {
 	pub vendor: &'a str,
 	pub version: &'a str,
@@ -35,6 +40,11 @@ pub struct BiosInfo<'a> {
 }
 
 #[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize),
+	serde(crate = "serde1")
+)]
 pub struct SystemInfo<'a> {
 	pub manufacturer: &'a str,
 	pub product_name: &'a str,
@@ -84,4 +94,79 @@ impl Bios {
 			family: stru.get_str(info.family)?
 		})
 	}
+}
+
+impl BiosInfo<'_> {
+	/// Copies every value so the info no longer borrows from [`Bios`].
+	pub fn to_owned_info(&self) -> OwnedBiosInfo {
+		OwnedBiosInfo {
+			vendor: self.vendor.into(),
+			version: self.version.into(),
+			release_date: self.release_date.into(),
+			major: self.major,
+			minor: self.minor
+		}
+	}
+}
+
+/// An owned version of [`BiosInfo`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct OwnedBiosInfo {
+	pub vendor: String,
+	pub version: String,
+	pub release_date: String,
+	pub major: u8,
+	pub minor: u8
+}
+
+impl SystemInfo<'_> {
+	/// Copies every value so the info no longer borrows from [`Bios`].
+	pub fn to_owned_info(&self) -> OwnedSystemInfo {
+		OwnedSystemInfo {
+			manufacturer: self.manufacturer.into(),
+			product_name: self.product_name.into(),
+			version: self.version.into(),
+			serial_number: self.serial_number.into(),
+			uuid: self.uuid,
+			sku_number: self.sku_number.into(),
+			family: self.family.into()
+		}
+	}
+}
+
+/// An owned version of [`SystemInfo`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct OwnedSystemInfo {
+	pub manufacturer: String,
+	pub product_name: String,
+	pub version: String,
+	pub serial_number: String,
+	/// is exactly 16bytes long
+	pub uuid: Uuid,
+	pub sku_number: String,
+	pub family: String
+}
+
+/// Serializes the bios and system information.
+#[cfg(feature = "serde")]
+impl serde1::Serialize for Bios {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where S: serde1::Serializer {
+		use serde1::ser::SerializeStruct;
+
+		let mut s = serializer.serialize_struct("Bios", 2)?;
+		s.serialize_field("bios_info", &self.bios_info())?;
+		s.serialize_field("system_info", &self.system_info())?;
+		s.end()
+	}
 }
\ No newline at end of file
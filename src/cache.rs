@@ -0,0 +1,159 @@
+//! Cache readings for a given time.
+//!
+//! Useful if the same information is requested often, for example by every
+//! request to a dashboard. The file is only read again after the ttl
+//! expired.
+//!
+//! ## Example
+//! ```
+//! use linux_info::cache::Cached;
+//! use linux_info::memory::Memory;
+//! use std::time::Duration;
+//!
+//! let memory: Cached<Memory> = Cached::new(Duration::from_secs(1));
+//! // reads /proc/meminfo
+//! let total = memory.get().unwrap().total_memory();
+//! // returns the cached value
+//! let available = memory.get().unwrap().available_memory();
+//! ```
+
+use crate::cpu::Cpu;
+use crate::memory::{Memory, VmStat};
+use crate::system::{Uptime, Hostname, HostInfo, OsRelease, LoadAvg, Stat};
+use crate::storage::{Partitions, MountPoints, Raids, DiskStats};
+use crate::network::interfaces::NetDev;
+use crate::power::sleep::{SleepStates, MemSleep};
+use crate::gpu::nvidia::DriverVersion;
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+use crate::bios::Bios;
+
+use std::{fmt, io};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A type which can be read without any arguments.
+pub trait Readable: Sized {
+	fn read() -> io::Result<Self>;
+}
+
+macro_rules! readable {
+	($($ty:ty),*) => ($(
+		impl Readable for $ty {
+			fn read() -> io::Result<Self> {
+				<$ty>::read()
+			}
+		}
+	)*)
+}
+
+readable!(
+	Cpu, Memory, VmStat, Uptime, Hostname, HostInfo, OsRelease, LoadAvg, Stat,
+	Partitions, MountPoints, Raids, DiskStats, NetDev, SleepStates, MemSleep,
+	DriverVersion
+);
+
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+readable!(Bios);
+
+/// A value which is read again once it is older than the ttl.
+///
+/// Can be shared between threads, concurrent calls to [`Cached::get`] only
+/// read the value once. Errors are not cached.
+pub struct Cached<T> {
+	ttl: Duration,
+	read: Box<dyn Fn() -> io::Result<T> + Send + Sync>,
+	value: Mutex<Option<(Instant, Arc<T>)>>
+}
+
+impl<T: 'static> Cached<T> {
+	pub fn new(ttl: Duration) -> Self
+	where T: Readable {
+		Self::from_fn(ttl, T::read)
+	}
+
+	/// Caches the result of a function, which can capture values like a
+	/// path or an interface name.
+	///
+	/// ```
+	/// # use linux_info::cache::Cached;
+	/// # use linux_info::snapshot::{Snapshot, SnapshotOptions};
+	/// # use std::time::Duration;
+	/// let snapshot = Cached::from_fn(Duration::from_secs(5), || {
+	///     Ok(Snapshot::collect(SnapshotOptions::default()))
+	/// });
+	/// ```
+	pub fn from_fn<F>(ttl: Duration, read: F) -> Self
+	where F: Fn() -> io::Result<T> + Send + Sync + 'static {
+		Self {
+			ttl,
+			read: Box::new(read),
+			value: Mutex::new(None)
+		}
+	}
+
+	pub fn ttl(&self) -> Duration {
+		self.ttl
+	}
+
+	/// Returns the cached value or reads it if it expired.
+	pub fn get(&self) -> io::Result<Arc<T>> {
+		let mut value = self.value.lock().unwrap_or_else(|e| e.into_inner());
+
+		if let Some((read_at, v)) = &*value {
+			if read_at.elapsed() < self.ttl {
+				return Ok(v.clone())
+			}
+		}
+
+		let v = Arc::new((self.read)()?);
+		*value = Some((Instant::now(), v.clone()));
+		Ok(v)
+	}
+
+	/// Forces the next call to [`Cached::get`] to read the value again.
+	pub fn invalidate(&self) {
+		*self.value.lock().unwrap_or_else(|e| e.into_inner()) = None;
+	}
+}
+
+impl<T: fmt::Debug> fmt::Debug for Cached<T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Cached")
+			.field("ttl", &self.ttl)
+			.field("value", &self.value)
+			.finish_non_exhaustive()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	static READS: AtomicUsize = AtomicUsize::new(0);
+
+	fn read() -> io::Result<usize> {
+		Ok(READS.fetch_add(1, Ordering::SeqCst))
+	}
+
+	#[test]
+	fn ttl() {
+		let cached = Cached::from_fn(Duration::from_secs(60), read);
+		let first = *cached.get().unwrap();
+		assert_eq!(*cached.get().unwrap(), first);
+		cached.invalidate();
+		assert_eq!(*cached.get().unwrap(), first + 1);
+
+		let uncached = Cached::from_fn(Duration::ZERO, read);
+		assert_ne!(*uncached.get().unwrap(), *uncached.get().unwrap());
+	}
+
+	#[test]
+	fn capture() {
+		let name = String::from("lo");
+		let cached = Cached::from_fn(Duration::from_secs(60), move || {
+			Ok(format!("/sys/class/net/{}", name))
+		});
+		assert_eq!(*cached.get().unwrap(), "/sys/class/net/lo");
+	}
+}
@@ -0,0 +1,239 @@
+//! Decode the `CPU implementer` and `CPU part` codes which arm cpus report in
+//! `/proc/cpuinfo` instead of a `model name`.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::cpu::Cpu;
+//!
+//! let info = Cpu::read().unwrap();
+//! for entry in info.entries() {
+//!     if let Some(arm) = entry.arm() {
+//!         println!(
+//!             "{} {}",
+//!             arm.vendor().unwrap_or("unknown"),
+//!             arm.core_name().unwrap_or("unknown")
+//!         );
+//!     }
+//! }
+//! ```
+
+use super::CpuEntry;
+
+/// The identification of an arm core, the fields of the `MIDR_EL1`
+/// register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct ArmCpu {
+	/// The company which designed the core for example `0x41` for Arm.
+	pub implementer: u8,
+	/// The major revision.
+	pub variant: u8,
+	/// The core, its meaning depends on the implementer.
+	pub part: u16,
+	/// The minor revision.
+	pub revision: u8,
+	/// The architecture version for example 8, newer cores report 8 as
+	/// well.
+	pub architecture: u8
+}
+
+impl ArmCpu {
+	/// Reads the codes from a cpuinfo entry, `None` if the entry does not
+	/// belong to an arm cpu.
+	pub fn from_entry(entry: &CpuEntry) -> Option<Self> {
+		Some(Self {
+			implementer: parse_num(entry.value("CPU implementer")?)? as u8,
+			variant: entry.value("CPU variant")
+				.and_then(parse_num)
+				.unwrap_or(0) as u8,
+			part: parse_num(entry.value("CPU part")?)? as u16,
+			revision: entry.value("CPU revision")
+				.and_then(parse_num)
+				.unwrap_or(0) as u8,
+			architecture: entry.value("CPU architecture")
+				.and_then(parse_num)
+				.unwrap_or(0) as u8
+		})
+	}
+
+	/// The name of the implementer for example `ARM` or `Qualcomm`.
+	pub fn vendor(&self) -> Option<&'static str> {
+		vendor_name(self.implementer)
+	}
+
+	/// The name of the core for example `Cortex-A72` or `Neoverse-N1`.
+	pub fn core_name(&self) -> Option<&'static str> {
+		core_name(self.implementer, self.part)
+	}
+
+	/// The revision written like arm does in its manuals for example
+	/// `r0p3`.
+	pub fn stepping(&self) -> String {
+		format!("r{}p{}", self.variant, self.revision)
+	}
+}
+
+/// Parses a hex value like `0x41` or a decimal value like `8`.
+fn parse_num(s: &str) -> Option<u32> {
+	match s.strip_prefix("0x") {
+		Some(hex) => u32::from_str_radix(hex, 16).ok(),
+		None => s.parse().ok()
+	}
+}
+
+/// Returns the name of an implementer code.
+pub fn vendor_name(implementer: u8) -> Option<&'static str> {
+	Some(match implementer {
+		0x41 => "ARM",
+		0x42 => "Broadcom",
+		0x43 => "Cavium",
+		0x44 => "DEC",
+		0x46 => "Fujitsu",
+		0x48 => "HiSilicon",
+		0x49 => "Infineon",
+		0x4d => "Motorola",
+		0x4e => "NVIDIA",
+		0x50 => "APM",
+		0x51 => "Qualcomm",
+		0x53 => "Samsung",
+		0x56 => "Marvell",
+		0x61 => "Apple",
+		0x66 => "Faraday",
+		0x69 => "Intel",
+		0x6d => "Microsoft",
+		0x70 => "Phytium",
+		0xc0 => "Ampere",
+		_ => return None
+	})
+}
+
+/// Returns the name of a core, parts are only unique per implementer.
+pub fn core_name(implementer: u8, part: u16) -> Option<&'static str> {
+	Some(match (implementer, part) {
+		(0x41, 0xb76) => "ARM1176",
+		(0x41, 0xc07) => "Cortex-A7",
+		(0x41, 0xc08) => "Cortex-A8",
+		(0x41, 0xc09) => "Cortex-A9",
+		(0x41, 0xc0d) => "Cortex-A12",
+		(0x41, 0xc0e) => "Cortex-A17",
+		(0x41, 0xc0f) => "Cortex-A15",
+		(0x41, 0xd01) => "Cortex-A32",
+		(0x41, 0xd02) => "Cortex-A34",
+		(0x41, 0xd03) => "Cortex-A53",
+		(0x41, 0xd04) => "Cortex-A35",
+		(0x41, 0xd05) => "Cortex-A55",
+		(0x41, 0xd06) => "Cortex-A65",
+		(0x41, 0xd07) => "Cortex-A57",
+		(0x41, 0xd08) => "Cortex-A72",
+		(0x41, 0xd09) => "Cortex-A73",
+		(0x41, 0xd0a) => "Cortex-A75",
+		(0x41, 0xd0b) => "Cortex-A76",
+		(0x41, 0xd0c) => "Neoverse-N1",
+		(0x41, 0xd0d) => "Cortex-A77",
+		(0x41, 0xd0e) => "Cortex-A76AE",
+		(0x41, 0xd40) => "Neoverse-V1",
+		(0x41, 0xd41) => "Cortex-A78",
+		(0x41, 0xd42) => "Cortex-A78AE",
+		(0x41, 0xd43) => "Cortex-A65AE",
+		(0x41, 0xd44) => "Cortex-X1",
+		(0x41, 0xd46) => "Cortex-A510",
+		(0x41, 0xd47) => "Cortex-A710",
+		(0x41, 0xd48) => "Cortex-X2",
+		(0x41, 0xd49) => "Neoverse-N2",
+		(0x41, 0xd4a) => "Neoverse-E1",
+		(0x41, 0xd4b) => "Cortex-A78C",
+		(0x41, 0xd4c) => "Cortex-X1C",
+		(0x41, 0xd4d) => "Cortex-A715",
+		(0x41, 0xd4e) => "Cortex-X3",
+		(0x41, 0xd4f) => "Neoverse-V2",
+		(0x41, 0xd80) => "Cortex-A520",
+		(0x41, 0xd81) => "Cortex-A720",
+		(0x41, 0xd82) => "Cortex-X4",
+		(0x41, 0xd84) => "Neoverse-V3",
+		(0x41, 0xd85) => "Cortex-X925",
+		(0x41, 0xd87) => "Cortex-A725",
+		(0x41, 0xd8e) => "Neoverse-N3",
+		(0x42, 0x00f) => "Brahma-B15",
+		(0x42, 0x100) => "Brahma-B53",
+		(0x42, 0x516) => "ThunderX2",
+		(0x43, 0x0a1) => "ThunderX 88XX",
+		(0x43, 0x0a2) => "ThunderX 81XX",
+		(0x43, 0x0a3) => "ThunderX 83XX",
+		(0x43, 0x0af) => "ThunderX2 99xx",
+		(0x43, 0x0b8) => "ThunderX3 T110",
+		(0x46, 0x001) => "A64FX",
+		(0x48, 0xd01) => "TaiShan-v110",
+		(0x48, 0xd02) => "TaiShan-v120",
+		(0x4e, 0x000) => "Denver",
+		(0x4e, 0x003) => "Denver 2",
+		(0x4e, 0x004) => "Carmel",
+		(0x50, 0x000) => "X-Gene",
+		(0x51, 0x800) => "Kryo 2XX Gold",
+		(0x51, 0x801) => "Kryo 2XX Silver",
+		(0x51, 0x802) => "Kryo 3XX Gold",
+		(0x51, 0x803) => "Kryo 3XX Silver",
+		(0x51, 0x804) => "Kryo 4XX Gold",
+		(0x51, 0x805) => "Kryo 4XX Silver",
+		(0x51, 0xc00) => "Falkor",
+		(0x51, 0xc01) => "Saphira",
+		(0x51, 0x001) => "Oryon",
+		(0x61, 0x022) => "Icestorm",
+		(0x61, 0x023) => "Firestorm",
+		(0x61, 0x024) => "Icestorm Pro",
+		(0x61, 0x025) => "Firestorm Pro",
+		(0x61, 0x028) => "Icestorm Max",
+		(0x61, 0x029) => "Firestorm Max",
+		(0x61, 0x032) => "Blizzard",
+		(0x61, 0x033) => "Avalanche",
+		(0x70, 0x662) => "FTC662",
+		(0x70, 0x663) => "FTC663",
+		(0xc0, 0xac3) => "Ampere-1",
+		(0xc0, 0xac4) => "Ampere-1a",
+		_ => return None
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cpu::{Cpu, CpuFlag};
+
+	#[test]
+	fn decode() {
+		let info = Cpu::from_string("\
+processor	: 0
+BogoMIPS	: 108.00
+Features	: fp asimd evtstrm crc32 cpuid
+CPU implementer	: 0x41
+CPU architecture: 8
+CPU variant	: 0x0
+CPU part	: 0xd08
+CPU revision	: 3
+
+processor	: 1
+BogoMIPS	: 50.00
+Features	: fp asimd evtstrm aes pmull sha1 sha2 crc32 atomics
+CPU implementer	: 0x41
+CPU architecture: 8
+CPU variant	: 0x3
+CPU part	: 0xd0c
+CPU revision	: 1".into());
+
+		let a72 = info.first().unwrap().arm().unwrap();
+		assert_eq!(a72.vendor(), Some("ARM"));
+		assert_eq!(a72.core_name(), Some("Cortex-A72"));
+		assert_eq!(a72.architecture, 8);
+		assert_eq!(a72.stepping(), "r0p3");
+
+		let n1 = info.entries().nth(1).unwrap();
+		assert_eq!(n1.model_name().unwrap(), "ARM Neoverse-N1");
+		assert!(n1.has_flag(CpuFlag::Atomics));
+		assert_eq!(n1.flags().count(), 9);
+
+		assert_eq!(core_name(0x51, 0xd08), None);
+	}
+}
@@ -0,0 +1,255 @@
+//! Read the cache hierarchy from `/sys/devices/system/cpu/cpuN/cache`.
+//!
+//! Every cache lists the cpus which share it, so the l3 caches show which
+//! cores belong together (for example a CCX on AMD).
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::cpu::cache::CpuCache;
+//!
+//! for cache in CpuCache::all().unwrap() {
+//!     println!(
+//!         "L{} {:?} {} shared by {:?}",
+//!         cache.level, cache.kind, cache.size, cache.shared_cpus
+//!     );
+//! }
+//! ```
+
+use crate::unit::DataSize;
+use crate::util::{
+	read_sys_string, read_sys_value, read_sys_opt, parse_cpu_list
+};
+
+use std::{fs, io};
+use std::path::Path;
+
+const CPU_PATH: &str = "/sys/devices/system/cpu";
+
+/// A cache of one or more cpus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct CpuCache {
+	/// The level for example 1 for the l1 cache.
+	pub level: u8,
+	pub kind: CacheType,
+	pub size: DataSize,
+	/// The size of a cache line in bytes, mostly 64.
+	pub line_size: Option<u32>,
+	pub ways_of_associativity: Option<u32>,
+	pub number_of_sets: Option<u32>,
+	/// The cpus which share this cache.
+	pub shared_cpus: Vec<u32>
+}
+
+impl CpuCache {
+	/// Returns every cache of the system once, sorted by level and the
+	/// first cpu which uses it.
+	pub fn all() -> io::Result<Vec<Self>> {
+		Self::all_in(Path::new(CPU_PATH))
+	}
+
+	fn all_in(path: &Path) -> io::Result<Vec<Self>> {
+		let mut list: Vec<Self> = vec![];
+		for entry in fs::read_dir(path)? {
+			let entry = entry?;
+			let cpu = entry.file_name().to_str()
+				.and_then(|n| n.strip_prefix("cpu"))
+				.and_then(|n| n.parse::<u32>().ok());
+			if cpu.is_none() {
+				continue
+			}
+
+			for cache in read_caches(&entry.path().join("cache"))? {
+				if !list.contains(&cache) {
+					list.push(cache);
+				}
+			}
+		}
+		list.sort_by(|a, b| {
+			(a.level, a.shared_cpus.first(), a.kind.order())
+				.cmp(&(b.level, b.shared_cpus.first(), b.kind.order()))
+		});
+		Ok(list)
+	}
+
+	/// Returns the caches a cpu uses, from l1 to the last level.
+	///
+	/// Returns an empty list if the kernel does not know the caches, for
+	/// example in some virtual machines.
+	pub fn of_cpu(cpu: u32) -> io::Result<Vec<Self>> {
+		let path = Path::new(CPU_PATH)
+			.join(format!("cpu{}", cpu))
+			.join("cache");
+		read_caches(&path)
+	}
+
+	/// Returns true if the cpu uses this cache.
+	pub fn is_shared_by(&self, cpu: u32) -> bool {
+		self.shared_cpus.contains(&cpu)
+	}
+}
+
+fn read_caches(path: &Path) -> io::Result<Vec<CpuCache>> {
+	let dir = match fs::read_dir(path) {
+		Ok(d) => d,
+		Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+		Err(e) => return Err(e)
+	};
+
+	let mut list = vec![];
+	for entry in dir {
+		let entry = entry?;
+		// the caches are named index0, index1 ...
+		let index: u32 = match entry.file_name().to_str()
+			.and_then(|n| n.strip_prefix("index"))
+			.and_then(|n| n.parse().ok())
+		{
+			Some(i) => i,
+			None => continue
+		};
+		list.push((index, read_cache(&entry.path())?));
+	}
+	list.sort_by_key(|(i, _)| *i);
+	Ok(list.into_iter().map(|(_, c)| c).collect())
+}
+
+fn read_cache(path: &Path) -> io::Result<CpuCache> {
+	let size = read_sys_string(path.join("size"))?;
+	let shared = read_sys_string(path.join("shared_cpu_list"))?;
+
+	Ok(CpuCache {
+		level: read_sys_value(path.join("level"))?,
+		kind: read_sys_string(path.join("type"))?.as_str().into(),
+		size: parse_size(&size).ok_or_else(|| io::Error::new(
+			io::ErrorKind::InvalidData,
+			format!("invalid cache size {:?}", size)
+		))?,
+		line_size: read_sys_opt(path.join("coherency_line_size"))?,
+		ways_of_associativity: read_sys_opt(
+			path.join("ways_of_associativity")
+		)?,
+		number_of_sets: read_sys_opt(path.join("number_of_sets"))?,
+		shared_cpus: parse_cpu_list(&shared).ok_or_else(|| io::Error::new(
+			io::ErrorKind::InvalidData,
+			format!("invalid cpu list {:?}", shared)
+		))?
+	})
+}
+
+/// Parses a size like `48K` where K means KiB.
+fn parse_size(s: &str) -> Option<DataSize> {
+	let (num, shift) = match s.as_bytes().last()? {
+		b'K' => (&s[..s.len() - 1], 10),
+		b'M' => (&s[..s.len() - 1], 20),
+		b'G' => (&s[..s.len() - 1], 30),
+		_ => (s, 0)
+	};
+	num.parse::<u128>().ok()
+		.map(|n| DataSize::from_bytes(n << shift))
+}
+
+/// What a cache stores.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub enum CacheType {
+	Data,
+	Instruction,
+	/// Data and instructions.
+	Unified,
+	Unknown(String)
+}
+
+impl CacheType {
+	fn order(&self) -> u8 {
+		match self {
+			Self::Data => 0,
+			Self::Instruction => 1,
+			Self::Unified => 2,
+			Self::Unknown(_) => 3
+		}
+	}
+}
+
+impl From<&str> for CacheType {
+	fn from(s: &str) -> Self {
+		match s {
+			"Data" => Self::Data,
+			"Instruction" => Self::Instruction,
+			"Unified" => Self::Unified,
+			o => Self::Unknown(o.into())
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sizes() {
+		assert_eq!(parse_size("32K"), Some(DataSize::from_bytes(32 * 1024)));
+		assert_eq!(parse_size("1M"), Some(DataSize::from_bytes(1024 * 1024)));
+		assert_eq!(parse_size("2G"), Some(DataSize::from_bytes(2 << 30)));
+		assert_eq!(parse_size("512"), Some(DataSize::from_bytes(512)));
+		assert_eq!(parse_size(""), None);
+		assert_eq!(parse_size("K"), None);
+		assert_eq!(parse_size("12X"), None);
+	}
+
+	#[test]
+	fn all_sorted() {
+		let dir = std::env::temp_dir()
+			.join(format!("linux-info-cpu-cache-{}", std::process::id()));
+		// two cores with two threads each, which share the l3 cache
+		let caches = [
+			(1, "Data", "48K"),
+			(1, "Instruction", "32K"),
+			(2, "Unified", "1280K"),
+			(3, "Unified", "24M")
+		];
+		for cpu in 0..4u32 {
+			let first = cpu / 2 * 2;
+			for (i, (level, kind, size)) in caches.iter().enumerate() {
+				let path = dir.join(format!("cpu{}/cache/index{}", cpu, i));
+				fs::create_dir_all(&path).unwrap();
+				let shared = match level {
+					3 => "0-3".to_string(),
+					_ => format!("{}-{}", first, first + 1)
+				};
+				fs::write(path.join("level"), format!("{}\n", level)).unwrap();
+				fs::write(path.join("type"), format!("{}\n", kind)).unwrap();
+				fs::write(path.join("size"), format!("{}\n", size)).unwrap();
+				fs::write(path.join("shared_cpu_list"), shared + "\n").unwrap();
+			}
+		}
+		// other entries in the cpu directory are skipped
+		fs::create_dir_all(dir.join("cpuidle")).unwrap();
+		fs::write(dir.join("online"), "0-3\n").unwrap();
+
+		let list = CpuCache::all_in(&dir).unwrap();
+		fs::remove_dir_all(&dir).unwrap();
+
+		let order: Vec<_> = list.iter()
+			.map(|c| (c.level, c.kind.clone(), c.shared_cpus.clone()))
+			.collect();
+		assert_eq!(order, [
+			(1, CacheType::Data, vec![0, 1]),
+			(1, CacheType::Instruction, vec![0, 1]),
+			(1, CacheType::Data, vec![2, 3]),
+			(1, CacheType::Instruction, vec![2, 3]),
+			(2, CacheType::Unified, vec![0, 1]),
+			(2, CacheType::Unified, vec![2, 3]),
+			(3, CacheType::Unified, vec![0, 1, 2, 3])
+		]);
+		assert_eq!(list[6].size, DataSize::from_bytes(24 << 20));
+		assert_eq!(list[0].line_size, None);
+	}
+}
@@ -0,0 +1,348 @@
+//! Query the cpu directly with the `cpuid` instruction.
+//!
+//! This works even if `/proc/cpuinfo` is masked or virtualized, for example
+//! in some containers. Inside a virtual machine the hypervisor decides what
+//! `cpuid` returns.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::cpu::{CpuFlag, cpuid::CpuId};
+//!
+//! let id = CpuId::read();
+//! println!(
+//!     "{} family {} model {}",
+//!     id.brand.as_deref().unwrap_or(&id.vendor), id.family, id.model
+//! );
+//! if id.has_flag(CpuFlag::Avx2) == Some(true) {
+//!     println!("avx2 supported");
+//! }
+//! ```
+
+use super::CpuFlag;
+
+use std::arch::x86_64::{__cpuid_count, _xgetbv};
+
+/// Executes `cpuid` and returns eax, ebx, ecx and edx.
+///
+/// The leaf should not be higher than [`CpuId::max_leaf`] or
+/// [`CpuId::max_extended_leaf`], unsupported leafs return unspecified
+/// values.
+// cpuid is only unsafe in older rust versions
+#[allow(unused_unsafe)]
+pub fn raw(leaf: u32, subleaf: u32) -> [u32; 4] {
+	let r = unsafe { __cpuid_count(leaf, subleaf) };
+	[r.eax, r.ebx, r.ecx, r.edx]
+}
+
+/// Reads the extended control register 0, which contains the register
+/// states the os saves on context switches.
+///
+/// ## Safety
+/// The os needs to have enabled `xsave` (OSXSAVE in leaf 1).
+#[target_feature(enable = "xsave")]
+unsafe fn xcr0() -> u64 {
+	_xgetbv(0)
+}
+
+const EAX: usize = 0;
+const EBX: usize = 1;
+const ECX: usize = 2;
+const EDX: usize = 3;
+
+/// The identification and features of the cpu the calling thread runs on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct CpuId {
+	/// The vendor for example `GenuineIntel` or `AuthenticAMD`.
+	pub vendor: String,
+	/// The brand for example `AMD Ryzen 9 3900XT 12-Core Processor`.
+	pub brand: Option<String>,
+	/// The family including the extended family, like `cpu family` in
+	/// `/proc/cpuinfo`.
+	pub family: u32,
+	/// The model including the extended model.
+	pub model: u32,
+	pub stepping: u32,
+	/// The highest basic leaf.
+	pub max_leaf: u32,
+	/// The highest extended leaf (starting at `0x80000000`).
+	pub max_extended_leaf: u32,
+	/// The vendor of the hypervisor for example `KVMKVMKVM` if running in a
+	/// virtual machine.
+	pub hypervisor: Option<String>,
+	features: Features
+}
+
+/// The registers of the leafs which contain feature bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+struct Features {
+	leaf1: [u32; 4],
+	leaf7: [u32; 4],
+	leaf7_1: [u32; 4],
+	ext1: [u32; 4],
+	/// The enabled register states, 0 if the os does not support xsave.
+	xcr0: u64
+}
+
+impl CpuId {
+	pub fn read() -> Self {
+		let leaf0 = raw(0, 0);
+		let max_leaf = leaf0[EAX];
+		let max_extended_leaf = raw(0x8000_0000, 0)[EAX];
+
+		let basic = |leaf, subleaf| {
+			if leaf <= max_leaf { raw(leaf, subleaf) } else { [0; 4] }
+		};
+		let leaf1 = basic(1, 0);
+		// OSXSAVE
+		let xcr0 = if leaf1[ECX] & (1 << 27) != 0 {
+			unsafe { xcr0() }
+		} else {
+			0
+		};
+
+		let brand = (max_extended_leaf >= 0x8000_0004).then(|| {
+			let regs: Vec<u32> = (0x8000_0002..=0x8000_0004)
+				.flat_map(|l| raw(l, 0).to_vec())
+				.collect();
+			regs_to_string(&regs)
+		}).filter(|b| !b.is_empty());
+
+		let (family, model, stepping) = signature(leaf1[EAX]);
+
+		let mut id = Self {
+			vendor: regs_to_string(&[leaf0[EBX], leaf0[EDX], leaf0[ECX]]),
+			brand,
+			family,
+			model,
+			stepping,
+			max_leaf,
+			max_extended_leaf,
+			hypervisor: None,
+			features: Features {
+				leaf1,
+				leaf7: basic(7, 0),
+				leaf7_1: basic(7, 1),
+				ext1: if max_extended_leaf >= 0x8000_0001 {
+					raw(0x8000_0001, 0)
+				} else {
+					[0; 4]
+				},
+				xcr0
+			}
+		};
+
+		if id.has_flag(CpuFlag::Hypervisor) == Some(true) {
+			let leaf = raw(0x4000_0000, 0);
+			id.hypervisor = Some(regs_to_string(&leaf[EBX..=EDX]))
+				.filter(|h| !h.is_empty());
+		}
+
+		id
+	}
+
+	/// Returns if the cpu supports a feature, `None` if the feature cannot
+	/// be detected with cpuid or is not an x86 feature.
+	///
+	/// AVX, AVX-512 and AMX features are only reported if the os enabled
+	/// their register state (checked with `xgetbv`), like `/proc/cpuinfo`
+	/// does. Use [`raw`] to get the bits the cpu reports. Using AMX also
+	/// requires requesting permission with `arch_prctl`.
+	pub fn has_flag(&self, flag: impl Into<CpuFlag>) -> Option<bool> {
+		let flag = flag.into();
+		let (leaf, reg, bit) = flag_bit(&flag)?;
+		let regs = match leaf {
+			Leaf::Basic1 => self.features.leaf1,
+			Leaf::Basic7 => self.features.leaf7,
+			Leaf::Basic7Sub1 => self.features.leaf7_1,
+			Leaf::Extended1 => self.features.ext1
+		};
+		let state = xsave_state(flag.name());
+		Some(
+			regs[reg] & (1 << bit) != 0 &&
+			self.features.xcr0 & state == state
+		)
+	}
+
+	/// Returns every feature which is supported and has a [`CpuFlag`].
+	pub fn flags(&self) -> Vec<CpuFlag> {
+		FLAGS.iter()
+			.map(|(name, ..)| CpuFlag::from(*name))
+			.filter(|f| self.has_flag(f.clone()) == Some(true))
+			.collect()
+	}
+}
+
+/// Decodes the family, model and stepping from eax of leaf 1.
+fn signature(eax: u32) -> (u32, u32, u32) {
+	let stepping = eax & 0xf;
+	let base_family = (eax >> 8) & 0xf;
+	let mut model = (eax >> 4) & 0xf;
+
+	let family = if base_family == 0xf {
+		base_family + ((eax >> 20) & 0xff)
+	} else {
+		base_family
+	};
+	if base_family == 0x6 || base_family == 0xf {
+		model += ((eax >> 16) & 0xf) << 4;
+	}
+
+	(family, model, stepping)
+}
+
+/// Turns registers into a string, they contain the bytes in little endian.
+fn regs_to_string(regs: &[u32]) -> String {
+	let bytes: Vec<u8> = regs.iter()
+		.flat_map(|r| r.to_le_bytes().to_vec())
+		.take_while(|b| *b != 0)
+		.collect();
+	String::from_utf8_lossy(&bytes).trim().to_string()
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Leaf {
+	Basic1,
+	Basic7,
+	Basic7Sub1,
+	Extended1
+}
+
+/// The flags with the leaf, register and bit which contain them.
+const FLAGS: &[(&str, Leaf, usize, u32)] = &[
+	("fpu", Leaf::Basic1, EDX, 0),
+	("tsc", Leaf::Basic1, EDX, 4),
+	("cx8", Leaf::Basic1, EDX, 8),
+	("cmov", Leaf::Basic1, EDX, 15),
+	("mmx", Leaf::Basic1, EDX, 23),
+	("sse", Leaf::Basic1, EDX, 25),
+	("sse2", Leaf::Basic1, EDX, 26),
+	("ht", Leaf::Basic1, EDX, 28),
+	("pni", Leaf::Basic1, ECX, 0),
+	("pclmulqdq", Leaf::Basic1, ECX, 1),
+	("vmx", Leaf::Basic1, ECX, 5),
+	("ssse3", Leaf::Basic1, ECX, 9),
+	("fma", Leaf::Basic1, ECX, 12),
+	("cx16", Leaf::Basic1, ECX, 13),
+	("sse4_1", Leaf::Basic1, ECX, 19),
+	("sse4_2", Leaf::Basic1, ECX, 20),
+	("movbe", Leaf::Basic1, ECX, 22),
+	("popcnt", Leaf::Basic1, ECX, 23),
+	("aes", Leaf::Basic1, ECX, 25),
+	("xsave", Leaf::Basic1, ECX, 26),
+	("avx", Leaf::Basic1, ECX, 28),
+	("f16c", Leaf::Basic1, ECX, 29),
+	("rdrand", Leaf::Basic1, ECX, 30),
+	("hypervisor", Leaf::Basic1, ECX, 31),
+	("bmi1", Leaf::Basic7, EBX, 3),
+	("avx2", Leaf::Basic7, EBX, 5),
+	("smep", Leaf::Basic7, EBX, 7),
+	("bmi2", Leaf::Basic7, EBX, 8),
+	("erms", Leaf::Basic7, EBX, 9),
+	("avx512f", Leaf::Basic7, EBX, 16),
+	("avx512dq", Leaf::Basic7, EBX, 17),
+	("rdseed", Leaf::Basic7, EBX, 18),
+	("adx", Leaf::Basic7, EBX, 19),
+	("smap", Leaf::Basic7, EBX, 20),
+	("avx512cd", Leaf::Basic7, EBX, 28),
+	("sha_ni", Leaf::Basic7, EBX, 29),
+	("avx512bw", Leaf::Basic7, EBX, 30),
+	("avx512vl", Leaf::Basic7, EBX, 31),
+	("vaes", Leaf::Basic7, ECX, 9),
+	("vpclmulqdq", Leaf::Basic7, ECX, 10),
+	("avx512_vnni", Leaf::Basic7, ECX, 11),
+	("rdpid", Leaf::Basic7, ECX, 22),
+	("amx_bf16", Leaf::Basic7, EDX, 22),
+	("amx_tile", Leaf::Basic7, EDX, 24),
+	("amx_int8", Leaf::Basic7, EDX, 25),
+	("avx_vnni", Leaf::Basic7Sub1, EAX, 4),
+	("avx512_bf16", Leaf::Basic7Sub1, EAX, 5),
+	("svm", Leaf::Extended1, ECX, 2),
+	("sse4a", Leaf::Extended1, ECX, 6),
+	("nx", Leaf::Extended1, EDX, 20),
+	("lm", Leaf::Extended1, EDX, 29)
+];
+
+/// The register states (bits of xcr0) a feature needs.
+fn xsave_state(name: &str) -> u64 {
+	// sse and avx (xmm and ymm registers)
+	const AVX: u64 = 0b110;
+	// opmask and the upper zmm registers
+	const AVX512: u64 = AVX | 0b1110_0000;
+	// tile config and tile data
+	const AMX: u64 = 0b11 << 17;
+
+	match name {
+		n if n.starts_with("avx512") => AVX512,
+		n if n.starts_with("amx") => AMX,
+		"avx" | "avx2" | "avx_vnni" | "fma" | "f16c" | "vaes" |
+		"vpclmulqdq" => AVX,
+		_ => 0
+	}
+}
+
+fn flag_bit(flag: &CpuFlag) -> Option<(Leaf, usize, u32)> {
+	FLAGS.iter()
+		.find(|(name, ..)| *name == flag.name())
+		.map(|(_, leaf, reg, bit)| (*leaf, *reg, *bit))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn decode() {
+		// AMD Ryzen 9 3900XT
+		assert_eq!(signature(0x0087_0f10), (23, 113, 0));
+		// Intel Core i7-8700
+		assert_eq!(signature(0x0009_06ea), (6, 158, 10));
+
+		let vendor = [0x6874_7541, 0x6974_6e65, 0x444d_4163];
+		assert_eq!(regs_to_string(&vendor), "AuthenticAMD");
+
+		let id = CpuId {
+			vendor: "GenuineIntel".into(),
+			brand: None,
+			family: 6,
+			model: 158,
+			stepping: 10,
+			max_leaf: 22,
+			max_extended_leaf: 0x8000_0008,
+			hypervisor: None,
+			features: Features {
+				leaf1: [0, 0, 1 << 20, 1 << 26],
+				leaf7: [0, 1 << 5 | 1 << 16, 0, 0],
+				xcr0: 0b111,
+				..Default::default()
+			}
+		};
+		assert_eq!(id.has_flag(CpuFlag::Sse4_2), Some(true));
+		assert_eq!(id.has_flag("avx2"), Some(true));
+		// the os did not enable the avx-512 state
+		assert_eq!(id.has_flag(CpuFlag::Avx512f), Some(false));
+		assert_eq!(id.has_flag(CpuFlag::Sve), None);
+		assert_eq!(
+			id.flags(),
+			[CpuFlag::Sse2, CpuFlag::Sse4_2, CpuFlag::Avx2]
+		);
+
+		let mut no_avx = id.clone();
+		no_avx.features.xcr0 = 0b11;
+		assert_eq!(no_avx.has_flag("avx2"), Some(false));
+		assert_eq!(no_avx.has_flag(CpuFlag::Sse4_2), Some(true));
+
+		let mut avx512 = id;
+		avx512.features.xcr0 = 0b1110_0111;
+		assert_eq!(avx512.has_flag(CpuFlag::Avx512f), Some(true));
+	}
+}
@@ -0,0 +1,180 @@
+//! The features a cpu reports in the `flags` line of `/proc/cpuinfo` on x86
+//! and in the `Features` line on arm.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::cpu::{Cpu, CpuFlag};
+//!
+//! let info = Cpu::read().unwrap();
+//! let first = info.first().unwrap();
+//! if first.has_flag(CpuFlag::Avx2) || first.has_flag(CpuFlag::Sve) {
+//!     println!("vector extensions available");
+//! }
+//! ```
+
+use std::fmt;
+
+macro_rules! cpu_flags {
+	($(
+		$(#[$doc:meta])*
+		$var:ident = $name:expr
+	),*) => (
+		/// A cpu feature, named like the kernel lists it.
+		#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+		#[cfg_attr(
+			feature = "serde",
+			derive(serde1::Serialize, serde1::Deserialize),
+			serde(crate = "serde1")
+		)]
+		pub enum CpuFlag {
+			$(
+				$(#[$doc])*
+				$var,
+			)*
+			/// A flag which has no variant.
+			Other(String)
+		}
+
+		impl CpuFlag {
+			/// The name in `/proc/cpuinfo` for example `sse4_2`.
+			pub fn name(&self) -> &str {
+				match self {
+					$(Self::$var => $name,)*
+					Self::Other(o) => o
+				}
+			}
+		}
+
+		impl From<&str> for CpuFlag {
+			fn from(s: &str) -> Self {
+				match s {
+					$($name => Self::$var,)*
+					o => Self::Other(o.into())
+				}
+			}
+		}
+	)
+}
+
+cpu_flags! {
+	// x86
+	Fpu = "fpu",
+	Tsc = "tsc",
+	Cx8 = "cx8",
+	Cmov = "cmov",
+	Mmx = "mmx",
+	Sse = "sse",
+	Sse2 = "sse2",
+	/// Hyper-threading, also set by single threaded cpus.
+	Ht = "ht",
+	/// No execute bit.
+	Nx = "nx",
+	/// 64 bit long mode.
+	Lm = "lm",
+	ConstantTsc = "constant_tsc",
+	NonstopTsc = "nonstop_tsc",
+	/// Sse3, named after the codename Prescott New Instructions.
+	Pni = "pni",
+	Pclmulqdq = "pclmulqdq",
+	/// Intel virtualization.
+	Vmx = "vmx",
+	/// Amd virtualization.
+	Svm = "svm",
+	Ssse3 = "ssse3",
+	Fma = "fma",
+	Cx16 = "cx16",
+	Sse4_1 = "sse4_1",
+	Sse4_2 = "sse4_2",
+	Sse4a = "sse4a",
+	Movbe = "movbe",
+	Popcnt = "popcnt",
+	/// Aes instructions, on x86 and arm.
+	Aes = "aes",
+	Xsave = "xsave",
+	Avx = "avx",
+	F16c = "f16c",
+	Rdrand = "rdrand",
+	/// Running in a virtual machine.
+	Hypervisor = "hypervisor",
+	Bmi1 = "bmi1",
+	Bmi2 = "bmi2",
+	Avx2 = "avx2",
+	Erms = "erms",
+	Smep = "smep",
+	Smap = "smap",
+	Rdseed = "rdseed",
+	Adx = "adx",
+	ShaNi = "sha_ni",
+	Avx512f = "avx512f",
+	Avx512dq = "avx512dq",
+	Avx512cd = "avx512cd",
+	Avx512bw = "avx512bw",
+	Avx512vl = "avx512vl",
+	Avx512Vnni = "avx512_vnni",
+	Avx512Bf16 = "avx512_bf16",
+	AvxVnni = "avx_vnni",
+	Vaes = "vaes",
+	Vpclmulqdq = "vpclmulqdq",
+	AmxTile = "amx_tile",
+	AmxBf16 = "amx_bf16",
+	AmxInt8 = "amx_int8",
+	Rdpid = "rdpid",
+	// arm
+	/// Floating point.
+	Fp = "fp",
+	/// Advanced simd (neon).
+	Asimd = "asimd",
+	Evtstrm = "evtstrm",
+	Pmull = "pmull",
+	Sha1 = "sha1",
+	Sha2 = "sha2",
+	Sha3 = "sha3",
+	Sha512 = "sha512",
+	Crc32 = "crc32",
+	/// Large system extensions atomics.
+	Atomics = "atomics",
+	Fphp = "fphp",
+	Asimdhp = "asimdhp",
+	Cpuid = "cpuid",
+	Asimdrdm = "asimdrdm",
+	Lrcpc = "lrcpc",
+	Dcpop = "dcpop",
+	/// Dot product instructions.
+	Asimddp = "asimddp",
+	Sm3 = "sm3",
+	Sm4 = "sm4",
+	Asimdfhm = "asimdfhm",
+	/// Scalable vector extension.
+	Sve = "sve",
+	Sve2 = "sve2",
+	Paca = "paca",
+	Pacg = "pacg",
+	Bti = "bti",
+	Mte = "mte",
+	I8mm = "i8mm",
+	Bf16 = "bf16",
+	Sme = "sme"
+}
+
+impl fmt::Display for CpuFlag {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn names() {
+		assert_eq!(CpuFlag::from("sse4_2"), CpuFlag::Sse4_2);
+		assert_eq!(CpuFlag::from("sve"), CpuFlag::Sve);
+		assert_eq!(CpuFlag::Avx512Vnni.name(), "avx512_vnni");
+		assert_eq!(
+			CpuFlag::from("user_shstk"),
+			CpuFlag::Other("user_shstk".into())
+		);
+		assert_eq!(CpuFlag::from("pni").to_string(), "pni");
+	}
+}
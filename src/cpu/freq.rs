@@ -0,0 +1,299 @@
+//! Read the frequency scaling state of every cpu from
+//! `/sys/devices/system/cpu/cpuN/cpufreq`.
+//!
+//! Frequencies are in kHz like the kernel reports them, which is more
+//! precise than the MHz in `/proc/cpuinfo`. Turbo / boost can be read and
+//! switched with [`boost`] and [`set_boost`].
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::cpu::freq::CpuFreq;
+//!
+//! for cpu in CpuFreq::all().unwrap() {
+//!     println!(
+//!         "cpu{} {} kHz ({} - {}) governor {}",
+//!         cpu.cpu(), cpu.current().unwrap(), cpu.min().unwrap(),
+//!         cpu.max().unwrap(), cpu.governor().unwrap()
+//!     );
+//! }
+//! ```
+
+use crate::util::{read_sys_string, read_sys_value, read_sys_opt};
+use super::online_cpu_dirs;
+
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+
+const CPU_PATH: &str = "/sys/devices/system/cpu";
+
+/// The cpufreq policy of a cpu for example
+/// `/sys/devices/system/cpu/cpu0/cpufreq`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpuFreq {
+	cpu: u32,
+	path: PathBuf
+}
+
+impl CpuFreq {
+	/// Returns every online cpu which supports frequency scaling.
+	///
+	/// Returns an empty list if there is no cpufreq driver, for example in
+	/// most virtual machines.
+	pub fn all() -> io::Result<Vec<Self>> {
+		Self::all_in(Path::new(CPU_PATH))
+	}
+
+	fn all_in(dir: &Path) -> io::Result<Vec<Self>> {
+		online_cpu_dirs(dir, "cpufreq").map(|l| {
+			l.into_iter()
+				.map(|(cpu, path)| Self { cpu, path })
+				.collect()
+		})
+	}
+
+	/// Returns the cpufreq policy of a cpu, `None` if the cpu does not
+	/// exist, is offline or has no cpufreq driver.
+	pub fn from_cpu(cpu: u32) -> Option<Self> {
+		let path = Path::new(CPU_PATH)
+			.join(format!("cpu{}", cpu))
+			.join("cpufreq");
+		path.is_dir().then(|| Self { cpu, path })
+	}
+
+	/// The number of the cpu.
+	pub fn cpu(&self) -> u32 {
+		self.cpu
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// The current frequency in kHz as the kernel last saw it.
+	pub fn current(&self) -> io::Result<u32> {
+		read_sys_value(self.path.join("scaling_cur_freq"))
+	}
+
+	/// The lowest frequency the governor may select in kHz.
+	pub fn min(&self) -> io::Result<u32> {
+		read_sys_value(self.path.join("scaling_min_freq"))
+	}
+
+	/// The highest frequency the governor may select in kHz.
+	pub fn max(&self) -> io::Result<u32> {
+		read_sys_value(self.path.join("scaling_max_freq"))
+	}
+
+	/// The lowest frequency the hardware supports in kHz.
+	pub fn hardware_min(&self) -> io::Result<u32> {
+		read_sys_value(self.path.join("cpuinfo_min_freq"))
+	}
+
+	/// The highest frequency the hardware supports in kHz, with boost
+	/// enabled this is the boost frequency.
+	pub fn hardware_max(&self) -> io::Result<u32> {
+		read_sys_value(self.path.join("cpuinfo_max_freq"))
+	}
+
+	/// The active governor for example `schedutil`, `powersave` or
+	/// `performance`.
+	pub fn governor(&self) -> io::Result<String> {
+		read_sys_string(self.path.join("scaling_governor"))
+	}
+
+	/// The governors which can be set.
+	pub fn available_governors(&self) -> io::Result<Vec<String>> {
+		read_sys_string(self.path.join("scaling_available_governors"))
+			.map(|s| s.split_whitespace().map(Into::into).collect())
+	}
+
+	/// The scaling driver for example `intel_pstate`, `amd-pstate-epp` or
+	/// `acpi-cpufreq`.
+	pub fn driver(&self) -> io::Result<String> {
+		read_sys_string(self.path.join("scaling_driver"))
+	}
+
+	/// The energy performance preference for example
+	/// `balance_performance`, only available with the `intel_pstate` and
+	/// `amd-pstate` drivers.
+	pub fn energy_performance_preference(&self) -> io::Result<Option<String>> {
+		read_sys_opt(self.path.join("energy_performance_preference"))
+	}
+
+	/// Reads every value at once.
+	pub fn info(&self) -> io::Result<CpuFreqInfo> {
+		Ok(CpuFreqInfo {
+			cpu: self.cpu,
+			current: self.current()?,
+			min: self.min()?,
+			max: self.max()?,
+			hardware_min: self.hardware_min()?,
+			hardware_max: self.hardware_max()?,
+			governor: self.governor()?,
+			available_governors: self.available_governors()?,
+			driver: self.driver()?
+		})
+	}
+
+	/// Changes the governor, requires root.
+	///
+	/// Returns an error with the kind `InvalidInput` if the governor is
+	/// not available.
+	pub fn set_governor(&self, governor: &str) -> io::Result<()> {
+		fs::write(self.path.join("scaling_governor"), governor)
+	}
+}
+
+/// The frequency scaling state of a cpu, frequencies are in kHz.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct CpuFreqInfo {
+	pub cpu: u32,
+	pub current: u32,
+	pub min: u32,
+	pub max: u32,
+	pub hardware_min: u32,
+	pub hardware_max: u32,
+	pub governor: String,
+	pub available_governors: Vec<String>,
+	pub driver: String
+}
+
+/// Where turbo / boost is switched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub enum BoostControl {
+	/// `/sys/devices/system/cpu/intel_pstate/no_turbo`.
+	IntelPstate,
+	/// `/sys/devices/system/cpu/cpufreq/boost`, used by `acpi-cpufreq`.
+	Global,
+	/// The `boost` file of every policy, used by `amd-pstate`.
+	Policy
+}
+
+/// If the cpu may run above its base frequency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct Boost {
+	/// With [`BoostControl::Policy`] this is only true if every policy has
+	/// boost enabled.
+	pub enabled: bool,
+	pub control: BoostControl,
+	/// The scaling driver for example `intel_pstate` or `acpi-cpufreq`.
+	pub driver: Option<String>
+}
+
+/// Returns if turbo / boost is enabled, `None` if the scaling driver does
+/// not allow to control it, for example in most virtual machines.
+pub fn boost() -> io::Result<Option<Boost>> {
+	let control = match boost_control()? {
+		Some(c) => c,
+		None => return Ok(None)
+	};
+	let cpu = Path::new(CPU_PATH);
+
+	let enabled = match control {
+		BoostControl::IntelPstate => {
+			read_sys_value::<u8>(cpu.join("intel_pstate/no_turbo"))? == 0
+		},
+		BoostControl::Global => {
+			read_sys_value::<u8>(cpu.join("cpufreq/boost"))? != 0
+		},
+		BoostControl::Policy => {
+			let mut enabled = true;
+			for freq in CpuFreq::all()? {
+				if read_sys_value::<u8>(freq.path.join("boost"))? == 0 {
+					enabled = false;
+				}
+			}
+			enabled
+		}
+	};
+
+	let driver = CpuFreq::all()?
+		.first()
+		.and_then(|f| f.driver().ok());
+
+	Ok(Some(Boost { enabled, control, driver }))
+}
+
+/// Enables or disables turbo / boost, requires root.
+///
+/// Disabling boost makes benchmarks more reproducible. Returns an error
+/// with the kind `NotFound` if boost cannot be controlled. With
+/// `intel_pstate` enabling fails if turbo was disabled by the firmware.
+pub fn set_boost(enabled: bool) -> io::Result<()> {
+	let cpu = Path::new(CPU_PATH);
+	match boost_control()? {
+		Some(BoostControl::IntelPstate) => fs::write(
+			cpu.join("intel_pstate/no_turbo"),
+			if enabled { "0" } else { "1" }
+		),
+		Some(BoostControl::Global) => fs::write(
+			cpu.join("cpufreq/boost"),
+			if enabled { "1" } else { "0" }
+		),
+		Some(BoostControl::Policy) => {
+			for freq in CpuFreq::all()? {
+				fs::write(freq.path.join("boost"), if enabled { "1" } else {
+					"0"
+				})?;
+			}
+			Ok(())
+		},
+		None => Err(io::Error::new(
+			io::ErrorKind::NotFound,
+			"boost cannot be controlled"
+		))
+	}
+}
+
+fn boost_control() -> io::Result<Option<BoostControl>> {
+	let cpu = Path::new(CPU_PATH);
+	if cpu.join("intel_pstate/no_turbo").is_file() {
+		return Ok(Some(BoostControl::IntelPstate))
+	}
+	if cpu.join("cpufreq/boost").is_file() {
+		return Ok(Some(BoostControl::Global))
+	}
+
+	let policies = CpuFreq::all()?;
+	let per_policy = !policies.is_empty() &&
+		policies.iter().all(|f| f.path.join("boost").is_file());
+	Ok(per_policy.then(|| BoostControl::Policy))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn all_online() {
+		let dir = std::env::temp_dir()
+			.join(format!("linux-info-cpufreq-{}", std::process::id()));
+		for cpu in 0..4 {
+			fs::create_dir_all(dir.join(format!("cpu{}/cpufreq", cpu)))
+				.unwrap();
+		}
+		// cpu2 is offline but keeps its cpufreq directory
+		fs::write(dir.join("online"), "0-1,3\n").unwrap();
+		fs::remove_dir(dir.join("cpu3/cpufreq")).unwrap();
+
+		let all = CpuFreq::all_in(&dir).unwrap();
+		assert_eq!(all.iter().map(|c| c.cpu()).collect::<Vec<_>>(), [0, 1]);
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+}
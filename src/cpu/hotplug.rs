@@ -0,0 +1,231 @@
+//! Read which cpus are online, offline, present or possible from
+//! `/sys/devices/system/cpu` and bring cpus online or offline.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::cpu::hotplug;
+//!
+//! let online = hotplug::online().unwrap();
+//! println!("{} cpus online: {}", online.len(), online);
+//! // requires root
+//! if hotplug::is_hotpluggable(3) {
+//!     hotplug::set_online(3, false).unwrap();
+//! }
+//! ```
+
+use crate::util::{read_sys_value, parse_cpu_list};
+
+use std::{fmt, fs, io};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const CPU_PATH: &str = "/sys/devices/system/cpu";
+
+/// The cpus which are online.
+pub fn online() -> io::Result<CpuList> {
+	read_list("online")
+}
+
+/// The cpus which are present but offline.
+pub fn offline() -> io::Result<CpuList> {
+	read_list("offline")
+}
+
+/// The cpus which are physically present.
+pub fn present() -> io::Result<CpuList> {
+	read_list("present")
+}
+
+/// The cpus the kernel reserved space for, including the ones which could
+/// be added later.
+pub fn possible() -> io::Result<CpuList> {
+	read_list("possible")
+}
+
+fn read_list(name: &str) -> io::Result<CpuList> {
+	read_sys_value(Path::new(CPU_PATH).join(name))
+}
+
+fn online_path(cpu: u32) -> PathBuf {
+	Path::new(CPU_PATH)
+		.join(format!("cpu{}", cpu))
+		.join("online")
+}
+
+/// Returns true if the cpu is online.
+pub fn is_online(cpu: u32) -> io::Result<bool> {
+	match read_sys_value::<u8>(online_path(cpu)) {
+		Ok(o) => Ok(o != 0),
+		// cpus which cannot be taken offline have no online file
+		Err(e) if e.kind() == io::ErrorKind::NotFound => {
+			online().map(|l| l.contains(cpu))
+		},
+		Err(e) => Err(e)
+	}
+}
+
+/// Returns true if the cpu can be taken offline, on x86 this is mostly
+/// every cpu except cpu0.
+pub fn is_hotpluggable(cpu: u32) -> bool {
+	online_path(cpu).is_file()
+}
+
+/// Brings a cpu online or takes it offline, requires root.
+///
+/// Returns an error with the kind `NotFound` if the cpu is not hotpluggable.
+pub fn set_online(cpu: u32, online: bool) -> io::Result<()> {
+	// writing a missing sysfs file tries to create it which fails with
+	// PermissionDenied
+	if !is_hotpluggable(cpu) {
+		return Err(io::Error::new(
+			io::ErrorKind::NotFound,
+			format!("cpu{} is not hotpluggable", cpu)
+		))
+	}
+
+	fs::write(online_path(cpu), if online { "1" } else { "0" })
+}
+
+/// A set of cpus, which the kernel writes as a list like `0-3,8` or as a
+/// mask like `ff,00000000`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct CpuList {
+	cpus: Vec<u32>
+}
+
+impl CpuList {
+	/// Creates a list from cpu numbers, they get sorted and deduplicated.
+	pub fn new(mut cpus: Vec<u32>) -> Self {
+		cpus.sort_unstable();
+		cpus.dedup();
+		Self { cpus }
+	}
+
+	/// Parses a hex mask like `ff,00000000`, which is split into groups of
+	/// 32 bits with the highest cpus first.
+	pub fn from_mask(s: &str) -> Option<Self> {
+		let mut cpus = vec![];
+		let digits = s.trim().bytes()
+			.filter(|b| *b != b',')
+			.rev()
+			.enumerate();
+		for (i, digit) in digits {
+			let nibble = (digit as char).to_digit(16)?;
+			for bit in 0..4 {
+				if nibble & (1 << bit) != 0 {
+					cpus.push(i as u32 * 4 + bit);
+				}
+			}
+		}
+		Some(Self { cpus })
+	}
+
+	pub fn contains(&self, cpu: u32) -> bool {
+		self.cpus.binary_search(&cpu).is_ok()
+	}
+
+	pub fn len(&self) -> usize {
+		self.cpus.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.cpus.is_empty()
+	}
+
+	/// The cpu numbers in ascending order.
+	pub fn cpus(&self) -> &[u32] {
+		&self.cpus
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item=u32> + '_ {
+		self.cpus.iter().copied()
+	}
+}
+
+impl FromStr for CpuList {
+	type Err = ParseCpuListError;
+
+	/// Parses a list like `0-3,8,10-11`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		parse_cpu_list(s)
+			.map(Self::new)
+			.ok_or(ParseCpuListError)
+	}
+}
+
+/// Writes the list in the same format as the kernel for example `0-3,8`.
+impl fmt::Display for CpuList {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let mut iter = self.cpus.iter().copied().peekable();
+		let mut first = true;
+		while let Some(start) = iter.next() {
+			let mut end = start;
+			while iter.peek() == Some(&(end + 1)) {
+				end = iter.next().unwrap();
+			}
+
+			if !first {
+				f.write_str(",")?;
+			}
+			first = false;
+			if start == end {
+				write!(f, "{}", start)?;
+			} else {
+				write!(f, "{}-{}", start, end)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl From<CpuList> for Vec<u32> {
+	fn from(list: CpuList) -> Self {
+		list.cpus
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCpuListError;
+
+impl fmt::Display for ParseCpuListError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("invalid cpu list")
+	}
+}
+
+impl std::error::Error for ParseCpuListError {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn list() {
+		let list: CpuList = "8,0-3,10-11\n".parse().unwrap();
+		assert_eq!(list.cpus(), [0, 1, 2, 3, 8, 10, 11]);
+		assert!(list.contains(10));
+		assert!(!list.contains(4));
+		assert_eq!(list.to_string(), "0-3,8,10-11");
+		assert!("".parse::<CpuList>().unwrap().is_empty());
+		assert!("1-x".parse::<CpuList>().is_err());
+	}
+
+	#[test]
+	fn mask() {
+		let list = CpuList::from_mask("00000001,0000000f").unwrap();
+		assert_eq!(list.cpus(), [0, 1, 2, 3, 32]);
+		assert_eq!(list.to_string(), "0-3,32");
+		assert!(CpuList::from_mask("zz").is_none());
+	}
+
+	#[test]
+	fn not_hotpluggable() {
+		let e = set_online(u32::MAX, true).unwrap_err();
+		assert_eq!(e.kind(), io::ErrorKind::NotFound);
+	}
+}
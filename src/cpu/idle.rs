@@ -0,0 +1,157 @@
+//! Read the idle states (C-states) of every cpu from
+//! `/sys/devices/system/cpu/cpuN/cpuidle`.
+//!
+//! The usage and time counters are cumulative since boot, to see where the
+//! cores spent a period compare two readings.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::cpu::idle::CpuIdle;
+//!
+//! for cpu in CpuIdle::all().unwrap() {
+//!     for state in cpu.states().unwrap() {
+//!         println!(
+//!             "cpu{} {} entered {} times, {:?} total",
+//!             cpu.cpu(), state.name, state.usage, state.time
+//!         );
+//!     }
+//! }
+//! ```
+
+use crate::util::{read_sys_string, read_sys_value, read_sys_opt};
+use super::online_cpu_dirs;
+
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const CPU_PATH: &str = "/sys/devices/system/cpu";
+
+/// The cpuidle directory of a cpu for example
+/// `/sys/devices/system/cpu/cpu0/cpuidle`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpuIdle {
+	cpu: u32,
+	path: PathBuf
+}
+
+impl CpuIdle {
+	/// Returns every online cpu which has idle states.
+	///
+	/// Returns an empty list if there is no cpuidle driver, for example in
+	/// some virtual machines.
+	pub fn all() -> io::Result<Vec<Self>> {
+		online_cpu_dirs(Path::new(CPU_PATH), "cpuidle").map(|l| {
+			l.into_iter()
+				.map(|(cpu, path)| Self { cpu, path })
+				.collect()
+		})
+	}
+
+	/// Returns the cpuidle directory of a cpu, `None` if the cpu does not
+	/// exist, is offline or has no idle states.
+	pub fn from_cpu(cpu: u32) -> Option<Self> {
+		let path = Path::new(CPU_PATH)
+			.join(format!("cpu{}", cpu))
+			.join("cpuidle");
+		path.is_dir().then(|| Self { cpu, path })
+	}
+
+	/// The number of the cpu.
+	pub fn cpu(&self) -> u32 {
+		self.cpu
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// Reads every idle state, sorted from the shallowest to the deepest.
+	pub fn states(&self) -> io::Result<Vec<IdleState>> {
+		let mut list = vec![];
+		for entry in fs::read_dir(&self.path)? {
+			let entry = entry?;
+			// the states are named state0, state1 ...
+			let index = match entry.file_name().to_str()
+				.and_then(|n| n.strip_prefix("state"))
+				.and_then(|n| n.parse().ok())
+			{
+				Some(i) => i,
+				None => continue
+			};
+			list.push(IdleState::read(index, &entry.path())?);
+		}
+		list.sort_by_key(|s| s.index);
+		Ok(list)
+	}
+}
+
+/// An idle state of a cpu for example
+/// `/sys/devices/system/cpu/cpu0/cpuidle/state2`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct IdleState {
+	/// The number of the state, higher states are deeper.
+	pub index: u32,
+	/// The name for example `POLL`, `C1E` or `C6`.
+	pub name: String,
+	/// The description for example `MWAIT 0x20`.
+	pub description: String,
+	/// How many times the state was entered.
+	pub usage: u64,
+	/// The total time spent in this state.
+	pub time: Duration,
+	/// The time needed to leave this state.
+	pub latency: Duration,
+	/// The minimum time which should be spent in this state to save
+	/// energy.
+	pub target_residency: Duration,
+	/// If the state was disabled, for example by writing to `disable`.
+	pub disabled: bool,
+	/// How many times the cpu woke up earlier than the target residency,
+	/// so a shallower state would have been better.
+	pub above: Option<u64>,
+	/// How many times the cpu slept longer than the target residency of a
+	/// deeper state, so a deeper state would have been better.
+	pub below: Option<u64>
+}
+
+impl IdleState {
+	fn read(index: u32, path: &Path) -> io::Result<Self> {
+		let micros = |name: &str| -> io::Result<Duration> {
+			read_sys_value(path.join(name)).map(Duration::from_micros)
+		};
+
+		Ok(Self {
+			index,
+			name: read_sys_string(path.join("name"))?,
+			description: read_sys_string(path.join("desc"))?,
+			usage: read_sys_value(path.join("usage"))?,
+			time: micros("time")?,
+			latency: micros("latency")?,
+			target_residency: micros("residency")?,
+			disabled: read_sys_opt::<u8>(path.join("disable"))?
+				.map(|d| d != 0)
+				.unwrap_or(false),
+			above: read_sys_opt(path.join("above"))?,
+			below: read_sys_opt(path.join("below"))?
+		})
+	}
+}
+
+/// Returns the cpuidle driver for example `intel_idle` or `acpi_idle`,
+/// `None` if there is no driver.
+pub fn driver() -> io::Result<Option<String>> {
+	read_sys_opt(Path::new(CPU_PATH).join("cpuidle/current_driver"))
+		.map(|d: Option<String>| d.filter(|d| d != "none"))
+}
+
+/// Returns the cpuidle governor which selects the states for example
+/// `menu` or `teo`.
+pub fn governor() -> io::Result<Option<String>> {
+	read_sys_opt(Path::new(CPU_PATH).join("cpuidle/current_governor_ro"))
+}
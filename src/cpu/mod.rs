@@ -0,0 +1,663 @@
+//!
+//! The data is retrieved from `/proc/cpuinfo`
+//!
+//! ```
+//! use linux_info::cpu::Cpu;
+//! let info = Cpu::read().unwrap();
+//! let model_name = info.first_value("model name").unwrap();
+//! // or every model name
+//! let model_names = info.unique_values("model name");
+//! ```
+//!
+//! To list all availabe key's [linuxwiki.org](https://linuxwiki.org/proc/cpuinfo). Or you can use the api
+//! ```
+//! use linux_info::cpu::Cpu;
+//! let info = Cpu::read().expect("no cpu info");
+//!	let first = info.first().expect("no cpu found");
+//! let keys = first.keys();
+//! ```
+//!
+//! The frequency scaling state of every cpu is available in [`freq`]
+//! (including [`boost`] to switch turbo on and off), the idle states and
+//! how long they were used in [`idle`], the cache hierarchy in [`cache`],
+//! which cpus are online in [`hotplug`] and the state of the mitigations
+//! against cpu vulnerabilities in [`vulnerabilities`]. Arm cpus which have
+//! no `model name` can be decoded with [`arm`] and the flags of every cpu
+//! are parsed into [`CpuFlag`].
+//!
+//! On x86_64 [`cpuid`] queries the cpu directly, which also works if
+//! `/proc/cpuinfo` is not available.
+//!
+//! To size a thread pool in a container use
+//! [`available_parallelism_cgroup_aware`], which considers the cpuset and
+//! the cpu quota of the cgroup.
+//!
+//! [`freq`]: crate::cpu::freq
+//! [`boost`]: crate::cpu::boost
+//! [`idle`]: crate::cpu::idle
+//! [`cache`]: crate::cpu::cache
+//! [`hotplug`]: crate::cpu::hotplug
+//! [`vulnerabilities`]: crate::cpu::vulnerabilities
+//! [`arm`]: crate::cpu::arm
+//! [`CpuFlag`]: crate::cpu::CpuFlag
+//! [`cpuid`]: crate::cpu::cpuid
+//! [`available_parallelism_cgroup_aware`]:
+//! crate::cpu::available_parallelism_cgroup_aware
+
+pub mod arm;
+pub mod cache;
+#[cfg(target_arch = "x86_64")]
+pub mod cpuid;
+pub mod flags;
+pub mod freq;
+pub mod hotplug;
+pub mod idle;
+pub mod vulnerabilities;
+
+use crate::util::{
+	read_to_string, read_to_string_mut, read_sys_value, parse_cpu_list
+};
+use crate::system::{cgroup_dir, online_cpus};
+#[cfg(feature = "async")]
+use crate::util::{read_to_string_async, read_to_string_mut_async};
+
+use std::path::{Path, PathBuf};
+use std::{fmt, io};
+use std::borrow::Cow;
+
+use hotplug::CpuList;
+
+pub use flags::CpuFlag;
+pub use freq::{boost, set_boost, Boost, BoostControl};
+
+#[cfg(feature = "serde")]
+use serde1::ser::{Serialize, Serializer};
+
+/// Read cpu information from /proc/cpuinfo.
+///
+/// The position of every value is indexed when reading, so looking up a
+/// value does not scan the text again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cpu {
+	raw: String,
+	index: Index
+}
+
+impl Cpu {
+
+	fn path() -> &'static Path {
+		Path::new("/proc/cpuinfo")
+	}
+
+	fn from_raw(raw: String) -> Self {
+		let mut index = Index::default();
+		index.build(&raw);
+		Self { raw, index }
+	}
+
+	#[cfg(test)]
+	fn from_string(raw: String) -> Self {
+		Self::from_raw(raw)
+	}
+
+	/// Reads cpu infos from /proc/cpuinfo.
+	pub fn read() -> io::Result<Self> {
+		read_to_string(Self::path()).map(Self::from_raw)
+	}
+
+	/// Reloads information without allocating.
+	pub fn reload(&mut self) -> io::Result<()> {
+		read_to_string_mut(Self::path(), &mut self.raw)?;
+		self.index.build(&self.raw);
+		Ok(())
+	}
+
+	/// Reads the file without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		read_to_string_async(Self::path()).await.map(Self::from_raw)
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(Self::path(), &mut self.raw).await?;
+		self.index.build(&self.raw);
+		Ok(())
+	}
+
+	/// Main method to get cpu infos. Returns every entry.
+	pub fn entries<'a>(&'a self) -> impl Iterator<Item=CpuEntry<'a>> {
+		(0..self.index.entries.len())
+			.map(move |nth| CpuEntry { cpu: self, nth })
+	}
+
+	/// Returns the first entry.
+	pub fn first<'a>(&'a self) -> Option<CpuEntry<'a>> {
+		self.entries().next()
+	}
+
+	/// Returns the value of the first.
+	pub fn first_value<'a>(&'a self, key: &str) -> Option<&'a str> {
+		self.first()
+			.and_then(|i| i.value(key))
+	}
+
+	/// Returns the unique values to a specific key.
+	pub fn unique_values<'a>(&'a self, key: &str) -> Vec<&'a str> {
+		let mut list = vec![];
+		let key = match self.index.key(&self.raw, key) {
+			Some(k) => k,
+			None => return list
+		};
+		for nth in 0..self.index.entries.len() {
+			if let Some(v) = self.value_at(nth, key) {
+				if !list.contains(&v) {
+					list.push(v);
+				}
+			}
+		}
+		list
+	}
+
+	fn value_at(&self, nth: usize, key: usize) -> Option<&str> {
+		self.index.value(nth, key)
+			.map(|(start, end)| &self.raw[start..end])
+	}
+
+	/// Returns the amount of cores.
+	pub fn cores(&self) -> usize {
+		self.entries().count()
+	}
+
+}
+
+#[derive(Clone)]
+pub struct CpuEntry<'a> {
+	cpu: &'a Cpu,
+	nth: usize
+}
+
+impl<'a> CpuEntry<'a> {
+
+	fn raw(&self) -> &'a str {
+		let (start, end) = self.cpu.index.entries[self.nth];
+		&self.cpu.raw[start..end]
+	}
+
+	/// returns every key and valu ein the cpu info
+	pub fn values(&self) -> impl Iterator<Item=Option<(&'a str, &'a str)>> {
+		self.raw().split('\n')
+			.map(|line| {
+				// TODO: after 1.52 update tot split_once
+				let mut iter = line.splitn(2, ':');
+				let (key, value) = (iter.next()?, iter.next()?);
+				Some((key.trim(), value.trim()))
+			})
+	}
+
+	/// get a value to it's corresponding key
+	pub fn value(&self, key: &str) -> Option<&'a str> {
+		let cpu = self.cpu;
+		cpu.index.key(&cpu.raw, key)
+			.and_then(|key| cpu.value_at(self.nth, key))
+	}
+
+	/// list all available keys
+	pub fn keys(&self) -> impl Iterator<Item=&'a str> {
+		self.values()
+			.filter_map(|kv| kv)
+			.map(|(k, _)| k)
+	}
+
+	/// The model name, on arm the decoded vendor and core name for example
+	/// `ARM Cortex-A72`.
+	pub fn model_name(&self) -> Option<Cow<'a, str>> {
+		if let Some(name) = self.value("model name") {
+			return Some(Cow::Borrowed(name))
+		}
+		let arm = self.arm()?;
+		match (arm.vendor(), arm.core_name()) {
+			(Some(v), Some(c)) => Some(format!("{} {}", v, c).into()),
+			(Some(v), None) => Some(format!("{} 0x{:03x}", v, arm.part).into()),
+			_ => None
+		}
+	}
+
+	/// Returns the implementer and part codes of an arm cpu.
+	pub fn arm(&self) -> Option<arm::ArmCpu> {
+		arm::ArmCpu::from_entry(self)
+	}
+
+	/// The features the cpu supports, `flags` on x86 and `Features` on arm.
+	pub fn flags(&self) -> impl Iterator<Item=CpuFlag> + 'a {
+		self.raw_flags().map(CpuFlag::from)
+	}
+
+	/// The names of the features as written in `/proc/cpuinfo`.
+	pub fn raw_flags(&self) -> impl Iterator<Item=&'a str> {
+		self.value("flags")
+			.or_else(|| self.value("Features"))
+			.unwrap_or("")
+			.split_whitespace()
+	}
+
+	/// Returns true if the cpu supports a feature, accepts a [`CpuFlag`] or
+	/// a name like `avx2`.
+	pub fn has_flag(&self, flag: impl Into<CpuFlag>) -> bool {
+		let flag = flag.into();
+		self.raw_flags().any(|f| f == flag.name())
+	}
+
+	/// Copies every key and value so the entry no longer borrows from
+	/// [`Cpu`].
+	pub fn to_owned_entry(&self) -> OwnedCpuEntry {
+		OwnedCpuEntry {
+			values: self.values()
+				.flatten()
+				.map(|(k, v)| (k.to_string(), v.to_string()))
+				.collect()
+		}
+	}
+
+}
+
+impl fmt::Debug for CpuEntry<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("CpuEntry")
+			.field("raw", &self.raw())
+			.finish()
+	}
+}
+
+impl PartialEq for CpuEntry<'_> {
+	fn eq(&self, other: &Self) -> bool {
+		self.raw() == other.raw()
+	}
+}
+
+impl Eq for CpuEntry<'_> {}
+
+/// The start and end of a string in the raw text.
+type Span = (usize, usize);
+
+/// The position of every key and value in /proc/cpuinfo.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+struct Index {
+	/// Every key once, in the order they first appear.
+	keys: Vec<Span>,
+	/// Every entry which contains a value.
+	entries: Vec<Span>,
+	/// The value of every key per entry, each entry has `keys.len()`
+	/// values.
+	values: Vec<Option<Span>>
+}
+
+impl Index {
+	/// Indexes the text again, reusing the allocations.
+	fn build(&mut self, raw: &str) {
+		self.keys.clear();
+		self.entries.clear();
+		self.values.clear();
+
+		// collect the keys first so every entry has the same width
+		for (key, _) in fields(raw, (0, raw.len())) {
+			if self.key(raw, &raw[key.0..key.1]).is_none() {
+				self.keys.push(key);
+			}
+		}
+
+		let mut start = 0;
+		for entry in raw.split("\n\n") {
+			let span = (start, start + entry.len());
+			start = span.1 + 2;
+
+			let row = self.values.len();
+			self.values.resize(row + self.keys.len(), None);
+			let mut empty = true;
+			for (key, value) in fields(raw, span) {
+				empty = false;
+				if let Some(k) = self.key(raw, &raw[key.0..key.1]) {
+					// the first value wins like in a linear search
+					self.values[row + k].get_or_insert(value);
+				}
+			}
+
+			// the file ends with an empty line
+			if empty {
+				self.values.truncate(row);
+			} else {
+				self.entries.push(span);
+			}
+		}
+	}
+
+	/// Returns the position of a key, ignoring the case.
+	fn key(&self, raw: &str, key: &str) -> Option<usize> {
+		self.keys.iter()
+			.position(|&(start, end)| raw[start..end].eq_ignore_ascii_case(key))
+	}
+
+	fn value(&self, nth: usize, key: usize) -> Option<Span> {
+		self.values[nth * self.keys.len() + key]
+	}
+}
+
+/// Returns the trimmed key and value of every line in a part of the text.
+fn fields(raw: &str, span: Span) -> impl Iterator<Item=(Span, Span)> + '_ {
+	let trimmed = |s: &str, offset: usize| {
+		let start = offset + s.len() - s.trim_start().len();
+		(start, start + s.trim().len())
+	};
+
+	let mut start = span.0;
+	raw[span.0..span.1].split('\n').filter_map(move |line| {
+		let line_start = start;
+		start += line.len() + 1;
+		let (key, value) = line.split_once(':')?;
+		Some((
+			trimmed(key, line_start),
+			trimmed(value, line_start + key.len() + 1)
+		))
+	})
+}
+
+/// An owned version of [`CpuEntry`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct OwnedCpuEntry {
+	/// every key and value in the order of /proc/cpuinfo
+	pub values: Vec<(String, String)>
+}
+
+impl OwnedCpuEntry {
+	/// get a value to it's corresponding key
+	pub fn value(&self, key: &str) -> Option<&str> {
+		self.values.iter()
+			.find(|(k, _)| k.eq_ignore_ascii_case(key))
+			.map(|(_, v)| v.as_str())
+	}
+
+	/// list all available keys
+	pub fn keys(&self) -> impl Iterator<Item=&str> {
+		self.values.iter()
+			.map(|(k, _)| k.as_str())
+	}
+}
+
+/// Returns how many threads can run in parallel, considering the cpus the
+/// process is allowed to run on (affinity and cpuset) and the cpu quota of
+/// its cgroup.
+///
+/// Unlike [`online_cpus`] this is the right value to size a thread pool in
+/// a container. A quota of a fraction of a cpu is rounded up and at least 1
+/// is returned.
+pub fn available_parallelism_cgroup_aware() -> io::Result<usize> {
+	// the affinity already contains the restrictions of the cpuset
+	let cpus = match allowed_cpus() {
+		Some(c) => c,
+		None => online_cpus()?
+	};
+	let limit = cgroup_cpu_limit()
+		.map(|l| l.ceil() as usize)
+		.unwrap_or(cpus);
+	Ok(cpus.min(limit).max(1))
+}
+
+/// The number of cpus the cpu quota of the cgroup allows, for example `1.5`
+/// for a quota of 150ms every 100ms, `None` if there is no quota.
+///
+/// If a parent cgroup has a lower quota, that one is returned.
+pub fn cgroup_cpu_limit() -> Option<f64> {
+	let raw = read_to_string("/proc/self/cgroup").ok()?;
+	let (dir, v2) = cgroup_dir(&raw, "cpu")?;
+
+	dir.ancestors()
+		.take_while(|d| d.starts_with("/sys/fs/cgroup"))
+		.filter_map(|d| if v2 {
+			parse_cpu_max(&read_to_string(d.join("cpu.max")).ok()?)
+		} else {
+			parse_cfs_quota(
+				&read_to_string(d.join("cpu.cfs_quota_us")).ok()?,
+				&read_to_string(d.join("cpu.cfs_period_us")).ok()?
+			)
+		})
+		.fold(None, |min: Option<f64>, l| Some(min.map_or(l, |m| m.min(l))))
+}
+
+/// The number of cpus the process is allowed to run on.
+fn allowed_cpus() -> Option<usize> {
+	read_to_string("/proc/self/status").ok()?
+		.lines()
+		.find_map(|l| l.strip_prefix("Cpus_allowed_list:"))
+		.and_then(|l| parse_cpu_list(l.trim()))
+		.map(|l| l.len())
+		.filter(|l| *l > 0)
+}
+
+/// Parses `cpu.max` of cgroup v2 which contains the quota and the period
+/// for example `150000 100000` or `max 100000`.
+fn parse_cpu_max(raw: &str) -> Option<f64> {
+	let mut parts = raw.split_whitespace();
+	let quota: f64 = parts.next()?.parse().ok()?;
+	let period: f64 = parts.next().unwrap_or("100000").parse().ok()?;
+	(period > 0.0).then(|| quota / period)
+}
+
+/// Parses `cpu.cfs_quota_us` and `cpu.cfs_period_us` of cgroup v1, no quota
+/// is written as `-1`.
+fn parse_cfs_quota(quota: &str, period: &str) -> Option<f64> {
+	let quota: f64 = quota.trim().parse().ok()?;
+	let period: f64 = period.trim().parse().ok()?;
+	(quota > 0.0 && period > 0.0).then(|| quota / period)
+}
+
+/// Returns every online cpu in `dir` together with its `name` directory,
+/// for example `cpu0/cpufreq`. Cpus without the directory are skipped.
+fn online_cpu_dirs(dir: &Path, name: &str) -> io::Result<Vec<(u32, PathBuf)>> {
+	let online: CpuList = read_sys_value(dir.join("online"))?;
+	Ok(online.iter()
+		.map(|cpu| (cpu, dir.join(format!("cpu{}", cpu)).join(name)))
+		.filter(|(_, path)| path.is_dir())
+		.collect())
+}
+
+/// Serializes every entry.
+#[cfg(feature = "serde")]
+impl Serialize for Cpu {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where S: Serializer {
+		serializer.collect_seq(self.entries())
+	}
+}
+
+/// Serializes the entry as a map of keys and values.
+#[cfg(feature = "serde")]
+impl Serialize for CpuEntry<'_> {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where S: Serializer {
+		serializer.collect_map(self.values().flatten())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn cpu_info() -> Cpu {
+		Cpu::from_string("\
+processor	: 16
+vendor_id	: AuthenticAMD
+cpu family	: 23
+model		: 113
+model name	: AMD Ryzen 9 3900XT 12-Core Processor
+stepping	: 0
+microcode	: 0x8701021
+cpu MHz		: 2196.035
+cache size	: 512 KB
+physical id	: 0
+siblings	: 24
+core id		: 6
+cpu cores	: 12
+apicid		: 13
+initial apicid	: 13
+fpu		: yes
+fpu_exception	: yes
+cpuid level	: 16
+wp		: yes
+flags		: fpu vme de pse tsc msr pae mce cx8 apic sep mtrr pge mca cmov pat pse36 clflush mmx fxsr sse sse2 ht syscall nx mmxext fxsr_opt pdpe1gb rdtscp lm constant_tsc rep_good nopl nonstop_tsc cpuid extd_apicid aperfmperf pni pclmulqdq monitor ssse3 fma cx16 sse4_1 sse4_2 movbe popcnt aes xsave avx f16c rdrand lahf_lm cmp_legacy svm extapic cr8_legacy abm sse4a misalignsse 3dnowprefetch osvw ibs skinit wdt tce topoext perfctr_core perfctr_nb bpext perfctr_llc mwaitx cpb cat_l3 cdp_l3 hw_pstate sme ssbd mba sev ibpb stibp vmmcall fsgsbase bmi1 avx2 smep bmi2 cqm rdt_a rdseed adx smap clflushopt clwb sha_ni xsaveopt xsavec xgetbv1 xsaves cqm_llc cqm_occup_llc cqm_mbm_total cqm_mbm_local clzero irperf xsaveerptr rdpru wbnoinvd arat npt lbrv svm_lock nrip_save tsc_scale vmcb_clean flushbyasid decodeassists pausefilter pfthreshold avic v_vmsave_vmload vgif umip rdpid overflow_recov succor smca
+bugs		: sysret_ss_attrs spectre_v1 spectre_v2 spec_store_bypass
+bogomips	: 7586.59
+TLB size	: 3072 4K pages
+clflush size	: 64
+cache_alignment	: 64
+address sizes	: 43 bits physical, 48 bits virtual
+power management: ts ttp tm hwpstate cpb eff_freq_ro [13] [14]
+
+processor	: 17
+vendor_id	: AuthenticAMD
+cpu family	: 23
+model		: 113
+model name	: AMD Ryzen 9 3900XT 12-Core Processor
+stepping	: 0
+microcode	: 0x8701021
+cpu MHz		: 2196.035
+cache size	: 512 KB
+physical id	: 0
+siblings	: 24
+core id		: 6
+cpu cores	: 12
+apicid		: 13
+initial apicid	: 13
+fpu		: yes
+fpu_exception	: yes
+cpuid level	: 16
+wp		: yes
+flags		: fpu vme de pse tsc msr pae mce cx8 apic sep mtrr pge mca cmov pat pse36 clflush mmx fxsr sse sse2 ht syscall nx mmxext fxsr_opt pdpe1gb rdtscp lm constant_tsc rep_good nopl nonstop_tsc cpuid extd_apicid aperfmperf pni pclmulqdq monitor ssse3 fma cx16 sse4_1 sse4_2 movbe popcnt aes xsave avx f16c rdrand lahf_lm cmp_legacy svm extapic cr8_legacy abm sse4a misalignsse 3dnowprefetch osvw ibs skinit wdt tce topoext perfctr_core perfctr_nb bpext perfctr_llc mwaitx cpb cat_l3 cdp_l3 hw_pstate sme ssbd mba sev ibpb stibp vmmcall fsgsbase bmi1 avx2 smep bmi2 cqm rdt_a rdseed adx smap clflushopt clwb sha_ni xsaveopt xsavec xgetbv1 xsaves cqm_llc cqm_occup_llc cqm_mbm_total cqm_mbm_local clzero irperf xsaveerptr rdpru wbnoinvd arat npt lbrv svm_lock nrip_save tsc_scale vmcb_clean flushbyasid decodeassists pausefilter pfthreshold avic v_vmsave_vmload vgif umip rdpid overflow_recov succor smca
+bugs		: sysret_ss_attrs spectre_v1 spectre_v2 spec_store_bypass
+bogomips	: 7586.59
+TLB size	: 3072 4K pages
+clflush size	: 64
+cache_alignment	: 64
+address sizes	: 43 bits physical, 48 bits virtual
+power management: ts ttp tm hwpstate cpb eff_freq_ro [13] [14]\n\
+		".into())
+	}
+
+	#[test]
+	fn info_to_vec() {
+		let cpu_info = cpu_info();
+		let v: Vec<_> = cpu_info.entries().collect();
+		assert_eq!(v.len(), 2);
+	}
+
+	#[test]
+	fn info_values() {
+		let info = cpu_info();
+		let mut values = info.entries();
+		let first = values.next().unwrap();
+		println!("first {:?}", first.values().collect::<Vec<_>>());
+		let model_name = first.value("model name").unwrap();
+		assert_eq!(model_name, "AMD Ryzen 9 3900XT 12-Core Processor");
+		assert_eq!(first.model_name().unwrap(), model_name);
+		assert!(first.arm().is_none());
+		assert!(first.has_flag(CpuFlag::Avx2));
+		assert!(first.has_flag("sse4a"));
+		assert!(!first.has_flag(CpuFlag::Avx512f));
+		assert!(first.flags().any(|f| f == CpuFlag::Sse4_2));
+	}
+
+	#[test]
+	fn count_cores() {
+		let cpu_info = cpu_info();
+		assert_eq!(cpu_info.cores(), 2);
+	}
+
+	#[test]
+	fn owned_entry() {
+		let cpu_info = cpu_info();
+		let first = cpu_info.first().unwrap();
+		let owned = first.to_owned_entry();
+		assert_eq!(owned.value("model name"), first.value("model name"));
+		assert_eq!(owned.keys().count(), first.keys().count());
+	}
+
+	#[test]
+	fn cpu_quota() {
+		assert_eq!(parse_cpu_max("150000 100000\n"), Some(1.5));
+		assert_eq!(parse_cpu_max("max 100000\n"), None);
+		assert_eq!(parse_cfs_quota("200000\n", "100000\n"), Some(2.0));
+		assert_eq!(parse_cfs_quota("-1\n", "100000\n"), None);
+	}
+
+	#[test]
+	fn key_index() {
+		let info = Cpu::from_string("\
+processor	: 0
+model name	: Cortex-A55
+Features	: fp asimd
+
+processor	: 1
+model name	: Cortex-A76
+CPU part	: 0xd0b
+model name	: duplicate
+
+Hardware	: Generic
+
+".into());
+		assert_eq!(info.cores(), 3);
+		let big = info.entries().nth(1).unwrap();
+		assert_eq!(big.value("Model Name"), Some("Cortex-A76"));
+		assert_eq!(big.value("cpu part"), Some("0xd0b"));
+		assert_eq!(big.value("Features"), None);
+		assert_eq!(info.first().unwrap().value("CPU part"), None);
+		assert_eq!(
+			info.unique_values("model name"),
+			["Cortex-A55", "Cortex-A76"]
+		);
+		assert!(info.unique_values("bogomips").is_empty());
+		assert_eq!(info.entries().last().unwrap().keys().count(), 1);
+	}
+
+	#[test]
+	fn trailing_empty_entry() {
+		let info = Cpu::from_string("\
+processor	: 0
+vendor_id	: GenuineIntel
+
+processor	: 1
+vendor_id	: GenuineIntel
+
+".into());
+		assert_eq!(info.cores(), 2);
+		assert_eq!(info.entries().count(), 2);
+		let last = info.entries().last().unwrap();
+		assert_eq!(last.value("Processor"), Some("1"));
+		assert_eq!(last.value("VENDOR_ID"), Some("GenuineIntel"));
+		assert_eq!(Cpu::from_string(String::new()).cores(), 0);
+	}
+
+	#[test]
+	fn reload_keeps_index() {
+		let mut info = Cpu::read().unwrap();
+		let keys = info.index.keys.capacity();
+		let values = info.index.values.capacity();
+		info.reload().unwrap();
+		assert_eq!(info.index.keys.capacity(), keys);
+		assert_eq!(info.index.values.capacity(), values);
+	}
+
+	#[test]
+	fn unique_values() {
+		let cpu_info = cpu_info();
+		let un = cpu_info.unique_values("model name");
+		assert_eq!(un.len(), 1);
+	}
+
+}
\ No newline at end of file
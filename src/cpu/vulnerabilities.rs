@@ -0,0 +1,269 @@
+//! Read which cpu vulnerabilities (Spectre, Meltdown, MDS ...) affect the
+//! system and how the kernel mitigates them, from
+//! `/sys/devices/system/cpu/vulnerabilities`.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::cpu::vulnerabilities::{vulnerabilities, Status};
+//!
+//! for v in vulnerabilities().unwrap() {
+//!     if v.status == Status::Vulnerable {
+//!         println!("{} is vulnerable: {}", v.vulnerability, v.details);
+//!     }
+//! }
+//! ```
+
+use crate::util::read_sys_string;
+
+use std::{fmt, fs, io};
+
+const VULNERABILITIES_PATH: &str = "/sys/devices/system/cpu/vulnerabilities";
+
+/// Returns the state of every vulnerability the kernel knows about.
+///
+/// Returns an empty list if the kernel is older than 4.15.
+pub fn vulnerabilities() -> io::Result<Vec<VulnerabilityState>> {
+	let dir = match fs::read_dir(VULNERABILITIES_PATH) {
+		Ok(d) => d,
+		Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+		Err(e) => return Err(e)
+	};
+
+	let mut list = vec![];
+	for entry in dir {
+		let entry = entry?;
+		let name = match entry.file_name().into_string() {
+			Ok(n) => n,
+			Err(_) => continue
+		};
+		let details = read_sys_string(entry.path())?;
+		list.push(VulnerabilityState::new(name.as_str().into(), details));
+	}
+	list.sort_by(|a, b| a.vulnerability.name().cmp(b.vulnerability.name()));
+	Ok(list)
+}
+
+/// Returns the state of one vulnerability, `None` if the kernel does not
+/// know it.
+pub fn vulnerability(
+	vulnerability: Vulnerability
+) -> io::Result<Option<VulnerabilityState>> {
+	let path = format!("{}/{}", VULNERABILITIES_PATH, vulnerability.name());
+	match read_sys_string(path) {
+		Ok(d) => Ok(Some(VulnerabilityState::new(vulnerability, d))),
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+		Err(e) => Err(e)
+	}
+}
+
+/// A hardware vulnerability, named like the file in sysfs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub enum Vulnerability {
+	Meltdown,
+	SpectreV1,
+	SpectreV2,
+	/// Spectre variant 4.
+	SpecStoreBypass,
+	/// L1 terminal fault (Foreshadow).
+	L1tf,
+	/// Microarchitectural data sampling (ZombieLoad, RIDL, Fallout).
+	Mds,
+	TsxAsyncAbort,
+	ItlbMultihit,
+	/// Special register buffer data sampling (CrossTalk).
+	Srbds,
+	MmioStaleData,
+	Retbleed,
+	/// AMD Inception.
+	SpecRstackOverflow,
+	/// Intel Downfall.
+	GatherDataSampling,
+	RegFileDataSampling,
+	IndirectTargetSelection,
+	/// A vulnerability added by a newer kernel.
+	Other(String)
+}
+
+impl Vulnerability {
+	/// The name of the file in sysfs for example `spectre_v2`.
+	pub fn name(&self) -> &str {
+		match self {
+			Self::Meltdown => "meltdown",
+			Self::SpectreV1 => "spectre_v1",
+			Self::SpectreV2 => "spectre_v2",
+			Self::SpecStoreBypass => "spec_store_bypass",
+			Self::L1tf => "l1tf",
+			Self::Mds => "mds",
+			Self::TsxAsyncAbort => "tsx_async_abort",
+			Self::ItlbMultihit => "itlb_multihit",
+			Self::Srbds => "srbds",
+			Self::MmioStaleData => "mmio_stale_data",
+			Self::Retbleed => "retbleed",
+			Self::SpecRstackOverflow => "spec_rstack_overflow",
+			Self::GatherDataSampling => "gather_data_sampling",
+			Self::RegFileDataSampling => "reg_file_data_sampling",
+			Self::IndirectTargetSelection => "indirect_target_selection",
+			Self::Other(o) => o
+		}
+	}
+}
+
+impl From<&str> for Vulnerability {
+	fn from(s: &str) -> Self {
+		match s {
+			"meltdown" => Self::Meltdown,
+			"spectre_v1" => Self::SpectreV1,
+			"spectre_v2" => Self::SpectreV2,
+			"spec_store_bypass" => Self::SpecStoreBypass,
+			"l1tf" => Self::L1tf,
+			"mds" => Self::Mds,
+			"tsx_async_abort" => Self::TsxAsyncAbort,
+			"itlb_multihit" => Self::ItlbMultihit,
+			"srbds" => Self::Srbds,
+			"mmio_stale_data" => Self::MmioStaleData,
+			"retbleed" => Self::Retbleed,
+			"spec_rstack_overflow" => Self::SpecRstackOverflow,
+			"gather_data_sampling" => Self::GatherDataSampling,
+			"reg_file_data_sampling" => Self::RegFileDataSampling,
+			"indirect_target_selection" => Self::IndirectTargetSelection,
+			o => Self::Other(o.into())
+		}
+	}
+}
+
+impl fmt::Display for Vulnerability {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+/// If the system is affected by a vulnerability.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub enum Status {
+	NotAffected,
+	Mitigated,
+	Vulnerable,
+	/// The kernel cannot tell, for example in a virtual machine which hides
+	/// the cpu bugs.
+	Unknown
+}
+
+impl From<&str> for Status {
+	fn from(s: &str) -> Self {
+		// itlb_multihit prefixes the state with `KVM: `
+		let s = s.strip_prefix("KVM: ").unwrap_or(s);
+		if s.starts_with("Not affected") {
+			Self::NotAffected
+		} else if s.starts_with("Mitigation") {
+			Self::Mitigated
+		} else if s.starts_with("Vulnerable") ||
+			s.starts_with("Processor vulnerable")
+		{
+			Self::Vulnerable
+		} else {
+			Self::Unknown
+		}
+	}
+}
+
+/// The state of a vulnerability.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct VulnerabilityState {
+	pub vulnerability: Vulnerability,
+	pub status: Status,
+	/// The line the kernel reports for example
+	/// `Mitigation: Retpolines; IBPB: conditional; STIBP: disabled`.
+	pub details: String
+}
+
+impl VulnerabilityState {
+	fn new(vulnerability: Vulnerability, details: String) -> Self {
+		Self {
+			vulnerability,
+			status: details.as_str().into(),
+			details
+		}
+	}
+
+	/// The mitigation the kernel uses for example `PTI`, `None` if the
+	/// vulnerability is not mitigated.
+	pub fn mitigation(&self) -> Option<&str> {
+		let s = self.details.strip_prefix("KVM: ").unwrap_or(&self.details);
+		s.strip_prefix("Mitigation: ")
+	}
+
+	/// Returns true if a mitigation is active but part of it is reported as
+	/// vulnerable, for example `...; BHI: Vulnerable` for `spectre_v2` or
+	/// `SMT vulnerable` for `mds`.
+	pub fn is_partially_vulnerable(&self) -> bool {
+		self.status == Status::Mitigated &&
+		self.mitigation()
+			.map(|m| m.contains("Vulnerable") || m.contains("vulnerable"))
+			.unwrap_or(false)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn state(name: &str, details: &str) -> VulnerabilityState {
+		VulnerabilityState::new(name.into(), details.into())
+	}
+
+	#[test]
+	fn status() {
+		let meltdown = state("meltdown", "Mitigation: PTI");
+		assert_eq!(meltdown.vulnerability, Vulnerability::Meltdown);
+		assert_eq!(meltdown.status, Status::Mitigated);
+		assert_eq!(meltdown.mitigation(), Some("PTI"));
+		assert!(!meltdown.is_partially_vulnerable());
+
+		let v2 = state(
+			"spectre_v2",
+			"Mitigation: Enhanced / Automatic IBRS; IBPB: conditional; \
+			PBRSB-eIBRS: SW sequence; BHI: Vulnerable"
+		);
+		assert_eq!(v2.status, Status::Mitigated);
+		assert!(v2.is_partially_vulnerable());
+
+		let mds = state(
+			"mds",
+			"Vulnerable: Clear CPU buffers attempted, no microcode; SMT \
+			vulnerable"
+		);
+		assert_eq!(mds.status, Status::Vulnerable);
+		assert_eq!(mds.mitigation(), None);
+
+		let itlb = state("itlb_multihit", "KVM: Mitigation: VMX disabled");
+		assert_eq!(itlb.status, Status::Mitigated);
+		assert_eq!(itlb.mitigation(), Some("VMX disabled"));
+
+		let new = state("ghostwrite", "Not affected");
+		assert_eq!(
+			new.vulnerability,
+			Vulnerability::Other("ghostwrite".into())
+		);
+		assert_eq!(new.status, Status::NotAffected);
+
+		assert_eq!(
+			Status::from("Unknown: Dependent on hypervisor status"),
+			Status::Unknown
+		);
+	}
+}
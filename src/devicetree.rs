@@ -0,0 +1,134 @@
+//! Identify boards (for example a Raspberry Pi) from the device tree, the way
+//! dmi identifies x86 machines.
+//!
+//! Reads `/sys/firmware/devicetree/base` and falls back to `/proc/device-tree`.
+
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+
+const PATHS: &[&str] = &[
+	"/sys/firmware/devicetree/base",
+	"/proc/device-tree"
+];
+
+/// The root node of the device tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceTree {
+	model: Option<String>,
+	compatible: Option<String>,
+	serial_number: Option<String>
+}
+
+impl DeviceTree {
+	fn path() -> Option<&'static Path> {
+		PATHS.iter()
+			.map(Path::new)
+			.find(|p| p.is_dir())
+	}
+
+	#[cfg(test)]
+	fn from_strings(
+		model: Option<&str>,
+		compatible: Option<&str>,
+		serial_number: Option<&str>
+	) -> Self {
+		Self {
+			model: model.map(Into::into),
+			compatible: compatible.map(Into::into),
+			serial_number: serial_number.map(Into::into)
+		}
+	}
+
+	/// Reads the root node of the device tree.
+	///
+	/// Returns `None` if the system has no device tree (for example on x86).
+	pub fn read() -> io::Result<Option<Self>> {
+		let path = match Self::path() {
+			Some(p) => p,
+			None => return Ok(None)
+		};
+
+		Ok(Some(Self {
+			model: read_opt(path.join("model"))?,
+			compatible: read_opt(path.join("compatible"))?,
+			serial_number: read_opt(path.join("serial-number"))?
+		}))
+	}
+
+	/// The model of the board for example `Raspberry Pi 4 Model B Rev 1.4`.
+	pub fn model(&self) -> Option<&str> {
+		self.model.as_deref()
+			.and_then(|m| strings(m).next())
+	}
+
+	/// The compatible strings from the most to the least specific for
+	/// example `raspberrypi,4-model-b` and `brcm,bcm2711`.
+	pub fn compatible(&self) -> impl Iterator<Item=&str> {
+		self.compatible.as_deref()
+			.into_iter()
+			.flat_map(strings)
+	}
+
+	/// Returns true if the board is compatible with the given string.
+	pub fn is_compatible(&self, s: &str) -> bool {
+		self.compatible().any(|c| c == s)
+	}
+
+	/// The vendor of the board for example `raspberrypi`, which is the prefix
+	/// of the most specific compatible string.
+	pub fn vendor(&self) -> Option<&str> {
+		self.compatible().next()?
+			.split_once(',')
+			.map(|(v, _)| v)
+	}
+
+	/// The serial number of the board if the firmware provides one.
+	pub fn serial_number(&self) -> Option<&str> {
+		self.serial_number.as_deref()
+			.and_then(|s| strings(s).next())
+	}
+}
+
+/// Device tree strings are nul terminated and string lists are nul
+/// separated.
+fn strings(s: &str) -> impl Iterator<Item=&str> {
+	s.split('\0')
+		.map(str::trim)
+		.filter(|s| !s.is_empty())
+}
+
+fn read_opt(path: PathBuf) -> io::Result<Option<String>> {
+	match fs::read(path) {
+		Ok(v) => Ok(Some(String::from_utf8_lossy(&v).into_owned())),
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+		Err(e) => Err(e)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn raspberry_pi() {
+		let dt = DeviceTree::from_strings(
+			Some("Raspberry Pi 4 Model B Rev 1.4\0"),
+			Some("raspberrypi,4-model-b\0brcm,bcm2711\0"),
+			Some("10000000abcdef01\0")
+		);
+		assert_eq!(dt.model(), Some("Raspberry Pi 4 Model B Rev 1.4"));
+		let compatible: Vec<_> = dt.compatible().collect();
+		assert_eq!(compatible, ["raspberrypi,4-model-b", "brcm,bcm2711"]);
+		assert!(dt.is_compatible("brcm,bcm2711"));
+		assert_eq!(dt.vendor(), Some("raspberrypi"));
+		assert_eq!(dt.serial_number(), Some("10000000abcdef01"));
+	}
+
+	#[test]
+	fn empty() {
+		let dt = DeviceTree::from_strings(None, None, None);
+		assert_eq!(dt.model(), None);
+		assert_eq!(dt.compatible().count(), 0);
+		assert_eq!(dt.vendor(), None);
+	}
+}
@@ -0,0 +1,114 @@
+//! Compare two readings and get what changed between them.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::memory::Memory;
+//! use std::{thread, time::Duration};
+//!
+//! let before = Memory::read().unwrap();
+//! thread::sleep(Duration::from_secs(1));
+//! let after = Memory::read().unwrap();
+//!
+//! for change in before.diff(&after) {
+//!     println!("{}: {:?} -> {:?}", change.key, change.old, change.new);
+//! }
+//! ```
+
+/// A value which was added, removed or changed.
+///
+/// `old` is `None` if the value was added, `new` is `None` if it was
+/// removed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct Change<K, V> {
+	pub key: K,
+	pub old: Option<V>,
+	pub new: Option<V>
+}
+
+impl<K, V> Change<K, V> {
+	pub fn kind(&self) -> ChangeKind {
+		match (&self.old, &self.new) {
+			(None, _) => ChangeKind::Added,
+			(_, None) => ChangeKind::Removed,
+			_ => ChangeKind::Changed
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", rename_all = "snake_case")
+)]
+pub enum ChangeKind {
+	Added,
+	Removed,
+	Changed
+}
+
+/// Compares two lists of key value pairs.
+///
+/// The changes are returned in the order of `new`, followed by the removed
+/// values.
+pub(crate) fn diff_by_key<K, V>(
+	old: impl IntoIterator<Item=(K, V)>,
+	new: impl IntoIterator<Item=(K, V)>
+) -> Vec<Change<K, V>>
+where
+	K: PartialEq,
+	V: PartialEq
+{
+	let mut old: Vec<_> = old.into_iter().map(Some).collect();
+	let mut changes = vec![];
+
+	for (key, value) in new {
+		let prev = old.iter_mut()
+			.find(|o| matches!(o, Some((k, _)) if *k == key))
+			.and_then(Option::take);
+
+		match prev {
+			Some((_, prev)) if prev == value => {},
+			prev => changes.push(Change {
+				key,
+				old: prev.map(|(_, v)| v),
+				new: Some(value)
+			})
+		}
+	}
+
+	changes.extend(old.into_iter().flatten().map(|(key, value)| Change {
+		key,
+		old: Some(value),
+		new: None
+	}));
+
+	changes
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn by_key() {
+		let changes = diff_by_key(
+			vec![("a", 1), ("b", 2), ("c", 3)],
+			vec![("d", 4), ("b", 2), ("a", 5)]
+		);
+		assert_eq!(changes, vec![
+			Change { key: "d", old: None, new: Some(4) },
+			Change { key: "a", old: Some(1), new: Some(5) },
+			Change { key: "c", old: Some(3), new: None }
+		]);
+		let kinds: Vec<_> = changes.iter().map(Change::kind).collect();
+		assert_eq!(kinds, [
+			ChangeKind::Added, ChangeKind::Changed, ChangeKind::Removed
+		]);
+	}
+}
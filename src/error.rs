@@ -0,0 +1,313 @@
+//! The error type of this crate.
+//!
+//! Most functions return an [`io::Error`] so existing code keeps working.
+//! Parse errors and missing fields carry an [`Error`] with the path and the
+//! reason, which can be recovered with `Error::from(io_error)`.
+//!
+//! ```
+//! use linux_info::Error;
+//! use linux_info::system::Uptime;
+//!
+//! if let Err(e) = Uptime::read() {
+//!     let e = Error::from(e);
+//!     eprintln!("reading {:?} failed: {}", e.path(), e);
+//! }
+//! ```
+//!
+//! Errors returned by the os are wrapped as well, so
+//! [`io::Error::kind`] keeps working but the error code has to be read with
+//! [`Error::raw_os_error_of`].
+//!
+//! ```
+//! use linux_info::Error;
+//! use linux_info::cpu::Cpu;
+//!
+//! match Cpu::read() {
+//!     Ok(cpu) => println!("{} cores", cpu.cores()),
+//!     Err(e) if Error::raw_os_error_of(&e) == Some(libc::EACCES) => {
+//!         eprintln!("permission denied");
+//!     },
+//!     Err(e) => eprintln!("{}", e)
+//! }
+//! ```
+
+use std::{io, fmt};
+use std::error::Error as StdError;
+use std::path::{Path, PathBuf};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+	/// Reading or writing a file failed.
+	Io {
+		path: Option<PathBuf>,
+		source: io::Error
+	},
+	/// A value could not be parsed.
+	Parse {
+		path: Option<PathBuf>,
+		/// The line starting at 1.
+		line: Option<usize>,
+		field: Option<String>,
+		message: String
+	},
+	/// A required field is missing.
+	MissingField {
+		path: Option<PathBuf>,
+		field: String
+	},
+	#[cfg(feature = "dbus")]
+	#[cfg_attr(docsrs, doc(cfg(any(
+		feature = "network-manager",
+		feature = "modem-manager",
+		feature = "network",
+		feature = "upower"
+	))))]
+	Dbus(dbus::Error),
+	#[cfg(feature = "zbus")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "zbus")))]
+	Zbus(zbus1::Error)
+}
+
+impl Error {
+	pub(crate) fn io(path: impl AsRef<Path>, source: io::Error) -> Self {
+		Self::Io {
+			path: Some(path.as_ref().into()),
+			source
+		}
+	}
+
+	pub(crate) fn parse(
+		path: impl AsRef<Path>,
+		line: Option<usize>,
+		message: impl Into<String>
+	) -> Self {
+		Self::Parse {
+			path: Some(path.as_ref().into()),
+			line,
+			field: None,
+			message: message.into()
+		}
+	}
+
+	pub(crate) fn parse_field(
+		path: impl AsRef<Path>,
+		line: Option<usize>,
+		field: impl Into<String>,
+		message: impl Into<String>
+	) -> Self {
+		Self::Parse {
+			path: Some(path.as_ref().into()),
+			line,
+			field: Some(field.into()),
+			message: message.into()
+		}
+	}
+
+	pub(crate) fn missing_field(
+		path: impl AsRef<Path>,
+		field: impl Into<String>
+	) -> Self {
+		Self::MissingField {
+			path: Some(path.as_ref().into()),
+			field: field.into()
+		}
+	}
+
+	/// The file which caused the error.
+	pub fn path(&self) -> Option<&Path> {
+		match self {
+			Self::Io { path, .. } |
+			Self::Parse { path, .. } |
+			Self::MissingField { path, .. } => path.as_deref(),
+			#[cfg(feature = "dbus")]
+			Self::Dbus(_) => None,
+			#[cfg(feature = "zbus")]
+			Self::Zbus(_) => None
+		}
+	}
+
+	/// The matching [`io::ErrorKind`].
+	pub fn kind(&self) -> io::ErrorKind {
+		match self {
+			Self::Io { source, .. } => source.kind(),
+			Self::Parse { .. } |
+			Self::MissingField { .. } => io::ErrorKind::InvalidData,
+			#[cfg(feature = "dbus")]
+			Self::Dbus(_) => io::ErrorKind::Other,
+			#[cfg(feature = "zbus")]
+			Self::Zbus(_) => io::ErrorKind::Other
+		}
+	}
+
+	/// The os error code if the error was returned by the os.
+	pub fn raw_os_error(&self) -> Option<i32> {
+		match self {
+			Self::Io { source, .. } => source.raw_os_error(),
+			_ => None
+		}
+	}
+
+	/// Returns the os error code of an io error, even if it was wrapped by
+	/// this crate.
+	pub fn raw_os_error_of(e: &io::Error) -> Option<i32> {
+		e.raw_os_error().or_else(|| {
+			e.get_ref()?
+				.downcast_ref::<Self>()?
+				.raw_os_error()
+		})
+	}
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Io { path: Some(path), source } => {
+				write!(f, "{}: {}", path.display(), source)
+			},
+			Self::Io { path: None, source } => fmt::Display::fmt(source, f),
+			Self::Parse { path, line, field, message } => {
+				if let Some(path) = path {
+					write!(f, "{}:", path.display())?;
+				}
+				if let Some(line) = line {
+					write!(f, "{}:", line)?;
+				}
+				if path.is_some() || line.is_some() {
+					f.write_str(" ")?;
+				}
+				match field {
+					Some(field) => {
+						write!(f, "failed to parse {} from {}", field, message)
+					},
+					None => write!(f, "failed to parse {}", message)
+				}
+			},
+			Self::MissingField { path: Some(path), field } => {
+				write!(f, "{}: missing field {}", path.display(), field)
+			},
+			Self::MissingField { path: None, field } => {
+				write!(f, "missing field {}", field)
+			},
+			#[cfg(feature = "dbus")]
+			Self::Dbus(e) => write!(f, "dbus: {}", e),
+			#[cfg(feature = "zbus")]
+			Self::Zbus(e) => write!(f, "dbus: {}", e)
+		}
+	}
+}
+
+impl StdError for Error {
+	fn source(&self) -> Option<&(dyn StdError + 'static)> {
+		match self {
+			Self::Io { source, .. } => Some(source),
+			#[cfg(feature = "dbus")]
+			Self::Dbus(e) => Some(e),
+			#[cfg(feature = "zbus")]
+			Self::Zbus(e) => Some(e),
+			_ => None
+		}
+	}
+}
+
+/// Recovers the [`Error`] if the io error was created by this crate.
+impl From<io::Error> for Error {
+	fn from(e: io::Error) -> Self {
+		let is_ours = e.get_ref()
+			.map(|i| i.is::<Self>())
+			.unwrap_or(false);
+
+		if is_ours {
+			// is_ours guarantees both unwraps succeed
+			*e.into_inner().unwrap().downcast::<Self>().unwrap()
+		} else {
+			Self::Io { path: None, source: e }
+		}
+	}
+}
+
+/// Keeps the [`io::ErrorKind`], the [`Error`] can be recovered with
+/// `Error::from(io_error)` or [`io::Error::get_ref`].
+impl From<Error> for io::Error {
+	fn from(e: Error) -> Self {
+		match e {
+			Error::Io { path: None, source } => source,
+			e => io::Error::new(e.kind(), e)
+		}
+	}
+}
+
+#[cfg(feature = "dbus")]
+impl From<dbus::Error> for Error {
+	fn from(e: dbus::Error) -> Self {
+		Self::Dbus(e)
+	}
+}
+
+#[cfg(feature = "zbus")]
+impl From<zbus1::Error> for Error {
+	fn from(e: zbus1::Error) -> Self {
+		Self::Zbus(e)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn roundtrip() {
+		let e = Error::io(
+			"/proc/stat",
+			io::Error::from_raw_os_error(libc::EACCES)
+		);
+		let io_e: io::Error = e.into();
+		assert_eq!(io_e.kind(), io::ErrorKind::PermissionDenied);
+		assert_eq!(Error::raw_os_error_of(&io_e), Some(libc::EACCES));
+		assert!(io_e.to_string().starts_with("/proc/stat: "));
+		let inner = io_e.get_ref().unwrap().downcast_ref::<Error>().unwrap();
+		assert_eq!(inner.path(), Some(Path::new("/proc/stat")));
+		assert!(io_e.get_ref().unwrap().source().is_some());
+
+		let e = Error::io(
+			"/proc/stat",
+			io::Error::new(io::ErrorKind::UnexpectedEof, "short read")
+		);
+		let io_e: io::Error = e.into();
+		assert_eq!(io_e.kind(), io::ErrorKind::UnexpectedEof);
+		assert!(io_e.to_string().starts_with("/proc/stat: "));
+
+		let e = Error::from(io_e);
+		assert_eq!(e.path(), Some(Path::new("/proc/stat")));
+	}
+
+	#[test]
+	fn parse() {
+		let e = Error::parse("/sys/class/net/lo/mtu", Some(1), "\"abc\"");
+		assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+		assert_eq!(
+			e.to_string(),
+			"/sys/class/net/lo/mtu:1: failed to parse \"abc\""
+		);
+
+		let e = Error::parse_field("/proc/loadavg", Some(1), "load1", "\"x\"");
+		assert_eq!(
+			e.to_string(),
+			"/proc/loadavg:1: failed to parse load1 from \"x\""
+		);
+
+		let e = Error::missing_field("/proc/meminfo", "MemTotal");
+		assert_eq!(e.to_string(), "/proc/meminfo: missing field MemTotal");
+	}
+
+	#[test]
+	fn plain_io() {
+		let e = Error::from(io::Error::from(io::ErrorKind::NotFound));
+		assert_eq!(e.path(), None);
+		let io_e: io::Error = e.into();
+		assert_eq!(io_e.kind(), io::ErrorKind::NotFound);
+		assert!(io_e.get_ref().is_none());
+	}
+}
@@ -0,0 +1,277 @@
+//! Read the inotify and fanotify limits from `/proc/sys/fs` and how many
+//! watches every user currently uses.
+//!
+//! When a user reaches `max_user_watches` new watches fail with `ENOSPC`
+//! ("no space left on device"), which file watchers often report in a
+//! confusing way. The usage is counted by scanning `/proc/[pid]/fdinfo`, so
+//! watches of processes owned by other users are only visible as root.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::fsnotify::{InotifyLimits, usage_by_user};
+//!
+//! let limits = InotifyLimits::read().unwrap();
+//! for user in usage_by_user().unwrap() {
+//!     println!(
+//!         "uid {} uses {} of {} watches",
+//!         user.uid, user.inotify_watches, limits.max_user_watches
+//!     );
+//! }
+//! ```
+
+use crate::util::read_sys_value;
+use crate::process::Process;
+
+use std::{fs, io};
+use std::path::Path;
+use std::collections::BTreeMap;
+
+const INOTIFY_PATH: &str = "/proc/sys/fs/inotify";
+const FANOTIFY_PATH: &str = "/proc/sys/fs/fanotify";
+
+/// The inotify limits, they apply per user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct InotifyLimits {
+	/// How many watches a user can create.
+	pub max_user_watches: u64,
+	/// How many inotify instances a user can create.
+	pub max_user_instances: u64,
+	/// How many events can be queued per instance before they get dropped.
+	pub max_queued_events: u64
+}
+
+impl InotifyLimits {
+	pub fn read() -> io::Result<Self> {
+		let path = Path::new(INOTIFY_PATH);
+		Ok(Self {
+			max_user_watches: read_sys_value(path.join("max_user_watches"))?,
+			max_user_instances: read_sys_value(
+				path.join("max_user_instances")
+			)?,
+			max_queued_events: read_sys_value(path.join("max_queued_events"))?
+		})
+	}
+}
+
+/// The fanotify limits, they apply per user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct FanotifyLimits {
+	/// How many marks a user can create.
+	pub max_user_marks: u64,
+	/// How many fanotify groups a user can create.
+	pub max_user_groups: u64,
+	/// How many events can be queued per group before they get dropped.
+	pub max_queued_events: u64
+}
+
+impl FanotifyLimits {
+	/// Returns `None` if the kernel is older than 5.13, which had fixed
+	/// limits.
+	pub fn read() -> io::Result<Option<Self>> {
+		let path = Path::new(FANOTIFY_PATH);
+		let max_user_marks = match read_sys_value(path.join("max_user_marks")) {
+			Ok(m) => m,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+			Err(e) => return Err(e)
+		};
+		Ok(Some(Self {
+			max_user_marks,
+			max_user_groups: read_sys_value(path.join("max_user_groups"))?,
+			max_queued_events: read_sys_value(path.join("max_queued_events"))?
+		}))
+	}
+}
+
+/// The inotify and fanotify usage of a process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct ProcessUsage {
+	pub pid: u32,
+	pub uid: u32,
+	pub inotify_instances: u64,
+	pub inotify_watches: u64,
+	pub fanotify_groups: u64,
+	pub fanotify_marks: u64
+}
+
+impl ProcessUsage {
+	/// Counts the instances and watches of a process.
+	///
+	/// Requires permission to read the file descriptors of the process.
+	pub fn read(process: &Process) -> io::Result<Self> {
+		let mut usage = Self {
+			pid: process.pid(),
+			uid: process.uid()?,
+			..Default::default()
+		};
+
+		let path = process.path();
+		for entry in fs::read_dir(path.join("fd"))? {
+			let entry = entry?;
+			// the fd might have been closed in the meantime
+			let link = match fs::read_link(entry.path()) {
+				Ok(l) => l,
+				Err(_) => continue
+			};
+			let kind = match link.to_str() {
+				Some("anon_inode:inotify") => Kind::Inotify,
+				Some("anon_inode:[fanotify]") => Kind::Fanotify,
+				_ => continue
+			};
+			let fdinfo = match fs::read_to_string(
+				path.join("fdinfo").join(entry.file_name())
+			) {
+				Ok(f) => f,
+				Err(_) => continue
+			};
+
+			let marks = count_marks(kind, &fdinfo);
+			match kind {
+				Kind::Inotify => {
+					usage.inotify_instances += 1;
+					usage.inotify_watches += marks;
+				},
+				Kind::Fanotify => {
+					usage.fanotify_groups += 1;
+					usage.fanotify_marks += marks;
+				}
+			}
+		}
+
+		Ok(usage)
+	}
+
+	fn is_empty(&self) -> bool {
+		self.inotify_instances == 0 && self.fanotify_groups == 0
+	}
+}
+
+/// The inotify and fanotify usage of a user, summed over its processes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct UserUsage {
+	pub uid: u32,
+	/// How many processes use inotify or fanotify.
+	pub processes: u64,
+	pub inotify_instances: u64,
+	pub inotify_watches: u64,
+	pub fanotify_groups: u64,
+	pub fanotify_marks: u64
+}
+
+impl UserUsage {
+	/// How much of `max_user_watches` is used, between 0 and 1.
+	pub fn watches_usage(&self, limits: &InotifyLimits) -> f64 {
+		if limits.max_user_watches == 0 {
+			return 0.0
+		}
+		self.inotify_watches as f64 / limits.max_user_watches as f64
+	}
+}
+
+/// Returns the usage of every process which uses inotify or fanotify,
+/// sorted by pid.
+///
+/// Processes which cannot be read are skipped.
+pub fn usage_by_process() -> io::Result<Vec<ProcessUsage>> {
+	Ok(Process::all()?
+		.iter()
+		.filter_map(|p| ProcessUsage::read(p).ok())
+		.filter(|u| !u.is_empty())
+		.collect())
+}
+
+/// Returns the usage of every user which uses inotify or fanotify, sorted by
+/// uid.
+///
+/// Instances which are shared between processes (for example after a fork)
+/// are counted once per process, and the kernel charges an instance to the
+/// user which created it, so the numbers can differ slightly from what the
+/// kernel counts.
+pub fn usage_by_user() -> io::Result<Vec<UserUsage>> {
+	let mut users: BTreeMap<u32, UserUsage> = BTreeMap::new();
+	for process in usage_by_process()? {
+		let user = users.entry(process.uid).or_insert_with(|| UserUsage {
+			uid: process.uid,
+			..Default::default()
+		});
+		user.processes += 1;
+		user.inotify_instances += process.inotify_instances;
+		user.inotify_watches += process.inotify_watches;
+		user.fanotify_groups += process.fanotify_groups;
+		user.fanotify_marks += process.fanotify_marks;
+	}
+	Ok(users.into_values().collect())
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+	Inotify,
+	Fanotify
+}
+
+/// Counts the watches or marks listed in a fdinfo file.
+fn count_marks(kind: Kind, fdinfo: &str) -> u64 {
+	let lines = fdinfo.lines();
+	let count = match kind {
+		Kind::Inotify => lines
+			.filter(|l| l.starts_with("inotify wd:"))
+			.count(),
+		// the group itself is listed as `fanotify flags:..`
+		Kind::Fanotify => lines
+			.filter_map(|l| l.strip_prefix("fanotify "))
+			.filter(|l| !l.starts_with("flags:"))
+			.count()
+	};
+	count as u64
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn marks() {
+		let inotify = "\
+pos:	0
+flags:	02004000
+mnt_id:	15
+ino:	1057
+inotify wd:2 ino:a2b sdev:800013 mask:fc6 ignored_mask:0 \
+fhandle-bytes:8 fhandle-type:1 f_handle:2b0a0000e3c1a4f1
+inotify wd:1 ino:192627 sdev:800013 mask:fc6 ignored_mask:0 \
+fhandle-bytes:8 fhandle-type:1 f_handle:27261900802dfd73
+";
+		assert_eq!(count_marks(Kind::Inotify, inotify), 2);
+
+		let fanotify = "\
+pos:	0
+flags:	02
+mnt_id:	15
+fanotify flags:10 event-flags:0
+fanotify mnt_id:20 mflags:0 mask:3b ignored_mask:0
+fanotify ino:4f969 sdev:800013 mflags:0 mask:3b ignored_mask:40000000 \
+fhandle-bytes:8 fhandle-type:1 f_handle:69f90400c275b5b4
+";
+		assert_eq!(count_marks(Kind::Fanotify, fanotify), 2);
+		assert_eq!(count_marks(Kind::Inotify, "pos:	0\n"), 0);
+	}
+}
@@ -0,0 +1,273 @@
+//! Get gpu information and live metrics from drm in `/sys/class/drm`.
+//!
+//! Utilization and memory are only reported by amdgpu, the frequency by i915.
+//! Temperature, fan and power are read from the hwmon of the card if the
+//! driver provides one.
+//!
+//! The proprietary nvidia driver exposes almost nothing through drm, see
+//! [`nvidia`] for that.
+
+pub mod nvidia;
+
+use crate::util::{read_sys_string, read_sys_value, read_sys_opt};
+use crate::sensors::Hwmon;
+use crate::unit::DataSize;
+use crate::pci::PciDevice;
+
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+
+const DRM_PATH: &str = "/sys/class/drm";
+
+/// A drm card for example `/sys/class/drm/card0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gpu {
+	path: PathBuf
+}
+
+impl Gpu {
+	/// Returns all drm cards (connectors like `card0-HDMI-A-1` are skipped).
+	pub fn all() -> io::Result<Vec<Self>> {
+		let mut list = vec![];
+		for entry in fs::read_dir(DRM_PATH)? {
+			let entry = entry?;
+			let name = entry.file_name();
+			let is_card = name.to_str()
+				.and_then(|n| n.strip_prefix("card"))
+				.map(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+				.unwrap_or(false);
+
+			if is_card {
+				list.push(Self::from_path(entry.path()));
+			}
+		}
+		list.sort_by(|a, b| a.path.cmp(&b.path));
+		Ok(list)
+	}
+
+	/// Creates a gpu from a path like `/sys/class/drm/card0`.
+	pub fn from_path(path: impl Into<PathBuf>) -> Self {
+		Self { path: path.into() }
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	fn device(&self) -> PathBuf {
+		self.path.join("device")
+	}
+
+	/// The name of the card for example `card0`.
+	pub fn name(&self) -> Option<&str> {
+		self.path.file_name()?.to_str()
+	}
+
+	/// The kernel driver for example `amdgpu` or `i915`.
+	pub fn driver(&self) -> io::Result<String> {
+		let link = fs::read_link(self.device().join("driver"))?;
+		link.file_name()
+			.and_then(|n| n.to_str())
+			.map(Into::into)
+			.ok_or_else(|| io::Error::new(
+				io::ErrorKind::InvalidData,
+				"invalid driver link"
+			))
+	}
+
+	/// The pci vendor id for example `0x1002`.
+	pub fn vendor_id(&self) -> io::Result<u16> {
+		read_hex(self.device().join("vendor"))
+	}
+
+	/// The pci device id.
+	pub fn device_id(&self) -> io::Result<u16> {
+		read_hex(self.device().join("device"))
+	}
+
+	pub fn vendor(&self) -> io::Result<GpuVendor> {
+		self.vendor_id()
+			.map(GpuVendor::from_id)
+	}
+
+	/// The pci device of the card, to check its link state.
+	pub fn pci_device(&self) -> Option<PciDevice> {
+		PciDevice::of_device(self.device())
+	}
+
+	/// How busy the gpu is in percent (amdgpu only).
+	pub fn busy_percent(&self) -> io::Result<Option<u8>> {
+		read_sys_opt(self.device().join("gpu_busy_percent"))
+	}
+
+	/// How busy the memory controller is in percent (amdgpu only).
+	pub fn mem_busy_percent(&self) -> io::Result<Option<u8>> {
+		read_sys_opt(self.device().join("mem_busy_percent"))
+	}
+
+	/// The vram and gtt usage (amdgpu only).
+	pub fn memory(&self) -> io::Result<Option<GpuMemory>> {
+		let device = self.device();
+		let size = |name: &str| {
+			read_sys_value(device.join(name))
+				.map(|b: u64| DataSize::from_bytes(b.into()))
+		};
+		let vram_total = match read_sys_opt::<u64>(
+			device.join("mem_info_vram_total")
+		)? {
+			Some(t) => DataSize::from_bytes(t.into()),
+			None => return Ok(None)
+		};
+
+		Ok(Some(GpuMemory {
+			vram_total,
+			vram_used: size("mem_info_vram_used")?,
+			vis_vram_total: size("mem_info_vis_vram_total")?,
+			vis_vram_used: size("mem_info_vis_vram_used")?,
+			gtt_total: size("mem_info_gtt_total")?,
+			gtt_used: size("mem_info_gtt_used")?
+		}))
+	}
+
+	/// The frequencies of the gpu (i915 only).
+	pub fn frequency(&self) -> io::Result<Option<GpuFrequency>> {
+		let actual = match read_sys_opt(self.path.join("gt_act_freq_mhz"))? {
+			Some(a) => a,
+			None => return Ok(None)
+		};
+
+		Ok(Some(GpuFrequency {
+			actual,
+			current: read_sys_value(self.path.join("gt_cur_freq_mhz"))?,
+			min: read_sys_value(self.path.join("gt_min_freq_mhz"))?,
+			max: read_sys_value(self.path.join("gt_max_freq_mhz"))?,
+			boost: read_sys_opt(self.path.join("gt_boost_freq_mhz"))?
+		}))
+	}
+
+	/// The hwmon of the card which contains temperature, fan and power
+	/// sensors.
+	pub fn hwmon(&self) -> io::Result<Option<Hwmon>> {
+		Hwmon::from_device(self.device())
+	}
+
+	/// Reads all metrics which are available for this card.
+	pub fn metrics(&self) -> io::Result<GpuMetrics> {
+		let hwmon = self.hwmon()?;
+		let (temperature, fan_rpm, power) = match hwmon {
+			Some(hwmon) => (
+				hwmon.temperatures()?.first().map(|t| t.celsius),
+				hwmon.fans()?.first().map(|f| f.rpm),
+				hwmon.power()?.first().map(|p| p.watts)
+			),
+			None => (None, None, None)
+		};
+
+		Ok(GpuMetrics {
+			busy_percent: self.busy_percent()?,
+			mem_busy_percent: self.mem_busy_percent()?,
+			temperature,
+			fan_rpm,
+			power,
+			frequency: self.frequency()?,
+			memory: self.memory()?
+		})
+	}
+}
+
+fn read_hex(path: impl AsRef<Path>) -> io::Result<u16> {
+	let s = read_sys_string(path)?;
+	u16::from_str_radix(s.trim_start_matches("0x"), 16)
+		.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub enum GpuVendor {
+	Amd,
+	Intel,
+	Nvidia,
+	Other(u16)
+}
+
+impl GpuVendor {
+	pub fn from_id(id: u16) -> Self {
+		match id {
+			0x1002 => Self::Amd,
+			0x8086 => Self::Intel,
+			0x10de => Self::Nvidia,
+			o => Self::Other(o)
+		}
+	}
+}
+
+/// Frequencies in MHz.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct GpuFrequency {
+	/// The frequency the hardware is actually running at.
+	pub actual: u32,
+	/// The frequency requested by the driver.
+	pub current: u32,
+	pub min: u32,
+	pub max: u32,
+	pub boost: Option<u32>
+}
+
+/// The memory of a gpu, there is no statfs like total for gpu memory so
+/// this is only available if the driver reports it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct GpuMemory {
+	/// The dedicated video memory.
+	pub vram_total: DataSize,
+	pub vram_used: DataSize,
+	/// The part of the vram the cpu can access directly, the same as the
+	/// total if resizable bar is enabled.
+	pub vis_vram_total: DataSize,
+	pub vis_vram_used: DataSize,
+	/// The system memory the gpu can map through the graphics translation
+	/// table.
+	pub gtt_total: DataSize,
+	pub gtt_used: DataSize
+}
+
+impl GpuMemory {
+	/// The vram which is not used.
+	pub fn vram_free(&self) -> DataSize {
+		self.vram_total.checked_sub(self.vram_used).unwrap_or_default()
+	}
+}
+
+/// A snapshot of the live metrics of a gpu.
+///
+/// Every value is optional since most of them depend on the driver.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct GpuMetrics {
+	pub busy_percent: Option<u8>,
+	pub mem_busy_percent: Option<u8>,
+	/// Degree Celsius
+	pub temperature: Option<f64>,
+	pub fan_rpm: Option<u32>,
+	/// Watt
+	pub power: Option<f64>,
+	pub frequency: Option<GpuFrequency>,
+	pub memory: Option<GpuMemory>
+}
@@ -0,0 +1,189 @@
+//! Get information about nvidia gpus using the proprietary driver.
+//!
+//! Without any feature only the driver version from /proc/driver/nvidia is
+//! available. Enable the `nvml` feature to get utilization, memory and
+//! temperature from NVML (requires `libnvidia-ml.so` at runtime).
+
+use crate::util::{read_to_string, read_to_string_mut};
+#[cfg(feature = "async")]
+use crate::util::{read_to_string_async, read_to_string_mut_async};
+
+use std::io;
+use std::path::Path;
+
+/// Read the driver version from /proc/driver/nvidia/version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DriverVersion {
+	raw: String
+}
+
+impl DriverVersion {
+	fn path() -> &'static Path {
+		Path::new("/proc/driver/nvidia/version")
+	}
+
+	#[cfg(test)]
+	fn from_string(raw: String) -> Self {
+		Self {raw}
+	}
+
+	/// Reads the file /proc/driver/nvidia/version.
+	///
+	/// Fails with `NotFound` if the nvidia driver is not loaded.
+	pub fn read() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string(Self::path())?
+		})
+	}
+
+	/// Reloads information without allocating.
+	pub fn reload(&mut self) -> io::Result<()> {
+		read_to_string_mut(Self::path(), &mut self.raw)
+	}
+
+	/// Reads the file without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string_async(Self::path()).await?
+		})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(Self::path(), &mut self.raw).await
+	}
+
+	/// The full line describing the kernel module.
+	pub fn nvrm(&self) -> Option<&str> {
+		self.raw.lines()
+			.find_map(|l| l.strip_prefix("NVRM version:"))
+			.map(str::trim)
+	}
+
+	/// The driver version for example `535.104.05`.
+	pub fn version(&self) -> Option<&str> {
+		self.nvrm()?
+			.split_whitespace()
+			.find(|w| {
+				w.contains('.') &&
+				w.bytes().all(|b| b.is_ascii_digit() || b == b'.')
+			})
+	}
+}
+
+#[cfg(feature = "nvml")]
+#[cfg_attr(docsrs, doc(cfg(feature = "nvml")))]
+pub use self::nvml::*;
+
+#[cfg(feature = "nvml")]
+mod nvml {
+	use crate::unit::DataSize;
+
+	use nvml_wrapper::Nvml as NvmlLib;
+	use nvml_wrapper::enum_wrappers::device::TemperatureSensor;
+	pub use nvml_wrapper::error::NvmlError;
+
+	/// A handle to the NVML library.
+	pub struct Nvml {
+		inner: NvmlLib
+	}
+
+	impl Nvml {
+		/// Loads and initializes the NVML library.
+		pub fn init() -> Result<Self, NvmlError> {
+			NvmlLib::init()
+				.map(|inner| Self { inner })
+		}
+
+		/// The version of the installed driver.
+		pub fn driver_version(&self) -> Result<String, NvmlError> {
+			self.inner.sys_driver_version()
+		}
+
+		/// The number of nvidia gpus.
+		pub fn device_count(&self) -> Result<u32, NvmlError> {
+			self.inner.device_count()
+		}
+
+		/// Reads the metrics of the gpu at the given index.
+		pub fn metrics(&self, index: u32) -> Result<NvidiaMetrics, NvmlError> {
+			let device = self.inner.device_by_index(index)?;
+			let utilization = device.utilization_rates()?;
+			let memory = device.memory_info()?;
+
+			Ok(NvidiaMetrics {
+				index,
+				name: device.name()?,
+				uuid: device.uuid()?,
+				busy_percent: utilization.gpu,
+				mem_busy_percent: utilization.memory,
+				memory_total: DataSize::from_size_bytes(memory.total).unwrap(),
+				memory_used: DataSize::from_size_bytes(memory.used).unwrap(),
+				temperature: device.temperature(TemperatureSensor::Gpu)?,
+				fan_percent: not_supported(device.fan_speed(0))?,
+				power: not_supported(device.power_usage())?
+					.map(|mw| mw as f64 / 1_000.0)
+			})
+		}
+
+		/// Reads the metrics of all gpus.
+		pub fn all_metrics(&self) -> Result<Vec<NvidiaMetrics>, NvmlError> {
+			(0..self.device_count()?)
+				.map(|i| self.metrics(i))
+				.collect()
+		}
+	}
+
+	/// Converts the `NotSupported` error into `None`.
+	fn not_supported<T>(
+		r: Result<T, NvmlError>
+	) -> Result<Option<T>, NvmlError> {
+		match r {
+			Ok(v) => Ok(Some(v)),
+			Err(NvmlError::NotSupported) => Ok(None),
+			Err(e) => Err(e)
+		}
+	}
+
+	/// A snapshot of the metrics of a nvidia gpu.
+	#[derive(Debug, Clone, PartialEq)]
+	pub struct NvidiaMetrics {
+		pub index: u32,
+		pub name: String,
+		pub uuid: String,
+		pub busy_percent: u32,
+		pub mem_busy_percent: u32,
+		pub memory_total: DataSize,
+		pub memory_used: DataSize,
+		/// Degree Celsius
+		pub temperature: u32,
+		/// Fan speed in percent, if the gpu has a fan.
+		pub fan_percent: Option<u32>,
+		/// Watt
+		pub power: Option<f64>
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn driver_version() {
+		let version = DriverVersion::from_string("\
+NVRM version: NVIDIA UNIX x86_64 Kernel Module  535.104.05  Sat Aug 19 01:15:15 UTC 2023
+GCC version:  gcc version 12.3.0 (Ubuntu 12.3.0-1ubuntu1~22.04)
+".into());
+		assert_eq!(version.version(), Some("535.104.05"));
+
+		let version = DriverVersion::from_string("\
+NVRM version: NVIDIA UNIX Open Kernel Module for x86_64  550.54.14  Release Build  (dvs-builder@U16-I3-B03-4-3)  Thu Feb 22 01:25:25 UTC 2024
+GCC version:  gcc version 12.2.0 (Debian 12.2.0-14)
+".into());
+		assert_eq!(version.version(), Some("550.54.14"));
+	}
+}
@@ -0,0 +1,310 @@
+//! List hid devices from `/sys/class/hidraw`, to find a specific device
+//! like a ups or a macro pad without libusb.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::hidraw::HidrawDevice;
+//!
+//! for dev in HidrawDevice::all().unwrap() {
+//!     let info = dev.info().unwrap();
+//!     println!(
+//!         "{:?} {:04x}:{:04x} {} interface {:?} usages {:x?}",
+//!         dev.dev_path(), info.vendor_id, info.product_id, info.name,
+//!         dev.interface_number(), dev.usages().unwrap()
+//!     );
+//! }
+//! ```
+
+use crate::util::read_sys_string;
+
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+
+const HIDRAW_PATH: &str = "/sys/class/hidraw";
+
+/// A hidraw device for example `/sys/class/hidraw/hidraw0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HidrawDevice {
+	path: PathBuf
+}
+
+impl HidrawDevice {
+	/// Returns all hidraw devices.
+	pub fn all() -> io::Result<Vec<Self>> {
+		let dir = match fs::read_dir(HIDRAW_PATH) {
+			Ok(d) => d,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+			Err(e) => return Err(e)
+		};
+
+		let mut list = dir.map(|e| e.map(|e| Self::from_path(e.path())))
+			.collect::<io::Result<Vec<_>>>()?;
+		list.sort_by(|a, b| a.path.cmp(&b.path));
+		Ok(list)
+	}
+
+	/// Returns every device with the given vendor and product id.
+	pub fn by_ids(vendor_id: u16, product_id: u16) -> io::Result<Vec<Self>> {
+		let mut list = vec![];
+		for dev in Self::all()? {
+			let info = dev.info()?;
+			if info.vendor_id == vendor_id && info.product_id == product_id {
+				list.push(dev);
+			}
+		}
+		Ok(list)
+	}
+
+	/// Creates a device from a path like `/sys/class/hidraw/hidraw0`.
+	pub fn from_path(path: impl Into<PathBuf>) -> Self {
+		Self { path: path.into() }
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// The name of the device for example `hidraw0`.
+	pub fn name(&self) -> Option<&str> {
+		self.path.file_name()?.to_str()
+	}
+
+	/// The character device for example `/dev/hidraw0`.
+	pub fn dev_path(&self) -> Option<PathBuf> {
+		self.name()
+			.map(|n| Path::new("/dev").join(n))
+	}
+
+	fn device(&self) -> PathBuf {
+		self.path.join("device")
+	}
+
+	/// Reads the ids and names of the hid device.
+	pub fn info(&self) -> io::Result<HidInfo> {
+		let uevent = read_sys_string(self.device().join("uevent"))?;
+		HidInfo::parse(&uevent).ok_or_else(|| io::Error::new(
+			io::ErrorKind::InvalidData,
+			"uevent without HID_ID"
+		))
+	}
+
+	/// The usb interface number, a device with multiple interfaces (like a
+	/// keyboard with media keys) has a hidraw device per interface.
+	///
+	/// Returns `None` if the device is not connected over usb.
+	pub fn interface_number(&self) -> Option<u8> {
+		let device = fs::canonicalize(self.device()).ok()?;
+		let s = read_sys_string(device.parent()?.join("bInterfaceNumber"))
+			.ok()?;
+		u8::from_str_radix(&s, 16).ok()
+	}
+
+	/// The raw report descriptor.
+	pub fn report_descriptor(&self) -> io::Result<Vec<u8>> {
+		fs::read(self.device().join("report_descriptor"))
+	}
+
+	/// The usage page and usage of every top level application collection,
+	/// for example `(0x01, 0x06)` for a keyboard or `(0x84, 0x04)` for a
+	/// ups.
+	pub fn usages(&self) -> io::Result<Vec<(u16, u16)>> {
+		self.report_descriptor()
+			.map(|d| application_usages(&d))
+	}
+}
+
+/// The bus a hid device is connected to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub enum HidBus {
+	Usb,
+	Bluetooth,
+	Virtual,
+	I2c,
+	Other(u16)
+}
+
+impl HidBus {
+	/// Creates the bus from a value of linux/input.h.
+	pub fn from_id(id: u16) -> Self {
+		match id {
+			0x03 => Self::Usb,
+			0x05 => Self::Bluetooth,
+			0x06 => Self::Virtual,
+			0x18 => Self::I2c,
+			o => Self::Other(o)
+		}
+	}
+}
+
+/// The identity of a hid device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct HidInfo {
+	pub bus: HidBus,
+	pub vendor_id: u16,
+	pub product_id: u16,
+	/// The name reported by the device.
+	pub name: String,
+	/// Where the device is connected for example
+	/// `usb-0000:00:14.0-2/input1`.
+	pub phys: String,
+	/// The serial number, if the device reports one.
+	pub uniq: Option<String>
+}
+
+impl HidInfo {
+	fn parse(uevent: &str) -> Option<Self> {
+		let mut id = None;
+		let mut name = String::new();
+		let mut phys = String::new();
+		let mut uniq = None;
+		for line in uevent.lines() {
+			let (key, value) = match line.split_once('=') {
+				Some(kv) => kv,
+				None => continue
+			};
+			match key {
+				// HID_ID=0003:0000046D:0000C52B
+				"HID_ID" => {
+					let mut parts = value.split(':')
+						.map(|p| u32::from_str_radix(p, 16).ok());
+					id = Some((
+						parts.next()??,
+						parts.next()??,
+						parts.next()??
+					));
+				},
+				"HID_NAME" => name = value.into(),
+				"HID_PHYS" => phys = value.into(),
+				"HID_UNIQ" if !value.is_empty() => uniq = Some(value.into()),
+				_ => {}
+			}
+		}
+
+		let (bus, vendor, product) = id?;
+		Some(Self {
+			bus: HidBus::from_id(bus as u16),
+			vendor_id: vendor as u16,
+			product_id: product as u16,
+			name,
+			phys,
+			uniq
+		})
+	}
+}
+
+/// Walks the short items of a report descriptor and returns the usages of
+/// the top level application collections.
+fn application_usages(desc: &[u8]) -> Vec<(u16, u16)> {
+	let mut list = vec![];
+	let mut usage_page = 0u16;
+	let mut usage = None;
+	let mut depth = 0u32;
+
+	let mut i = 0;
+	while i < desc.len() {
+		let prefix = desc[i];
+		// a long item, the second byte is the size of the data
+		if prefix == 0xfe {
+			let size = desc.get(i + 1).copied().unwrap_or(0) as usize;
+			i += 3 + size;
+			continue
+		}
+
+		let size = match prefix & 0x3 {
+			3 => 4,
+			s => s as usize
+		};
+		let data = match desc.get(i + 1..i + 1 + size) {
+			Some(d) => d,
+			None => break
+		};
+		i += 1 + size;
+		let value = data.iter().rev().fold(0u32, |v, b| v << 8 | *b as u32);
+
+		match prefix & 0xfc {
+			// usage page
+			0x04 => usage_page = value as u16,
+			// usage, four bytes contain the page in the upper half
+			0x08 => usage = Some(match size {
+				4 => ((value >> 16) as u16, value as u16),
+				_ => (usage_page, value as u16)
+			}),
+			// collection
+			0xa0 => {
+				if depth == 0 && value == 1 {
+					list.extend(usage);
+				}
+				depth += 1;
+				usage = None;
+			},
+			// end collection
+			0xc0 => depth = depth.saturating_sub(1),
+			// input, output and feature clear the local items
+			0x80 | 0x90 | 0xb0 => usage = None,
+			_ => {}
+		}
+	}
+
+	list
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn uevent() {
+		let info = HidInfo::parse("\
+DRIVER=hid-generic
+HID_ID=0003:0000051D:00000002
+HID_NAME=American Power Conversion Back-UPS ES 700G FW:871.O4 .I USB FW:O4
+HID_PHYS=usb-0000:00:14.0-3/input0
+HID_UNIQ=4B1624P12345
+MODALIAS=hid:b0003g0001v0000051Dp00000002
+").unwrap();
+		assert_eq!(info.bus, HidBus::Usb);
+		assert_eq!(info.vendor_id, 0x051d);
+		assert_eq!(info.product_id, 0x0002);
+		assert!(info.name.starts_with("American Power Conversion"));
+		assert_eq!(info.phys, "usb-0000:00:14.0-3/input0");
+		assert_eq!(info.uniq.as_deref(), Some("4B1624P12345"));
+
+		assert!(HidInfo::parse("HID_NAME=test\n").is_none());
+	}
+
+	#[test]
+	fn report_descriptor() {
+		let desc = [
+			// usage page (generic desktop), usage (keyboard)
+			0x05, 0x01, 0x09, 0x06,
+			// collection (application)
+			0xa1, 0x01,
+			// usage page (keyboard), usage minimum, usage maximum
+			0x05, 0x07, 0x19, 0xe0, 0x29, 0xe7,
+			// collection (physical), end collection
+			0xa1, 0x00, 0xc0,
+			// input
+			0x81, 0x02,
+			// end collection
+			0xc0,
+			// usage page (power device), usage (ups), collection
+			0x06, 0x84, 0x00, 0x09, 0x04, 0xa1, 0x01, 0xc0,
+			// usage (consumer control) with the page, collection
+			0x0b, 0x01, 0x00, 0x0c, 0x00, 0xa1, 0x01, 0xc0
+		];
+		assert_eq!(
+			application_usages(&desc),
+			[(0x01, 0x06), (0x84, 0x04), (0x0c, 0x01)]
+		);
+	}
+}
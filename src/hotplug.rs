@@ -0,0 +1,273 @@
+//! Get notified when devices are added, removed or changed.
+//!
+//! Listens on the kernel uevent netlink socket, the same source udev uses, so
+//! usb, disk or network hotplug can be handled without polling.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::hotplug::HotplugMonitor;
+//!
+//! let mut monitor = HotplugMonitor::new().unwrap();
+//! loop {
+//!     let event = monitor.recv().unwrap();
+//!     println!("{:?} {} {:?}", event.action(), event.devpath(), event.subsystem());
+//! }
+//! ```
+
+use std::{io, mem};
+use std::collections::HashMap;
+use std::os::unix::io::{AsRawFd, RawFd};
+
+const BUFFER_SIZE: usize = 8 * 1024;
+/// The multicast group of the kernel (udev uses group 2).
+const KERNEL_GROUP: u32 = 1;
+
+/// A netlink socket receiving uevents from the kernel.
+#[derive(Debug)]
+pub struct HotplugMonitor {
+	fd: RawFd,
+	buf: Vec<u8>
+}
+
+impl HotplugMonitor {
+	/// Opens a new netlink socket and subscribes to kernel uevents.
+	pub fn new() -> io::Result<Self> {
+		let fd = unsafe {
+			libc::socket(
+				libc::AF_NETLINK,
+				libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+				libc::NETLINK_KOBJECT_UEVENT
+			)
+		};
+		if fd < 0 {
+			return Err(io::Error::last_os_error())
+		}
+
+		// from here on drop closes the socket
+		let me = Self {
+			fd,
+			buf: vec![0; BUFFER_SIZE]
+		};
+
+		let r = unsafe {
+			let mut addr: libc::sockaddr_nl = mem::zeroed();
+			addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+			addr.nl_groups = KERNEL_GROUP;
+			libc::bind(
+				fd,
+				&addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+				mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t
+			)
+		};
+		if r < 0 {
+			return Err(io::Error::last_os_error())
+		}
+
+		Ok(me)
+	}
+
+	/// Moves the socket into or out of nonblocking mode.
+	///
+	/// In nonblocking mode `recv` returns an error with the kind
+	/// `WouldBlock` if no event is available.
+	pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+		unsafe {
+			let flags = libc::fcntl(self.fd, libc::F_GETFL);
+			if flags < 0 {
+				return Err(io::Error::last_os_error())
+			}
+
+			let flags = match nonblocking {
+				true => flags | libc::O_NONBLOCK,
+				false => flags & !libc::O_NONBLOCK
+			};
+			match libc::fcntl(self.fd, libc::F_SETFL, flags) {
+				-1 => Err(io::Error::last_os_error()),
+				_ => Ok(())
+			}
+		}
+	}
+
+	/// Waits for the next event.
+	///
+	/// Messages which are not valid uevents are skipped.
+	pub fn recv(&mut self) -> io::Result<UEvent> {
+		loop {
+			let r = unsafe {
+				libc::recv(
+					self.fd,
+					self.buf.as_mut_ptr() as *mut libc::c_void,
+					self.buf.len(),
+					0
+				)
+			};
+			if r < 0 {
+				let e = io::Error::last_os_error();
+				if e.kind() == io::ErrorKind::Interrupted {
+					continue
+				}
+				return Err(e)
+			}
+
+			if let Some(ev) = UEvent::parse(&self.buf[..r as usize]) {
+				return Ok(ev)
+			}
+		}
+	}
+}
+
+impl Iterator for HotplugMonitor {
+	type Item = io::Result<UEvent>;
+
+	fn next(&mut self) -> Option<io::Result<UEvent>> {
+		Some(self.recv())
+	}
+}
+
+impl AsRawFd for HotplugMonitor {
+	fn as_raw_fd(&self) -> RawFd {
+		self.fd
+	}
+}
+
+impl Drop for HotplugMonitor {
+	fn drop(&mut self) {
+		unsafe {
+			libc::close(self.fd);
+		}
+	}
+}
+
+/// An event sent by the kernel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct UEvent {
+	action: Action,
+	devpath: String,
+	properties: HashMap<String, String>
+}
+
+impl UEvent {
+	/// Parses a message like `add@/devices/...\0ACTION=add\0...`.
+	fn parse(buf: &[u8]) -> Option<Self> {
+		let s = String::from_utf8_lossy(buf);
+		let mut parts = s.split('\0');
+
+		let (action, devpath) = parts.next()?.split_once('@')?;
+		let properties = parts
+			.filter_map(|p| p.split_once('='))
+			.map(|(k, v)| (k.to_string(), v.to_string()))
+			.collect();
+
+		Some(Self {
+			action: Action::from_str(action),
+			devpath: devpath.into(),
+			properties
+		})
+	}
+
+	pub fn action(&self) -> &Action {
+		&self.action
+	}
+
+	/// The path of the device in sysfs (without `/sys`).
+	pub fn devpath(&self) -> &str {
+		&self.devpath
+	}
+
+	/// The subsystem for example `usb`, `block` or `net`.
+	pub fn subsystem(&self) -> Option<&str> {
+		self.property("SUBSYSTEM")
+	}
+
+	/// The device type for example `disk` or `partition`.
+	pub fn devtype(&self) -> Option<&str> {
+		self.property("DEVTYPE")
+	}
+
+	/// The device node name for example `sdb1`.
+	pub fn devname(&self) -> Option<&str> {
+		self.property("DEVNAME")
+	}
+
+	/// The sequence number of the event.
+	pub fn seqnum(&self) -> Option<u64> {
+		self.property("SEQNUM")?.parse().ok()
+	}
+
+	pub fn property(&self, key: &str) -> Option<&str> {
+		self.properties.get(key)
+			.map(|s| s.as_str())
+	}
+
+	/// All properties including `ACTION`, `DEVPATH` and `SUBSYSTEM`.
+	pub fn properties(&self) -> &HashMap<String, String> {
+		&self.properties
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub enum Action {
+	Add,
+	Remove,
+	Change,
+	Move,
+	Online,
+	Offline,
+	Bind,
+	Unbind,
+	Other(String)
+}
+
+impl Action {
+	fn from_str(s: &str) -> Self {
+		match s {
+			"add" => Self::Add,
+			"remove" => Self::Remove,
+			"change" => Self::Change,
+			"move" => Self::Move,
+			"online" => Self::Online,
+			"offline" => Self::Offline,
+			"bind" => Self::Bind,
+			"unbind" => Self::Unbind,
+			o => Self::Other(o.into())
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_uevent() {
+		let msg = b"add@/devices/pci0000:00/0000:00:14.0/usb1/1-2\0\
+ACTION=add\0\
+DEVPATH=/devices/pci0000:00/0000:00:14.0/usb1/1-2\0\
+SUBSYSTEM=usb\0\
+DEVNAME=bus/usb/001/005\0\
+DEVTYPE=usb_device\0\
+SEQNUM=4321\0";
+		let ev = UEvent::parse(msg).unwrap();
+		assert_eq!(ev.action(), &Action::Add);
+		assert_eq!(ev.devpath(), "/devices/pci0000:00/0000:00:14.0/usb1/1-2");
+		assert_eq!(ev.subsystem(), Some("usb"));
+		assert_eq!(ev.devtype(), Some("usb_device"));
+		assert_eq!(ev.devname(), Some("bus/usb/001/005"));
+		assert_eq!(ev.seqnum(), Some(4321));
+	}
+
+	#[test]
+	fn parse_invalid() {
+		assert!(UEvent::parse(b"libudev\0\xfe\xed").is_none());
+	}
+}
@@ -0,0 +1,968 @@
+//! Read sensors and the FRU inventory from the BMC using `/dev/ipmi0`.
+//!
+//! Requires the `ipmi_devintf` and `ipmi_si` kernel modules and usually root
+//! permissions.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::ipmi::Ipmi;
+//!
+//! let mut ipmi = Ipmi::open().unwrap();
+//! for sensor in ipmi.sensors().unwrap() {
+//!     println!("{}: {:?} {:?}", sensor.name, sensor.value, sensor.unit);
+//! }
+//! let fru = ipmi.fru(0).unwrap();
+//! println!("{:?}", fru.board);
+//! ```
+
+use std::{io, mem};
+use std::fs::{File, OpenOptions};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::os::unix::io::AsRawFd;
+use std::os::raw::{c_int, c_uint, c_long, c_short, c_ulong};
+
+const PATHS: &[&str] = &["/dev/ipmi0", "/dev/ipmi/0", "/dev/ipmidev/0"];
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+const IPMI_SYSTEM_INTERFACE_ADDR_TYPE: c_int = 0x0c;
+const IPMI_BMC_CHANNEL: c_short = 0x0f;
+const IPMI_RESPONSE_RECV_TYPE: c_int = 1;
+const IPMI_MAX_ADDR_SIZE: usize = 32;
+const IPMI_MAX_MSG_LENGTH: usize = 272;
+
+const NETFN_SENSOR: u8 = 0x04;
+const NETFN_APP: u8 = 0x06;
+const NETFN_STORAGE: u8 = 0x0a;
+
+/// The address the BMC uses for its own sensors.
+const BMC_SLAVE_ADDR: u8 = 0x20;
+/// How many bytes are read at once from the sdr repository or the fru.
+const READ_CHUNK: u8 = 16;
+
+#[repr(C)]
+struct IpmiMsg {
+	netfn: u8,
+	cmd: u8,
+	data_len: u16,
+	data: *mut u8
+}
+
+#[repr(C)]
+struct IpmiReq {
+	addr: *mut u8,
+	addr_len: c_uint,
+	msgid: c_long,
+	msg: IpmiMsg
+}
+
+#[repr(C)]
+struct IpmiRecv {
+	recv_type: c_int,
+	addr: *mut u8,
+	addr_len: c_uint,
+	msgid: c_long,
+	msg: IpmiMsg
+}
+
+#[repr(C)]
+struct SystemInterfaceAddr {
+	addr_type: c_int,
+	channel: c_short,
+	lun: u8
+}
+
+const fn ioc(dir: u32, nr: u32, size: usize) -> c_ulong {
+	((dir << 30) | ((size as u32) << 16) | ((b'i' as u32) << 8) | nr) as c_ulong
+}
+
+// see linux/ipmi.h
+const IPMICTL_RECEIVE_MSG_TRUNC: c_ulong = ioc(3, 11, mem::size_of::<IpmiRecv>());
+const IPMICTL_SEND_COMMAND: c_ulong = ioc(2, 13, mem::size_of::<IpmiReq>());
+
+fn invalid_data(msg: &str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// A connection to the local BMC.
+#[derive(Debug)]
+pub struct Ipmi {
+	file: File,
+	msgid: c_long,
+	timeout: Duration
+}
+
+impl Ipmi {
+	/// Opens the first ipmi device which exists.
+	pub fn open() -> io::Result<Self> {
+		let path = PATHS.iter()
+			.map(Path::new)
+			.find(|p| p.exists())
+			.ok_or_else(|| io::Error::new(
+				io::ErrorKind::NotFound,
+				"no ipmi device found"
+			))?;
+
+		Self::open_path(path)
+	}
+
+	/// Opens the ipmi device at the given path.
+	pub fn open_path(path: impl AsRef<Path>) -> io::Result<Self> {
+		let file = OpenOptions::new()
+			.read(true)
+			.write(true)
+			.open(path)?;
+
+		Ok(Self {
+			file,
+			msgid: 0,
+			timeout: TIMEOUT
+		})
+	}
+
+	/// Sets how long to wait for a response from the BMC. Defaults to 5
+	/// seconds.
+	pub fn set_timeout(&mut self, timeout: Duration) {
+		self.timeout = timeout;
+	}
+
+	/// Sends a raw command to the BMC and returns the response without the
+	/// completion code.
+	///
+	/// A completion code other than zero is returned as an error.
+	pub fn raw_command(
+		&mut self,
+		netfn: u8,
+		cmd: u8,
+		data: &[u8]
+	) -> io::Result<Vec<u8>> {
+		self.command(0, netfn, cmd, data)
+	}
+
+	fn command(
+		&mut self,
+		lun: u8,
+		netfn: u8,
+		cmd: u8,
+		data: &[u8]
+	) -> io::Result<Vec<u8>> {
+		self.msgid = self.msgid.wrapping_add(1);
+		let msgid = self.msgid;
+		let fd = self.file.as_raw_fd();
+
+		let mut addr = SystemInterfaceAddr {
+			addr_type: IPMI_SYSTEM_INTERFACE_ADDR_TYPE,
+			channel: IPMI_BMC_CHANNEL,
+			lun: lun & 0b11
+		};
+		let mut data = data.to_vec();
+		let mut req = IpmiReq {
+			addr: &mut addr as *mut SystemInterfaceAddr as *mut u8,
+			addr_len: mem::size_of::<SystemInterfaceAddr>() as c_uint,
+			msgid,
+			msg: IpmiMsg {
+				netfn,
+				cmd,
+				data_len: data.len() as u16,
+				data: data.as_mut_ptr()
+			}
+		};
+
+		let r = unsafe { libc::ioctl(fd, IPMICTL_SEND_COMMAND as _, &mut req) };
+		if r < 0 {
+			return Err(io::Error::last_os_error())
+		}
+
+		loop {
+			self.wait_readable()?;
+
+			let mut addr = [0u8; IPMI_MAX_ADDR_SIZE];
+			let mut buf = [0u8; IPMI_MAX_MSG_LENGTH];
+			let mut recv = IpmiRecv {
+				recv_type: 0,
+				addr: addr.as_mut_ptr(),
+				addr_len: addr.len() as c_uint,
+				msgid: 0,
+				msg: IpmiMsg {
+					netfn: 0,
+					cmd: 0,
+					data_len: buf.len() as u16,
+					data: buf.as_mut_ptr()
+				}
+			};
+
+			let r = unsafe {
+				libc::ioctl(fd, IPMICTL_RECEIVE_MSG_TRUNC as _, &mut recv)
+			};
+			if r < 0 {
+				return Err(io::Error::last_os_error())
+			}
+
+			// skip events and responses to other requests
+			if recv.recv_type != IPMI_RESPONSE_RECV_TYPE || recv.msgid != msgid {
+				continue
+			}
+
+			let len = (recv.msg.data_len as usize).min(buf.len());
+			let resp = &buf[..len];
+			return match resp.first() {
+				Some(0) => Ok(resp[1..].to_vec()),
+				Some(cc) => Err(io::Error::new(
+					io::ErrorKind::Other,
+					format!("ipmi completion code {:#04x}", cc)
+				)),
+				None => Err(invalid_data("empty ipmi response"))
+			}
+		}
+	}
+
+	fn wait_readable(&self) -> io::Result<()> {
+		let mut pfd = libc::pollfd {
+			fd: self.file.as_raw_fd(),
+			events: libc::POLLIN,
+			revents: 0
+		};
+		let timeout = self.timeout.as_millis().min(c_int::MAX as u128);
+
+		loop {
+			let r = unsafe { libc::poll(&mut pfd, 1, timeout as c_int) };
+			match r {
+				-1 => {
+					let e = io::Error::last_os_error();
+					if e.kind() != io::ErrorKind::Interrupted {
+						return Err(e)
+					}
+				},
+				0 => return Err(io::Error::new(
+					io::ErrorKind::TimedOut,
+					"ipmi response timed out"
+				)),
+				_ => return Ok(())
+			}
+		}
+	}
+
+	/// Returns information about the BMC.
+	pub fn device_id(&mut self) -> io::Result<DeviceId> {
+		let data = self.raw_command(NETFN_APP, 0x01, &[])?;
+		DeviceId::parse(&data)
+			.ok_or_else(|| invalid_data("invalid device id response"))
+	}
+
+	/// Reads all sensor records from the sdr repository.
+	///
+	/// Records which don't describe a sensor are skipped.
+	pub fn sensor_records(&mut self) -> io::Result<Vec<SensorRecord>> {
+		let resv = self.raw_command(NETFN_STORAGE, 0x22, &[])?;
+		if resv.len() < 2 {
+			return Err(invalid_data("invalid sdr reservation"))
+		}
+		let resv = [resv[0], resv[1]];
+
+		let mut list = vec![];
+		let mut id: u16 = 0;
+		loop {
+			let (next, record) = self.read_sdr(resv, id)?;
+			if let Some(record) = SensorRecord::parse(id, &record) {
+				list.push(record);
+			}
+
+			if next == 0xffff || next == id {
+				break
+			}
+			id = next;
+		}
+
+		Ok(list)
+	}
+
+	/// Reads one record of the sdr repository and returns the id of the next
+	/// record.
+	fn read_sdr(
+		&mut self,
+		resv: [u8; 2],
+		id: u16
+	) -> io::Result<(u16, Vec<u8>)> {
+		let id_bytes = id.to_le_bytes();
+		let mut record = vec![];
+		let mut next = 0xffff;
+		// the header contains the length of the record
+		let mut len = 5;
+
+		while record.len() < len {
+			let offset = record.len();
+			let count = (len - offset).min(READ_CHUNK as usize);
+			// the offset is a single byte, records are at most 260 bytes long
+			let offset_byte = u8::try_from(offset)
+				.map_err(|_| invalid_data("sdr record too long"))?;
+			let data = self.raw_command(NETFN_STORAGE, 0x23, &[
+				resv[0], resv[1],
+				id_bytes[0], id_bytes[1],
+				offset_byte, count as u8
+			])?;
+			if data.len() < 3 {
+				return Err(invalid_data("invalid sdr response"))
+			}
+
+			next = u16::from_le_bytes([data[0], data[1]]);
+			record.extend_from_slice(&data[2..]);
+			if offset == 0 {
+				len = 5 + *record.get(4)
+					.ok_or_else(|| invalid_data("invalid sdr header"))? as usize;
+			}
+		}
+
+		record.truncate(len);
+		Ok((next, record))
+	}
+
+	/// Reads the current value of a sensor.
+	///
+	/// Returns `None` if the reading is not available or if the sensor is
+	/// not owned by the BMC.
+	pub fn sensor_reading(
+		&mut self,
+		record: &SensorRecord
+	) -> io::Result<Option<SensorReading>> {
+		if record.owner_id != BMC_SLAVE_ADDR {
+			return Ok(None)
+		}
+
+		let data = self.command(
+			record.owner_lun,
+			NETFN_SENSOR,
+			0x2d,
+			&[record.number]
+		)?;
+		Ok(SensorReading::parse(record, &data))
+	}
+
+	/// Reads all sensor records and their current value.
+	pub fn sensors(&mut self) -> io::Result<Vec<Sensor>> {
+		let records = self.sensor_records()?;
+		let mut list = Vec::with_capacity(records.len());
+
+		for record in records {
+			// some sensors fail if they are disabled or not present
+			let reading = self.sensor_reading(&record).ok().flatten();
+			list.push(Sensor {
+				name: record.name.clone(),
+				number: record.number,
+				kind: record.kind,
+				unit: record.unit,
+				raw: reading.as_ref().map(|r| r.raw),
+				value: reading.and_then(|r| r.value)
+			});
+		}
+
+		Ok(list)
+	}
+
+	/// Reads and parses the fru inventory with the given id (0 is the
+	/// mainboard).
+	pub fn fru(&mut self, id: u8) -> io::Result<Fru> {
+		let data = self.fru_raw(id)?;
+		Fru::parse(&data)
+			.ok_or_else(|| invalid_data("invalid fru data"))
+	}
+
+	/// Reads the raw fru inventory with the given id.
+	pub fn fru_raw(&mut self, id: u8) -> io::Result<Vec<u8>> {
+		let info = self.raw_command(NETFN_STORAGE, 0x10, &[id])?;
+		if info.len() < 2 {
+			return Err(invalid_data("invalid fru info"))
+		}
+		let size = u16::from_le_bytes([info[0], info[1]]) as usize;
+
+		let mut data = Vec::with_capacity(size);
+		while data.len() < size {
+			let offset = (data.len() as u16).to_le_bytes();
+			let count = (size - data.len()).min(READ_CHUNK as usize);
+			let resp = self.raw_command(NETFN_STORAGE, 0x11, &[
+				id, offset[0], offset[1], count as u8
+			])?;
+
+			match resp.first() {
+				Some(0) | None => break,
+				Some(&n) => {
+					let n = (n as usize).min(resp.len() - 1);
+					data.extend_from_slice(&resp[1..=n]);
+				}
+			}
+		}
+
+		Ok(data)
+	}
+}
+
+/// Information about the BMC.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct DeviceId {
+	pub device_id: u8,
+	pub device_revision: u8,
+	/// Firmware version for example `1.23`.
+	pub firmware_version: String,
+	/// Ipmi version for example `2.0`.
+	pub ipmi_version: String,
+	/// IANA enterprise number of the manufacturer.
+	pub manufacturer_id: u32,
+	pub product_id: u16
+}
+
+impl DeviceId {
+	fn parse(d: &[u8]) -> Option<Self> {
+		if d.len() < 11 {
+			return None
+		}
+
+		Some(Self {
+			device_id: d[0],
+			device_revision: d[1] & 0x0f,
+			firmware_version: format!("{}.{:02x}", d[2] & 0x7f, d[3]),
+			ipmi_version: format!("{}.{}", d[4] & 0x0f, d[4] >> 4),
+			manufacturer_id: u32::from_le_bytes([d[6], d[7], d[8], 0]),
+			product_id: u16::from_le_bytes([d[9], d[10]])
+		})
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub enum SensorKind {
+	Temperature,
+	Voltage,
+	Current,
+	Fan,
+	PhysicalSecurity,
+	Processor,
+	PowerSupply,
+	PowerUnit,
+	Memory,
+	Other(u8)
+}
+
+impl SensorKind {
+	fn from_u8(num: u8) -> Self {
+		match num {
+			0x01 => Self::Temperature,
+			0x02 => Self::Voltage,
+			0x03 => Self::Current,
+			0x04 => Self::Fan,
+			0x05 => Self::PhysicalSecurity,
+			0x07 => Self::Processor,
+			0x08 => Self::PowerSupply,
+			0x09 => Self::PowerUnit,
+			0x0c => Self::Memory,
+			o => Self::Other(o)
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub enum SensorUnit {
+	Unspecified,
+	Celsius,
+	Fahrenheit,
+	Kelvin,
+	Volts,
+	Amps,
+	Watts,
+	Rpm,
+	Percent,
+	Other(u8)
+}
+
+impl SensorUnit {
+	fn from_u8(num: u8) -> Self {
+		match num {
+			0 => Self::Unspecified,
+			1 => Self::Celsius,
+			2 => Self::Fahrenheit,
+			3 => Self::Kelvin,
+			4 => Self::Volts,
+			5 => Self::Amps,
+			6 => Self::Watts,
+			18 => Self::Rpm,
+			o => Self::Other(o)
+		}
+	}
+}
+
+/// The factors to convert a raw reading (only available for full sensor
+/// records with linear conversion).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Conversion {
+	/// 0 unsigned, 1 one's complement, 2 two's complement
+	format: u8,
+	m: i16,
+	b: i16,
+	r_exp: i8,
+	b_exp: i8
+}
+
+impl Conversion {
+	fn convert(&self, raw: u8) -> f64 {
+		let x = match self.format {
+			1 if raw & 0x80 != 0 => -((!raw & 0x7f) as f64),
+			2 => raw as i8 as f64,
+			_ => raw as f64
+		};
+
+		let m = self.m as f64;
+		let b = self.b as f64 * 10f64.powi(self.b_exp as i32);
+		(m * x + b) * 10f64.powi(self.r_exp as i32)
+	}
+}
+
+fn sign_extend(val: u16, bits: u32) -> i16 {
+	let shift = 16 - bits;
+	((val << shift) as i16) >> shift
+}
+
+/// A full or compact sensor record from the sdr repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SensorRecord {
+	pub record_id: u16,
+	pub owner_id: u8,
+	pub owner_lun: u8,
+	pub number: u8,
+	pub entity_id: u8,
+	pub kind: SensorKind,
+	pub unit: SensorUnit,
+	pub name: String,
+	conversion: Option<Conversion>
+}
+
+impl SensorRecord {
+	fn parse(record_id: u16, d: &[u8]) -> Option<Self> {
+		let (name_at, conversion) = match *d.get(3)? {
+			// full sensor record
+			0x01 => {
+				// the name starts at 47
+				if d.len() < 48 {
+					return None
+				}
+				let format = d.get(20)? >> 6;
+				let linear = d.get(23)? & 0x7f == 0;
+				let conversion = (format != 3 && linear).then(|| Conversion {
+					format,
+					m: sign_extend(
+						d[24] as u16 | ((d[25] as u16 & 0xc0) << 2), 10
+					),
+					b: sign_extend(
+						d[26] as u16 | ((d[27] as u16 & 0xc0) << 2), 10
+					),
+					r_exp: sign_extend((d[29] >> 4) as u16, 4) as i8,
+					b_exp: sign_extend((d[29] & 0x0f) as u16, 4) as i8
+				});
+				(47, conversion)
+			},
+			// compact sensor record
+			0x02 => (31, None),
+			_ => return None
+		};
+
+		let name_len = (*d.get(name_at)? & 0x1f) as usize;
+		let name = d.get(name_at + 1..)?;
+		let name = &name[..name_len.min(name.len())];
+
+		Some(Self {
+			record_id,
+			owner_id: d[5],
+			owner_lun: d[6] & 0b11,
+			number: d[7],
+			entity_id: d[8],
+			kind: SensorKind::from_u8(d[12]),
+			unit: SensorUnit::from_u8(*d.get(21)?),
+			name: String::from_utf8_lossy(name).trim().into(),
+			conversion
+		})
+	}
+
+	/// Returns true if raw readings can be converted into a value.
+	pub fn is_analog(&self) -> bool {
+		self.conversion.is_some()
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct SensorReading {
+	pub raw: u8,
+	/// The converted value, only available for analog sensors.
+	pub value: Option<f64>,
+	/// The state bits of discrete sensors.
+	pub state: u16
+}
+
+impl SensorReading {
+	fn parse(record: &SensorRecord, d: &[u8]) -> Option<Self> {
+		let flags = *d.get(1)?;
+		// reading unavailable or scanning disabled
+		if flags & 0x20 != 0 || flags & 0x40 == 0 {
+			return None
+		}
+
+		let raw = d[0];
+		Some(Self {
+			raw,
+			value: record.conversion.map(|c| c.convert(raw)),
+			state: u16::from_le_bytes([
+				d.get(2).copied().unwrap_or(0),
+				d.get(3).copied().unwrap_or(0)
+			])
+		})
+	}
+}
+
+/// A sensor with its current value.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct Sensor {
+	pub name: String,
+	pub number: u8,
+	pub kind: SensorKind,
+	pub unit: SensorUnit,
+	pub raw: Option<u8>,
+	pub value: Option<f64>
+}
+
+/// The fru inventory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct Fru {
+	pub chassis: Option<FruChassis>,
+	pub board: Option<FruBoard>,
+	pub product: Option<FruProduct>
+}
+
+impl Fru {
+	fn parse(d: &[u8]) -> Option<Self> {
+		// common header
+		if d.len() < 8 || d[0] & 0x0f != 1 || checksum(&d[..8]) != 0 {
+			return None
+		}
+
+		let area = |i: usize| -> Option<&[u8]> {
+			let start = d[i] as usize * 8;
+			if start == 0 {
+				return None
+			}
+			let len = *d.get(start + 1)? as usize * 8;
+			d.get(start..start + len)
+		};
+
+		Some(Self {
+			chassis: area(2).and_then(FruChassis::parse),
+			board: area(3).and_then(FruBoard::parse),
+			product: area(4).and_then(FruProduct::parse)
+		})
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct FruChassis {
+	/// The smbios chassis type.
+	pub kind: u8,
+	pub part_number: String,
+	pub serial_number: String
+}
+
+impl FruChassis {
+	fn parse(d: &[u8]) -> Option<Self> {
+		let mut fields = FruFields::new(d.get(3..)?);
+		Some(Self {
+			kind: d[2],
+			part_number: fields.next()?,
+			serial_number: fields.next()?
+		})
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct FruBoard {
+	pub manufactured: Option<SystemTime>,
+	pub manufacturer: String,
+	pub product_name: String,
+	pub serial_number: String,
+	pub part_number: String
+}
+
+impl FruBoard {
+	fn parse(d: &[u8]) -> Option<Self> {
+		let date = d.get(3..6)?;
+		let minutes = u32::from_le_bytes([date[0], date[1], date[2], 0]);
+		// minutes since 1996-01-01 00:00 UTC, zero means unspecified
+		let manufactured = (minutes != 0).then(|| {
+			UNIX_EPOCH + Duration::from_secs(820_454_400 + minutes as u64 * 60)
+		});
+
+		let mut fields = FruFields::new(d.get(6..)?);
+		Some(Self {
+			manufactured,
+			manufacturer: fields.next()?,
+			product_name: fields.next()?,
+			serial_number: fields.next()?,
+			part_number: fields.next()?
+		})
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct FruProduct {
+	pub manufacturer: String,
+	pub name: String,
+	pub part_number: String,
+	pub version: String,
+	pub serial_number: String,
+	pub asset_tag: String
+}
+
+impl FruProduct {
+	fn parse(d: &[u8]) -> Option<Self> {
+		let mut fields = FruFields::new(d.get(3..)?);
+		Some(Self {
+			manufacturer: fields.next()?,
+			name: fields.next()?,
+			part_number: fields.next()?,
+			version: fields.next()?,
+			serial_number: fields.next()?,
+			asset_tag: fields.next()?
+		})
+	}
+}
+
+fn checksum(d: &[u8]) -> u8 {
+	d.iter().fold(0u8, |a, b| a.wrapping_add(*b))
+}
+
+/// Iterates over the type/length encoded fields of a fru area.
+struct FruFields<'a> {
+	d: &'a [u8]
+}
+
+impl<'a> FruFields<'a> {
+	fn new(d: &'a [u8]) -> Self {
+		Self { d }
+	}
+}
+
+impl Iterator for FruFields<'_> {
+	type Item = String;
+
+	fn next(&mut self) -> Option<String> {
+		let tl = *self.d.first()?;
+		// end of fields
+		if tl == 0xc1 {
+			return None
+		}
+
+		let len = (tl & 0x3f) as usize;
+		let data = self.d.get(1..1 + len)?;
+		self.d = &self.d[1 + len..];
+
+		let s = match tl >> 6 {
+			// binary
+			0 => data.iter().map(|b| format!("{:02x}", b)).collect(),
+			1 => decode_bcd_plus(data),
+			2 => decode_6bit_ascii(data),
+			_ => String::from_utf8_lossy(data).into_owned()
+		};
+		Some(s.trim().into())
+	}
+}
+
+fn decode_bcd_plus(d: &[u8]) -> String {
+	d.iter()
+		.flat_map(|b| [b >> 4, b & 0x0f])
+		.map(|n| match n {
+			0..=9 => (b'0' + n) as char,
+			0x0a => ' ',
+			0x0b => '-',
+			0x0c => '.',
+			_ => '?'
+		})
+		.collect()
+}
+
+fn decode_6bit_ascii(d: &[u8]) -> String {
+	let mut s = String::with_capacity(d.len() * 4 / 3);
+	for chunk in d.chunks(3) {
+		let mut v = [0u8; 3];
+		v[..chunk.len()].copy_from_slice(chunk);
+		let bits = u32::from_le_bytes([v[0], v[1], v[2], 0]);
+		let chars = (chunk.len() * 8) / 6;
+		for i in 0..chars {
+			let c = ((bits >> (i * 6)) & 0x3f) as u8;
+			s.push((c + 0x20) as char);
+		}
+	}
+	s
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn full_record(
+		number: u8,
+		kind: u8,
+		unit: u8,
+		m: u8,
+		exps: u8,
+		name: &str
+	) -> Vec<u8> {
+		let mut d = vec![0u8; 48];
+		d[2] = 0x51;
+		d[3] = 0x01;
+		d[5] = BMC_SLAVE_ADDR;
+		d[7] = number;
+		d[8] = 0x03;
+		d[12] = kind;
+		d[21] = unit;
+		d[24] = m;
+		d[29] = exps;
+		d[47] = 0xc0 | name.len() as u8;
+		d.extend_from_slice(name.as_bytes());
+		d[4] = (d.len() - 5) as u8;
+		d
+	}
+
+	#[test]
+	fn temperature_record() {
+		let d = full_record(1, 0x01, 1, 1, 0, "CPU Temp");
+		let record = SensorRecord::parse(1, &d).unwrap();
+		assert_eq!(record.name, "CPU Temp");
+		assert_eq!(record.number, 1);
+		assert_eq!(record.kind, SensorKind::Temperature);
+		assert_eq!(record.unit, SensorUnit::Celsius);
+		assert!(record.is_analog());
+
+		let reading = SensorReading::parse(&record, &[42, 0x40, 0, 0]).unwrap();
+		assert_eq!(reading.value, Some(42.0));
+
+		// reading unavailable
+		assert!(SensorReading::parse(&record, &[42, 0x60]).is_none());
+	}
+
+	#[test]
+	fn voltage_record() {
+		// m = 2, r_exp = -2
+		let d = full_record(2, 0x02, 4, 2, 0xe0, "12V");
+		let record = SensorRecord::parse(2, &d).unwrap();
+		assert_eq!(record.kind, SensorKind::Voltage);
+		assert_eq!(record.unit, SensorUnit::Volts);
+
+		let reading = SensorReading::parse(&record, &[96, 0x40]).unwrap();
+		let value = reading.value.unwrap();
+		assert!((value - 1.92).abs() < 1e-9);
+	}
+
+	#[test]
+	fn short_record() {
+		let d = full_record(1, 0x01, 1, 1, 0, "CPU Temp");
+		for len in [4, 24, 26, 29, 30, 47] {
+			assert!(SensorRecord::parse(1, &d[..len]).is_none());
+		}
+		assert!(SensorRecord::parse(1, &d[..48]).is_some());
+	}
+
+	#[test]
+	fn sign_extension() {
+		assert_eq!(sign_extend(0x3ff, 10), -1);
+		assert_eq!(sign_extend(0x1ff, 10), 511);
+		assert_eq!(sign_extend(0xe, 4), -2);
+	}
+
+	#[test]
+	fn device_id() {
+		let id = DeviceId::parse(&[
+			0x20, 0x81, 0x01, 0x23, 0x02, 0xbf, 0x57, 0x01, 0x00, 0x34, 0x12
+		]).unwrap();
+		assert_eq!(id.firmware_version, "1.23");
+		assert_eq!(id.ipmi_version, "2.0");
+		assert_eq!(id.manufacturer_id, 343);
+		assert_eq!(id.product_id, 0x1234);
+	}
+
+	#[test]
+	fn fru() {
+		let mut board = vec![0x01, 0x00, 0x00, 0x10, 0x00, 0x00];
+		for field in ["Vendor", "Board", "SN123", "PN456"] {
+			board.push(0xc0 | field.len() as u8);
+			board.extend_from_slice(field.as_bytes());
+		}
+		board.push(0xc1);
+		while (board.len() + 1) % 8 != 0 {
+			board.push(0);
+		}
+		board.push(0);
+		board[1] = (board.len() / 8) as u8;
+
+		let mut header = vec![0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00];
+		header.push(0u8.wrapping_sub(checksum(&header)));
+		let mut d = header;
+		d.extend_from_slice(&board);
+
+		let fru = Fru::parse(&d).unwrap();
+		assert!(fru.chassis.is_none());
+		assert!(fru.product.is_none());
+		let board = fru.board.unwrap();
+		assert_eq!(board.manufacturer, "Vendor");
+		assert_eq!(board.product_name, "Board");
+		assert_eq!(board.serial_number, "SN123");
+		assert_eq!(board.part_number, "PN456");
+		assert_eq!(
+			board.manufactured,
+			Some(UNIX_EPOCH + Duration::from_secs(820_454_400 + 0x10 * 60))
+		);
+	}
+
+	#[test]
+	fn fru_encodings() {
+		assert_eq!(decode_bcd_plus(&[0x12, 0xb3]), "12-3");
+		// "IPMI" in 6-bit packed ascii
+		assert_eq!(decode_6bit_ascii(&[0x29, 0xdc, 0xa6]), "IPMI");
+	}
+}
@@ -0,0 +1,418 @@
+//! Get the state of the running kernel which matters for patch compliance:
+//! loaded livepatches, module signature enforcement, lockdown and the taint
+//! flags.
+//!
+//! [`CoreDump`] shows if and where core dumps of crashing processes are
+//! written.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::kernel::{Livepatch, ModuleSignature};
+//!
+//! for patch in Livepatch::all().unwrap() {
+//!     println!("{:?} enabled {}", patch.name(), patch.enabled().unwrap());
+//! }
+//! let sig = ModuleSignature::read().unwrap();
+//! println!("unsigned modules can be loaded: {}", !sig.is_enforced());
+//! ```
+
+use crate::util::{read_sys_string, read_sys_value, read_sys_opt};
+
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+
+const LIVEPATCH_PATH: &str = "/sys/kernel/livepatch";
+const SIG_ENFORCE_PATH: &str = "/sys/module/module/parameters/sig_enforce";
+const LOCKDOWN_PATH: &str = "/sys/kernel/security/lockdown";
+const MODULES_DISABLED_PATH: &str = "/proc/sys/kernel/modules_disabled";
+const TAINTED_PATH: &str = "/proc/sys/kernel/tainted";
+const CORE_PATTERN_PATH: &str = "/proc/sys/kernel/core_pattern";
+const CORE_USES_PID_PATH: &str = "/proc/sys/kernel/core_uses_pid";
+const SUID_DUMPABLE_PATH: &str = "/proc/sys/fs/suid_dumpable";
+const COREDUMP_CONF_PATH: &str = "/etc/systemd/coredump.conf";
+const COREDUMP_CONF_DIR: &str = "/etc/systemd/coredump.conf.d";
+
+/// A loaded livepatch for example `/sys/kernel/livepatch/kpatch_5_15_0_1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Livepatch {
+	path: PathBuf
+}
+
+impl Livepatch {
+	/// Returns every loaded livepatch, empty if the kernel does not support
+	/// livepatching.
+	pub fn all() -> io::Result<Vec<Self>> {
+		let dir = match fs::read_dir(LIVEPATCH_PATH) {
+			Ok(d) => d,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+			Err(e) => return Err(e)
+		};
+
+		let mut list = dir.map(|e| e.map(|e| Self::from_path(e.path())))
+			.collect::<io::Result<Vec<_>>>()?;
+		list.sort_by(|a, b| a.path.cmp(&b.path));
+		Ok(list)
+	}
+
+	/// Creates a livepatch from a path like
+	/// `/sys/kernel/livepatch/kpatch_5_15_0_1`.
+	pub fn from_path(path: impl Into<PathBuf>) -> Self {
+		Self { path: path.into() }
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// The name of the patch module.
+	pub fn name(&self) -> Option<&str> {
+		self.path.file_name()?.to_str()
+	}
+
+	/// Returns true if the patch is applied.
+	pub fn enabled(&self) -> io::Result<bool> {
+		read_sys_value::<u8>(self.path.join("enabled")).map(|e| e != 0)
+	}
+
+	/// Returns true while tasks are still switched to or from the patch.
+	pub fn in_transition(&self) -> io::Result<bool> {
+		read_sys_value::<u8>(self.path.join("transition")).map(|t| t != 0)
+	}
+
+	/// The objects the patch changes, `vmlinux` for the kernel itself or
+	/// the name of a module.
+	pub fn objects(&self) -> io::Result<Vec<String>> {
+		let mut list = vec![];
+		for entry in fs::read_dir(&self.path)? {
+			let entry = entry?;
+			if !entry.file_type()?.is_dir() {
+				continue
+			}
+			if let Some(name) = entry.file_name().to_str() {
+				list.push(name.to_string());
+			}
+		}
+		list.sort();
+		Ok(list)
+	}
+}
+
+/// The kernel lockdown mode, see `man 7 kernel_lockdown`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub enum Lockdown {
+	None,
+	/// Prevents changing the running kernel, which includes loading
+	/// unsigned modules.
+	Integrity,
+	/// Integrity and additionally prevents reading kernel memory.
+	Confidentiality,
+	Unknown(String)
+}
+
+impl Lockdown {
+	/// Reads the active mode, `None` if the lockdown lsm is not enabled or
+	/// securityfs is not mounted.
+	pub fn read() -> io::Result<Option<Self>> {
+		read_sys_opt::<String>(LOCKDOWN_PATH)
+			.map(|l| l.map(|l| Self::parse(&l)))
+	}
+
+	/// Parses a line like `none [integrity] confidentiality`.
+	fn parse(s: &str) -> Self {
+		let active = s.split_whitespace()
+			.find_map(|l| l.strip_prefix('[')?.strip_suffix(']'))
+			.unwrap_or(s);
+		match active {
+			"none" => Self::None,
+			"integrity" => Self::Integrity,
+			"confidentiality" => Self::Confidentiality,
+			o => Self::Unknown(o.into())
+		}
+	}
+}
+
+/// If and how the kernel checks module signatures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct ModuleSignature {
+	/// True if `module.sig_enforce` is set or the kernel is built with
+	/// `CONFIG_MODULE_SIG_FORCE`, `None` if the kernel does not support
+	/// module signatures.
+	pub sig_enforce: Option<bool>,
+	/// `None` if lockdown is not available.
+	pub lockdown: Option<Lockdown>,
+	/// True if loading modules is disabled until the next boot.
+	pub modules_disabled: bool,
+	/// True if an unsigned module was loaded since boot.
+	pub unsigned_module_loaded: bool
+}
+
+impl ModuleSignature {
+	pub fn read() -> io::Result<Self> {
+		let sig_enforce = read_sys_opt::<String>(SIG_ENFORCE_PATH)?
+			.map(|s| s == "Y");
+		let modules_disabled = read_sys_opt::<u8>(MODULES_DISABLED_PATH)?
+			.map(|d| d != 0)
+			.unwrap_or(false);
+
+		Ok(Self {
+			sig_enforce,
+			lockdown: Lockdown::read()?,
+			modules_disabled,
+			unsigned_module_loaded: Taint::read()?.unsigned_module()
+		})
+	}
+
+	/// Returns true if unsigned modules cannot be loaded, either because
+	/// signatures are enforced, lockdown is active or loading modules is
+	/// disabled.
+	pub fn is_enforced(&self) -> bool {
+		let lockdown = matches!(
+			self.lockdown,
+			Some(Lockdown::Integrity) | Some(Lockdown::Confidentiality)
+		);
+		self.sig_enforce.unwrap_or(false) || lockdown || self.modules_disabled
+	}
+}
+
+/// The taint flags of the kernel from `/proc/sys/kernel/tainted`, see
+/// the kernel documentation `admin-guide/tainted-kernels`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct Taint(pub u64);
+
+impl Taint {
+	pub fn read() -> io::Result<Self> {
+		read_sys_value(TAINTED_PATH).map(Self)
+	}
+
+	fn bit(&self, bit: u32) -> bool {
+		self.0 & (1 << bit) != 0
+	}
+
+	/// Returns true if the kernel is not tainted.
+	pub fn is_clean(&self) -> bool {
+		self.0 == 0
+	}
+
+	/// A module with a non gpl license was loaded (`P`).
+	pub fn proprietary_module(&self) -> bool {
+		self.bit(0)
+	}
+
+	/// A module was force loaded (`F`).
+	pub fn forced_module(&self) -> bool {
+		self.bit(1)
+	}
+
+	/// A module which is not part of the kernel tree was loaded (`O`).
+	pub fn out_of_tree_module(&self) -> bool {
+		self.bit(12)
+	}
+
+	/// An unsigned module was loaded (`E`).
+	pub fn unsigned_module(&self) -> bool {
+		self.bit(13)
+	}
+
+	/// A livepatch was applied (`K`).
+	pub fn livepatched(&self) -> bool {
+		self.bit(15)
+	}
+
+	/// The letters as printed in kernel oops messages for example `POE`.
+	pub fn letters(&self) -> String {
+		"PFSRMBUDAWCIOELKXTN".chars()
+			.enumerate()
+			.filter(|(i, _)| self.bit(*i as u32))
+			.map(|(_, c)| c)
+			.collect()
+	}
+}
+
+/// Where core dumps are written and if the current process would write
+/// one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct CoreDump {
+	/// The file name pattern for example `core` or a program which receives
+	/// the dump like `|/usr/lib/systemd/systemd-coredump %P %u ...`.
+	pub pattern: String,
+	/// True if `.PID` is appended to the file name, if the pattern does not
+	/// contain `%p`.
+	pub uses_pid: bool,
+	/// If processes which changed their privileges (setuid) dump, 0 means
+	/// no, 1 yes and 2 only readable by root.
+	pub suid_dumpable: u8,
+	/// The soft `RLIMIT_CORE` of the current process in bytes, `None` if
+	/// unlimited.
+	pub soft_limit: Option<u64>,
+	/// The hard `RLIMIT_CORE` of the current process, `None` if unlimited.
+	pub hard_limit: Option<u64>,
+	/// The `Storage=` setting of systemd-coredump, `None` if the dumps are
+	/// not handled by systemd-coredump.
+	pub systemd_storage: Option<String>
+}
+
+impl CoreDump {
+	pub fn read() -> io::Result<Self> {
+		let pattern = read_sys_string(CORE_PATTERN_PATH)?;
+		let (soft_limit, hard_limit) = core_limits()?;
+
+		let mut dump = Self {
+			pattern,
+			uses_pid: read_sys_value::<u8>(CORE_USES_PID_PATH)? != 0,
+			suid_dumpable: read_sys_opt(SUID_DUMPABLE_PATH)?.unwrap_or(0),
+			soft_limit,
+			hard_limit,
+			systemd_storage: None
+		};
+		if dump.is_systemd_coredump() {
+			dump.systemd_storage = Some(systemd_storage()?);
+		}
+		Ok(dump)
+	}
+
+	/// The program which receives the dumps, `None` if they are written to
+	/// a file.
+	pub fn handler(&self) -> Option<&str> {
+		self.pattern.strip_prefix('|')?
+			.split_whitespace()
+			.next()
+	}
+
+	/// Returns true if the dumps are handled by systemd-coredump, use
+	/// `coredumpctl` to list them.
+	pub fn is_systemd_coredump(&self) -> bool {
+		self.handler()
+			.map(|h| h.ends_with("systemd-coredump"))
+			.unwrap_or(false)
+	}
+
+	/// Returns true if a crash of the current process would be captured.
+	///
+	/// A soft limit of zero disables dumps, which systemd-coredump also
+	/// respects, and systemd-coredump with `Storage=none` only logs the
+	/// crash.
+	pub fn is_enabled(&self) -> bool {
+		let storage = self.systemd_storage.as_deref()
+			.map(|s| s != "none")
+			.unwrap_or(true);
+		self.soft_limit != Some(0) && !self.pattern.is_empty() && storage
+	}
+}
+
+// rlim_t is not u64 on every target
+#[allow(clippy::unnecessary_cast)]
+fn core_limits() -> io::Result<(Option<u64>, Option<u64>)> {
+	let mut limit = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+	let r = unsafe { libc::getrlimit(libc::RLIMIT_CORE, &mut limit) };
+	if r != 0 {
+		return Err(io::Error::last_os_error())
+	}
+
+	let finite = |l: libc::rlim_t| {
+		Some(l).filter(|l| *l != libc::RLIM_INFINITY).map(|l| l as u64)
+	};
+	Ok((finite(limit.rlim_cur), finite(limit.rlim_max)))
+}
+
+/// Reads `Storage=` from coredump.conf and its drop-ins, the last one wins.
+fn systemd_storage() -> io::Result<String> {
+	let mut files = vec![PathBuf::from(COREDUMP_CONF_PATH)];
+	if let Ok(dir) = fs::read_dir(COREDUMP_CONF_DIR) {
+		let mut drop_ins = dir.filter_map(|e| e.ok())
+			.map(|e| e.path())
+			.filter(|p| p.extension().map(|e| e == "conf").unwrap_or(false))
+			.collect::<Vec<_>>();
+		drop_ins.sort();
+		files.extend(drop_ins);
+	}
+
+	let mut storage = None;
+	for file in files {
+		let conf = match fs::read_to_string(&file) {
+			Ok(c) => c,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+			Err(e) => return Err(e)
+		};
+		storage = parse_storage(&conf).or(storage);
+	}
+	Ok(storage.unwrap_or_else(|| "external".into()))
+}
+
+fn parse_storage(conf: &str) -> Option<String> {
+	conf.lines()
+		.filter_map(|l| l.trim().strip_prefix("Storage="))
+		.next_back()
+		.map(|s| s.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn lockdown() {
+		assert_eq!(
+			Lockdown::parse("none [integrity] confidentiality"),
+			Lockdown::Integrity
+		);
+		assert_eq!(
+			Lockdown::parse("[none] integrity confidentiality"),
+			Lockdown::None
+		);
+	}
+
+	#[test]
+	fn taint() {
+		// P, O and E
+		let taint = Taint(1 | 1 << 12 | 1 << 13);
+		assert!(taint.proprietary_module());
+		assert!(taint.unsigned_module());
+		assert!(!taint.livepatched());
+		assert_eq!(taint.letters(), "POE");
+		assert!(Taint(0).is_clean());
+	}
+
+	#[test]
+	fn core_dump() {
+		let mut dump = CoreDump {
+			pattern: "|/usr/lib/systemd/systemd-coredump %P %u %g %s %t \
+				%c %h".into(),
+			uses_pid: false,
+			suid_dumpable: 2,
+			soft_limit: None,
+			hard_limit: None,
+			systemd_storage: parse_storage("[Coredump]\n#Storage=external\n\
+				Storage=none\n")
+		};
+		assert_eq!(dump.handler(), Some("/usr/lib/systemd/systemd-coredump"));
+		assert!(dump.is_systemd_coredump());
+		assert!(!dump.is_enabled());
+
+		dump.pattern = "core".into();
+		dump.systemd_storage = None;
+		assert_eq!(dump.handler(), None);
+		assert!(dump.is_enabled());
+		dump.soft_limit = Some(0);
+		assert!(!dump.is_enabled());
+	}
+}
@@ -1,25 +1,192 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![doc = include_str!("../README.md")]
 
+/// Generates an enum for a numeric dbus value with safe conversions from and
+/// to the raw value. Values which are not known are kept in the `Unknown`
+/// variant, so new values added by the daemon are not lost.
+///
+/// Variants are ordered by their raw value.
+#[cfg(any(
+	feature = "network-manager",
+	feature = "modem-manager",
+	feature = "upower",
+	feature = "zbus"
+))]
+macro_rules! dbus_enum {
+	(
+		$(#[$attr:meta])*
+		pub enum $name:ident: $ty:ty {
+			$(#[$unknown_doc:meta])*
+			Unknown,
+			$(
+				$(#[$doc:meta])*
+				$var:ident = $val:literal
+			),*
+		}
+	) => (
+		$(#[$attr])*
+		pub enum $name {
+			$(#[$unknown_doc])*
+			Unknown($ty),
+			$(
+				$(#[$doc])*
+				$var
+			),*
+		}
+
+		impl $name {
+			/// Returns the raw value.
+			pub fn value(&self) -> $ty {
+				match self {
+					Self::Unknown(n) => *n,
+					$(Self::$var => $val),*
+				}
+			}
+		}
+
+		impl From<$ty> for $name {
+			fn from(num: $ty) -> Self {
+				match num {
+					$($val => Self::$var,)*
+					n => Self::Unknown(n)
+				}
+			}
+		}
+
+		impl From<$name> for $ty {
+			fn from(v: $name) -> Self {
+				v.value()
+			}
+		}
+
+		impl PartialOrd for $name {
+			fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+				Some(self.cmp(other))
+			}
+		}
+
+		impl Ord for $name {
+			fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+				self.value().cmp(&other.value())
+			}
+		}
+	)
+}
+
 /// Get cpu information.
 pub mod cpu;
 /// Get memory information.
 pub mod memory;
 // Get system information (uptime, hostname, usernames, groups).
 pub mod system;
+/// Get the livepatch, module signature, taint and core dump state of the
+/// kernel.
+pub mod kernel;
 // Get storage information (partitions, mounts, stats, raids).
 pub mod storage;
+/// Get information about processes and their sockets.
+pub mod process;
+/// Get the inotify and fanotify limits and usage per user.
+pub mod fsnotify;
 #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
 /// get bios / system information
 pub mod bios;
-#[cfg(feature = "network")]
-#[cfg_attr(docsrs, doc(cfg(feature = "network")))]
+/// Get network information.
 pub mod network;
+/// Get power information.
+pub mod power;
+/// Read hardware sensors (temperatures, fans, power).
+pub mod sensors;
+/// Get gpu information and metrics.
+pub mod gpu;
+/// List pci devices with their link state and error counters.
+pub mod pci;
+/// List usb devices and their power state.
+pub mod usb;
+/// List hid devices.
+pub mod hidraw;
+/// Get trusted platform module information.
+pub mod tpm;
+/// Identify boards using the device tree.
+pub mod devicetree;
+/// List ptp hardware clocks.
+pub mod ptp;
+/// Get notified about hotplug events.
+pub mod hotplug;
+/// Get nfs server exports and statistics.
+pub mod nfs;
+#[cfg(feature = "ipmi")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ipmi")))]
+pub mod ipmi;
+#[cfg(feature = "audio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "audio")))]
+/// Get the default audio devices and their volume.
+pub mod audio;
+/// Collect a snapshot of the whole system.
+pub mod snapshot;
+/// Periodically sample cpu, memory, disk and network usage.
+pub mod monitor;
+/// Compute rates from kernel counters.
+pub mod rate;
+/// Compare readings and get what changed.
+pub mod diff;
+/// Cache readings for a given time.
+pub mod cache;
+#[cfg(feature = "metrics")]
+#[cfg_attr(docsrs, doc(cfg(feature = "metrics")))]
+/// Export prometheus metrics.
+pub mod metrics;
 
 mod util;
+pub mod error;
+
+pub use error::{Error, Result};
 
 
 pub mod unit {
 	use super::*;
-	pub use util::{DataSize, DataSizeUnit};
-}
\ No newline at end of file
+	pub use util::{
+		DataSize, DataSizeUnit, ParseDataSizeError, UnitSystem, DataSizeDisplay
+	};
+	#[cfg(feature = "serde")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+	pub use util::human;
+}
+
+#[cfg(all(test, any(
+	feature = "network-manager",
+	feature = "modem-manager",
+	feature = "upower",
+	feature = "zbus"
+)))]
+mod tests {
+	dbus_enum! {
+		#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+		pub enum State: u32 {
+			Unknown,
+			Off = 1,
+			On = 10
+		}
+	}
+
+	#[test]
+	fn dbus_enum() {
+		assert_eq!(State::from(1), State::Off);
+		assert_eq!(State::from(10), State::On);
+		assert_eq!(State::from(0), State::Unknown(0));
+		assert_eq!(State::from(42), State::Unknown(42));
+
+		for raw in [0, 1, 5, 10, 42] {
+			assert_eq!(State::from(raw).value(), raw);
+			assert_eq!(u32::from(State::from(raw)), raw);
+		}
+
+		assert!(State::Unknown(0) < State::Off);
+		assert!(State::Off < State::Unknown(5));
+		assert!(State::Unknown(5) < State::On);
+		assert!(State::On < State::Unknown(42));
+		let mut states = vec![State::On, State::Unknown(3), State::Off];
+		states.sort();
+		assert_eq!(states, [State::Off, State::Unknown(3), State::On]);
+	}
+}
@@ -1,5 +1,9 @@
 //!
-//! The data is retrieved from `/proc/meminfo`
+//! The data is retrieved from `/proc/meminfo`, virtual memory statistics
+//! from `/proc/vmstat`.
+//!
+//! Inside a container `MemTotal` is the memory of the host, use
+//! [`MemoryLimit`] to get the memory the process can actually use.
 //!
 //! To list all availabe key's [linuxwiki.org](https://linuxwiki.org/proc/meminfo). Or you can use the api
 //! ```
@@ -9,10 +13,20 @@
 //! ```
 
 use crate::unit::DataSize;
-use crate::util::read_to_string_mut;
+use crate::rate::Rate;
+use crate::diff::{Change, diff_by_key};
+use crate::util::{read_to_string, read_to_string_mut, line_of};
+use crate::error::{self, Error};
+use crate::system::{cgroup_memory_file, parse_memory_limit};
+#[cfg(feature = "async")]
+use crate::util::{read_to_string_async, read_to_string_mut_async};
 
 use std::path::Path;
-use std::{fs, io};
+use std::io;
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde1::ser::{Serialize, Serializer};
 
 /// Read memory information from /proc/meminfo.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -27,14 +41,14 @@ impl Memory {
 	}
 
 	#[cfg(test)]
-	fn from_string(raw: String) -> Self {
+	pub(crate) fn from_string(raw: String) -> Self {
 		Self {raw}
 	}
 
 	/// Read memory infos from /proc/meminfo.
 	pub fn read() -> io::Result<Self> {
 		Ok(Self {
-			raw: fs::read_to_string(Self::path())?
+			raw: read_to_string(Self::path())?
 		})
 	}
 
@@ -43,6 +57,22 @@ impl Memory {
 		read_to_string_mut(Self::path(), &mut self.raw)
 	}
 
+	/// Reads the file without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string_async(Self::path()).await?
+		})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(Self::path(), &mut self.raw).await
+	}
+
 	/// Get all key and values.
 	pub fn values<'a>(&'a self) -> impl Iterator<Item=(&'a str, &'a str)> {
 		self.raw.split('\n')
@@ -66,10 +96,16 @@ impl Memory {
 			.find_map(|(k, v)| k.eq_ignore_ascii_case(key).then(|| v))
 	}
 
+	/// Returns every key which was added, removed or whose value changed
+	/// compared to the newer reading.
+	pub fn diff<'a>(&'a self, newer: &'a Self) -> Vec<Change<&'a str, &'a str>> {
+		diff_by_key(self.values(), newer.values())
+	}
+
 	/// Get size by key.
 	pub fn size_value<'a>(&'a self, key: &str) -> Option<DataSize> {
 		self.value(key)
-			.and_then(DataSize::from_str)
+			.and_then(DataSize::from_kernel_str)
 	}
 
 	/// Returns the total memory.
@@ -87,11 +123,294 @@ impl Memory {
 		self.size_value("MemAvailable")
 	}
 
+	/// Like [`Memory::value`] but returns an error if the key is missing.
+	pub fn try_value<'a>(&'a self, key: &str) -> error::Result<&'a str> {
+		self.value(key)
+			.ok_or_else(|| Error::missing_field(Self::path(), key))
+	}
+
+	/// Like [`Memory::size_value`] but returns an error naming the line if
+	/// the key is missing or the value can't be parsed.
+	pub fn try_size_value(&self, key: &str) -> error::Result<DataSize> {
+		let value = self.try_value(key)?;
+		DataSize::from_kernel_str(value)
+			.ok_or_else(|| Error::parse_field(
+				Self::path(),
+				Some(line_of(&self.raw, value)),
+				key,
+				format!("{:?}", value)
+			))
+	}
+
+	/// Like [`Memory::total_memory`] but returns an error.
+	pub fn try_total_memory(&self) -> error::Result<DataSize> {
+		self.try_size_value("MemTotal")
+	}
+
+	/// Like [`Memory::free_memory`] but returns an error.
+	pub fn try_free_memory(&self) -> error::Result<DataSize> {
+		self.try_size_value("MemFree")
+	}
+
+	/// Like [`Memory::available_memory`] but returns an error.
+	pub fn try_available_memory(&self) -> error::Result<DataSize> {
+		self.try_size_value("MemAvailable")
+	}
+
+}
+
+/// Read virtual memory statistics from /proc/vmstat.
+///
+/// Most values are counters since boot, use [`VmStat::rate`] to get the
+/// change per second.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VmStat {
+	raw: String
+}
+
+impl VmStat {
+
+	fn path() -> &'static Path {
+		Path::new("/proc/vmstat")
+	}
+
+	#[cfg(test)]
+	fn from_string(raw: String) -> Self {
+		Self {raw}
+	}
+
+	/// Read virtual memory statistics from /proc/vmstat.
+	pub fn read() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string(Self::path())?
+		})
+	}
+
+	/// Reloads information without allocating.
+	pub fn reload(&mut self) -> io::Result<()> {
+		read_to_string_mut(Self::path(), &mut self.raw)
+	}
+
+	/// Reads the file without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string_async(Self::path()).await?
+		})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(Self::path(), &mut self.raw).await
+	}
+
+	/// Get all keys and values.
+	pub fn values(&self) -> impl Iterator<Item=(&str, u64)> {
+		self.raw.lines()
+			.filter_map(|line| {
+				let (key, value) = line.split_once(' ')?;
+				Some((key, value.trim().parse().ok()?))
+			})
+	}
+
+	/// Get value by key for example `pgfault`.
+	pub fn value(&self, key: &str) -> Option<u64> {
+		self.values()
+			.find_map(|(k, v)| (k == key).then(|| v))
+	}
+
+	/// Returns every key which was added, removed or whose value changed
+	/// compared to the newer reading.
+	pub fn diff<'a>(&'a self, newer: &'a Self) -> Vec<Change<&'a str, u64>> {
+		diff_by_key(self.values(), newer.values())
+	}
+
+	/// Computes the change per second of a counter since an earlier reading.
+	pub fn rate(
+		&self,
+		earlier: &Self,
+		key: &str,
+		elapsed: Duration
+	) -> Option<Rate> {
+		Some(Rate::from_counters(
+			self.value(key)?,
+			earlier.value(key)?,
+			elapsed
+		))
+	}
+
+}
+
+/// The memory the current process can use, considering the cgroup limits,
+/// the memory reserved for hugepages and swap.
+///
+/// This is the number heap sizing logic wants instead of `MemTotal`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct MemoryLimit {
+	/// `MemTotal` of the host.
+	pub total: DataSize,
+	/// `MemAvailable` of the host.
+	pub host_available: DataSize,
+	/// The memory reserved for hugepages, which normal allocations cannot
+	/// use.
+	pub hugepages: DataSize,
+	/// The lowest memory limit of the cgroup and its parents, `None` if
+	/// there is no limit.
+	pub cgroup_limit: Option<DataSize>,
+	/// The memory the cgroup uses, including the page cache.
+	pub cgroup_usage: Option<DataSize>,
+	/// The swap the process can use.
+	pub swap: DataSize
+}
+
+impl MemoryLimit {
+	/// Reads `/proc/meminfo` and the cgroup of the current process.
+	///
+	/// The cgroup is read on a best effort basis, if it cannot be read the
+	/// limits of the host are returned.
+	pub fn read() -> io::Result<Self> {
+		let memory = Memory::read()?;
+		let cgroup = read_to_string("/proc/self/cgroup").ok()
+			.and_then(|raw| cgroup_memory_file(&raw))
+			.map(|path| CgroupMemory::read(&path))
+			.unwrap_or_default();
+		Self::new(&memory, cgroup)
+	}
+
+	fn new(memory: &Memory, cgroup: CgroupMemory) -> io::Result<Self> {
+		let total = memory.try_total_memory()?;
+		let swap_total = memory.size_value("SwapTotal").unwrap_or_default();
+
+		// Hugetlb contains every hugepage size but requires linux 4.17
+		let hugepages = memory.size_value("Hugetlb").or_else(|| {
+			let count: u128 = memory.value("HugePages_Total")?.parse().ok()?;
+			let size = memory.size_value("Hugepagesize")?;
+			Some(DataSize::from_bytes(count * size.as_bytes()))
+		}).unwrap_or_default();
+
+		Ok(Self {
+			total,
+			host_available: memory.available_memory()
+				.or_else(|| memory.free_memory())
+				.unwrap_or_default(),
+			hugepages,
+			cgroup_limit: cgroup.limit,
+			cgroup_usage: cgroup.usage,
+			swap: cgroup.swap_limit
+				.map(|l| l.min(swap_total))
+				.unwrap_or(swap_total)
+		})
+	}
+
+	/// The memory the process can use without swapping, the lower of the
+	/// cgroup limit and the host memory without hugepages.
+	pub fn effective(&self) -> DataSize {
+		let host = self.total - self.hugepages;
+		match self.cgroup_limit {
+			Some(limit) => limit.min(host),
+			None => host
+		}
+	}
+
+	/// The memory the process can use including swap.
+	pub fn effective_with_swap(&self) -> DataSize {
+		self.effective() + self.swap
+	}
+
+	/// The memory which can still be allocated without swapping, the lower
+	/// of `MemAvailable` and what is left until the cgroup limit.
+	///
+	/// The cgroup usage includes the page cache, which can be reclaimed, so
+	/// this is a lower bound.
+	pub fn available(&self) -> DataSize {
+		match (self.cgroup_limit, self.cgroup_usage) {
+			(Some(limit), Some(usage)) => {
+				(limit - usage).min(self.host_available)
+			},
+			_ => self.host_available
+		}
+	}
+}
+
+/// Returns the memory the current process can use, see
+/// [`MemoryLimit::effective`].
+pub fn effective_memory() -> io::Result<DataSize> {
+	MemoryLimit::read().map(|l| l.effective())
+}
+
+#[derive(Debug, Default)]
+struct CgroupMemory {
+	limit: Option<DataSize>,
+	usage: Option<DataSize>,
+	swap_limit: Option<DataSize>
+}
+
+impl CgroupMemory {
+	/// Reads the cgroup from the file containing the memory limit, which
+	/// is `memory.max` for cgroup v2 and `memory.limit_in_bytes` for v1.
+	fn read(limit_file: &Path) -> Self {
+		let dir = match limit_file.parent() {
+			Some(d) => d,
+			None => return Self::default()
+		};
+		let v2 = limit_file.ends_with("memory.max");
+
+		// a parent cgroup can have a lower limit
+		let lowest = |name: &str| {
+			dir.ancestors()
+				.take_while(|d| d.starts_with("/sys/fs/cgroup"))
+				.filter_map(|d| read_to_string(d.join(name)).ok())
+				.filter_map(|l| parse_memory_limit(&l))
+				.min()
+		};
+		let usage = |name: &str| {
+			read_to_string(dir.join(name)).ok()
+				.and_then(|u| u.trim().parse::<u128>().ok())
+				.map(DataSize::from_bytes)
+		};
+
+		let limit = lowest(if v2 { "memory.max" } else {
+			"memory.limit_in_bytes"
+		});
+		let swap_limit = if v2 {
+			lowest("memory.swap.max")
+		} else {
+			// v1 limits memory and swap together
+			lowest("memory.memsw.limit_in_bytes")
+				.map(|l| l - limit.unwrap_or_default())
+		};
+
+		Self {
+			limit,
+			usage: usage(if v2 { "memory.current" } else {
+				"memory.usage_in_bytes"
+			}),
+			swap_limit
+		}
+	}
+}
+
+/// Serializes every key and value.
+#[cfg(feature = "serde")]
+impl Serialize for Memory {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where S: Serializer {
+		serializer.collect_map(self.values())
+	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::diff::ChangeKind;
 	use crate::unit::DataSizeUnit;
 
 	fn memory_info() -> Memory {
@@ -157,4 +476,100 @@ DirectMap1G:    22020096 kB\
 		assert_eq!(total_memory.to(&DataSizeUnit::Kb), 32853280.0);
 	}
 
-}
\ No newline at end of file
+	#[test]
+	fn memory_limit() {
+		let mem = memory_info();
+		let host = MemoryLimit::new(&mem, CgroupMemory::default()).unwrap();
+		assert_eq!(host.effective(), DataSize::from_kib(32853280.0));
+		assert_eq!(host.available(), DataSize::from_kib(28781828.0));
+		assert_eq!(
+			host.effective_with_swap(),
+			DataSize::from_kib(32853280.0 + 2097148.0)
+		);
+
+		let cgroup = CgroupMemory {
+			limit: Some(DataSize::from_gib(4.0)),
+			usage: Some(DataSize::from_gib(1.0)),
+			swap_limit: Some(DataSize::from_bytes(0))
+		};
+		let limit = MemoryLimit::new(&mem, cgroup).unwrap();
+		assert_eq!(limit.effective(), DataSize::from_gib(4.0));
+		assert_eq!(limit.effective_with_swap(), DataSize::from_gib(4.0));
+		assert_eq!(limit.available(), DataSize::from_gib(3.0));
+
+		// 1024 reserved 2 MiB pages
+		let mem = Memory::from_string("\
+MemTotal:        4194304 kB
+MemAvailable:    1048576 kB
+HugePages_Total:    1024
+Hugepagesize:       2048 kB
+".into());
+		let limit = MemoryLimit::new(&mem, CgroupMemory::default()).unwrap();
+		assert_eq!(limit.hugepages, DataSize::from_gib(2.0));
+		assert_eq!(limit.effective(), DataSize::from_gib(2.0));
+	}
+
+	#[test]
+	fn strict_values() {
+		let mem = Memory::from_string("\
+MemTotal:       32853280 kB
+MemFree:             abc kB
+".into());
+		let e = mem.try_free_memory().unwrap_err();
+		assert_eq!(
+			e.to_string(),
+			"/proc/meminfo:2: failed to parse MemFree from \"abc kB\""
+		);
+		let e = mem.try_available_memory().unwrap_err();
+		assert!(matches!(e, Error::MissingField { .. }));
+	}
+
+	#[test]
+	fn vm_stat() {
+		let earlier = VmStat::from_string("\
+nr_free_pages 229669
+pgpgin 30489752
+pgfault 341253820
+".into());
+		let stat = VmStat::from_string("\
+nr_free_pages 229001
+pgpgin 30489752
+pgfault 341254820
+".into());
+		assert_eq!(stat.values().count(), 3);
+		assert_eq!(stat.value("nr_free_pages"), Some(229001));
+		assert_eq!(stat.value("pswpin"), None);
+		let rate = stat.rate(&earlier, "pgfault", Duration::from_secs(4));
+		assert_eq!(rate.unwrap().per_second(), 250.0);
+		let keys: Vec<_> = earlier.diff(&stat).into_iter()
+			.map(|c| c.key)
+			.collect();
+		assert_eq!(keys, ["nr_free_pages", "pgfault"]);
+	}
+
+	#[test]
+	fn memory_diff() {
+		let earlier = Memory::from_string("\
+MemTotal:       32853280 kB
+MemFree:          919776 kB
+Buffers:          298460 kB
+".into());
+		let mem = Memory::from_string("\
+MemTotal:       32853280 kB
+MemFree:          819776 kB
+MemAvailable:   28781828 kB
+".into());
+		let diff = earlier.diff(&mem);
+		assert_eq!(diff.len(), 3);
+		assert_eq!(diff[0].key, "MemFree");
+		assert_eq!(diff[0].kind(), ChangeKind::Changed);
+		assert_eq!(diff[0].old, Some("919776 kB"));
+		assert_eq!(diff[0].new, Some("819776 kB"));
+		assert_eq!(diff[1].key, "MemAvailable");
+		assert_eq!(diff[1].kind(), ChangeKind::Added);
+		assert_eq!(diff[2].key, "Buffers");
+		assert_eq!(diff[2].kind(), ChangeKind::Removed);
+		assert_eq!(diff[2].old, Some("298460 kB"));
+		assert!(mem.diff(&mem).is_empty());
+	}
+}
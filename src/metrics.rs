@@ -0,0 +1,447 @@
+//! Export the readers as prometheus metrics.
+//!
+//! [`SystemCollector`] reads every value when the registry is gathered, like
+//! the node exporter does.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::metrics::SystemCollector;
+//! use linux_info::metrics::prometheus::{Registry, TextEncoder};
+//!
+//! let registry = Registry::new();
+//! registry.register(Box::new(SystemCollector::new().unwrap())).unwrap();
+//!
+//! let text = TextEncoder::new()
+//!     .encode_to_string(&registry.gather())
+//!     .unwrap();
+//! println!("{}", text);
+//! ```
+
+use crate::system::{self, Stat, LoadAvg};
+use crate::memory::Memory;
+use crate::storage::DiskStats;
+use crate::network::interfaces::NetDev;
+use crate::sensors::Hwmon;
+use crate::unit::DataSize;
+
+use std::sync::Mutex;
+
+pub use prometheus;
+use prometheus::{Opts, Gauge, GaugeVec, CounterVec, Result};
+use prometheus::core::{Collector, Desc};
+use prometheus::proto::MetricFamily;
+
+const NAMESPACE: &str = "linux";
+
+fn opts(name: &str, help: &str) -> Opts {
+	Opts::new(name, help).namespace(NAMESPACE)
+}
+
+fn bytes(size: Option<DataSize>) -> Option<f64> {
+	size.map(|s| s.as_bytes() as f64)
+}
+
+/// Collects cpu, load, memory, disk, network and hwmon sensor metrics.
+///
+/// Sources which can't be read are skipped.
+///
+/// The hwmon metrics are labeled with the chip name, the hwmon directory
+/// (`hwmon0`) and the sensor label.
+#[derive(Debug)]
+pub struct SystemCollector {
+	// prevents concurrent scrapes from mixing up the values
+	lock: Mutex<()>,
+	clock_ticks: f64,
+	cpu_seconds: CounterVec,
+	load1: Gauge,
+	load5: Gauge,
+	load15: Gauge,
+	memory: GaugeVec,
+	disk_read_bytes: CounterVec,
+	disk_written_bytes: CounterVec,
+	disk_reads: CounterVec,
+	disk_writes: CounterVec,
+	disk_io_seconds: CounterVec,
+	network_receive_bytes: CounterVec,
+	network_transmit_bytes: CounterVec,
+	network_receive_packets: CounterVec,
+	network_transmit_packets: CounterVec,
+	network_receive_errors: CounterVec,
+	network_transmit_errors: CounterVec,
+	network_receive_drops: CounterVec,
+	network_transmit_drops: CounterVec,
+	temperature: GaugeVec,
+	fan: GaugeVec,
+	power: GaugeVec
+}
+
+impl SystemCollector {
+	pub fn new() -> Result<Self> {
+		let ticks = system::clock_ticks().unwrap_or(100);
+
+		let disk = |name, help| CounterVec::new(opts(name, help), &["device"]);
+		let net = disk;
+		let hwmon = |name, help| {
+			GaugeVec::new(opts(name, help), &["chip", "hwmon", "sensor"])
+		};
+
+		Ok(Self {
+			lock: Mutex::new(()),
+			clock_ticks: ticks as f64,
+			cpu_seconds: CounterVec::new(
+				opts("cpu_seconds_total", "Seconds the cpus spent in each mode."),
+				&["cpu", "mode"]
+			)?,
+			load1: Gauge::with_opts(opts("load1", "1m load average."))?,
+			load5: Gauge::with_opts(opts("load5", "5m load average."))?,
+			load15: Gauge::with_opts(opts("load15", "15m load average."))?,
+			memory: GaugeVec::new(
+				opts("memory_bytes", "Memory information from /proc/meminfo."),
+				&["kind"]
+			)?,
+			disk_read_bytes: disk(
+				"disk_read_bytes_total",
+				"The total number of bytes read."
+			)?,
+			disk_written_bytes: disk(
+				"disk_written_bytes_total",
+				"The total number of bytes written."
+			)?,
+			disk_reads: disk(
+				"disk_reads_completed_total",
+				"The total number of reads completed."
+			)?,
+			disk_writes: disk(
+				"disk_writes_completed_total",
+				"The total number of writes completed."
+			)?,
+			disk_io_seconds: disk(
+				"disk_io_time_seconds_total",
+				"Total seconds spent doing I/Os."
+			)?,
+			network_receive_bytes: net(
+				"network_receive_bytes_total",
+				"The total number of bytes received."
+			)?,
+			network_transmit_bytes: net(
+				"network_transmit_bytes_total",
+				"The total number of bytes transmitted."
+			)?,
+			network_receive_packets: net(
+				"network_receive_packets_total",
+				"The total number of packets received."
+			)?,
+			network_transmit_packets: net(
+				"network_transmit_packets_total",
+				"The total number of packets transmitted."
+			)?,
+			network_receive_errors: net(
+				"network_receive_errors_total",
+				"The total number of receive errors."
+			)?,
+			network_transmit_errors: net(
+				"network_transmit_errors_total",
+				"The total number of transmit errors."
+			)?,
+			network_receive_drops: net(
+				"network_receive_drop_total",
+				"The total number of dropped received packets."
+			)?,
+			network_transmit_drops: net(
+				"network_transmit_drop_total",
+				"The total number of dropped transmitted packets."
+			)?,
+			temperature: hwmon(
+				"hwmon_temperature_celsius",
+				"Hardware monitor temperature."
+			)?,
+			fan: hwmon("hwmon_fan_rpm", "Hardware monitor fan speed.")?,
+			power: hwmon("hwmon_power_watts", "Hardware monitor power usage.")?
+		})
+	}
+
+	fn counters(&self) -> [&CounterVec; 14] {
+		[
+			&self.cpu_seconds,
+			&self.disk_read_bytes,
+			&self.disk_written_bytes,
+			&self.disk_reads,
+			&self.disk_writes,
+			&self.disk_io_seconds,
+			&self.network_receive_bytes,
+			&self.network_transmit_bytes,
+			&self.network_receive_packets,
+			&self.network_transmit_packets,
+			&self.network_receive_errors,
+			&self.network_transmit_errors,
+			&self.network_receive_drops,
+			&self.network_transmit_drops
+		]
+	}
+
+	fn gauges(&self) -> [&GaugeVec; 4] {
+		[&self.memory, &self.temperature, &self.fan, &self.power]
+	}
+
+	fn update_cpu(&self, stat: &Stat) {
+		let cpus = stat.values()
+			.filter_map(|(k, v)| Some((k.strip_prefix("cpu")?, v)))
+			// skip the total
+			.filter(|(n, _)| !n.is_empty());
+
+		for (cpu, values) in cpus {
+			let modes = [
+				"user", "nice", "system", "idle", "iowait", "irq", "softirq",
+				"steal"
+			];
+			for (mode, v) in modes.iter().zip(values) {
+				self.cpu_seconds.with_label_values(&[cpu, mode])
+					.inc_by(v as f64 / self.clock_ticks);
+			}
+		}
+	}
+
+	fn update_load(&self, load: &LoadAvg) {
+		if let Some((one, five, fifteen)) = load.average() {
+			self.load1.set(one.into());
+			self.load5.set(five.into());
+			self.load15.set(fifteen.into());
+		}
+	}
+
+	fn update_memory(&self, mem: &Memory) {
+		let kinds = [
+			("total", "MemTotal"),
+			("free", "MemFree"),
+			("available", "MemAvailable"),
+			("buffers", "Buffers"),
+			("cached", "Cached"),
+			("swap_total", "SwapTotal"),
+			("swap_free", "SwapFree")
+		];
+		for (kind, key) in kinds.iter() {
+			if let Some(v) = bytes(mem.size_value(key)) {
+				self.memory.with_label_values(&[kind]).set(v);
+			}
+		}
+	}
+
+	fn update_disks(&self, stats: &DiskStats) {
+		for d in stats.entries() {
+			let l = &[d.name.as_str()];
+			self.disk_read_bytes.with_label_values(l)
+				.inc_by(d.sectors_read as f64 * 512.0);
+			self.disk_written_bytes.with_label_values(l)
+				.inc_by(d.sectors_written as f64 * 512.0);
+			self.disk_reads.with_label_values(l)
+				.inc_by(d.reads_completed as f64);
+			self.disk_writes.with_label_values(l)
+				.inc_by(d.writes_completed as f64);
+			self.disk_io_seconds.with_label_values(l)
+				.inc_by(d.time_io as f64 / 1000.0);
+		}
+	}
+
+	fn update_network(&self, dev: &NetDev) {
+		for e in dev.entries() {
+			let l = &[e.name.as_str()];
+			self.network_receive_bytes.with_label_values(l)
+				.inc_by(e.rx_bytes as f64);
+			self.network_transmit_bytes.with_label_values(l)
+				.inc_by(e.tx_bytes as f64);
+			self.network_receive_packets.with_label_values(l)
+				.inc_by(e.rx_packets as f64);
+			self.network_transmit_packets.with_label_values(l)
+				.inc_by(e.tx_packets as f64);
+			self.network_receive_errors.with_label_values(l)
+				.inc_by(e.rx_errors as f64);
+			self.network_transmit_errors.with_label_values(l)
+				.inc_by(e.tx_errors as f64);
+			self.network_receive_drops.with_label_values(l)
+				.inc_by(e.rx_dropped as f64);
+			self.network_transmit_drops.with_label_values(l)
+				.inc_by(e.tx_dropped as f64);
+		}
+	}
+
+	fn update_hwmon(&self, hwmon: &Hwmon) {
+		let chip = match hwmon.name() {
+			Ok(n) => n,
+			Err(_) => return
+		};
+		// two chips can have the same name (for example two nvme drives), so
+		// the directory name (hwmon0) keeps their label sets apart
+		let dir = hwmon.path().file_name()
+			.and_then(|n| n.to_str())
+			.unwrap_or_default();
+		let sensor = |kind: &str, index: u32, label: Option<String>| {
+			label.unwrap_or_else(|| format!("{}{}", kind, index))
+		};
+
+		for t in hwmon.temperatures().unwrap_or_default() {
+			let s = sensor("temp", t.index, t.label);
+			self.temperature.with_label_values(&[&chip, dir, &s])
+				.set(t.celsius);
+		}
+
+		for f in hwmon.fans().unwrap_or_default() {
+			let s = sensor("fan", f.index, f.label);
+			self.fan.with_label_values(&[&chip, dir, &s])
+				.set(f.rpm.into());
+		}
+
+		for p in hwmon.power().unwrap_or_default() {
+			let s = sensor("power", p.index, p.label);
+			self.power.with_label_values(&[&chip, dir, &s])
+				.set(p.watts);
+		}
+	}
+}
+
+impl Collector for SystemCollector {
+	fn desc(&self) -> Vec<&Desc> {
+		let mut descs = vec![];
+		descs.extend(self.counters().iter().flat_map(|c| c.desc()));
+		descs.extend(self.gauges().iter().flat_map(|g| g.desc()));
+		descs.extend(self.load1.desc());
+		descs.extend(self.load5.desc());
+		descs.extend(self.load15.desc());
+		descs
+	}
+
+	fn collect(&self) -> Vec<MetricFamily> {
+		let _lock = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+
+		// the kernel already counts, so the counters are rebuilt on every
+		// scrape
+		self.counters().iter().for_each(|c| c.reset());
+		self.gauges().iter().for_each(|g| g.reset());
+
+		if let Ok(stat) = Stat::read() {
+			self.update_cpu(&stat);
+		}
+		if let Ok(load) = LoadAvg::read() {
+			self.update_load(&load);
+		}
+		if let Ok(mem) = Memory::read() {
+			self.update_memory(&mem);
+		}
+		if let Ok(stats) = DiskStats::read() {
+			self.update_disks(&stats);
+		}
+		if let Ok(dev) = NetDev::read() {
+			self.update_network(&dev);
+		}
+		for hwmon in Hwmon::all().unwrap_or_default() {
+			self.update_hwmon(&hwmon);
+		}
+
+		let mut families = vec![];
+		families.extend(self.counters().iter().flat_map(|c| c.collect()));
+		families.extend(self.gauges().iter().flat_map(|g| g.collect()));
+		families.extend(self.load1.collect());
+		families.extend(self.load5.collect());
+		families.extend(self.load15.collect());
+		families
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use std::fs;
+
+	#[test]
+	fn register() {
+		let registry = prometheus::Registry::new();
+		registry.register(Box::new(SystemCollector::new().unwrap())).unwrap();
+		let families = registry.gather();
+		assert!(families.iter().any(|f| f.get_name() == "linux_load1"));
+	}
+
+	#[test]
+	fn cpu_and_memory() {
+		let c = SystemCollector::new().unwrap();
+		c.update_cpu(&Stat::from_string("\
+cpu  300 0 200 0 0 0 0 0 0 0
+cpu0 100 0 100 0 0 0 0 0 0 0
+cpu1 200 0 100 0 0 0 0 0 0 0
+".into()));
+		let secs = |cpu, mode| {
+			c.cpu_seconds.with_label_values(&[cpu, mode]).get()
+		};
+		assert_eq!(secs("1", "user"), 200.0 / c.clock_ticks);
+		assert_eq!(secs("0", "system"), 100.0 / c.clock_ticks);
+		// the total is skipped, two cpus with eight modes each
+		assert_eq!(c.cpu_seconds.collect()[0].get_metric().len(), 16);
+
+		c.update_load(&LoadAvg::from_string(
+			"0.50 0.25 0.10 1/1000 4242\n".into()
+		));
+		assert_eq!(c.load1.get(), 0.5);
+		assert_eq!(c.load15.get(), 0.1f32 as f64);
+
+		c.update_memory(&Memory::from_string("\
+MemTotal:       32853280 kB
+MemFree:          919776 kB
+".into()));
+		let mem = |kind| c.memory.with_label_values(&[kind]).get();
+		assert_eq!(mem("total"), 32853280.0 * 1024.0);
+		assert_eq!(mem("free"), 919776.0 * 1024.0);
+	}
+
+	#[test]
+	fn disks_and_network() {
+		let c = SystemCollector::new().unwrap();
+		c.update_disks(&DiskStats::from_string("\
+   8       0 sda 4137 1210 332950 2346 111 41 1202 254 0 1560 2600
+".into()));
+		let sda = &["sda"];
+		assert_eq!(c.disk_reads.with_label_values(sda).get(), 4137.0);
+		assert_eq!(
+			c.disk_read_bytes.with_label_values(sda).get(),
+			332950.0 * 512.0
+		);
+		assert_eq!(c.disk_io_seconds.with_label_values(sda).get(), 1.56);
+
+		c.update_network(&NetDev::from_string("\
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+wlp2s0: 1389547352 1051402    0 1200    0     0          0         0 62093219  411233    0    3    0     0       0          0
+".into()));
+		let wl = &["wlp2s0"];
+		assert_eq!(
+			c.network_receive_bytes.with_label_values(wl).get(),
+			1389547352.0
+		);
+		assert_eq!(c.network_receive_drops.with_label_values(wl).get(), 1200.0);
+		assert_eq!(c.network_transmit_drops.with_label_values(wl).get(), 3.0);
+	}
+
+	#[test]
+	fn hwmon_same_name() {
+		let dir = std::env::temp_dir()
+			.join(format!("linux-info-metrics-{}", std::process::id()));
+		for (hwmon, temp) in [("hwmon0", "41850"), ("hwmon1", "38850")] {
+			let path = dir.join(hwmon);
+			fs::create_dir_all(&path).unwrap();
+			fs::write(path.join("name"), "nvme\n").unwrap();
+			fs::write(path.join("temp1_input"), temp).unwrap();
+			fs::write(path.join("temp1_label"), "Composite\n").unwrap();
+		}
+
+		let c = SystemCollector::new().unwrap();
+		c.update_hwmon(&Hwmon::from_path(dir.join("hwmon0")));
+		c.update_hwmon(&Hwmon::from_path(dir.join("hwmon1")));
+		fs::remove_dir_all(&dir).unwrap();
+
+		let temp = |hwmon| {
+			c.temperature.with_label_values(&["nvme", hwmon, "Composite"]).get()
+		};
+		assert_eq!(temp("hwmon0"), 41.85);
+		assert_eq!(temp("hwmon1"), 38.85);
+		let family = &c.temperature.collect()[0];
+		assert_eq!(family.get_metric().len(), 2);
+	}
+}
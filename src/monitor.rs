@@ -0,0 +1,517 @@
+//! Periodically sample sources and receive the values over a channel or a
+//! callback.
+//!
+//! Every source runs on its own thread, or with the `async` feature as a
+//! tokio task using [`spawn_async`]. Counters like disk or network traffic
+//! are turned into rates per second by the source.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::monitor::{Monitor, CpuUsageMonitor, NetDevSource};
+//! use std::time::Duration;
+//!
+//! let mut monitor = Monitor::new();
+//! let cpu = monitor.add(CpuUsageMonitor::new().unwrap(), Duration::from_secs(1));
+//! monitor.add_callback(
+//!     NetDevSource::new().unwrap(),
+//!     Duration::from_secs(5),
+//!     |sample| {
+//!         println!("{:?}", sample);
+//!         true
+//!     }
+//! );
+//!
+//! for sample in cpu.iter().take(10) {
+//!     println!("cpu {:.1}%", sample.unwrap().value.total * 100.0);
+//! }
+//! ```
+
+use crate::system::{Stat, CpuStat};
+use crate::memory::Memory;
+use crate::storage::{DiskStats, DiskStat};
+use crate::network::interfaces::{NetDev, NetDevEntry};
+use crate::snapshot::MemorySnapshot;
+
+use std::{io, thread};
+use std::sync::mpsc::{self, Sender, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Something which can be sampled periodically.
+pub trait Source: Send + 'static {
+	type Value: Send + 'static;
+
+	/// Reads the current value.
+	///
+	/// Sources which compute deltas return the change since the previous
+	/// call (or since they were created).
+	fn sample(&mut self) -> io::Result<Self::Value>;
+}
+
+/// A value returned by a source.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct Sample<T> {
+	pub taken_at: SystemTime,
+	pub value: T
+}
+
+/// Runs sources on their own thread until the monitor is dropped.
+#[derive(Debug, Default)]
+pub struct Monitor {
+	// dropping a sender stops the corresponding thread
+	stops: Vec<Sender<()>>
+}
+
+impl Monitor {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Samples the source every interval and sends the result to the
+	/// returned receiver.
+	///
+	/// The thread stops when the receiver or the monitor is dropped.
+	pub fn add<S>(
+		&mut self,
+		source: S,
+		interval: Duration
+	) -> Receiver<io::Result<Sample<S::Value>>>
+	where S: Source {
+		let (tx, rx) = mpsc::channel();
+		self.add_callback(source, interval, move |s| tx.send(s).is_ok());
+		rx
+	}
+
+	/// Samples the source every interval and calls `f` with the result.
+	///
+	/// The thread stops when `f` returns false or the monitor is dropped.
+	pub fn add_callback<S, F>(
+		&mut self,
+		mut source: S,
+		interval: Duration,
+		mut f: F
+	)
+	where
+		S: Source,
+		F: FnMut(io::Result<Sample<S::Value>>) -> bool + Send + 'static
+	{
+		let (stop_tx, stop_rx) = mpsc::channel::<()>();
+		self.stops.push(stop_tx);
+
+		thread::spawn(move || loop {
+			match stop_rx.recv_timeout(interval) {
+				Err(RecvTimeoutError::Timeout) => {},
+				// the monitor was dropped
+				_ => return
+			}
+
+			let sample = source.sample()
+				.map(|value| Sample {
+					taken_at: SystemTime::now(),
+					value
+				});
+
+			if !f(sample) {
+				return
+			}
+		});
+	}
+
+	/// Returns the amount of sources added.
+	pub fn len(&self) -> usize {
+		self.stops.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.stops.is_empty()
+	}
+}
+
+/// Samples the source every interval on the tokio runtime and sends the
+/// result to the returned receiver.
+///
+/// The task stops after the receiver is dropped. The sources only read small
+/// files from procfs, so they are called directly on the runtime.
+///
+/// ## Panics
+/// If called outside of a tokio runtime.
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub fn spawn_async<S>(
+	mut source: S,
+	interval: Duration
+) -> tokio::sync::mpsc::UnboundedReceiver<io::Result<Sample<S::Value>>>
+where S: Source {
+	let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+
+	tokio::spawn(async move {
+		let mut interval = tokio::time::interval(interval);
+		// the first tick completes immediately
+		interval.tick().await;
+		loop {
+			interval.tick().await;
+			if tx.is_closed() {
+				return
+			}
+
+			let sample = source.sample()
+				.map(|value| Sample {
+					taken_at: SystemTime::now(),
+					value
+				});
+
+			if tx.send(sample).is_err() {
+				return
+			}
+		}
+	});
+
+	rx
+}
+
+/// Cpu usage between two samples of /proc/stat.
+///
+/// Call [`CpuUsageMonitor::sample`] directly or run it periodically with
+/// [`Monitor::add`] or [`spawn_async`].
+#[derive(Debug)]
+pub struct CpuUsageMonitor {
+	stat: Stat,
+	total: CpuStat,
+	cores: Vec<(usize, CpuStat)>
+}
+
+impl CpuUsageMonitor {
+	/// Reads /proc/stat as the first reference point.
+	pub fn new() -> io::Result<Self> {
+		let stat = Stat::read()?;
+		Ok(Self {
+			total: stat.cpu().unwrap_or_default(),
+			cores: cores(&stat),
+			stat
+		})
+	}
+
+	/// Returns the usage since the previous sample or since the monitor
+	/// was created.
+	pub fn sample(&mut self) -> io::Result<CpuUsage> {
+		self.stat.reload()?;
+		Ok(self.usage())
+	}
+
+	fn usage(&mut self) -> CpuUsage {
+		let total = self.stat.cpu().unwrap_or_default();
+		let cores = cores(&self.stat);
+
+		// offline cpus are not listed so the cores are matched by their id
+		let usage = CpuUsage {
+			total: total.usage(&self.total),
+			cores: cores.iter()
+				.filter_map(|(id, n)| {
+					let (_, p) = self.cores.iter().find(|(p, _)| p == id)?;
+					Some((*id, n.usage(p)))
+				})
+				.collect()
+		};
+
+		self.total = total;
+		self.cores = cores;
+
+		usage
+	}
+}
+
+fn cores(stat: &Stat) -> Vec<(usize, CpuStat)> {
+	stat.cpus().collect()
+}
+
+/// Cpu usage from 0 to 1.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct CpuUsage {
+	pub total: f64,
+	/// The id and usage of every cpu which was online in both samples.
+	pub cores: Vec<(usize, f64)>
+}
+
+impl CpuUsage {
+	/// The usage of all cpus in percent.
+	pub fn total_percent(&self) -> f64 {
+		self.total * 100.0
+	}
+
+	/// The id and usage of every cpu in percent.
+	pub fn cores_percent(&self) -> impl Iterator<Item=(usize, f64)> + '_ {
+		self.cores.iter().map(|&(id, c)| (id, c * 100.0))
+	}
+}
+
+impl Source for CpuUsageMonitor {
+	type Value = CpuUsage;
+
+	fn sample(&mut self) -> io::Result<CpuUsage> {
+		CpuUsageMonitor::sample(self)
+	}
+}
+
+/// Reads /proc/meminfo.
+#[derive(Debug)]
+pub struct MemorySource {
+	memory: Memory
+}
+
+impl MemorySource {
+	pub fn new() -> io::Result<Self> {
+		Memory::read()
+			.map(|memory| Self { memory })
+	}
+}
+
+impl Source for MemorySource {
+	type Value = MemorySnapshot;
+
+	fn sample(&mut self) -> io::Result<MemorySnapshot> {
+		self.memory.reload()?;
+		Ok(MemorySnapshot::from_memory(&self.memory))
+	}
+}
+
+/// Disk throughput between two samples of /proc/diskstats.
+#[derive(Debug)]
+pub struct DiskStatsSource {
+	stats: DiskStats,
+	previous: Vec<DiskStat>,
+	last: Instant
+}
+
+impl DiskStatsSource {
+	pub fn new() -> io::Result<Self> {
+		let stats = DiskStats::read()?;
+		Ok(Self {
+			previous: stats.entries().collect(),
+			stats,
+			last: Instant::now()
+		})
+	}
+}
+
+/// The throughput of a disk per second.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct DiskIo {
+	pub name: String,
+	pub reads: f64,
+	pub writes: f64,
+	pub read_bytes: f64,
+	pub written_bytes: f64,
+	/// The fraction of time the disk was busy from 0 to 1.
+	pub busy: f64
+}
+
+impl Source for DiskStatsSource {
+	type Value = Vec<DiskIo>;
+
+	fn sample(&mut self) -> io::Result<Vec<DiskIo>> {
+		self.stats.reload()?;
+		let now = Instant::now();
+		let elapsed = now - self.last;
+		let current: Vec<_> = self.stats.entries().collect();
+
+		let list = current.iter()
+			.filter_map(|n| {
+				let p = self.previous.iter().find(|p| p.name == n.name)?;
+				let rates = n.rates(p, elapsed);
+
+				Some(DiskIo {
+					name: n.name.clone(),
+					reads: rates.reads.per_second(),
+					writes: rates.writes.per_second(),
+					read_bytes: rates.read_bytes.per_second(),
+					written_bytes: rates.written_bytes.per_second(),
+					busy: rates.busy
+				})
+			})
+			.collect();
+
+		self.previous = current;
+		self.last = now;
+
+		Ok(list)
+	}
+}
+
+/// Network throughput between two samples of /proc/net/dev.
+#[derive(Debug)]
+pub struct NetDevSource {
+	dev: NetDev,
+	previous: Vec<NetDevEntry>,
+	last: Instant
+}
+
+impl NetDevSource {
+	pub fn new() -> io::Result<Self> {
+		let dev = NetDev::read()?;
+		Ok(Self {
+			previous: dev.entries().collect(),
+			dev,
+			last: Instant::now()
+		})
+	}
+}
+
+/// The throughput of a network interface per second.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct NetIo {
+	pub name: String,
+	pub rx_bytes: f64,
+	pub tx_bytes: f64,
+	pub rx_packets: f64,
+	pub tx_packets: f64
+}
+
+impl Source for NetDevSource {
+	type Value = Vec<NetIo>;
+
+	fn sample(&mut self) -> io::Result<Vec<NetIo>> {
+		self.dev.reload()?;
+		let now = Instant::now();
+		let elapsed = now - self.last;
+		let current: Vec<_> = self.dev.entries().collect();
+
+		let list = current.iter()
+			.filter_map(|n| {
+				let p = self.previous.iter().find(|p| p.name == n.name)?;
+				let rates = n.rates(p, elapsed);
+
+				Some(NetIo {
+					name: n.name.clone(),
+					rx_bytes: rates.rx_bytes.per_second(),
+					tx_bytes: rates.tx_bytes.per_second(),
+					rx_packets: rates.rx_packets.per_second(),
+					tx_packets: rates.tx_packets.per_second()
+				})
+			})
+			.collect();
+
+		self.previous = current;
+		self.last = now;
+
+		Ok(list)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	struct Counter(u32);
+
+	impl Source for Counter {
+		type Value = u32;
+
+		fn sample(&mut self) -> io::Result<u32> {
+			self.0 += 1;
+			Ok(self.0)
+		}
+	}
+
+	#[test]
+	fn channel() {
+		let mut monitor = Monitor::new();
+		let rx = monitor.add(Counter(0), Duration::from_millis(1));
+		let values: Vec<_> = rx.iter()
+			.take(3)
+			.map(|s| s.unwrap().value)
+			.collect();
+		assert_eq!(values, [1, 2, 3]);
+		assert_eq!(monitor.len(), 1);
+	}
+
+	#[test]
+	fn callback_stops() {
+		let (tx, rx) = mpsc::channel();
+		let mut monitor = Monitor::new();
+		monitor.add_callback(Counter(0), Duration::from_millis(1), move |s| {
+			let v = s.unwrap().value;
+			tx.send(v).unwrap();
+			v < 2
+		});
+		// the sender is dropped once the callback returned false
+		let values: Vec<_> = rx.iter().collect();
+		assert_eq!(values, [1, 2]);
+	}
+
+	#[test]
+	fn cpu_offline() {
+		let stat = |raw: &str| Stat::from_string(raw.into());
+		let first = stat("\
+cpu  200 0 0 200 0 0 0 0 0 0
+cpu0 100 0 0 100 0 0 0 0 0 0
+cpu1 100 0 0 100 0 0 0 0 0 0
+cpu2 0 0 0 0 0 0 0 0 0 0
+");
+		let mut monitor = CpuUsageMonitor {
+			total: first.cpu().unwrap(),
+			cores: cores(&first),
+			stat: first
+		};
+
+		// cpu1 went offline
+		monitor.stat = stat("\
+cpu  300 0 0 300 0 0 0 0 0 0
+cpu0 200 0 0 100 0 0 0 0 0 0
+cpu2 0 0 0 100 0 0 0 0 0 0
+");
+		let usage = monitor.usage();
+		assert_eq!(usage.total, 0.5);
+		assert_eq!(usage.cores, [(0, 1.0), (2, 0.0)]);
+
+		// and came back
+		monitor.stat = stat("\
+cpu  400 0 0 400 0 0 0 0 0 0
+cpu0 250 0 0 150 0 0 0 0 0 0
+cpu1 100 0 0 100 0 0 0 0 0 0
+cpu2 50 0 0 150 0 0 0 0 0 0
+");
+		let usage = monitor.usage();
+		assert_eq!(usage.cores, [(0, 0.5), (2, 0.5)]);
+		assert_eq!(
+			usage.cores_percent().collect::<Vec<_>>(),
+			[(0, 50.0), (2, 50.0)]
+		);
+	}
+
+	#[cfg(feature = "async")]
+	#[test]
+	fn async_channel() {
+		let rt = tokio::runtime::Builder::new_current_thread()
+			.enable_time()
+			.build()
+			.unwrap();
+		let values = rt.block_on(async {
+			let mut rx = spawn_async(Counter(0), Duration::from_millis(1));
+			let mut values = vec![];
+			while values.len() < 3 {
+				values.push(rx.recv().await.unwrap().unwrap().value);
+			}
+			values
+		});
+		assert_eq!(values, [1, 2, 3]);
+	}
+}
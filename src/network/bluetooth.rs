@@ -0,0 +1,254 @@
+//! Connect to BlueZ, the linux bluetooth daemon.
+
+use std::time::Duration;
+use std::sync::Arc;
+
+use dbus::{Error, Path};
+use dbus::arg::{self, PropMap, RefArg};
+use dbus::blocking::{SyncConnection, Proxy};
+use dbus::blocking::stdintf::org_freedesktop_dbus::{
+	ObjectManager, Properties
+};
+
+const DBUS_NAME: &str = "org.bluez";
+const DBUS_PATH: &str = "/";
+const ADAPTER_INTERFACE: &str = "org.bluez.Adapter1";
+const DEVICE_INTERFACE: &str = "org.bluez.Device1";
+const BATTERY_INTERFACE: &str = "org.bluez.Battery1";
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Clone)]
+struct Dbus {
+	conn: Arc<SyncConnection>,
+	timeout: Duration
+}
+
+impl Dbus {
+	fn connect() -> Result<Self, Error> {
+		SyncConnection::new_system()
+			.map(Arc::new)
+			.map(|conn| Self { conn, timeout: TIMEOUT })
+	}
+
+	fn proxy<'a, 'b>(
+		&'b self,
+		path: impl Into<Path<'a>>
+	) -> Proxy<'a, &'b SyncConnection> {
+		self.conn.with_proxy(DBUS_NAME, path, self.timeout)
+	}
+
+	/// Returns all object paths which implement the given interface.
+	fn objects_with(
+		&self,
+		interface: &str
+	) -> Result<Vec<Path<'static>>, Error> {
+		let objects = self.proxy(DBUS_PATH).get_managed_objects()?;
+		let paths = objects.into_iter()
+			.filter(|(_, interfaces)| interfaces.contains_key(interface))
+			.map(|(path, _)| path)
+			.collect();
+
+		Ok(paths)
+	}
+}
+
+#[derive(Clone)]
+pub struct Bluetooth {
+	dbus: Dbus
+}
+
+impl Bluetooth {
+	/// Connects to the system bus with the default timeout of 2 seconds.
+	pub fn connect() -> Result<Self, Error> {
+		Dbus::connect()
+			.map(|dbus| Self { dbus })
+	}
+
+	/// Creates a Bluetooth from an existing system bus connection.
+	pub fn from_connection(conn: Arc<SyncConnection>) -> Self {
+		Self {
+			dbus: Dbus { conn, timeout: TIMEOUT }
+		}
+	}
+
+	/// Returns all bluetooth adapters.
+	pub fn adapters(&self) -> Result<Vec<Adapter>, Error> {
+		let adapters = self.dbus.objects_with(ADAPTER_INTERFACE)?
+			.into_iter()
+			.map(|path| Adapter {
+				dbus: self.dbus.clone(),
+				path
+			})
+			.collect();
+
+		Ok(adapters)
+	}
+
+	/// Returns all devices known to BlueZ, which includes paired devices
+	/// and devices found in a discovery.
+	pub fn devices(&self) -> Result<Vec<Device>, Error> {
+		let devices = self.dbus.objects_with(DEVICE_INTERFACE)?
+			.into_iter()
+			.map(|path| Device {
+				dbus: self.dbus.clone(),
+				path
+			})
+			.collect();
+
+		Ok(devices)
+	}
+}
+
+#[derive(Clone)]
+pub struct Adapter {
+	dbus: Dbus,
+	path: Path<'static>
+}
+
+impl Adapter {
+	fn get<R>(&self, name: &str) -> Result<R, Error>
+	where R: for<'b> arg::Get<'b> + 'static {
+		self.dbus.proxy(&self.path).get(ADAPTER_INTERFACE, name)
+	}
+
+	/// The dbus object path for example `/org/bluez/hci0`.
+	pub fn object_path(&self) -> &str {
+		&self.path
+	}
+
+	/// The Bluetooth device address.
+	pub fn address(&self) -> Result<String, Error> {
+		self.get("Address")
+	}
+
+	/// The Bluetooth system name (pretty hostname).
+	pub fn name(&self) -> Result<String, Error> {
+		self.get("Name")
+	}
+
+	/// The Bluetooth friendly name. Defaults to the system name.
+	pub fn alias(&self) -> Result<String, Error> {
+		self.get("Alias")
+	}
+
+	/// Switch an adapter on or off.
+	pub fn powered(&self) -> Result<bool, Error> {
+		self.get("Powered")
+	}
+
+	/// Switch an adapter to discoverable or non-discoverable to either make
+	/// it visible or hide it.
+	pub fn discoverable(&self) -> Result<bool, Error> {
+		self.get("Discoverable")
+	}
+
+	/// Switch an adapter to pairable or non-pairable.
+	pub fn pairable(&self) -> Result<bool, Error> {
+		self.get("Pairable")
+	}
+
+	/// Indicates that a device discovery procedure is active.
+	pub fn discovering(&self) -> Result<bool, Error> {
+		self.get("Discovering")
+	}
+}
+
+#[derive(Clone)]
+pub struct Device {
+	dbus: Dbus,
+	path: Path<'static>
+}
+
+impl Device {
+	fn get<R>(&self, name: &str) -> Result<R, Error>
+	where R: for<'b> arg::Get<'b> + 'static {
+		self.dbus.proxy(&self.path).get(DEVICE_INTERFACE, name)
+	}
+
+	/// The dbus object path for example `/org/bluez/hci0/dev_00_11_22_33_44_55`.
+	pub fn object_path(&self) -> &str {
+		&self.path
+	}
+
+	/// The object path of the adapter the device belongs to.
+	pub fn adapter(&self) -> Result<Adapter, Error> {
+		Ok(Adapter {
+			path: self.get("Adapter")?,
+			dbus: self.dbus.clone()
+		})
+	}
+
+	/// The Bluetooth device address of the remote device.
+	pub fn address(&self) -> Result<String, Error> {
+		self.get("Address")
+	}
+
+	/// The Bluetooth remote name.
+	///
+	/// It is better to always use the alias since the name might not be
+	/// available.
+	pub fn name(&self) -> Result<String, Error> {
+		self.get("Name")
+	}
+
+	/// The name alias for the remote device. If no alias is set it returns
+	/// the remote device name.
+	pub fn alias(&self) -> Result<String, Error> {
+		self.get("Alias")
+	}
+
+	/// Proposed icon name according to the freedesktop.org icon naming
+	/// specification.
+	pub fn icon(&self) -> Result<String, Error> {
+		self.get("Icon")
+	}
+
+	/// Indicates if the remote device is paired.
+	pub fn paired(&self) -> Result<bool, Error> {
+		self.get("Paired")
+	}
+
+	/// Indicates if the remote device is currently connected.
+	pub fn connected(&self) -> Result<bool, Error> {
+		self.get("Connected")
+	}
+
+	/// Indicates if the remote is seen as trusted.
+	pub fn trusted(&self) -> Result<bool, Error> {
+		self.get("Trusted")
+	}
+
+	/// Received Signal Strength Indicator of the remote device (inquiry or
+	/// advertising).
+	///
+	/// Returns `None` if the value is not available, which is the case if the
+	/// device was not seen in the current discovery.
+	pub fn rssi(&self) -> Result<Option<i16>, Error> {
+		let props = self.dbus.proxy(&self.path).get_all(DEVICE_INTERFACE)?;
+		Ok(prop_i64(&props, "RSSI").map(|v| v as i16))
+	}
+
+	/// The battery level in percent (0 - 100).
+	///
+	/// Returns `None` if the device does not report a battery level.
+	pub fn battery_percentage(&self) -> Result<Option<u8>, Error> {
+		let proxy = self.dbus.proxy(&self.path);
+		match proxy.get_all(BATTERY_INTERFACE) {
+			Ok(props) => Ok(prop_i64(&props, "Percentage").map(|v| v as u8)),
+			Err(e) if is_unknown_interface(&e) => Ok(None),
+			Err(e) => Err(e)
+		}
+	}
+}
+
+fn prop_i64(prop: &PropMap, key: &str) -> Option<i64> {
+	prop.get(key)?.as_i64()
+}
+
+fn is_unknown_interface(e: &Error) -> bool {
+	matches!(
+		e.name(),
+		Some("org.freedesktop.DBus.Error.InvalidArgs") |
+		Some("org.freedesktop.DBus.Error.UnknownInterface")
+	)
+}
@@ -0,0 +1,287 @@
+//! Dump tcp sockets with the kernel's `TCP_INFO` (rtt, congestion window,
+//! retransmits, pacing rate) using the inet-diag netlink interface, which
+//! is what `ss -ti` uses.
+//!
+//! Unlike `/proc/net/tcp` this does not require to be the owner of the
+//! socket to get the details.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::network::inet_diag::tcp_sockets;
+//!
+//! for s in tcp_sockets().unwrap() {
+//!     let info = match s.info {
+//!         Some(i) => i,
+//!         None => continue
+//!     };
+//!     println!(
+//!         "{} -> {} rtt {:?} cwnd {} retrans {}",
+//!         s.socket.local, s.socket.remote, info.rtt, info.snd_cwnd,
+//!         info.total_retrans
+//!     );
+//! }
+//! ```
+
+use super::sockets::{Protocol, Socket, TcpState};
+
+use super::netlink::{
+	Netlink, NETLINK_SOCK_DIAG, NLM_F_DUMP, attributes, c_string
+};
+
+use std::io;
+use std::time::Duration;
+use std::net::{SocketAddr, IpAddr, Ipv4Addr, Ipv6Addr};
+
+const SOCK_DIAG_BY_FAMILY: u16 = 20;
+
+// see linux/inet_diag.h
+const INET_DIAG_INFO: u16 = 2;
+const INET_DIAG_CONG: u16 = 4;
+const INET_DIAG_ULP_INFO: u16 = 28;
+const INET_ULP_INFO_NAME: u16 = 1;
+/// The size of struct inet_diag_msg.
+const DIAG_MSG_LEN: usize = 72;
+
+/// A tcp socket with the information from the kernel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct DiagSocket {
+	pub socket: Socket,
+	/// `None` if the kernel did not return it (for example for sockets in
+	/// `TimeWait`).
+	pub info: Option<TcpInfo>,
+	/// The congestion control algorithm for example `cubic` or `bbr`.
+	pub congestion: Option<String>,
+	/// The upper layer protocol for example `mptcp` for subflows of a
+	/// multipath tcp connection or `tls` for kernel tls.
+	///
+	/// Requires `CAP_NET_ADMIN`.
+	pub ulp: Option<String>
+}
+
+/// The `TCP_INFO` of a socket.
+///
+/// Values which were added in later kernel versions are `None` on older
+/// kernels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct TcpInfo {
+	pub state: TcpState,
+	/// Retransmits of the currently unacknowledged segment.
+	pub retransmits: u8,
+	/// The retransmission timeout.
+	pub rto: Duration,
+	pub snd_mss: u32,
+	pub rcv_mss: u32,
+	/// Segments which are considered lost.
+	pub lost: u32,
+	/// Segments which are currently retransmitted.
+	pub retrans: u32,
+	/// The smoothed round trip time.
+	pub rtt: Duration,
+	pub rtt_var: Duration,
+	/// The slow start threshold in segments.
+	pub snd_ssthresh: u32,
+	/// The congestion window in segments.
+	pub snd_cwnd: u32,
+	/// Retransmitted segments over the lifetime of the socket.
+	pub total_retrans: u32,
+	/// The pacing rate in bytes per second.
+	pub pacing_rate: Option<u64>,
+	pub bytes_acked: Option<u64>,
+	pub bytes_received: Option<u64>,
+	pub min_rtt: Option<Duration>,
+	/// The estimated delivery rate in bytes per second.
+	pub delivery_rate: Option<u64>,
+	pub bytes_sent: Option<u64>,
+	pub bytes_retrans: Option<u64>
+}
+
+impl TcpInfo {
+	/// Parses struct tcp_info from linux/tcp.h.
+	fn parse(data: &[u8]) -> Option<Self> {
+		let u32_at = |i: usize| {
+			data.get(i..i + 4)
+				.map(|b| u32::from_ne_bytes(b.try_into().unwrap()))
+		};
+		let u64_at = |i: usize| {
+			data.get(i..i + 8)
+				.map(|b| u64::from_ne_bytes(b.try_into().unwrap()))
+		};
+		let micros = |i: usize| u32_at(i).map(|u| Duration::from_micros(u.into()));
+
+		Some(Self {
+			state: (*data.first()?).into(),
+			retransmits: *data.get(2)?,
+			rto: micros(8)?,
+			snd_mss: u32_at(16)?,
+			rcv_mss: u32_at(20)?,
+			lost: u32_at(32)?,
+			retrans: u32_at(36)?,
+			rtt: micros(68)?,
+			rtt_var: micros(72)?,
+			snd_ssthresh: u32_at(76)?,
+			snd_cwnd: u32_at(80)?,
+			total_retrans: u32_at(100)?,
+			pacing_rate: u64_at(104),
+			bytes_acked: u64_at(120),
+			bytes_received: u64_at(128),
+			min_rtt: micros(148),
+			delivery_rate: u64_at(160),
+			bytes_sent: u64_at(200),
+			bytes_retrans: u64_at(208)
+		})
+	}
+}
+
+/// Returns every ipv4 and ipv6 tcp socket with its `TCP_INFO`.
+///
+/// Returns only ipv4 sockets if ipv6 is disabled.
+pub fn tcp_sockets() -> io::Result<Vec<DiagSocket>> {
+	let mut list = dump(Protocol::Tcp)?;
+	match dump(Protocol::Tcp6) {
+		Ok(l) => list.extend(l),
+		Err(e) if e.raw_os_error() == Some(libc::EAFNOSUPPORT) => {},
+		Err(e) => return Err(e)
+	}
+	Ok(list)
+}
+
+/// Returns every socket of a protocol, `TCP_INFO` is only available for tcp
+/// sockets.
+pub fn dump(protocol: Protocol) -> io::Result<Vec<DiagSocket>> {
+	let mut netlink = Netlink::open(NETLINK_SOCK_DIAG)?;
+
+	let mut list = vec![];
+	netlink.request(
+		SOCK_DIAG_BY_FAMILY,
+		NLM_F_DUMP,
+		&request(protocol),
+		|_, data| list.extend(parse_socket(protocol, data))
+	)?;
+	Ok(list)
+}
+
+/// Builds struct inet_diag_req_v2.
+fn request(protocol: Protocol) -> Vec<u8> {
+	let family = match protocol {
+		Protocol::Tcp | Protocol::Udp => libc::AF_INET,
+		Protocol::Tcp6 | Protocol::Udp6 => libc::AF_INET6
+	};
+	let proto = match protocol.is_tcp() {
+		true => libc::IPPROTO_TCP,
+		false => libc::IPPROTO_UDP
+	};
+	let ext = (1u8 << (INET_DIAG_INFO - 1)) | (1 << (INET_DIAG_CONG - 1));
+
+	let mut b = vec![family as u8, proto as u8, ext, 0];
+	// every state
+	b.extend_from_slice(&u32::MAX.to_ne_bytes());
+	// an empty inet_diag_sockid
+	b.resize(8 + 48, 0);
+	b
+}
+
+/// Parses struct inet_diag_msg followed by attributes.
+fn parse_socket(protocol: Protocol, data: &[u8]) -> Option<DiagSocket> {
+	if data.len() < DIAG_MSG_LEN {
+		return None
+	}
+	let u32_at = |i: usize| u32::from_ne_bytes(data[i..i + 4].try_into().unwrap());
+	let port_at = |i: usize| u16::from_be_bytes([data[i], data[i + 1]]);
+	let ip_at = |i: usize| -> IpAddr {
+		match data[0] as i32 {
+			libc::AF_INET6 => {
+				let b: [u8; 16] = data[i..i + 16].try_into().unwrap();
+				Ipv6Addr::from(b).into()
+			},
+			_ => Ipv4Addr::new(data[i], data[i + 1], data[i + 2], data[i + 3])
+				.into()
+		}
+	};
+
+	let socket = Socket {
+		protocol,
+		local: SocketAddr::new(ip_at(8), port_at(4)),
+		remote: SocketAddr::new(ip_at(24), port_at(6)),
+		state: data[1].into(),
+		rx_queue: u32_at(56).into(),
+		tx_queue: u32_at(60).into(),
+		uid: u32_at(64),
+		inode: u32_at(68).into()
+	};
+
+	let mut diag = DiagSocket {
+		socket,
+		info: None,
+		congestion: None,
+		ulp: None
+	};
+	for (ty, value) in attributes(&data[DIAG_MSG_LEN..]) {
+		match ty {
+			INET_DIAG_INFO => diag.info = TcpInfo::parse(value),
+			INET_DIAG_CONG => diag.congestion = Some(c_string(value)),
+			INET_DIAG_ULP_INFO => {
+				diag.ulp = attributes(value)
+					.find(|(ty, _)| *ty == INET_ULP_INFO_NAME)
+					.map(|(_, v)| c_string(v));
+			},
+			_ => {}
+		}
+	}
+
+	Some(diag)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::network::netlink::push_attr;
+
+	#[test]
+	fn diag_message() {
+		let mut msg = vec![libc::AF_INET as u8, 1, 0, 0];
+		msg.extend_from_slice(&22u16.to_be_bytes());
+		msg.extend_from_slice(&50000u16.to_be_bytes());
+		msg.extend_from_slice(&[192, 0, 2, 2]);
+		msg.resize(24, 0);
+		msg.extend_from_slice(&[192, 0, 2, 10]);
+		msg.resize(56, 0);
+		for v in [0u32, 36, 0, 4321] {
+			msg.extend_from_slice(&v.to_ne_bytes());
+		}
+
+		let mut info = vec![0u8; 232];
+		info[0] = 1;
+		info[68..72].copy_from_slice(&1500u32.to_ne_bytes());
+		info[80..84].copy_from_slice(&10u32.to_ne_bytes());
+		info[100..104].copy_from_slice(&3u32.to_ne_bytes());
+		info[104..112].copy_from_slice(&125000u64.to_ne_bytes());
+		push_attr(&mut msg, INET_DIAG_INFO, &info);
+		push_attr(&mut msg, INET_DIAG_CONG, b"cubic\0");
+
+		let s = parse_socket(Protocol::Tcp, &msg).unwrap();
+		assert_eq!(s.socket.local, "192.0.2.2:22".parse().unwrap());
+		assert_eq!(s.socket.remote, "192.0.2.10:50000".parse().unwrap());
+		assert_eq!(s.socket.state, TcpState::Established);
+		assert_eq!(s.socket.tx_queue, 36);
+		assert_eq!(s.socket.inode, 4321);
+		assert_eq!(s.congestion.as_deref(), Some("cubic"));
+		assert_eq!(s.ulp, None);
+		let info = s.info.as_ref().unwrap();
+		assert_eq!(info.rtt, Duration::from_micros(1500));
+		assert_eq!(info.snd_cwnd, 10);
+		assert_eq!(info.total_retrans, 3);
+		assert_eq!(info.pacing_rate, Some(125000));
+		assert_eq!(info.bytes_retrans, Some(0));
+		assert!(parse_socket(Protocol::Tcp, &msg[..40]).is_none());
+	}
+}
@@ -0,0 +1,688 @@
+//! List network interfaces from `/sys/class/net` and read their traffic
+//! counters from `/proc/net/dev`.
+//!
+//! Does not require the `network` feature.
+
+use crate::util::{
+	read_to_string, read_to_string_mut, read_sys_string, read_sys_value,
+	read_sys_opt, interface_addresses, SysDir
+};
+#[cfg(feature = "async")]
+use crate::util::{read_to_string_async, read_to_string_mut_async};
+use crate::unit::DataSize;
+use crate::rate::Rate;
+use crate::diff::{Change, diff_by_key};
+use crate::pci::PciDevice;
+use crate::error::Error;
+use super::lldp::{self, LldpNeighbor};
+
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::net::IpAddr;
+
+const NET_PATH: &str = "/sys/class/net";
+
+/// A network interface for example `/sys/class/net/eth0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interface {
+	path: PathBuf
+}
+
+impl Interface {
+	/// Returns all network interfaces including loopback.
+	pub fn all() -> io::Result<Vec<Self>> {
+		let dir = match fs::read_dir(NET_PATH) {
+			Ok(d) => d,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+			Err(e) => return Err(e)
+		};
+
+		let mut list = dir.map(|e| e.map(|e| Self::from_path(e.path())))
+			.collect::<io::Result<Vec<_>>>()?;
+		list.sort_by(|a, b| a.path.cmp(&b.path));
+		Ok(list)
+	}
+
+	/// Returns the interface with the given name if it exists.
+	pub fn by_name(name: &str) -> Option<Self> {
+		let path = Path::new(NET_PATH).join(name);
+		path.exists().then(|| Self::from_path(path))
+	}
+
+	/// Creates an interface from a path like `/sys/class/net/eth0`.
+	pub fn from_path(path: impl Into<PathBuf>) -> Self {
+		Self { path: path.into() }
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// The name of the interface for example `eth0`.
+	pub fn name(&self) -> Option<&str> {
+		self.path.file_name()?.to_str()
+	}
+
+	/// The index of the interface.
+	pub fn index(&self) -> io::Result<u32> {
+		read_sys_value(self.path.join("ifindex"))
+	}
+
+	/// The hardware address for example `00:11:22:33:44:55`.
+	pub fn mac_address(&self) -> io::Result<String> {
+		read_sys_string(self.path.join("address"))
+	}
+
+	pub fn mtu(&self) -> io::Result<u32> {
+		read_sys_value(self.path.join("mtu"))
+	}
+
+	/// The operational state for example `up`, `down` or `unknown`.
+	pub fn operstate(&self) -> io::Result<String> {
+		read_sys_string(self.path.join("operstate"))
+	}
+
+	/// Returns true if the interface has a carrier.
+	///
+	/// Returns `None` if the interface is administratively down.
+	pub fn carrier(&self) -> io::Result<Option<bool>> {
+		match read_sys_value::<u8>(self.path.join("carrier")) {
+			Ok(c) => Ok(Some(c == 1)),
+			// reading carrier of a down interface returns EINVAL
+			Err(e) if Error::raw_os_error_of(&e) == Some(libc::EINVAL) => Ok(None),
+			Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+			Err(e) => Err(e)
+		}
+	}
+
+	/// How often the carrier changed since the interface was created.
+	///
+	/// A counter which increases steadily points to a flapping link, like a
+	/// bad cable, see [`LinkMonitor`](super::link::LinkMonitor) to get
+	/// notified of every change.
+	pub fn carrier_changes(&self) -> io::Result<u64> {
+		read_sys_value(self.path.join("carrier_changes"))
+	}
+
+	/// How often the carrier went up and down, requires Linux 4.16.
+	///
+	/// Returns `None` if the kernel does not report them.
+	pub fn carrier_up_down_count(&self) -> io::Result<Option<(u64, u64)>> {
+		let up = match read_sys_opt(self.path.join("carrier_up_count"))? {
+			Some(u) => u,
+			None => return Ok(None)
+		};
+		let down = read_sys_value(self.path.join("carrier_down_count"))?;
+		Ok(Some((up, down)))
+	}
+
+	/// The switch and port this interface is connected to, received by
+	/// systemd-networkd or lldpd, see [`lldp`](super::lldp).
+	pub fn lldp_neighbors(&self) -> io::Result<Vec<LldpNeighbor>> {
+		let name = match self.name() {
+			Some(n) => n,
+			None => return Ok(vec![])
+		};
+		let mut list = lldp::neighbors()?;
+		list.retain(|n| n.interface == name);
+		Ok(list)
+	}
+
+	/// The pci device of the interface, `None` for virtual interfaces or
+	/// usb adapters.
+	pub fn pci_device(&self) -> Option<PciDevice> {
+		let device = self.path.join("device");
+		// of_device would return the usb controller for usb adapters
+		let subsystem = fs::read_link(device.join("subsystem")).ok()?;
+		if subsystem.ends_with("usb") {
+			return None
+		}
+		PciDevice::of_device(device)
+	}
+
+	/// The link speed in Mbit/s.
+	///
+	/// Returns `None` if the driver does not know the speed (for example for
+	/// wireless or virtual interfaces).
+	pub fn speed(&self) -> io::Result<Option<u32>> {
+		match read_sys_opt::<i64>(self.path.join("speed")) {
+			Ok(s) => Ok(s.and_then(|s| s.try_into().ok())),
+			Err(e) if Error::raw_os_error_of(&e) == Some(libc::EINVAL) => Ok(None),
+			Err(e) => Err(e)
+		}
+	}
+
+	/// Returns true if the interface is backed by a device and not virtual
+	/// (like loopback, bridges or veth pairs).
+	pub fn is_physical(&self) -> bool {
+		self.path.join("device").exists()
+	}
+
+	/// Returns true if the interface is a wireless interface.
+	pub fn is_wireless(&self) -> bool {
+		self.path.join("wireless").exists() ||
+		self.path.join("phy80211").exists()
+	}
+
+	/// Returns the interface flags like `ip link` shows them.
+	///
+	/// The kernel does not store `Running` in the flags, it is set if the
+	/// interface is up and the operational state is `up` or `unknown`.
+	pub fn flags(&self) -> io::Result<InterfaceFlags> {
+		let s = read_sys_string(self.path.join("flags"))?;
+		let mut flags = u32::from_str_radix(s.trim_start_matches("0x"), 16)
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+		let oper_up = matches!(self.operstate()?.as_str(), "up" | "unknown");
+		if flags & InterfaceFlag::Up as u32 != 0 && oper_up {
+			flags |= InterfaceFlag::Running as u32;
+		}
+		Ok(flags.into())
+	}
+
+	/// The length of the transmit queue in packets.
+	pub fn tx_queue_len(&self) -> io::Result<u32> {
+		read_sys_value(self.path.join("tx_queue_len"))
+	}
+
+	/// How ipv6 link local addresses are generated.
+	///
+	/// Returns `None` if ipv6 is disabled.
+	pub fn addr_gen_mode(&self) -> io::Result<Option<AddrGenMode>> {
+		let path = Path::new("/proc/sys/net/ipv6/conf")
+			.join(self.name().unwrap_or_default())
+			.join("addr_gen_mode");
+		Ok(read_sys_opt::<u32>(path)?.map(AddrGenMode::from))
+	}
+
+	/// Returns the ipv4 and ipv6 addresses assigned to the interface.
+	pub fn addresses(&self) -> io::Result<Vec<IpAddr>> {
+		let name = self.name().unwrap_or_default();
+		Ok(interface_addresses()?
+			.into_iter()
+			.filter(|(n, _)| n == name)
+			.map(|(_, ip)| ip)
+			.collect())
+	}
+
+	/// Reads the traffic counters from the `statistics` directory of the
+	/// interface, see [`InterfaceStatistics`] to sample them repeatedly.
+	pub fn statistics(&self) -> io::Result<InterfaceStatistics> {
+		InterfaceStatistics::open(self)
+	}
+}
+
+/// A flag of a network interface, see `IFF_*` in linux/if.h.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", rename_all = "snake_case")
+)]
+pub enum InterfaceFlag {
+	/// The interface is administratively up.
+	Up = 1 << 0,
+	Broadcast = 1 << 1,
+	Debug = 1 << 2,
+	Loopback = 1 << 3,
+	PointToPoint = 1 << 4,
+	NoTrailers = 1 << 5,
+	/// The interface is up and has a carrier.
+	Running = 1 << 6,
+	NoArp = 1 << 7,
+	/// Receives every packet.
+	Promisc = 1 << 8,
+	/// Receives every multicast packet.
+	AllMulti = 1 << 9,
+	/// A bonding master.
+	Master = 1 << 10,
+	/// A bonding slave.
+	Slave = 1 << 11,
+	Multicast = 1 << 12,
+	PortSel = 1 << 13,
+	AutoMedia = 1 << 14,
+	/// The addresses are lost when the interface goes down.
+	Dynamic = 1 << 15
+}
+
+impl InterfaceFlag {
+	const ALL: &'static [InterfaceFlag] = &[
+		InterfaceFlag::Up,
+		InterfaceFlag::Broadcast,
+		InterfaceFlag::Debug,
+		InterfaceFlag::Loopback,
+		InterfaceFlag::PointToPoint,
+		InterfaceFlag::NoTrailers,
+		InterfaceFlag::Running,
+		InterfaceFlag::NoArp,
+		InterfaceFlag::Promisc,
+		InterfaceFlag::AllMulti,
+		InterfaceFlag::Master,
+		InterfaceFlag::Slave,
+		InterfaceFlag::Multicast,
+		InterfaceFlag::PortSel,
+		InterfaceFlag::AutoMedia,
+		InterfaceFlag::Dynamic
+	];
+}
+
+/// The flags of a network interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", transparent)
+)]
+pub struct InterfaceFlags(u32);
+
+impl InterfaceFlags {
+	pub fn bits(&self) -> u32 {
+		self.0
+	}
+
+	/// Returns true if the given flag is set.
+	pub fn has(&self, flag: InterfaceFlag) -> bool {
+		self.0 & flag as u32 != 0
+	}
+
+	pub fn is_up(&self) -> bool {
+		self.has(InterfaceFlag::Up)
+	}
+
+	pub fn is_running(&self) -> bool {
+		self.has(InterfaceFlag::Running)
+	}
+
+	pub fn is_promisc(&self) -> bool {
+		self.has(InterfaceFlag::Promisc)
+	}
+
+	pub fn is_multicast(&self) -> bool {
+		self.has(InterfaceFlag::Multicast)
+	}
+
+	pub fn is_point_to_point(&self) -> bool {
+		self.has(InterfaceFlag::PointToPoint)
+	}
+
+	pub fn is_loopback(&self) -> bool {
+		self.has(InterfaceFlag::Loopback)
+	}
+
+	/// Returns every flag which is set.
+	pub fn iter(&self) -> impl Iterator<Item=InterfaceFlag> + '_ {
+		InterfaceFlag::ALL.iter()
+			.copied()
+			.filter(move |f| self.has(*f))
+	}
+}
+
+impl From<u32> for InterfaceFlags {
+	fn from(num: u32) -> Self {
+		Self(num)
+	}
+}
+
+/// How the kernel generates ipv6 link local addresses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", rename_all = "snake_case")
+)]
+pub enum AddrGenMode {
+	/// Derived from the mac address.
+	Eui64,
+	/// No address is generated.
+	None,
+	/// Stable per network but not derived from the mac address (rfc 7217).
+	StablePrivacy,
+	Random,
+	Unknown(u32)
+}
+
+impl From<u32> for AddrGenMode {
+	fn from(n: u32) -> Self {
+		match n {
+			0 => Self::Eui64,
+			1 => Self::None,
+			2 => Self::StablePrivacy,
+			3 => Self::Random,
+			n => Self::Unknown(n)
+		}
+	}
+}
+
+/// Read network traffic counters from /proc/net/dev.
+///
+/// To sample often see [`link_stats`](super::link_stats) which reads the
+/// same counters over rtnetlink.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetDev {
+	raw: String
+}
+
+impl NetDev {
+
+	fn path() -> &'static Path {
+		Path::new("/proc/net/dev")
+	}
+
+	#[cfg(test)]
+	pub(crate) fn from_string(raw: String) -> Self {
+		Self {raw}
+	}
+
+	/// Read network traffic counters from /proc/net/dev.
+	pub fn read() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string(Self::path())?
+		})
+	}
+
+	/// Reloads information without allocating.
+	pub fn reload(&mut self) -> io::Result<()> {
+		read_to_string_mut(Self::path(), &mut self.raw)
+	}
+
+	/// Reads the file without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string_async(Self::path()).await?
+		})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(Self::path(), &mut self.raw).await
+	}
+
+	/// Returns the counters of every interface.
+	pub fn entries(&self) -> impl Iterator<Item=NetDevEntry> + '_ {
+		self.raw.lines()
+			// skip the two header lines
+			.skip(2)
+			.filter_map(NetDevEntry::from_line)
+	}
+
+	/// Returns the counters of an interface by its name for example `eth0`.
+	pub fn by_name(&self, name: &str) -> Option<NetDevEntry> {
+		self.entries()
+			.find(|e| e.name == name)
+	}
+
+	/// Returns every interface which was added, removed or whose counters
+	/// changed compared to the newer reading.
+	pub fn diff(&self, newer: &Self) -> Vec<Change<String, NetDevEntry>> {
+		diff_by_key(
+			self.entries().map(|e| (e.name.clone(), e)),
+			newer.entries().map(|e| (e.name.clone(), e))
+		)
+	}
+
+}
+
+/// The traffic counters of a network interface.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct NetDevEntry {
+	pub name: String,
+	pub rx_bytes: u64,
+	pub rx_packets: u64,
+	pub rx_errors: u64,
+	pub rx_dropped: u64,
+	pub rx_fifo: u64,
+	pub rx_frame: u64,
+	pub rx_compressed: u64,
+	pub rx_multicast: u64,
+	pub tx_bytes: u64,
+	pub tx_packets: u64,
+	pub tx_errors: u64,
+	pub tx_dropped: u64,
+	pub tx_fifo: u64,
+	pub tx_collisions: u64,
+	pub tx_carrier: u64,
+	pub tx_compressed: u64
+}
+
+impl NetDevEntry {
+
+	fn from_line(line: &str) -> Option<Self> {
+		let (name, values) = line.split_once(':')?;
+		let mut iter = values.split_whitespace();
+		let mut num = || -> Option<u64> { iter.next()?.parse().ok() };
+
+		Some(Self {
+			name: name.trim().into(),
+			rx_bytes: num()?,
+			rx_packets: num()?,
+			rx_errors: num()?,
+			rx_dropped: num()?,
+			rx_fifo: num()?,
+			rx_frame: num()?,
+			rx_compressed: num()?,
+			rx_multicast: num()?,
+			tx_bytes: num()?,
+			tx_packets: num()?,
+			tx_errors: num()?,
+			tx_dropped: num()?,
+			tx_fifo: num()?,
+			tx_collisions: num()?,
+			tx_carrier: num()?,
+			tx_compressed: num()?
+		})
+	}
+
+	/// The amount of data received.
+	pub fn received(&self) -> DataSize {
+		DataSize::from_size_bytes(self.rx_bytes).unwrap()
+	}
+
+	/// The amount of data transmitted.
+	pub fn transmitted(&self) -> DataSize {
+		DataSize::from_size_bytes(self.tx_bytes).unwrap()
+	}
+
+	/// Computes the throughput since an earlier reading of the same
+	/// interface.
+	pub fn rates(&self, earlier: &Self, elapsed: Duration) -> NetDevRates {
+		let rate = |n, e| Rate::from_counters(n, e, elapsed);
+
+		NetDevRates {
+			rx_bytes: rate(self.rx_bytes, earlier.rx_bytes),
+			rx_packets: rate(self.rx_packets, earlier.rx_packets),
+			rx_errors: rate(self.rx_errors, earlier.rx_errors),
+			rx_dropped: rate(self.rx_dropped, earlier.rx_dropped),
+			tx_bytes: rate(self.tx_bytes, earlier.tx_bytes),
+			tx_packets: rate(self.tx_packets, earlier.tx_packets),
+			tx_errors: rate(self.tx_errors, earlier.tx_errors),
+			tx_dropped: rate(self.tx_dropped, earlier.tx_dropped)
+		}
+	}
+
+}
+
+/// The throughput of a network interface between two [`NetDevEntry`]s.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct NetDevRates {
+	pub rx_bytes: Rate,
+	pub rx_packets: Rate,
+	pub rx_errors: Rate,
+	pub rx_dropped: Rate,
+	pub tx_bytes: Rate,
+	pub tx_packets: Rate,
+	pub tx_errors: Rate,
+	pub tx_dropped: Rate
+}
+
+/// The traffic counters of one interface from
+/// `/sys/class/net/{name}/statistics`.
+///
+/// The directory stays open and the buffers are reused, so
+/// [`reload`](Self::reload) does not allocate. The counters are combined
+/// like in `/proc/net/dev`.
+#[derive(Debug)]
+pub struct InterfaceStatistics {
+	dir: SysDir,
+	entry: NetDevEntry
+}
+
+impl InterfaceStatistics {
+	fn open(interface: &Interface) -> io::Result<Self> {
+		let mut stats = Self {
+			dir: SysDir::open(interface.path.join("statistics"))?,
+			entry: NetDevEntry {
+				name: interface.name().unwrap_or_default().into(),
+				..Default::default()
+			}
+		};
+		stats.reload()?;
+		Ok(stats)
+	}
+
+	pub fn path(&self) -> &Path {
+		self.dir.path()
+	}
+
+	/// Reads the counters again.
+	pub fn reload(&mut self) -> io::Result<()> {
+		let dir = &mut self.dir;
+		let mut value = |name: &str| dir.read_value::<u64>(name);
+		let e = &mut self.entry;
+
+		e.rx_bytes = value("rx_bytes")?;
+		e.rx_packets = value("rx_packets")?;
+		e.rx_errors = value("rx_errors")?;
+		e.rx_dropped = value("rx_dropped")? + value("rx_missed_errors")?;
+		e.rx_fifo = value("rx_fifo_errors")?;
+		e.rx_frame = value("rx_length_errors")? + value("rx_over_errors")? +
+			value("rx_crc_errors")? + value("rx_frame_errors")?;
+		e.rx_compressed = value("rx_compressed")?;
+		e.rx_multicast = value("multicast")?;
+		e.tx_bytes = value("tx_bytes")?;
+		e.tx_packets = value("tx_packets")?;
+		e.tx_errors = value("tx_errors")?;
+		e.tx_dropped = value("tx_dropped")?;
+		e.tx_fifo = value("tx_fifo_errors")?;
+		e.tx_collisions = value("collisions")?;
+		e.tx_carrier = value("tx_carrier_errors")? +
+			value("tx_aborted_errors")? + value("tx_window_errors")? +
+			value("tx_heartbeat_errors")?;
+		e.tx_compressed = value("tx_compressed")?;
+		Ok(())
+	}
+
+	/// The counters of the last reading.
+	pub fn entry(&self) -> &NetDevEntry {
+		&self.entry
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::diff::ChangeKind;
+
+	#[test]
+	fn flags() {
+		let flags = InterfaceFlags::from(0x1043);
+		assert!(flags.is_up());
+		assert!(flags.is_running());
+		assert!(flags.is_multicast());
+		assert!(!flags.is_promisc());
+		assert!(!flags.is_point_to_point());
+		assert_eq!(flags.iter().collect::<Vec<_>>(), [
+			InterfaceFlag::Up,
+			InterfaceFlag::Broadcast,
+			InterfaceFlag::Running,
+			InterfaceFlag::Multicast
+		]);
+	}
+
+	#[test]
+	fn net_dev() {
+		let dev = NetDev::from_string("\
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo: 4553128   42136    0    0    0     0          0         0  4553128   42136    0    0    0     0       0          0
+wlp2s0: 1389547352 1051402    0 1200    0     0          0         0 62093219  411233    0    3    0     0       0          0
+".into());
+		assert_eq!(dev.entries().count(), 2);
+		let lo = dev.by_name("lo").unwrap();
+		assert_eq!(lo.rx_bytes, 4553128);
+		assert_eq!(lo.tx_packets, 42136);
+		let wl = dev.by_name("wlp2s0").unwrap();
+		assert_eq!(wl.rx_bytes, 1389547352);
+		assert_eq!(wl.rx_dropped, 1200);
+		assert_eq!(wl.tx_dropped, 3);
+		assert_eq!(wl.tx_compressed, 0);
+		assert!(dev.by_name("eth0").is_none());
+	}
+
+	#[test]
+	fn net_dev_rates() {
+		let earlier = NetDev::from_string("\
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+  eth0: 4294967000 10    0    0    0     0          0         0  1000   10    0    0    0     0       0          0
+".into());
+		let dev = NetDev::from_string("\
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+  eth0: 704 30    0    0    0     0          0         0  3000   10    0    0    0     0       0          0
+".into());
+		let rates = dev.by_name("eth0").unwrap().rates(
+			&earlier.by_name("eth0").unwrap(),
+			Duration::from_secs(2)
+		);
+		// the 32bit rx counter wrapped
+		assert_eq!(rates.rx_bytes.per_second(), 500.0);
+		assert_eq!(rates.rx_packets.per_second(), 10.0);
+		assert_eq!(rates.tx_bytes.per_second(), 1000.0);
+		assert_eq!(rates.tx_packets.per_second(), 0.0);
+	}
+
+	#[test]
+	fn net_dev_diff() {
+		let earlier = NetDev::from_string("\
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo: 4553128   42136    0    0    0     0          0         0  4553128   42136    0    0    0     0       0          0
+  eth0: 1000   10    0    0    0     0          0         0  1000   10    0    0    0     0       0          0
+".into());
+		let dev = NetDev::from_string("\
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo: 4553228   42137    0    0    0     0          0         0  4553228   42137    0    0    0     0       0          0
+ wg0: 0   0    0    0    0     0          0         0  0   0    0    0    0     0       0          0
+".into());
+		let diff = earlier.diff(&dev);
+		let kinds: Vec<_> = diff.iter()
+			.map(|c| (c.key.as_str(), c.kind()))
+			.collect();
+		assert_eq!(kinds, [
+			("lo", ChangeKind::Changed),
+			("wg0", ChangeKind::Added),
+			("eth0", ChangeKind::Removed)
+		]);
+		assert_eq!(diff[0].old.as_ref().unwrap().rx_bytes, 4553128);
+		assert_eq!(diff[0].new.as_ref().unwrap().rx_bytes, 4553228);
+		assert_eq!(diff[2].old.as_ref().unwrap().tx_packets, 10);
+		assert!(dev.diff(&dev).is_empty());
+	}
+}
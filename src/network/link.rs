@@ -0,0 +1,249 @@
+//! Get notified when the carrier of a network interface goes up or down,
+//! for example to find a flaky cable.
+//!
+//! Listens for rtnetlink link events, the counters in sysfs are available
+//! with [`Interface::carrier_changes`](super::interfaces::Interface::carrier_changes).
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::network::link::LinkMonitor;
+//!
+//! let mut monitor = LinkMonitor::new().unwrap();
+//! loop {
+//!     let ev = monitor.recv().unwrap();
+//!     println!(
+//!         "{:?} {} {} (changes {:?})",
+//!         ev.time, ev.interface, if ev.up { "up" } else { "down" },
+//!         ev.carrier_changes
+//!     );
+//! }
+//! ```
+
+use super::netlink::{
+	Netlink, NETLINK_ROUTE, NLM_F_DUMP, BUFFER_SIZE, attributes, c_string,
+	u32_attr
+};
+
+use std::{fmt, io};
+use std::time::SystemTime;
+use std::collections::{HashMap, VecDeque};
+use std::os::unix::io::{AsRawFd, RawFd};
+
+// see linux/rtnetlink.h
+const RTMGRP_LINK: u32 = 1;
+pub(crate) const RTM_NEWLINK: u16 = 16;
+const RTM_DELLINK: u16 = 17;
+pub(crate) const RTM_GETLINK: u16 = 18;
+/// The size of struct ifinfomsg.
+pub(crate) const IFINFO_LEN: usize = 16;
+
+// see linux/if_link.h
+pub(crate) const IFLA_IFNAME: u16 = 3;
+pub(crate) const IFLA_STATS64: u16 = 23;
+const IFLA_CARRIER: u16 = 33;
+const IFLA_CARRIER_CHANGES: u16 = 35;
+
+/// `IFF_LOWER_UP` from linux/if.h, set if the driver signals a carrier.
+const IFF_LOWER_UP: u32 = 1 << 16;
+
+/// The carrier of an interface changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct LinkEvent {
+	/// When the event was received.
+	pub time: SystemTime,
+	/// The index of the interface.
+	pub index: u32,
+	pub interface: String,
+	/// True if the carrier was detected, false if it was lost.
+	pub up: bool,
+	/// How often the carrier changed since the interface was created.
+	pub carrier_changes: Option<u32>
+}
+
+/// The state of a link from a rtnetlink message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LinkState {
+	index: u32,
+	interface: String,
+	carrier: bool,
+	carrier_changes: Option<u32>
+}
+
+/// A rtnetlink socket reporting carrier changes.
+///
+/// The kernel sends a message for every change of an interface, only the
+/// ones where the carrier changed are returned.
+pub struct LinkMonitor {
+	netlink: Netlink,
+	buf: Vec<u8>,
+	/// The carrier of every interface.
+	carriers: HashMap<u32, bool>,
+	queue: VecDeque<LinkEvent>
+}
+
+impl LinkMonitor {
+	/// Subscribes to link events and reads the current carrier of every
+	/// interface.
+	pub fn new() -> io::Result<Self> {
+		let netlink = Netlink::subscribe(NETLINK_ROUTE, RTMGRP_LINK)?;
+
+		// read the current state after subscribing so no change is missed
+		let mut carriers = HashMap::new();
+		let mut dump = Netlink::open(NETLINK_ROUTE)?;
+		dump.request(RTM_GETLINK, NLM_F_DUMP, &[0; IFINFO_LEN], |ty, data| {
+			if let Some(link) = parse_link(ty, data) {
+				carriers.insert(link.index, link.carrier);
+			}
+		})?;
+
+		Ok(Self {
+			netlink,
+			buf: vec![0; BUFFER_SIZE],
+			carriers,
+			queue: VecDeque::new()
+		})
+	}
+
+	/// Moves the socket into or out of nonblocking mode.
+	///
+	/// In nonblocking mode `recv` returns an error with the kind
+	/// `WouldBlock` if no event is available.
+	pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+		self.netlink.set_nonblocking(nonblocking)
+	}
+
+	/// Waits for the next carrier change.
+	///
+	/// Returns an error with `ENOBUFS` if the kernel dropped messages
+	/// because they were not received fast enough.
+	pub fn recv(&mut self) -> io::Result<LinkEvent> {
+		loop {
+			if let Some(ev) = self.queue.pop_front() {
+				return Ok(ev)
+			}
+
+			let time = SystemTime::now();
+			let carriers = &mut self.carriers;
+			let queue = &mut self.queue;
+			self.netlink.recv(&mut self.buf, |ty, data| {
+				if ty == RTM_DELLINK {
+					if let Some(index) = parse_index(data) {
+						carriers.remove(&index);
+					}
+					return
+				}
+
+				let link = match parse_link(ty, data) {
+					Some(l) => l,
+					None => return
+				};
+				let prev = carriers.insert(link.index, link.carrier);
+				if prev == Some(link.carrier) {
+					return
+				}
+				queue.push_back(LinkEvent {
+					time,
+					index: link.index,
+					interface: link.interface,
+					up: link.carrier,
+					carrier_changes: link.carrier_changes
+				});
+			})?;
+		}
+	}
+}
+
+impl fmt::Debug for LinkMonitor {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("LinkMonitor")
+			.field("netlink", &self.netlink)
+			.field("carriers", &self.carriers)
+			.field("queue", &self.queue)
+			.finish()
+	}
+}
+
+impl Iterator for LinkMonitor {
+	type Item = io::Result<LinkEvent>;
+
+	fn next(&mut self) -> Option<io::Result<LinkEvent>> {
+		Some(self.recv())
+	}
+}
+
+impl AsRawFd for LinkMonitor {
+	fn as_raw_fd(&self) -> RawFd {
+		self.netlink.as_raw_fd()
+	}
+}
+
+fn parse_index(data: &[u8]) -> Option<u32> {
+	data.get(4..8).map(|b| u32::from_ne_bytes(b.try_into().unwrap()))
+}
+
+/// Splits a `RTM_NEWLINK` message into struct ifinfomsg and the attributes
+/// which follow it.
+pub(crate) fn parse_ifinfo(ty: u16, data: &[u8]) -> Option<(&[u8], &[u8])> {
+	if ty != RTM_NEWLINK || data.len() < IFINFO_LEN {
+		return None
+	}
+	Some(data.split_at(IFINFO_LEN))
+}
+
+fn parse_link(ty: u16, data: &[u8]) -> Option<LinkState> {
+	let (info, attrs) = parse_ifinfo(ty, data)?;
+	let flags = u32::from_ne_bytes(info[8..12].try_into().unwrap());
+
+	let mut link = LinkState {
+		index: parse_index(info)?,
+		interface: String::new(),
+		carrier: flags & IFF_LOWER_UP != 0,
+		carrier_changes: None
+	};
+	for (ty, value) in attributes(attrs) {
+		match ty {
+			IFLA_IFNAME => link.interface = c_string(value),
+			IFLA_CARRIER => {
+				link.carrier = value.first().map(|c| *c != 0)
+					.unwrap_or(link.carrier);
+			},
+			IFLA_CARRIER_CHANGES => link.carrier_changes = u32_attr(value),
+			_ => {}
+		}
+	}
+
+	Some(link)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::network::netlink::push_attr;
+
+	#[test]
+	fn link_message() {
+		let mut msg = vec![0u8, 0, 1, 0];
+		msg.extend_from_slice(&3u32.to_ne_bytes());
+		// up, broadcast, running, multicast and lower up
+		msg.extend_from_slice(&0x11043u32.to_ne_bytes());
+		msg.extend_from_slice(&0u32.to_ne_bytes());
+		push_attr(&mut msg, IFLA_IFNAME, b"eth0\0");
+		push_attr(&mut msg, IFLA_CARRIER_CHANGES, &7u32.to_ne_bytes());
+
+		let link = parse_link(RTM_NEWLINK, &msg).unwrap();
+		assert_eq!(link.index, 3);
+		assert_eq!(link.interface, "eth0");
+		assert!(link.carrier);
+		assert_eq!(link.carrier_changes, Some(7));
+
+		push_attr(&mut msg, IFLA_CARRIER, &[0]);
+		assert!(!parse_link(RTM_NEWLINK, &msg).unwrap().carrier);
+		assert!(parse_link(RTM_DELLINK, &msg).is_none());
+		assert!(parse_link(RTM_NEWLINK, &msg[..8]).is_none());
+	}
+}
@@ -0,0 +1,151 @@
+//! Read the traffic counters of every interface with a single rtnetlink
+//! dump instead of reading many files in `/sys/class/net/*/statistics`.
+//!
+//! This has less overhead when sampling often, since the socket is reused
+//! and the kernel does not need to format the counters as text. The
+//! `link_stats_bench` example compares it with sysfs and `/proc/net/dev`,
+//! it is many times faster than sysfs and slightly faster than procfs. The
+//! counters are combined like the kernel does for `/proc/net/dev`, so the
+//! returned [`NetDevEntry`]s are the same as from
+//! [`NetDev`](super::interfaces::NetDev).
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::network::link_stats::LinkStatsReader;
+//!
+//! let mut reader = LinkStatsReader::new().unwrap();
+//! for entry in reader.read().unwrap() {
+//!     println!("{} rx {} tx {}", entry.name, entry.rx_bytes, entry.tx_bytes);
+//! }
+//! ```
+
+use super::interfaces::NetDevEntry;
+use super::link::{
+	RTM_GETLINK, IFINFO_LEN, IFLA_IFNAME, IFLA_STATS64, parse_ifinfo
+};
+use super::netlink::{
+	Netlink, NETLINK_ROUTE, NLM_F_DUMP, attributes, c_string, u64_attr
+};
+
+use std::io;
+
+/// Reads the counters of every interface over a reusable rtnetlink socket.
+#[derive(Debug)]
+pub struct LinkStatsReader {
+	netlink: Netlink
+}
+
+impl LinkStatsReader {
+	pub fn new() -> io::Result<Self> {
+		Ok(Self {
+			netlink: Netlink::open(NETLINK_ROUTE)?
+		})
+	}
+
+	/// Returns the counters of every interface, ordered by the interface
+	/// index.
+	pub fn read(&mut self) -> io::Result<Vec<NetDevEntry>> {
+		let mut list = vec![];
+		self.netlink.request(
+			RTM_GETLINK,
+			NLM_F_DUMP,
+			&[0; IFINFO_LEN],
+			|ty, data| list.extend(parse_link(ty, data))
+		)?;
+		Ok(list)
+	}
+}
+
+/// Returns the counters of every interface, see [`LinkStatsReader`] to
+/// reuse the socket.
+pub fn link_stats() -> io::Result<Vec<NetDevEntry>> {
+	LinkStatsReader::new()?.read()
+}
+
+fn parse_link(ty: u16, data: &[u8]) -> Option<NetDevEntry> {
+	let (_, attrs) = parse_ifinfo(ty, data)?;
+
+	let mut name = None;
+	let mut stats = None;
+	for (ty, value) in attributes(attrs) {
+		match ty {
+			IFLA_IFNAME => name = Some(c_string(value)),
+			IFLA_STATS64 => stats = Some(value),
+			_ => {}
+		}
+	}
+
+	parse_stats64(name?, stats?)
+}
+
+/// Parses struct rtnl_link_stats64 and combines the error counters like
+/// `dev_seq_printf_stats` in net/core/net-procfs.c.
+fn parse_stats64(name: String, data: &[u8]) -> Option<NetDevEntry> {
+	let field = |i: usize| data.get(i * 8..).and_then(u64_attr);
+	// the fields which exist since the struct was added
+	let f = |i: usize| field(i).unwrap_or(0);
+	field(22)?;
+
+	Some(NetDevEntry {
+		name,
+		rx_bytes: f(2),
+		rx_packets: f(0),
+		rx_errors: f(4),
+		// rx_dropped + rx_missed_errors
+		rx_dropped: f(6) + f(15),
+		rx_fifo: f(14),
+		// rx_length_errors + rx_over_errors + rx_crc_errors +
+		// rx_frame_errors
+		rx_frame: f(10) + f(11) + f(12) + f(13),
+		rx_compressed: f(21),
+		rx_multicast: f(8),
+		tx_bytes: f(3),
+		tx_packets: f(1),
+		tx_errors: f(5),
+		tx_dropped: f(7),
+		tx_fifo: f(18),
+		tx_collisions: f(9),
+		// tx_carrier_errors + tx_aborted_errors + tx_window_errors +
+		// tx_heartbeat_errors
+		tx_carrier: f(17) + f(16) + f(20) + f(19),
+		tx_compressed: f(22)
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::network::link::RTM_NEWLINK;
+	use crate::network::netlink::push_attr;
+
+	#[test]
+	fn stats_message() {
+		let mut msg = vec![0u8, 0, 1, 0];
+		msg.extend_from_slice(&2u32.to_ne_bytes());
+		msg.extend_from_slice(&0x11043u32.to_ne_bytes());
+		msg.extend_from_slice(&0u32.to_ne_bytes());
+		push_attr(&mut msg, IFLA_IFNAME, b"eth0\0");
+
+		// every field is its index + 1
+		let stats: Vec<u8> = (1..=24u64)
+			.flat_map(|v| v.to_ne_bytes().to_vec())
+			.collect();
+		push_attr(&mut msg, IFLA_STATS64, &stats);
+
+		let entry = parse_link(RTM_NEWLINK, &msg).unwrap();
+		assert_eq!(entry.name, "eth0");
+		assert_eq!(entry.rx_packets, 1);
+		assert_eq!(entry.tx_packets, 2);
+		assert_eq!(entry.rx_bytes, 3);
+		assert_eq!(entry.tx_bytes, 4);
+		assert_eq!(entry.rx_dropped, 7 + 16);
+		assert_eq!(entry.rx_frame, 11 + 12 + 13 + 14);
+		assert_eq!(entry.tx_carrier, 17 + 18 + 20 + 21);
+		assert_eq!(entry.rx_multicast, 9);
+		assert_eq!(entry.tx_collisions, 10);
+		assert_eq!(entry.tx_compressed, 23);
+
+		assert!(parse_stats64("eth0".into(), &stats[..64]).is_none());
+		assert!(parse_link(RTM_NEWLINK, &msg[..8]).is_none());
+	}
+}
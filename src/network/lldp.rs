@@ -0,0 +1,419 @@
+//! Get the LLDP neighbors (the switch and port an interface is plugged into)
+//! received by systemd-networkd or lldpd.
+//!
+//! systemd-networkd stores the received frames in
+//! `/run/systemd/netif/lldp` if `LLDP=yes` is set for the link (the
+//! default), lldpd is queried with `lldpcli`.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::network::lldp::neighbors;
+//!
+//! for n in neighbors().unwrap() {
+//!     println!(
+//!         "{} -> {:?} port {} vlan {:?}",
+//!         n.interface, n.system_name, n.port_id, n.port_vlan_id
+//!     );
+//! }
+//! ```
+
+use super::interfaces::Interface;
+
+use std::{fs, io};
+use std::process::Command;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+const NETWORKD_PATH: &str = "/run/systemd/netif/lldp";
+
+/// The ethernet header in front of the tlvs.
+const ETHER_HDR_LEN: usize = 14;
+const ETHERTYPE_LLDP: u16 = 0x88cc;
+
+// see IEEE 802.1AB
+const TLV_END: u8 = 0;
+const TLV_CHASSIS_ID: u8 = 1;
+const TLV_PORT_ID: u8 = 2;
+const TLV_PORT_DESCRIPTION: u8 = 4;
+const TLV_SYSTEM_NAME: u8 = 5;
+const TLV_SYSTEM_DESCRIPTION: u8 = 6;
+const TLV_MANAGEMENT_ADDRESS: u8 = 8;
+const TLV_ORG_SPECIFIC: u8 = 127;
+
+const OUI_802_1: [u8; 3] = [0x00, 0x80, 0xc2];
+const ORG_PORT_VLAN_ID: u8 = 1;
+const ORG_VLAN_NAME: u8 = 3;
+
+// the subtypes of the chassis and port id
+const ID_MAC_ADDRESS_CHASSIS: u8 = 4;
+const ID_MAC_ADDRESS_PORT: u8 = 3;
+const ID_NETWORK_ADDRESS_CHASSIS: u8 = 5;
+const ID_NETWORK_ADDRESS_PORT: u8 = 4;
+
+/// A device which announced itself on an interface.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct LldpNeighbor {
+	/// The local interface the neighbor was seen on.
+	pub interface: String,
+	/// Identifies the neighbor, mostly its mac address.
+	pub chassis_id: String,
+	/// Identifies the port of the neighbor, mostly the interface name like
+	/// `Gi1/0/12`.
+	pub port_id: String,
+	pub port_description: Option<String>,
+	/// The hostname of the neighbor.
+	pub system_name: Option<String>,
+	pub system_description: Option<String>,
+	pub management_address: Option<IpAddr>,
+	/// The untagged vlan of the port.
+	pub port_vlan_id: Option<u16>,
+	/// The vlans configured on the port, if the neighbor announces them.
+	pub vlans: Vec<LldpVlan>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct LldpVlan {
+	pub id: u16,
+	pub name: Option<String>
+}
+
+/// Returns the neighbors of every interface, from systemd-networkd or if it
+/// has not received any from lldpd.
+pub fn neighbors() -> io::Result<Vec<LldpNeighbor>> {
+	let list = networkd_neighbors()?;
+	if !list.is_empty() {
+		return Ok(list)
+	}
+	lldpd_neighbors()
+}
+
+/// Returns the neighbors received by systemd-networkd.
+///
+/// Returns an empty list if networkd is not running.
+pub fn networkd_neighbors() -> io::Result<Vec<LldpNeighbor>> {
+	let dir = match fs::read_dir(NETWORKD_PATH) {
+		Ok(d) => d,
+		Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+		Err(e) => return Err(e)
+	};
+
+	let interfaces = Interface::all()?;
+	let mut list = vec![];
+	for entry in dir {
+		let entry = entry?;
+		// the files are named after the interface index
+		let index: u32 = match entry.file_name().to_str()
+			.and_then(|n| n.parse().ok())
+		{
+			Some(i) => i,
+			None => continue
+		};
+		let interface = interfaces.iter()
+			.find(|i| i.index().ok() == Some(index))
+			.and_then(Interface::name)
+			.map(Into::into)
+			.unwrap_or_else(|| index.to_string());
+
+		let data = fs::read(entry.path())?;
+		list.extend(parse_networkd(&interface, &data));
+	}
+	list.sort_by(|a, b| a.interface.cmp(&b.interface));
+	Ok(list)
+}
+
+/// Returns the neighbors received by lldpd using `lldpcli`.
+///
+/// Returns an empty list if lldpd is not installed.
+pub fn lldpd_neighbors() -> io::Result<Vec<LldpNeighbor>> {
+	let output = Command::new("lldpcli")
+		.args(["-f", "keyvalue", "show", "neighbors", "details"])
+		.output();
+	let output = match output {
+		Ok(o) => o,
+		Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+		Err(e) => return Err(e)
+	};
+	if !output.status.success() {
+		let msg = String::from_utf8_lossy(&output.stderr);
+		return Err(io::Error::new(io::ErrorKind::Other, msg.trim()))
+	}
+
+	Ok(parse_keyvalue(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parses the file written by networkd, every frame is prefixed with its
+/// length as a little endian u64.
+fn parse_networkd(interface: &str, mut data: &[u8]) -> Vec<LldpNeighbor> {
+	let mut list = vec![];
+	while data.len() >= 8 {
+		let len = u64::from_le_bytes(data[..8].try_into().unwrap()) as usize;
+		let frame = match data[8..].get(..len) {
+			Some(f) => f,
+			None => break
+		};
+		list.extend(LldpNeighbor::parse_frame(interface, frame));
+		data = &data[8 + len..];
+	}
+	list
+}
+
+/// Parses the output of `lldpcli -f keyvalue`, the lines look like
+/// `lldp.eth0.chassis.name=switch`.
+fn parse_keyvalue(out: &str) -> Vec<LldpNeighbor> {
+	let mut list: Vec<LldpNeighbor> = vec![];
+	for line in out.lines() {
+		let (key, value) = match line.strip_prefix("lldp.")
+			.and_then(|l| l.split_once('='))
+		{
+			Some(kv) => kv,
+			None => continue
+		};
+		let (interface, field) = match split_key(key) {
+			Some(k) => k,
+			None => continue
+		};
+
+		// via is the first line of every neighbor
+		let new = list.last()
+			.map(|n| n.interface != interface || field == "via")
+			.unwrap_or(true);
+		if new {
+			list.push(LldpNeighbor {
+				interface: interface.into(),
+				..Default::default()
+			});
+		}
+		let n = list.last_mut().unwrap();
+
+		match field {
+			"chassis.mac" | "chassis.ip" | "chassis.ifname" |
+			"chassis.local" => n.chassis_id = value.into(),
+			"chassis.name" => n.system_name = Some(value.into()),
+			"chassis.descr" => n.system_description = Some(value.into()),
+			"chassis.mgmt-ip" if n.management_address.is_none() => {
+				n.management_address = value.parse().ok();
+			},
+			"port.mac" | "port.ip" | "port.ifname" |
+			"port.local" => n.port_id = value.into(),
+			"port.descr" => n.port_description = Some(value.into()),
+			"vlan.vlan-id" => {
+				if let Ok(id) = value.parse() {
+					n.vlans.push(LldpVlan { id, name: None });
+				}
+			},
+			"vlan.pvid" if value == "yes" => {
+				n.port_vlan_id = n.vlans.last().map(|v| v.id);
+			},
+			"vlan" => {
+				if let Some(v) = n.vlans.last_mut() {
+					v.name = Some(value.into());
+				}
+			},
+			_ => {}
+		}
+	}
+
+	list.retain(|n| !n.chassis_id.is_empty() && !n.port_id.is_empty());
+	list
+}
+
+/// Splits a key into the interface and the field, the interface name can
+/// contain dots.
+fn split_key(key: &str) -> Option<(&str, &str)> {
+	[".chassis.", ".port.", ".vlan", ".via", ".rid", ".age"].iter()
+		.filter_map(|m| key.find(m))
+		.min()
+		.map(|i| (&key[..i], &key[i + 1..]))
+}
+
+impl LldpNeighbor {
+	/// Parses an ethernet frame containing a lldp data unit.
+	fn parse_frame(interface: &str, frame: &[u8]) -> Option<Self> {
+		let ethertype = u16::from_be_bytes([*frame.get(12)?, *frame.get(13)?]);
+		if ethertype != ETHERTYPE_LLDP {
+			return None
+		}
+
+		let mut n = Self {
+			interface: interface.into(),
+			..Default::default()
+		};
+		let mut data = &frame[ETHER_HDR_LEN..];
+		while data.len() >= 2 {
+			let header = u16::from_be_bytes([data[0], data[1]]);
+			let ty = (header >> 9) as u8;
+			let len = (header & 0x1ff) as usize;
+			let value = data.get(2..2 + len)?;
+			data = &data[2 + len..];
+
+			match ty {
+				TLV_END => break,
+				TLV_CHASSIS_ID => n.chassis_id = parse_id(
+					value,
+					ID_MAC_ADDRESS_CHASSIS,
+					ID_NETWORK_ADDRESS_CHASSIS
+				)?,
+				TLV_PORT_ID => n.port_id = parse_id(
+					value,
+					ID_MAC_ADDRESS_PORT,
+					ID_NETWORK_ADDRESS_PORT
+				)?,
+				TLV_PORT_DESCRIPTION => {
+					n.port_description = Some(string(value));
+				},
+				TLV_SYSTEM_NAME => n.system_name = Some(string(value)),
+				TLV_SYSTEM_DESCRIPTION => {
+					n.system_description = Some(string(value));
+				},
+				TLV_MANAGEMENT_ADDRESS => {
+					// the first byte is the length of the subtype and address
+					let len = *value.first()? as usize;
+					if n.management_address.is_none() && len >= 1 {
+						n.management_address = value.get(1..1 + len)
+							.and_then(network_address);
+					}
+				},
+				TLV_ORG_SPECIFIC
+					if value.len() >= 6 && value[..3] == OUI_802_1 =>
+				{
+					let vid = u16::from_be_bytes([value[4], value[5]]);
+					match value[3] {
+						ORG_PORT_VLAN_ID => n.port_vlan_id = Some(vid),
+						ORG_VLAN_NAME => {
+							let len = *value.get(6)? as usize;
+							n.vlans.push(LldpVlan {
+								id: vid,
+								name: value.get(7..7 + len).map(string)
+							});
+						},
+						_ => {}
+					}
+				},
+				_ => {}
+			}
+		}
+
+		(!n.chassis_id.is_empty() && !n.port_id.is_empty()).then(|| n)
+	}
+}
+
+/// Formats a chassis or port id.
+fn parse_id(value: &[u8], mac: u8, network: u8) -> Option<String> {
+	let (subtype, id) = value.split_first()?;
+	Some(match *subtype {
+		s if s == mac && id.len() == 6 => mac_address(id),
+		s if s == network => network_address(id)?.to_string(),
+		_ => string(id)
+	})
+}
+
+fn mac_address(b: &[u8]) -> String {
+	b.iter()
+		.map(|b| format!("{:02x}", b))
+		.collect::<Vec<_>>()
+		.join(":")
+}
+
+/// Parses an address family number followed by the address.
+fn network_address(b: &[u8]) -> Option<IpAddr> {
+	match b.split_first()? {
+		(1, a) if a.len() == 4 => {
+			Some(Ipv4Addr::new(a[0], a[1], a[2], a[3]).into())
+		},
+		(2, a) if a.len() == 16 => {
+			let a: [u8; 16] = a.try_into().unwrap();
+			Some(Ipv6Addr::from(a).into())
+		},
+		_ => None
+	}
+}
+
+fn string(b: &[u8]) -> String {
+	String::from_utf8_lossy(b).trim_end_matches('\0').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn tlv(frame: &mut Vec<u8>, ty: u8, value: &[u8]) {
+		let header = (ty as u16) << 9 | value.len() as u16;
+		frame.extend_from_slice(&header.to_be_bytes());
+		frame.extend_from_slice(value);
+	}
+
+	#[test]
+	fn networkd_file() {
+		let mut frame = vec![0x01, 0x80, 0xc2, 0, 0, 0x0e];
+		frame.extend_from_slice(&[0x00, 0x11, 0x22, 0x33, 0x44, 0x56]);
+		frame.extend_from_slice(&ETHERTYPE_LLDP.to_be_bytes());
+		tlv(&mut frame, TLV_CHASSIS_ID, &[4, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+		tlv(&mut frame, TLV_PORT_ID, b"\x05Gi1/0/12");
+		tlv(&mut frame, 3, &120u16.to_be_bytes());
+		tlv(&mut frame, TLV_PORT_DESCRIPTION, b"server 12");
+		tlv(&mut frame, TLV_SYSTEM_NAME, b"switch1");
+		tlv(&mut frame, TLV_MANAGEMENT_ADDRESS, &[5, 1, 10, 0, 0, 1, 2, 0, 0, 0, 1, 0]);
+		tlv(&mut frame, TLV_ORG_SPECIFIC, &[0x00, 0x80, 0xc2, 1, 0, 100]);
+		tlv(&mut frame, TLV_ORG_SPECIFIC, b"\x00\x80\xc2\x03\x00\xc8\x07servers");
+		tlv(&mut frame, TLV_END, &[]);
+
+		let mut data = (frame.len() as u64).to_le_bytes().to_vec();
+		data.extend_from_slice(&frame);
+		let list = parse_networkd("eth0", &data);
+		assert_eq!(list, [LldpNeighbor {
+			interface: "eth0".into(),
+			chassis_id: "00:11:22:33:44:55".into(),
+			port_id: "Gi1/0/12".into(),
+			port_description: Some("server 12".into()),
+			system_name: Some("switch1".into()),
+			system_description: None,
+			management_address: Some(Ipv4Addr::new(10, 0, 0, 1).into()),
+			port_vlan_id: Some(100),
+			vlans: vec![LldpVlan { id: 200, name: Some("servers".into()) }]
+		}]);
+
+		// a truncated frame is ignored
+		assert!(parse_networkd("eth0", &data[..data.len() - 1]).is_empty());
+	}
+
+	#[test]
+	fn lldpcli() {
+		let out = "\
+lldp.eth0.100.via=LLDP
+lldp.eth0.100.rid=1
+lldp.eth0.100.chassis.mac=00:11:22:33:44:55
+lldp.eth0.100.chassis.name=switch1
+lldp.eth0.100.chassis.mgmt-ip=10.0.0.1
+lldp.eth0.100.chassis.mgmt-ip=fe80::1
+lldp.eth0.100.port.ifname=Gi1/0/12
+lldp.eth0.100.port.descr=server 12
+lldp.eth0.100.vlan.vlan-id=100
+lldp.eth0.100.vlan.pvid=yes
+lldp.eth0.100.vlan=VLAN100
+lldp.eth1.via=LLDP
+lldp.eth1.chassis.local=router
+lldp.eth1.port.local=7
+";
+		let list = parse_keyvalue(out);
+		assert_eq!(list.len(), 2);
+		let n = &list[0];
+		assert_eq!(n.interface, "eth0.100");
+		assert_eq!(n.chassis_id, "00:11:22:33:44:55");
+		assert_eq!(n.system_name.as_deref(), Some("switch1"));
+		assert_eq!(n.management_address, Some(Ipv4Addr::new(10, 0, 0, 1).into()));
+		assert_eq!(n.port_id, "Gi1/0/12");
+		assert_eq!(n.port_vlan_id, Some(100));
+		assert_eq!(n.vlans, [LldpVlan { id: 100, name: Some("VLAN100".into()) }]);
+		assert_eq!(list[1].interface, "eth1");
+		assert_eq!(list[1].port_id, "7");
+	}
+}
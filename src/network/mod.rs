@@ -1,8 +1,76 @@
 //! Get information about the network.
 //!
-//! TODO
-//! - list open ports
-//! - list network cards
+//! The interfaces, link, link_stats, lldp, qdisc, softnet, sockets,
+//! inet_diag, routes, multicast, wireless and wwan modules are always
+//! available. The network_manager and modem_manager modules require the
+//! `network-manager` and `modem-manager` feature, `network` enables both.
+//! The bluetooth and wpa_supplicant modules only need libdbus and are
+//! available with the `dbus` feature, which is also enabled by the other
+//! dbus features. The `zbus` feature provides the network manager without
+//! libdbus.
 
+/// Generates async versions of methods which just forward the call to the
+/// blocking method on a separate thread.
+#[cfg(all(
+	any(feature = "network-manager", feature = "modem-manager"),
+	feature = "async"
+))]
+macro_rules! async_fns {
+	($inner:ident {$(
+		fn $name:ident($($arg:ident: $ty:ty),*) -> $ret:ty;
+	)*}) => ($(
+		#[doc = concat!(
+			"See [`", stringify!($inner), "::", stringify!($name), "`]."
+		)]
+		pub async fn $name(&self, $($arg: $ty),*) -> Result<$ret, Error> {
+			let inner = self.inner.clone();
+			$crate::network::spawn_blocking(move || inner.$name($($arg),*)).await
+		}
+	)*)
+}
+
+pub mod interfaces;
+pub mod link;
+pub mod link_stats;
+pub mod lldp;
+pub mod qdisc;
+pub mod softnet;
+pub mod sockets;
+mod netlink;
+pub mod inet_diag;
+pub mod routes;
+pub mod multicast;
+pub mod wireless;
+pub mod wwan;
+mod ping;
+pub use ping::{ping, ping_addr};
+pub use routes::{default_interface, primary_ip};
+#[cfg(any(feature = "network-manager", feature = "zbus"))]
+#[cfg_attr(
+	docsrs,
+	doc(cfg(any(feature = "network-manager", feature = "zbus")))
+)]
 pub mod network_manager;
-pub mod modem_manager;
\ No newline at end of file
+#[cfg(feature = "modem-manager")]
+#[cfg_attr(docsrs, doc(cfg(feature = "modem-manager")))]
+pub mod modem_manager;
+#[cfg(feature = "dbus")]
+#[cfg_attr(docsrs, doc(cfg(feature = "dbus")))]
+pub mod bluetooth;
+#[cfg(feature = "dbus")]
+#[cfg_attr(docsrs, doc(cfg(feature = "dbus")))]
+pub mod wpa_supplicant;
+
+/// Runs a blocking dbus call on tokio's blocking thread pool.
+#[cfg(all(
+	any(feature = "network-manager", feature = "modem-manager"),
+	feature = "async"
+))]
+async fn spawn_blocking<F, T>(f: F) -> Result<T, dbus::Error>
+where
+	F: FnOnce() -> Result<T, dbus::Error> + Send + 'static,
+	T: Send + 'static
+{
+	tokio::task::spawn_blocking(f).await
+		.map_err(|e| dbus::Error::new_failed(&e.to_string()))?
+}
@@ -1,11 +1,17 @@
 //! Connect to the ModemManager
+//!
+//! If ModemManager is not running [`wwan`](super::wwan) still identifies the
+//! cellular hardware.
 
-use std::time::Duration;
+use std::fmt;
+use std::time::{Duration, SystemTime};
 use std::sync::Arc;
+use std::iter::FromIterator;
+use std::collections::VecDeque;
 
 use dbus::{Error, Path};
-use dbus::blocking::{Connection, Proxy};
-use dbus::blocking::stdintf::org_freedesktop_dbus::ObjectManager;
+use dbus::blocking::{SyncConnection, Proxy};
+use dbus::blocking::stdintf::org_freedesktop_dbus::{ObjectManager, Properties};
 use dbus::arg::{RefArg, PropMap};
 
 use mmdbus::modem::Modem as ModemAccess;
@@ -15,25 +21,44 @@ use mmdbus::sim::Sim as SimTrait;
 
 const DBUS_NAME: &str = "org.freedesktop.ModemManager1";
 const DBUS_PATH: &str = "/org/freedesktop/ModemManager1";
+const MODEM_INTERFACE: &str = "org.freedesktop.ModemManager1.Modem";
+const MODEM_3GPP_INTERFACE: &str = "org.freedesktop.ModemManager1.Modem.Modem3gpp";
+const MODEM_SIGNAL_INTERFACE: &str = "org.freedesktop.ModemManager1.Modem.Signal";
+const MODEM_CDMA_INTERFACE: &str =
+	"org.freedesktop.ModemManager1.Modem.ModemCdma";
+const MODEM_FIRMWARE_INTERFACE: &str =
+	"org.freedesktop.ModemManager1.Modem.Firmware";
+const MODEM_OMA_INTERFACE: &str = "org.freedesktop.ModemManager1.Modem.Oma";
 const TIMEOUT: Duration = Duration::from_secs(2);
 
 #[derive(Clone)]
 struct Dbus {
-	conn: Arc<Connection>
+	conn: Arc<SyncConnection>,
+	timeout: Duration
 }
 
 impl Dbus {
 	fn connect() -> Result<Self, Error> {
-		Connection::new_system()
+		SyncConnection::new_system()
 			.map(Arc::new)
-			.map(|conn| Self { conn })
+			.map(|conn| Self { conn, timeout: TIMEOUT })
 	}
 
 	fn proxy<'a, 'b>(
 		&'b self,
 		path: impl Into<Path<'a>>
-	) -> Proxy<'a, &'b Connection> {
-		self.conn.with_proxy(DBUS_NAME, path, TIMEOUT)
+	) -> Proxy<'a, &'b SyncConnection> {
+		self.conn.with_proxy(DBUS_NAME, path, self.timeout)
+	}
+
+	/// Returns true if the service currently owns its name on the bus.
+	fn name_has_owner(&self) -> Result<bool, Error> {
+		self.conn.with_proxy(
+			"org.freedesktop.DBus",
+			"/org/freedesktop/DBus",
+			self.timeout
+		).method_call("org.freedesktop.DBus", "NameHasOwner", (DBUS_NAME,))
+			.map(|(owned,): (bool,)| owned)
 	}
 }
 
@@ -43,11 +68,35 @@ pub struct ModemManager {
 }
 
 impl ModemManager {
+	/// Connects to the system bus with the default timeout of 2 seconds.
 	pub fn connect() -> Result<Self, Error> {
 		Dbus::connect()
 			.map(|dbus| Self { dbus })
 	}
 
+	/// Creates a builder which allows to configure the timeout or to use an
+	/// existing connection.
+	pub fn builder() -> ModemManagerBuilder {
+		ModemManagerBuilder::new()
+	}
+
+	/// Creates a ModemManager from an existing system bus connection.
+	///
+	/// This allows to share one connection between multiple managers.
+	pub fn from_connection(conn: Arc<SyncConnection>) -> Self {
+		Self {
+			dbus: Dbus { conn, timeout: TIMEOUT }
+		}
+	}
+
+	/// Returns true if ModemManager is running.
+	///
+	/// Other calls fail with a generic dbus error if the service is missing,
+	/// this allows to check for it before and degrade gracefully.
+	pub fn is_available(&self) -> Result<bool, Error> {
+		self.dbus.name_has_owner()
+	}
+
 	pub fn modems(&self) -> Result<Vec<Modem>, Error> {
 		let objects = self.dbus.proxy(DBUS_PATH).get_managed_objects()?;
 		let modems = objects.into_iter()
@@ -63,6 +112,59 @@ impl ModemManager {
 	}
 }
 
+/// A builder to create a [`ModemManager`].
+///
+/// ## Example
+/// ```no_run
+/// # use std::time::Duration;
+/// # use linux_info::network::modem_manager::ModemManager;
+/// let manager = ModemManager::builder()
+///     .timeout(Duration::from_secs(5))
+///     .connect()
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct ModemManagerBuilder {
+	conn: Option<Arc<SyncConnection>>,
+	timeout: Duration
+}
+
+impl ModemManagerBuilder {
+	fn new() -> Self {
+		Self {
+			conn: None,
+			timeout: TIMEOUT
+		}
+	}
+
+	/// Sets the timeout used for every dbus call (default 2 seconds).
+	pub fn timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = timeout;
+		self
+	}
+
+	/// Uses an existing connection instead of opening a new one to the
+	/// system bus.
+	pub fn connection(mut self, conn: Arc<SyncConnection>) -> Self {
+		self.conn = Some(conn);
+		self
+	}
+
+	/// Creates the ModemManager, opening a new connection to the system bus if
+	/// no connection was set.
+	pub fn connect(self) -> Result<ModemManager, Error> {
+		let conn = match self.conn {
+			Some(conn) => conn,
+			None => Arc::new(SyncConnection::new_system()?)
+		};
+
+		Ok(ModemManager {
+			dbus: Dbus { conn, timeout: self.timeout }
+		})
+	}
+}
+
+#[derive(Clone)]
 pub struct Modem {
 	dbus: Dbus,
 	path: Path<'static>
@@ -97,6 +199,42 @@ impl Modem {
 		self.dbus.proxy(&self.path).device()
 	}
 
+	/// List of MMModemCapability bitmasks, specifying the combinations of
+	/// generic family of access technologies the modem supports.
+	///
+	/// If the modem doesn't allow changing the current capabilities, a single
+	/// entry with the same value as "CurrentCapabilities" will be returned.
+	pub fn supported_capabilities(
+		&self
+	) -> Result<Vec<ModemCapabilities>, Error> {
+		self.dbus.proxy(&self.path).supported_capabilities()
+			.map(|v| v.into_iter().map(Into::into).collect())
+	}
+
+	/// Bitmask of MMModemCapability values, specifying the generic family of
+	/// access technologies the modem currently supports without a firmware
+	/// reload or reinitialization.
+	pub fn current_capabilities(&self) -> Result<ModemCapabilities, Error> {
+		self.dbus.proxy(&self.path).current_capabilities()
+			.map(Into::into)
+	}
+
+	/// Bitmask of MMBearerIpFamily values, specifying the IP families
+	/// supported by the modem.
+	pub fn supported_ip_families(&self) -> Result<IpFamilies, Error> {
+		self.dbus.proxy(&self.path).supported_ip_families()
+			.map(Into::into)
+	}
+
+	/// The maximum number of active packet data bearers the modem supports.
+	///
+	/// POTS and CDMA2000-only devices support one active bearer, while GSM/UMTS
+	/// and LTE/5GNR capable devices (including LTE/CDMA devices) typically
+	/// support at least two active bearers.
+	pub fn max_active_bearers(&self) -> Result<u32, Error> {
+		self.dbus.proxy(&self.path).max_active_bearers()
+	}
+
 	/// Overall state of the modem, given as a MMModemState value.
 	///
 	/// If the device's state cannot be determined, MM_MODEM_STATE_UNKNOWN will
@@ -204,45 +342,55 @@ impl Modem {
 	/// Available signal information for the CDMA1x access technology.
 	pub fn signal_cdma(&self) -> Result<SignalCdma, Error> {
 		let data = self.dbus.proxy(&self.path).cdma()?;
-		SignalCdma::from_prop_map(data)
+		SignalCdma::from_prop_map(&data)
 			.ok_or_else(|| Error::new_failed("cdma not found"))
 	}
 
 	/// Available signal information for the CDMA EV-DO access technology.
 	pub fn signal_evdo(&self) -> Result<SignalEvdo, Error> {
 		let data = self.dbus.proxy(&self.path).evdo()?;
-		SignalEvdo::from_prop_map(data)
+		SignalEvdo::from_prop_map(&data)
 			.ok_or_else(|| Error::new_failed("evdo not found"))
 	}
 
 	/// Available signal information for the GSM/GPRS access technology.
 	pub fn signal_gsm(&self) -> Result<SignalGsm, Error> {
 		let data = self.dbus.proxy(&self.path).gsm()?;
-		SignalGsm::from_prop_map(data)
+		SignalGsm::from_prop_map(&data)
 			.ok_or_else(|| Error::new_failed("gsm not found"))
 	}
 
 	/// Available signal information for the UMTS (WCDMA) access technology.
 	pub fn signal_umts(&self) -> Result<SignalUmts, Error> {
 		let data = self.dbus.proxy(&self.path).umts()?;
-		SignalUmts::from_prop_map(data)
+		SignalUmts::from_prop_map(&data)
 			.ok_or_else(|| Error::new_failed("umts not found"))
 	}
 
 	/// Available signal information for the LTE access technology.
 	pub fn signal_lte(&self) -> Result<SignalLte, Error> {
 		let data = self.dbus.proxy(&self.path).lte()?;
-		SignalLte::from_prop_map(data)
+		SignalLte::from_prop_map(&data)
 			.ok_or_else(|| Error::new_failed("lte not found"))
 	}
 
 	/// Available signal information for the 5G access technology.
 	pub fn signal_nr5g(&self) -> Result<SignalNr5g, Error> {
 		let data = self.dbus.proxy(&self.path).nr5g()?;
-		SignalNr5g::from_prop_map(data)
+		SignalNr5g::from_prop_map(&data)
 			.ok_or_else(|| Error::new_failed("nr5g not found"))
 	}
 
+	/// Available signal information for every access technology with one
+	/// `GetAll` call.
+	///
+	/// Requires [`Modem::signal_setup`] to be called with a rate above zero.
+	pub fn signal_info(&self) -> Result<SignalInfo, Error> {
+		self.dbus.proxy(&self.path)
+			.get_all(MODEM_SIGNAL_INTERFACE)
+			.map(|props| SignalInfo::from_prop_map(&props))
+	}
+
 	/// List of numbers (e.g. MSISDN in 3GPP) being currently handled by this
 	/// modem.
 	pub fn own_numbers(&self) -> Result<Vec<String>, Error> {
@@ -306,6 +454,83 @@ impl Modem {
 		ModemModem3gpp::operator_name(&self.dbus.proxy(&self.path))
 	}
 
+	/// The mobile equipment identifier of a CDMA modem.
+	///
+	/// ## Note
+	/// The CDMA interface is only available for CDMA modems once they are
+	/// enabled.
+	pub fn meid(&self) -> Result<String, Error> {
+		self.dbus.proxy(&self.path).get(MODEM_CDMA_INTERFACE, "Meid")
+	}
+
+	/// The electronic serial number of a CDMA modem.
+	///
+	/// ## Note
+	/// See [`Modem::meid`].
+	pub fn esn(&self) -> Result<String, Error> {
+		self.dbus.proxy(&self.path).get(MODEM_CDMA_INTERFACE, "Esn")
+	}
+
+	/// The system identifier of the serving CDMA network, `None` if not
+	/// known.
+	///
+	/// ## Note
+	/// See [`Modem::meid`].
+	pub fn sid(&self) -> Result<Option<u32>, Error> {
+		self.dbus.proxy(&self.path).get(MODEM_CDMA_INTERFACE, "Sid")
+			.map(|sid: u32| (sid != CDMA_SID_UNKNOWN).then(|| sid))
+	}
+
+	/// The network identifier of the serving CDMA network, `None` if not
+	/// known.
+	///
+	/// ## Note
+	/// See [`Modem::meid`].
+	pub fn nid(&self) -> Result<Option<u32>, Error> {
+		self.dbus.proxy(&self.path).get(MODEM_CDMA_INTERFACE, "Nid")
+			.map(|nid: u32| (nid != CDMA_NID_UNKNOWN).then(|| nid))
+	}
+
+	/// The registration state of the CDMA 1x network.
+	///
+	/// ## Note
+	/// See [`Modem::meid`].
+	pub fn cdma1x_registration_state(
+		&self
+	) -> Result<CdmaRegistrationState, Error> {
+		self.dbus.proxy(&self.path)
+			.get(MODEM_CDMA_INTERFACE, "Cdma1xRegistrationState")
+			.map(|s: u32| s.into())
+	}
+
+	/// The registration state of the EV-DO network.
+	///
+	/// ## Note
+	/// See [`Modem::meid`].
+	pub fn evdo_registration_state(
+		&self
+	) -> Result<CdmaRegistrationState, Error> {
+		self.dbus.proxy(&self.path)
+			.get(MODEM_CDMA_INTERFACE, "EvdoRegistrationState")
+			.map(|s: u32| s.into())
+	}
+
+	/// The activation state of a CDMA modem.
+	///
+	/// ## Note
+	/// See [`Modem::meid`].
+	pub fn activation_state(&self) -> Result<CdmaActivationState, Error> {
+		self.dbus.proxy(&self.path)
+			.get(MODEM_CDMA_INTERFACE, "ActivationState")
+			.map(|s: u32| s.into())
+	}
+
+	/// Provisions the modem over the air with the given carrier code.
+	pub fn activate(&self, carrier_code: &str) -> Result<(), Error> {
+		self.dbus.proxy(&self.path)
+			.method_call(MODEM_CDMA_INTERFACE, "Activate", (carrier_code,))
+	}
+
 	/// This SIM object is the one used for network registration and data
 	/// connection setup.
 	pub fn sim(&self) -> Result<Sim, Error> {
@@ -314,8 +539,56 @@ impl Modem {
 			dbus: self.dbus.clone()
 		})
 	}
+
+	/// The firmware interface to list and select firmware images.
+	///
+	/// Only available if the modem supports it.
+	pub fn firmware(&self) -> ModemFirmware {
+		ModemFirmware {
+			dbus: self.dbus.clone(),
+			path: self.path.clone()
+		}
+	}
+
+	/// The OMA device management interface, used by CDMA modems.
+	///
+	/// Only available if the modem supports it.
+	pub fn oma(&self) -> ModemOma {
+		ModemOma {
+			dbus: self.dbus.clone(),
+			path: self.path.clone()
+		}
+	}
+
+	/// Retrieves all properties of the modem with one `GetAll` call per
+	/// interface instead of one call per property.
+	///
+	/// The 3GPP, CDMA and signal interface are optional since they might not
+	/// be available (see [`Modem::imei`]).
+	pub fn all_properties(&self) -> Result<ModemInfo, Error> {
+		let proxy = self.dbus.proxy(&self.path);
+
+		let modem = proxy.get_all(MODEM_INTERFACE)?;
+		let mut info = ModemInfo::from_prop_map(&modem)
+			.ok_or_else(|| Error::new_failed("modem properties not found"))?;
+
+		if let Ok(props) = proxy.get_all(MODEM_3GPP_INTERFACE) {
+			info.modem_3gpp = Modem3gppInfo::from_prop_map(&props);
+		}
+
+		if let Ok(props) = proxy.get_all(MODEM_CDMA_INTERFACE) {
+			info.modem_cdma = ModemCdmaInfo::from_prop_map(&props);
+		}
+
+		if let Ok(props) = proxy.get_all(MODEM_SIGNAL_INTERFACE) {
+			info.signal = Some(SignalInfo::from_prop_map(&props));
+		}
+
+		Ok(info)
+	}
 }
 
+#[derive(Clone)]
 pub struct Sim {
 	dbus: Dbus,
 	path: Path<'static>
@@ -346,8 +619,258 @@ impl Sim {
 	}
 }
 
+/// The firmware interface of a modem, returned by [`Modem::firmware`].
+#[derive(Clone)]
+pub struct ModemFirmware {
+	dbus: Dbus,
+	path: Path<'static>
+}
+
+impl ModemFirmware {
+	/// Returns the unique id of the selected image (`None` if no image is
+	/// selected) and every installed image.
+	pub fn list(&self) -> Result<(Option<String>, Vec<FirmwareImage>), Error> {
+		let (selected, images): (String, Vec<PropMap>) = self.dbus
+			.proxy(&self.path)
+			.method_call(MODEM_FIRMWARE_INTERFACE, "List", ())?;
+
+		let images = images.iter()
+			.map(FirmwareImage::from_prop_map)
+			.collect::<Option<_>>()
+			.ok_or_else(|| Error::new_failed("invalid firmware image"))?;
+		Ok(((!selected.is_empty()).then(|| selected), images))
+	}
+
+	/// Selects the image which will be used the next time the modem is
+	/// enabled.
+	///
+	/// The modem might reboot to apply the change.
+	pub fn select(&self, unique_id: &str) -> Result<(), Error> {
+		self.dbus.proxy(&self.path)
+			.method_call(MODEM_FIRMWARE_INTERFACE, "Select", (unique_id,))
+	}
+
+	/// How the firmware of the modem can be updated.
+	pub fn update_settings(&self) -> Result<FirmwareUpdateSettings, Error> {
+		let (methods, settings): (u32, PropMap) = self.dbus
+			.proxy(&self.path)
+			.get(MODEM_FIRMWARE_INTERFACE, "UpdateSettings")?;
+
+		Ok(FirmwareUpdateSettings {
+			methods: FirmwareUpdateMethod::from_bits(methods),
+			device_ids: settings.get("device-ids")
+				.and_then(|v| v.0.as_iter())
+				.map(|iter| {
+					iter.filter_map(|v| v.as_str().map(Into::into)).collect()
+				})
+				.unwrap_or_default(),
+			version: prop_string(&settings, "version"),
+			fastboot_at: prop_string(&settings, "fastboot-at")
+		})
+	}
+}
+
+/// A firmware image installed on a modem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", rename_all = "camelCase")
+)]
+pub struct FirmwareImage {
+	pub image_type: FirmwareImageType,
+	pub unique_id: String,
+	/// Only set for gobi images.
+	pub gobi_pri_version: Option<String>,
+	pub gobi_pri_info: Option<String>,
+	pub gobi_boot_version: Option<String>,
+	pub gobi_pri_unique_id: Option<String>,
+	pub gobi_modem_unique_id: Option<String>
+}
+
+impl FirmwareImage {
+	fn from_prop_map(prop: &PropMap) -> Option<Self> {
+		Some(Self {
+			image_type: prop_u32(prop, "image-type")?.into(),
+			unique_id: prop_string(prop, "unique-id")?,
+			gobi_pri_version: prop_string(prop, "gobi-pri-version"),
+			gobi_pri_info: prop_string(prop, "gobi-pri-info"),
+			gobi_boot_version: prop_string(prop, "gobi-boot-version"),
+			gobi_pri_unique_id: prop_string(prop, "gobi-pri-unique-id"),
+			gobi_modem_unique_id: prop_string(prop, "gobi-modem-unique-id")
+		})
+	}
+}
+
+dbus_enum! {
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	#[cfg_attr(
+		feature = "serde",
+		derive(serde1::Serialize, serde1::Deserialize),
+		serde(crate = "serde1")
+	)]
+	pub enum FirmwareImageType: u32 {
+		/// Unknown firmware type (0), or a type which is not known to this
+		/// crate.
+		Unknown,
+		/// A generic firmware image.
+		Generic = 1,
+		/// A firmware image of a Qualcomm Gobi device.
+		Gobi = 2
+	}
+}
+
+/// How the firmware of a modem can be updated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", rename_all = "camelCase")
+)]
+pub struct FirmwareUpdateSettings {
+	pub methods: Vec<FirmwareUpdateMethod>,
+	/// The device ids used to find the matching firmware, the most specific
+	/// first.
+	pub device_ids: Vec<String>,
+	/// The current firmware version.
+	pub version: Option<String>,
+	/// The AT command to reboot into fastboot mode.
+	pub fastboot_at: Option<String>
+}
+
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+#[non_exhaustive]
+pub enum FirmwareUpdateMethod {
+	Fastboot = 1 << 0,
+	QmiPdc = 1 << 1,
+	MbimQdu = 1 << 2,
+	Firehose = 1 << 3,
+	Sahara = 1 << 4,
+	Dfota = 1 << 5,
+	CinterionFdl = 1 << 6
+}
+
+impl FirmwareUpdateMethod {
+	const ALL: &'static [FirmwareUpdateMethod] = &[
+		FirmwareUpdateMethod::Fastboot,
+		FirmwareUpdateMethod::QmiPdc,
+		FirmwareUpdateMethod::MbimQdu,
+		FirmwareUpdateMethod::Firehose,
+		FirmwareUpdateMethod::Sahara,
+		FirmwareUpdateMethod::Dfota,
+		FirmwareUpdateMethod::CinterionFdl
+	];
+
+	fn from_bits(bits: u32) -> Vec<Self> {
+		Self::ALL.iter()
+			.copied()
+			.filter(|m| bits & *m as u32 != 0)
+			.collect()
+	}
+}
+
+/// The OMA device management interface of a modem, returned by
+/// [`Modem::oma`].
+#[derive(Clone)]
+pub struct ModemOma {
+	dbus: Dbus,
+	path: Path<'static>
+}
+
+impl ModemOma {
+	/// Retrieves the enabled features and the session state.
+	pub fn info(&self) -> Result<OmaInfo, Error> {
+		let props = self.dbus.proxy(&self.path).get_all(MODEM_OMA_INTERFACE)?;
+		OmaInfo::from_prop_map(&props)
+			.ok_or_else(|| Error::new_failed("oma properties not found"))
+	}
+
+	/// Enables the given features, every other feature is disabled.
+	pub fn setup(&self, features: &[OmaFeature]) -> Result<(), Error> {
+		let features = features.iter().fold(0u32, |f, n| f | *n as u32);
+		self.dbus.proxy(&self.path)
+			.method_call(MODEM_OMA_INTERFACE, "Setup", (features,))
+	}
+
+	/// Starts a client initiated session.
+	pub fn start_client_initiated_session(
+		&self,
+		session_type: OmaSessionType
+	) -> Result<(), Error> {
+		self.dbus.proxy(&self.path).method_call(
+			MODEM_OMA_INTERFACE,
+			"StartClientInitiatedSession",
+			(u32::from(session_type),)
+		)
+	}
+
+	/// Accepts or rejects a pending network initiated session.
+	pub fn accept_network_initiated_session(
+		&self,
+		session_id: u32,
+		accept: bool
+	) -> Result<(), Error> {
+		self.dbus.proxy(&self.path).method_call(
+			MODEM_OMA_INTERFACE,
+			"AcceptNetworkInitiatedSession",
+			(session_id, accept)
+		)
+	}
+
+	/// Cancels the current session.
+	pub fn cancel_session(&self) -> Result<(), Error> {
+		self.dbus.proxy(&self.path)
+			.method_call(MODEM_OMA_INTERFACE, "CancelSession", ())
+	}
+}
+
+/// The OMA properties of a modem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", rename_all = "camelCase")
+)]
+pub struct OmaInfo {
+	pub features: Vec<OmaFeature>,
+	/// Network initiated sessions waiting to be accepted as (type, id).
+	pub pending_network_initiated_sessions: Vec<(OmaSessionType, u32)>,
+	pub session_type: OmaSessionType,
+	pub session_state: OmaSessionState
+}
+
+impl OmaInfo {
+	fn from_prop_map(prop: &PropMap) -> Option<Self> {
+		let features = prop_u32(prop, "Features")?;
+
+		Some(Self {
+			features: OmaFeature::ALL.iter()
+				.copied()
+				.filter(|f| features & *f as u32 != 0)
+				.collect(),
+			pending_network_initiated_sessions: prop
+				.get("PendingNetworkInitiatedSessions")?.0.as_iter()?
+				.map(|s| {
+					let mut iter = s.as_iter()?;
+					Some((
+						(iter.next()?.as_u64()? as u32).into(),
+						iter.next()?.as_u64()? as u32
+					))
+				})
+				.collect::<Option<_>>()?,
+			session_type: prop_u32(prop, "SessionType")?.into(),
+			session_state: (prop.get("SessionState")?.as_i64()? as i32).into()
+		})
+	}
+}
 
-#[repr(i32)]
+#[repr(u32)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(
 	feature = "serde",
@@ -355,52 +878,111 @@ impl Sim {
 	serde(crate = "serde1")
 )]
 #[non_exhaustive]
-pub enum ModemState {
-	/// The modem is unusable.
-	Failed = -1,
-	/// State unknown or not reportable.
-	Unknown = 0,
-	/// The modem is currently being initialized.
-	Initializing = 1,
-	/// The modem needs to be unlocked.
-	Locked = 2,
-	/// The modem is not enabled and is powered down.
-	Disabled = 3,
-	/// The modem is currently transitioning to the MM_MODEM_STATE_DISABLED
-	/// state.
-	Disabling = 4,
-	/// The modem is currently transitioning to the MM_MODEM_STATE_ENABLED
-	/// state.
-	Enabling = 5,
-	/// The modem is enabled and powered on but not registered with a network
-	/// provider and not available for data connections.
-	Enabled = 6,
-	/// The modem is searching for a network provider to register with.
-	Searching = 7,
-	/// The modem is registered with a network provider, and data connections
-	/// and messaging may be available for use.
-	Registered = 8,
-	/// The modem is disconnecting and deactivating the last active packet data
-	/// bearer. This state will not be entered if more than one packet data
-	/// bearer is active and one of the active bearers is deactivated.
-	Disconnecting = 9,
-	/// The modem is activating and connecting the first packet data bearer.
-	/// Subsequent bearer activations when another bearer is already active
-	/// do not cause this state to be entered.
-	Connecting = 10,
-	/// One or more packet data bearers is active and connected.
-	Connected = 11
-}
-
-impl From<i32> for ModemState {
-	fn from(num: i32) -> Self {
-		if num < -1 || num > 11 {
-			Self::Unknown
-		} else {
-			unsafe {
-				*(&num as *const i32 as *const Self)
-			}
-		}
+pub enum OmaFeature {
+	DeviceProvisioning = 1 << 0,
+	PrlUpdate = 1 << 1,
+	HandsFreeActivation = 1 << 2
+}
+
+impl OmaFeature {
+	const ALL: &'static [OmaFeature] = &[
+		OmaFeature::DeviceProvisioning,
+		OmaFeature::PrlUpdate,
+		OmaFeature::HandsFreeActivation
+	];
+}
+
+dbus_enum! {
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	#[cfg_attr(
+		feature = "serde",
+		derive(serde1::Serialize, serde1::Deserialize),
+		serde(crate = "serde1")
+	)]
+	pub enum OmaSessionType: u32 {
+		/// Unknown session type (0), or a type which is not known to this
+		/// crate.
+		Unknown,
+		ClientInitiatedDeviceConfigure = 10,
+		ClientInitiatedPrlUpdate = 11,
+		ClientInitiatedHandsFreeActivation = 12,
+		NetworkInitiatedDeviceConfigure = 20,
+		NetworkInitiatedPrlUpdate = 21,
+		DeviceInitiatedPrlUpdate = 30,
+		DeviceInitiatedHandsFreeActivation = 31
+	}
+}
+
+dbus_enum! {
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	#[cfg_attr(
+		feature = "serde",
+		derive(serde1::Serialize, serde1::Deserialize),
+		serde(crate = "serde1")
+	)]
+	pub enum OmaSessionState: i32 {
+		/// Unknown session state (0), or a state which is not known to this
+		/// crate.
+		Unknown,
+		/// The session failed.
+		Failed = -1,
+		Started = 1,
+		Retrying = 2,
+		Connecting = 3,
+		Connected = 4,
+		Authenticated = 5,
+		MdnDownloaded = 10,
+		MsidDownloaded = 11,
+		PrlDownloaded = 12,
+		MipProfileDownloaded = 13,
+		Completed = 20
+	}
+}
+
+dbus_enum! {
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	#[cfg_attr(
+		feature = "serde",
+		derive(serde1::Serialize, serde1::Deserialize),
+		serde(crate = "serde1")
+	)]
+	#[non_exhaustive]
+	pub enum ModemState: i32 {
+		/// State unknown or not reportable (0), or a state which is not
+		/// known to this crate.
+		Unknown,
+		/// The modem is unusable.
+		Failed = -1,
+		/// The modem is currently being initialized.
+		Initializing = 1,
+		/// The modem needs to be unlocked.
+		Locked = 2,
+		/// The modem is not enabled and is powered down.
+		Disabled = 3,
+		/// The modem is currently transitioning to the MM_MODEM_STATE_DISABLED
+		/// state.
+		Disabling = 4,
+		/// The modem is currently transitioning to the MM_MODEM_STATE_ENABLED
+		/// state.
+		Enabling = 5,
+		/// The modem is enabled and powered on but not registered with a
+		/// network provider and not available for data connections.
+		Enabled = 6,
+		/// The modem is searching for a network provider to register with.
+		Searching = 7,
+		/// The modem is registered with a network provider, and data
+		/// connections and messaging may be available for use.
+		Registered = 8,
+		/// The modem is disconnecting and deactivating the last active packet
+		/// data bearer. This state will not be entered if more than one packet
+		/// data bearer is active and one of the active bearers is deactivated.
+		Disconnecting = 9,
+		/// The modem is activating and connecting the first packet data bearer.
+		/// Subsequent bearer activations when another bearer is already active
+		/// do not cause this state to be entered.
+		Connecting = 10,
+		/// One or more packet data bearers is active and connected.
+		Connected = 11
 	}
 }
 
@@ -479,10 +1061,49 @@ impl ModemAccessTech {
 		ModemAccessTech::LteCatM,
 		ModemAccessTech::LteNbIoT
 	];
+
+	/// Returns the short name of the access technology for example `LTE`.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Unknown => "Unknown",
+			Self::Pots => "POTS",
+			Self::Gsm => "GSM",
+			Self::GsmCompact => "GSM-Compact",
+			Self::Gprs => "GPRS",
+			Self::Edge => "EDGE",
+			Self::Umts => "UMTS",
+			Self::Hsdpa => "HSDPA",
+			Self::Hsupa => "HSUPA",
+			Self::Hspa => "HSPA",
+			Self::HspaPlus => "HSPA+",
+			Self::T1xRtt => "1xRTT",
+			Self::Evdo0 => "EVDO0",
+			Self::EvdoA => "EVDOA",
+			Self::EvdoB => "EVDOB",
+			Self::Lte => "LTE",
+			Self::T5Gnr => "5GNR",
+			Self::LteCatM => "LTE-Cat-M",
+			Self::LteNbIoT => "LTE-NB-IoT",
+			Self::Any => "Any"
+		}
+	}
+}
+
+impl fmt::Display for ModemAccessTech {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
 }
 
 /// A list of modem Access Technologies
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// Formats as the list of access technologies for example `LTE|5GNR`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", transparent)
+)]
 pub struct ModemAccessTechs(u32);
 
 impl ModemAccessTechs {
@@ -517,6 +1138,46 @@ impl ModemAccessTechs {
 
 		unknown_iter.chain(any_iter).chain(other_iter)
 	}
+
+	/// Returns true if the access technology is in the list.
+	pub fn has(&self, tech: ModemAccessTech) -> bool {
+		match tech {
+			ModemAccessTech::Unknown => self.is_unknown(),
+			ModemAccessTech::Any => self.is_any(),
+			t => self.0 & t as u32 > 0
+		}
+	}
+}
+
+impl fmt::Display for ModemAccessTechs {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for (i, tech) in self.iter().enumerate() {
+			if i > 0 {
+				f.write_str("|")?;
+			}
+			f.write_str(tech.as_str())?;
+		}
+		Ok(())
+	}
+}
+
+impl fmt::Debug for ModemAccessTechs {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "ModemAccessTechs({})", self)
+	}
+}
+
+impl FromIterator<ModemAccessTech> for ModemAccessTechs {
+	fn from_iter<I>(iter: I) -> Self
+	where I: IntoIterator<Item=ModemAccessTech> {
+		Self(iter.into_iter().fold(0, |bits, t| bits | t as u32))
+	}
+}
+
+impl From<ModemAccessTech> for ModemAccessTechs {
+	fn from(tech: ModemAccessTech) -> Self {
+		Self(tech as u32)
+	}
 }
 
 impl From<u32> for ModemAccessTechs {
@@ -531,6 +1192,197 @@ impl From<ModemAccessTechs> for u32 {
 	}
 }
 
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+#[non_exhaustive]
+/// Flags describing one or more of the general access technology families
+/// that a modem supports.
+pub enum ModemCapability {
+	/// Modem has no capabilities.
+	None = 0,
+	/// Modem supports the analog wired telephone network (ie 56k dialup) and
+	/// does not have wireless/cellular capabilities.
+	Pots = 1 << 0,
+	/// Modem supports at least one of CDMA 1xRTT, EVDO revision 0, EVDO
+	/// revision A, or EVDO revision B.
+	CdmaEvdo = 1 << 1,
+	/// Modem supports at least one of GSM, GPRS, EDGE, UMTS, HSDPA, HSUPA, or
+	/// HSPA+ packet switched data capability.
+	GsmUmts = 1 << 2,
+	/// Modem has LTE data capability.
+	Lte = 1 << 3,
+	/// Modem has Iridium capabilities.
+	Iridium = 1 << 5,
+	/// Modem has 5GNR capabilities. Since 1.14.
+	T5Gnr = 1 << 6,
+	/// Modem has TDS capabilities. Since 1.20.
+	Tds = 1 << 7,
+	/// Mask specifying all capabilities.
+	Any = u32::MAX
+}
+
+impl ModemCapability {
+	/// All capabilities except None and Any
+	const ALL: &'static [ModemCapability] = &[
+		ModemCapability::Pots,
+		ModemCapability::CdmaEvdo,
+		ModemCapability::GsmUmts,
+		ModemCapability::Lte,
+		ModemCapability::Iridium,
+		ModemCapability::T5Gnr,
+		ModemCapability::Tds
+	];
+}
+
+/// A list of modem capabilities
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", transparent)
+)]
+pub struct ModemCapabilities(u32);
+
+impl ModemCapabilities {
+	/// Returns true if the modem has no capabilities.
+	pub fn is_none(&self) -> bool {
+		self.0 == ModemCapability::None as u32
+	}
+
+	/// Returns true if every capability is set.
+	pub fn is_any(&self) -> bool {
+		self.0 == ModemCapability::Any as u32
+	}
+
+	/// Returns true if the given capability is set.
+	pub fn has(&self, cap: ModemCapability) -> bool {
+		match cap {
+			ModemCapability::None => self.is_none(),
+			cap => self.0 & cap as u32 == cap as u32
+		}
+	}
+
+	pub fn iter<'a>(&'a self) -> impl Iterator<Item=ModemCapability> + 'a {
+		let is_any = self.is_any();
+
+		ModemCapability::ALL.iter()
+			.copied()
+			.filter(move |c| !is_any && self.has(*c))
+			.chain(is_any.then(|| ModemCapability::Any))
+	}
+}
+
+impl From<u32> for ModemCapabilities {
+	fn from(num: u32) -> Self {
+		Self(num)
+	}
+}
+
+impl From<ModemCapabilities> for u32 {
+	fn from(c: ModemCapabilities) -> Self {
+		c.0
+	}
+}
+
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+#[non_exhaustive]
+/// Type of IP family to be used in a given Bearer.
+pub enum IpFamily {
+	/// None or unknown.
+	None = 0,
+	/// IPv4.
+	Ipv4 = 1 << 0,
+	/// IPv6.
+	Ipv6 = 1 << 1,
+	/// IPv4 and IPv6.
+	Ipv4v6 = 1 << 2,
+	/// Non-IP Bearer. Since 1.20.
+	NonIp = 1 << 3,
+	/// Mask specifying all IP families.
+	Any = u32::MAX
+}
+
+impl IpFamily {
+	/// All ip families except None and Any
+	const ALL: &'static [IpFamily] = &[
+		IpFamily::Ipv4,
+		IpFamily::Ipv6,
+		IpFamily::Ipv4v6,
+		IpFamily::NonIp
+	];
+}
+
+/// A list of IP families
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", transparent)
+)]
+pub struct IpFamilies(u32);
+
+impl IpFamilies {
+	/// Returns true if no ip family is set.
+	pub fn is_none(&self) -> bool {
+		self.0 == IpFamily::None as u32
+	}
+
+	/// Returns true if every ip family is set.
+	pub fn is_any(&self) -> bool {
+		self.0 == IpFamily::Any as u32
+	}
+
+	/// Returns true if the given ip family is set.
+	pub fn has(&self, family: IpFamily) -> bool {
+		match family {
+			IpFamily::None => self.is_none(),
+			family => self.0 & family as u32 == family as u32
+		}
+	}
+
+	/// Returns true if IPv4 can be used, either alone or as dual stack.
+	pub fn has_ipv4(&self) -> bool {
+		self.has(IpFamily::Ipv4) || self.has(IpFamily::Ipv4v6)
+	}
+
+	/// Returns true if IPv6 can be used, either alone or as dual stack.
+	pub fn has_ipv6(&self) -> bool {
+		self.has(IpFamily::Ipv6) || self.has(IpFamily::Ipv4v6)
+	}
+
+	pub fn iter<'a>(&'a self) -> impl Iterator<Item=IpFamily> + 'a {
+		let is_any = self.is_any();
+
+		IpFamily::ALL.iter()
+			.copied()
+			.filter(move |f| !is_any && self.has(*f))
+			.chain(is_any.then(|| IpFamily::Any))
+	}
+}
+
+impl From<u32> for IpFamilies {
+	fn from(num: u32) -> Self {
+		Self(num)
+	}
+}
+
+impl From<IpFamilies> for u32 {
+	fn from(f: IpFamilies) -> Self {
+		f.0
+	}
+}
+
 const MODE_NONE: u32 = 0;
 /// CSD, GSM, and other circuit-switched technologies.
 const MODE_CS: u32 = 1 << 0;
@@ -545,8 +1397,24 @@ const MODE_5G: u32 = 1 << 4;
 /// Any mode can be used (only this value allowed for POTS modems).
 const MODE_ANY: u32 = u32::MAX;
 
-// not sure if i like it this way?
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The modes a modem supports or is allowed to use.
+///
+/// Formats as the list of modes for example `4G|5G`.
+///
+/// ## Example
+/// ```
+/// use linux_info::network::modem_manager::ModemMode;
+///
+/// let mode = ModemMode::new().with_3g().with_4g();
+/// assert_eq!(mode.to_string(), "3G|4G");
+/// assert_eq!(ModemMode::only_4g_5g().to_string(), "4G|5G");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", transparent)
+)]
 pub struct ModemMode(u32);
 
 impl ModemMode {
@@ -555,6 +1423,47 @@ impl ModemMode {
 		ModemMode(MODE_NONE)
 	}
 
+	/// Creates a ModemMode where any mode is allowed.
+	pub fn any() -> Self {
+		ModemMode(MODE_ANY)
+	}
+
+	/// Creates a ModemMode where only 4g (LTE) and 5g (5GNR) are allowed.
+	pub fn only_4g_5g() -> Self {
+		Self::new().with_4g().with_5g()
+	}
+
+	/// Adds the CS mode (CSD, GSM, and other circuit-switched
+	/// technologies).
+	pub fn with_cs(mut self) -> Self {
+		self.set_cs();
+		self
+	}
+
+	/// Adds the 2g mode (GPRS, EDGE).
+	pub fn with_2g(mut self) -> Self {
+		self.set_2g();
+		self
+	}
+
+	/// Adds the 3g mode (UMTS, HSxPA).
+	pub fn with_3g(mut self) -> Self {
+		self.set_3g();
+		self
+	}
+
+	/// Adds the 4g mode (LTE).
+	pub fn with_4g(mut self) -> Self {
+		self.set_4g();
+		self
+	}
+
+	/// Adds the 5g mode (5GNR).
+	pub fn with_5g(mut self) -> Self {
+		self.set_5g();
+		self
+	}
+
 	/// Any Mode is allowed, only allowed for POTS modems.
 	pub fn is_any(&self) -> bool {
 		self.0 == MODE_ANY
@@ -621,6 +1530,43 @@ impl ModemMode {
 	}
 }
 
+impl fmt::Display for ModemMode {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if self.is_any() {
+			return f.write_str("Any")
+		}
+		if self.is_none() {
+			return f.write_str("None")
+		}
+
+		let modes = [
+			(MODE_CS, "CS"),
+			(MODE_2G, "2G"),
+			(MODE_3G, "3G"),
+			(MODE_4G, "4G"),
+			(MODE_5G, "5G")
+		];
+		let mut first = true;
+		for (bit, name) in modes.iter() {
+			if self.0 & bit == 0 {
+				continue
+			}
+			if !first {
+				f.write_str("|")?;
+			}
+			first = false;
+			f.write_str(name)?;
+		}
+		Ok(())
+	}
+}
+
+impl fmt::Debug for ModemMode {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "ModemMode({})", self)
+	}
+}
+
 impl From<u32> for ModemMode {
 	fn from(num: u32) -> Self {
 		Self(num)
@@ -814,7 +1760,7 @@ pub struct SignalCdma {
 }
 
 impl SignalCdma {
-	fn from_prop_map(prop: PropMap) -> Option<Self> {
+	fn from_prop_map(prop: &PropMap) -> Option<Self> {
 		Some(Self {
 			rssi: prop.get("rssi")?
 				.as_f64()?,
@@ -842,7 +1788,7 @@ pub struct SignalEvdo {
 }
 
 impl SignalEvdo {
-	fn from_prop_map(prop: PropMap) -> Option<Self> {
+	fn from_prop_map(prop: &PropMap) -> Option<Self> {
 		Some(Self {
 			rssi: prop.get("rssi")?
 				.as_f64()?,
@@ -868,7 +1814,7 @@ pub struct SignalGsm {
 }
 
 impl SignalGsm {
-	fn from_prop_map(prop: PropMap) -> Option<Self> {
+	fn from_prop_map(prop: &PropMap) -> Option<Self> {
 		Some(Self {
 			rssi: prop.get("rssi")?
 				.as_f64()?
@@ -894,7 +1840,7 @@ pub struct SignalUmts {
 }
 
 impl SignalUmts {
-	fn from_prop_map(prop: PropMap) -> Option<Self> {
+	fn from_prop_map(prop: &PropMap) -> Option<Self> {
 		Some(Self {
 			rssi: prop.get("rssi")?
 				.as_f64()?,
@@ -926,7 +1872,7 @@ pub struct SignalLte {
 }
 
 impl SignalLte {
-	fn from_prop_map(prop: PropMap) -> Option<Self> {
+	fn from_prop_map(prop: &PropMap) -> Option<Self> {
 		Some(Self {
 			rssi: prop.get("rssi")?
 				.as_f64()?,
@@ -953,7 +1899,7 @@ pub struct SignalNr5g {
 }
 
 impl SignalNr5g {
-	fn from_prop_map(prop: PropMap) -> Option<Self> {
+	fn from_prop_map(prop: &PropMap) -> Option<Self> {
 		Some(Self {
 			rsrq: prop.get("rsrq")?
 				.as_f64()?,
@@ -965,37 +1911,706 @@ impl SignalNr5g {
 	}
 }
 
-#[repr(u32)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// All properties of a modem, returned by [`Modem::all_properties`].
+#[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(
 	feature = "serde",
 	derive(serde1::Serialize, serde1::Deserialize),
-	serde(crate = "serde1")
+	serde(crate = "serde1", rename_all = "camelCase")
 )]
-#[non_exhaustive]
-pub enum RegistrationState {
-	/// Not registered, not searching for new operator to register.
-	Idle = 0,
-	/// Registered on home network.
-	Home = 1,
-	/// Not registered, searching for new operator to register with.
-	Searching = 2,
-	/// Registration denied.
-	Denied = 3,
-	/// Unknown registration status.
-	Unknown = 4,
-	/// Registered on a roaming network.
-	Roaming = 5
-}
-
-impl From<u32> for RegistrationState {
-	fn from(num: u32) -> Self {
-		if num > 5 {
-		Self::Unknown
-		} else {
-			unsafe {
-				*(&num as *const u32 as *const Self)
-			}
+pub struct ModemInfo {
+	pub manufacturer: String,
+	pub model: String,
+	pub revision: String,
+	pub carrier_configuration: String,
+	pub device: String,
+	pub state: ModemState,
+	pub access_techs: ModemAccessTechs,
+	/// Signal quality in percent and if it was recently taken.
+	pub signal_quality: (u32, bool),
+	pub supported_capabilities: Vec<ModemCapabilities>,
+	pub current_capabilities: ModemCapabilities,
+	pub supported_ip_families: IpFamilies,
+	pub max_active_bearers: u32,
+	pub supported_modes: Vec<(ModemMode, ModemMode)>,
+	pub current_modes: (ModemMode, ModemMode),
+	pub supported_bands: Vec<ModemBand>,
+	pub current_bands: Vec<ModemBand>,
+	pub own_numbers: Vec<String>,
+	/// Only available if the modem is ready to be registered.
+	pub modem_3gpp: Option<Modem3gppInfo>,
+	/// Only available for enabled CDMA modems.
+	pub modem_cdma: Option<ModemCdmaInfo>,
+	/// Only available if the signal interface is supported.
+	pub signal: Option<SignalInfo>
+}
+
+impl ModemInfo {
+	fn from_prop_map(prop: &PropMap) -> Option<Self> {
+		Some(Self {
+			manufacturer: prop_string(prop, "Manufacturer")?,
+			model: prop_string(prop, "Model")?,
+			revision: prop_string(prop, "Revision")?,
+			carrier_configuration: prop_string(prop, "CarrierConfiguration")
+				.unwrap_or_default(),
+			device: prop_string(prop, "Device")?,
+			state: (prop.get("State")?.as_i64()? as i32).into(),
+			access_techs: prop_u32(prop, "AccessTechnologies")?.into(),
+			signal_quality: {
+				let mut iter = prop.get("SignalQuality")?.0.as_iter()?;
+				(
+					iter.next()?.as_u64()? as u32,
+					iter.next()?.as_u64()? > 0
+				)
+			},
+			supported_capabilities: prop_u32_list(
+				prop,
+				"SupportedCapabilities"
+			)?.into_iter().map(Into::into).collect(),
+			current_capabilities: prop_u32(prop, "CurrentCapabilities")?
+				.into(),
+			supported_ip_families: prop_u32(prop, "SupportedIpFamilies")?
+				.into(),
+			max_active_bearers: prop_u32(prop, "MaxActiveBearers")?,
+			supported_modes: prop.get("SupportedModes")?.0.as_iter()?
+				.map(mode_pair)
+				.collect::<Option<_>>()?,
+			current_modes: mode_pair(&prop.get("CurrentModes")?.0)?,
+			supported_bands: prop_u32_list(prop, "SupportedBands")?
+				.into_iter().map(Into::into).collect(),
+			current_bands: prop_u32_list(prop, "CurrentBands")?
+				.into_iter().map(Into::into).collect(),
+			own_numbers: prop.get("OwnNumbers")?.0.as_iter()?
+				.map(|n| n.as_str().map(Into::into))
+				.collect::<Option<_>>()?,
+			modem_3gpp: None,
+			modem_cdma: None,
+			signal: None
+		})
+	}
+}
+
+/// The 3GPP properties of a modem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", rename_all = "camelCase")
+)]
+pub struct Modem3gppInfo {
+	pub imei: String,
+	pub registration_state: RegistrationState,
+	pub operator_code: String,
+	pub operator_name: String
+}
+
+impl Modem3gppInfo {
+	fn from_prop_map(prop: &PropMap) -> Option<Self> {
+		Some(Self {
+			imei: prop_string(prop, "Imei")?,
+			registration_state: prop_u32(prop, "RegistrationState")?.into(),
+			operator_code: prop_string(prop, "OperatorCode")?,
+			operator_name: prop_string(prop, "OperatorName")?
+		})
+	}
+}
+
+/// The CDMA properties of a modem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", rename_all = "camelCase")
+)]
+pub struct ModemCdmaInfo {
+	pub meid: String,
+	pub esn: String,
+	pub sid: Option<u32>,
+	pub nid: Option<u32>,
+	pub cdma1x_registration_state: CdmaRegistrationState,
+	pub evdo_registration_state: CdmaRegistrationState,
+	pub activation_state: CdmaActivationState
+}
+
+impl ModemCdmaInfo {
+	fn from_prop_map(prop: &PropMap) -> Option<Self> {
+		let sid = prop_u32(prop, "Sid")?;
+		let nid = prop_u32(prop, "Nid")?;
+
+		Some(Self {
+			meid: prop_string(prop, "Meid")?,
+			esn: prop_string(prop, "Esn")?,
+			sid: (sid != CDMA_SID_UNKNOWN).then(|| sid),
+			nid: (nid != CDMA_NID_UNKNOWN).then(|| nid),
+			cdma1x_registration_state: prop_u32(
+				prop,
+				"Cdma1xRegistrationState"
+			)?.into(),
+			evdo_registration_state: prop_u32(prop, "EvdoRegistrationState")?
+				.into(),
+			activation_state: prop_u32(prop, "ActivationState")?.into()
+		})
+	}
+}
+
+const CDMA_SID_UNKNOWN: u32 = 99999;
+const CDMA_NID_UNKNOWN: u32 = 99999;
+
+dbus_enum! {
+	/// The registration state of a CDMA network.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	#[cfg_attr(
+		feature = "serde",
+		derive(serde1::Serialize, serde1::Deserialize),
+		serde(crate = "serde1")
+	)]
+	pub enum CdmaRegistrationState: u32 {
+		/// Registration status is unknown or the device is not registered
+		/// (0), or a status which is not known to this crate.
+		Unknown,
+		/// Registered, but roaming status is unknown or cannot be provided
+		/// by the device.
+		Registered = 1,
+		/// Registered on the home network.
+		Home = 2,
+		/// Registered on a roaming network.
+		Roaming = 3
+	}
+}
+
+dbus_enum! {
+	/// The activation state of a CDMA modem.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	#[cfg_attr(
+		feature = "serde",
+		derive(serde1::Serialize, serde1::Deserialize),
+		serde(crate = "serde1")
+	)]
+	pub enum CdmaActivationState: u32 {
+		/// Unknown activation state (0), or a state which is not known to
+		/// this crate.
+		Unknown,
+		NotActivated = 1,
+		Activating = 2,
+		/// Some of the provisioning was done but not all.
+		PartiallyActivated = 3,
+		Activated = 4
+	}
+}
+
+/// The signal information for every access technology, a value is `None`
+/// if no information is available for that technology.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", rename_all = "camelCase")
+)]
+pub struct SignalInfo {
+	/// Refresh rate in seconds, 0 if the extended signal information is
+	/// disabled.
+	pub rate: u32,
+	pub cdma: Option<SignalCdma>,
+	pub evdo: Option<SignalEvdo>,
+	pub gsm: Option<SignalGsm>,
+	pub umts: Option<SignalUmts>,
+	pub lte: Option<SignalLte>,
+	pub nr5g: Option<SignalNr5g>
+}
+
+impl SignalInfo {
+	fn from_prop_map(prop: &PropMap) -> Self {
+		Self {
+			rate: prop_u32(prop, "Rate").unwrap_or(0),
+			cdma: prop_map(prop, "Cdma").and_then(SignalCdma::from_prop_map),
+			evdo: prop_map(prop, "Evdo").and_then(SignalEvdo::from_prop_map),
+			gsm: prop_map(prop, "Gsm").and_then(SignalGsm::from_prop_map),
+			umts: prop_map(prop, "Umts").and_then(SignalUmts::from_prop_map),
+			lte: prop_map(prop, "Lte").and_then(SignalLte::from_prop_map),
+			nr5g: prop_map(prop, "Nr5g").and_then(SignalNr5g::from_prop_map)
+		}
+	}
+}
+
+/// A signal sample recorded by [`SignalHistory`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", rename_all = "camelCase")
+)]
+pub struct SignalSample {
+	pub time: SystemTime,
+	pub lte: Option<SignalLte>,
+	pub nr5g: Option<SignalNr5g>
+}
+
+/// The minimum, maximum and average of a signal value over a window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", rename_all = "camelCase")
+)]
+pub struct SignalStats {
+	pub min: f64,
+	pub max: f64,
+	pub avg: f64,
+	/// The amount of samples which had the value.
+	pub count: usize
+}
+
+/// Keeps the latest LTE and 5G signal samples of a modem in memory.
+///
+/// Call [`Modem::signal_setup`] first and then [`SignalHistory::record`]
+/// at the same rate.
+///
+/// ## Example
+/// ```no_run
+/// # use std::time::Duration;
+/// # use linux_info::network::modem_manager::{ModemManager, SignalHistory};
+/// let manager = ModemManager::connect().unwrap();
+/// let modem = manager.modems().unwrap().pop().unwrap();
+/// modem.signal_setup(5).unwrap();
+///
+/// // one hour of samples every 5 seconds
+/// let mut history = SignalHistory::new(720);
+/// loop {
+///     history.record(&modem).unwrap();
+///     let rsrp = history.stats(
+///         Duration::from_secs(5 * 60),
+///         |s| Some(s.lte?.rsrp)
+///     );
+///     println!("lte rsrp last 5 minutes {:?}", rsrp);
+///     std::thread::sleep(Duration::from_secs(5));
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalHistory {
+	samples: VecDeque<SignalSample>,
+	capacity: usize
+}
+
+impl SignalHistory {
+	/// Creates a history which keeps at most `capacity` samples, the oldest
+	/// sample is dropped when it is full.
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			samples: VecDeque::with_capacity(capacity),
+			capacity
+		}
+	}
+
+	/// Reads the current signal information of the modem and adds it.
+	pub fn record(&mut self, modem: &Modem) -> Result<SignalSample, Error> {
+		let info = modem.signal_info()?;
+		let sample = SignalSample {
+			time: SystemTime::now(),
+			lte: info.lte,
+			nr5g: info.nr5g
+		};
+		self.push(sample);
+		Ok(sample)
+	}
+
+	/// Adds a sample.
+	pub fn push(&mut self, sample: SignalSample) {
+		if self.capacity == 0 {
+			return
+		}
+		if self.samples.len() == self.capacity {
+			self.samples.pop_front();
 		}
+		self.samples.push_back(sample);
 	}
-}
\ No newline at end of file
+
+	/// Returns the samples, the oldest first.
+	pub fn samples(&self) -> impl Iterator<Item=&SignalSample> {
+		self.samples.iter()
+	}
+
+	pub fn latest(&self) -> Option<&SignalSample> {
+		self.samples.back()
+	}
+
+	pub fn len(&self) -> usize {
+		self.samples.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.samples.is_empty()
+	}
+
+	pub fn clear(&mut self) {
+		self.samples.clear();
+	}
+
+	/// Returns the statistics of a value over the samples not older than
+	/// `window`, samples where `value` returns `None` are skipped.
+	///
+	/// Returns `None` if no sample in the window has the value.
+	pub fn stats(
+		&self,
+		window: Duration,
+		value: impl Fn(&SignalSample) -> Option<f64>
+	) -> Option<SignalStats> {
+		let since = SystemTime::now().checked_sub(window);
+		let mut stats: Option<SignalStats> = None;
+		let mut sum = 0f64;
+
+		let values = self.samples.iter()
+			.filter(|s| since.map(|since| s.time >= since).unwrap_or(true))
+			.filter_map(value);
+		for v in values {
+			sum += v;
+			stats = Some(match stats {
+				Some(s) => SignalStats {
+					min: s.min.min(v),
+					max: s.max.max(v),
+					avg: 0.0,
+					count: s.count + 1
+				},
+				None => SignalStats { min: v, max: v, avg: 0.0, count: 1 }
+			});
+		}
+
+		stats.map(|s| SignalStats { avg: sum / s.count as f64, ..s })
+	}
+}
+
+fn prop_string(prop: &PropMap, key: &str) -> Option<String> {
+	prop.get(key)?.as_str().map(Into::into)
+}
+
+fn prop_u32(prop: &PropMap, key: &str) -> Option<u32> {
+	prop.get(key)?.as_u64()?.try_into().ok()
+}
+
+fn prop_u32_list(prop: &PropMap, key: &str) -> Option<Vec<u32>> {
+	prop.get(key)?.0.as_iter()?
+		.map(|v| v.as_u64()?.try_into().ok())
+		.collect()
+}
+
+fn prop_map<'a>(prop: &'a PropMap, key: &str) -> Option<&'a PropMap> {
+	dbus::arg::cast(&prop.get(key)?.0)
+}
+
+fn mode_pair(arg: &dyn RefArg) -> Option<(ModemMode, ModemMode)> {
+	let mut iter = arg.as_iter()?;
+	Some((
+		(iter.next()?.as_u64()? as u32).into(),
+		(iter.next()?.as_u64()? as u32).into()
+	))
+}
+
+dbus_enum! {
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	#[cfg_attr(
+		feature = "serde",
+		derive(serde1::Serialize, serde1::Deserialize),
+		serde(crate = "serde1")
+	)]
+	#[non_exhaustive]
+	pub enum RegistrationState: u32 {
+		/// Unknown registration status (4), or a status which is not known
+		/// to this crate.
+		Unknown,
+		/// Not registered, not searching for new operator to register.
+		Idle = 0,
+		/// Registered on home network.
+		Home = 1,
+		/// Not registered, searching for new operator to register with.
+		Searching = 2,
+		/// Registration denied.
+		Denied = 3,
+		/// Registered on a roaming network.
+		Roaming = 5
+	}
+}
+
+/// Async version of [`ModemManager`].
+///
+/// This does not use async dbus, every call runs the blocking libdbus call
+/// on tokio's blocking thread pool so the roundtrip does not stall the
+/// runtime but occupies a thread. Requires the `async` feature.
+#[cfg(all(feature = "async", feature = "dbus"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+#[derive(Clone)]
+pub struct AsyncModemManager {
+	inner: ModemManager
+}
+
+#[cfg(all(feature = "async", feature = "dbus"))]
+impl AsyncModemManager {
+	pub async fn connect() -> Result<Self, Error> {
+		super::spawn_blocking(ModemManager::connect).await
+			.map(|inner| Self { inner })
+	}
+
+	pub async fn modems(&self) -> Result<Vec<AsyncModem>, Error> {
+		let inner = self.inner.clone();
+		let modems = super::spawn_blocking(move || inner.modems()).await?;
+		Ok(modems.into_iter().map(|inner| AsyncModem { inner }).collect())
+	}
+
+	async_fns!(ModemManager {
+		fn is_available() -> bool;
+	});
+
+	/// Returns the blocking version.
+	pub fn blocking(&self) -> &ModemManager {
+		&self.inner
+	}
+}
+
+#[cfg(all(feature = "async", feature = "dbus"))]
+impl From<ModemManager> for AsyncModemManager {
+	fn from(inner: ModemManager) -> Self {
+		Self { inner }
+	}
+}
+
+/// Async version of [`Modem`].
+#[cfg(all(feature = "async", feature = "dbus"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+#[derive(Clone)]
+pub struct AsyncModem {
+	inner: Modem
+}
+
+#[cfg(all(feature = "async", feature = "dbus"))]
+impl AsyncModem {
+	async_fns!(Modem {
+		fn manufacturer() -> String;
+		fn model() -> String;
+		fn carrier_configuration() -> String;
+		fn device() -> String;
+		fn supported_capabilities() -> Vec<ModemCapabilities>;
+		fn current_capabilities() -> ModemCapabilities;
+		fn supported_ip_families() -> IpFamilies;
+		fn max_active_bearers() -> u32;
+		fn state() -> ModemState;
+		fn access_techs() -> ModemAccessTechs;
+		fn signal_quality() -> (u32, bool);
+		fn supported_modes() -> Vec<(ModemMode, ModemMode)>;
+		fn current_modes() -> (ModemMode, ModemMode);
+		fn set_current_modes(modes: (ModemMode, ModemMode)) -> ();
+		fn supported_bands() -> Vec<ModemBand>;
+		fn current_bands() -> Vec<ModemBand>;
+		fn signal_setup(rate: u32) -> ();
+		fn signal_cdma() -> SignalCdma;
+		fn signal_evdo() -> SignalEvdo;
+		fn signal_gsm() -> SignalGsm;
+		fn signal_umts() -> SignalUmts;
+		fn signal_lte() -> SignalLte;
+		fn signal_nr5g() -> SignalNr5g;
+		fn signal_info() -> SignalInfo;
+		fn own_numbers() -> Vec<String>;
+		fn imei() -> String;
+		fn registration_state() -> RegistrationState;
+		fn operator_code() -> String;
+		fn operator_name() -> String;
+		fn meid() -> String;
+		fn esn() -> String;
+		fn sid() -> Option<u32>;
+		fn nid() -> Option<u32>;
+		fn cdma1x_registration_state() -> CdmaRegistrationState;
+		fn evdo_registration_state() -> CdmaRegistrationState;
+		fn activation_state() -> CdmaActivationState;
+		fn all_properties() -> ModemInfo;
+	});
+
+	/// See [`Modem::set_current_bands`].
+	pub async fn set_current_bands(
+		&self,
+		bands: Vec<ModemBand>
+	) -> Result<(), Error> {
+		let inner = self.inner.clone();
+		super::spawn_blocking(move || inner.set_current_bands(&bands)).await
+	}
+
+	/// See [`Modem::sim`].
+	pub async fn sim(&self) -> Result<AsyncSim, Error> {
+		let inner = self.inner.clone();
+		super::spawn_blocking(move || inner.sim()).await
+			.map(|inner| AsyncSim { inner })
+	}
+
+	/// See [`Modem::activate`].
+	pub async fn activate(&self, carrier_code: String) -> Result<(), Error> {
+		let inner = self.inner.clone();
+		super::spawn_blocking(move || inner.activate(&carrier_code)).await
+	}
+
+	/// See [`Modem::firmware`].
+	pub fn firmware(&self) -> AsyncModemFirmware {
+		AsyncModemFirmware { inner: self.inner.firmware() }
+	}
+
+	/// See [`Modem::oma`].
+	pub fn oma(&self) -> AsyncModemOma {
+		AsyncModemOma { inner: self.inner.oma() }
+	}
+
+	/// Returns the blocking version.
+	pub fn blocking(&self) -> &Modem {
+		&self.inner
+	}
+}
+
+/// Async version of [`Sim`].
+#[cfg(all(feature = "async", feature = "dbus"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+#[derive(Clone)]
+pub struct AsyncSim {
+	inner: Sim
+}
+
+#[cfg(all(feature = "async", feature = "dbus"))]
+impl AsyncSim {
+	async_fns!(Sim {
+		fn identifier() -> String;
+		fn imsi() -> String;
+		fn eid() -> String;
+		fn operator_name() -> String;
+	});
+
+	/// Returns the blocking version.
+	pub fn blocking(&self) -> &Sim {
+		&self.inner
+	}
+}
+
+/// Async version of [`ModemFirmware`].
+#[cfg(all(feature = "async", feature = "dbus"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+#[derive(Clone)]
+pub struct AsyncModemFirmware {
+	inner: ModemFirmware
+}
+
+#[cfg(all(feature = "async", feature = "dbus"))]
+impl AsyncModemFirmware {
+	async_fns!(ModemFirmware {
+		fn list() -> (Option<String>, Vec<FirmwareImage>);
+		fn update_settings() -> FirmwareUpdateSettings;
+	});
+
+	/// See [`ModemFirmware::select`].
+	pub async fn select(&self, unique_id: String) -> Result<(), Error> {
+		let inner = self.inner.clone();
+		super::spawn_blocking(move || inner.select(&unique_id)).await
+	}
+
+	/// Returns the blocking version.
+	pub fn blocking(&self) -> &ModemFirmware {
+		&self.inner
+	}
+}
+
+/// Async version of [`ModemOma`].
+#[cfg(all(feature = "async", feature = "dbus"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+#[derive(Clone)]
+pub struct AsyncModemOma {
+	inner: ModemOma
+}
+
+#[cfg(all(feature = "async", feature = "dbus"))]
+impl AsyncModemOma {
+	async_fns!(ModemOma {
+		fn info() -> OmaInfo;
+		fn start_client_initiated_session(session_type: OmaSessionType) -> ();
+		fn accept_network_initiated_session(session_id: u32, accept: bool) -> ();
+		fn cancel_session() -> ();
+	});
+
+	/// See [`ModemOma::setup`].
+	pub async fn setup(&self, features: Vec<OmaFeature>) -> Result<(), Error> {
+		let inner = self.inner.clone();
+		super::spawn_blocking(move || inner.setup(&features)).await
+	}
+
+	/// Returns the blocking version.
+	pub fn blocking(&self) -> &ModemOma {
+		&self.inner
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn sample(age: u64, rsrp: f64, nr5g: Option<f64>) -> SignalSample {
+		SignalSample {
+			time: SystemTime::now() - Duration::from_secs(age),
+			lte: Some(SignalLte {
+				rssi: -60.0,
+				rsrq: -10.0,
+				rsrp,
+				snr: 12.0
+			}),
+			nr5g: nr5g.map(|rsrp| SignalNr5g {
+				rsrq: -11.0,
+				rsrp,
+				snr: 10.0
+			})
+		}
+	}
+
+	fn rsrp(s: &SignalSample) -> Option<f64> {
+		s.lte.map(|l| l.rsrp)
+	}
+
+	#[test]
+	fn signal_stats() {
+		let mut history = SignalHistory::new(10);
+		assert!(history.stats(Duration::from_secs(60), rsrp).is_none());
+
+		history.push(sample(30, -90.0, None));
+		history.push(sample(20, -100.0, Some(-95.0)));
+		history.push(sample(10, -80.0, None));
+
+		let stats = history.stats(Duration::from_secs(60), rsrp).unwrap();
+		assert_eq!(stats.min, -100.0);
+		assert_eq!(stats.max, -80.0);
+		assert_eq!(stats.avg, -90.0);
+		assert_eq!(stats.count, 3);
+
+		// samples without the value are skipped
+		let stats = history.stats(
+			Duration::from_secs(60),
+			|s| s.nr5g.map(|n| n.rsrp)
+		).unwrap();
+		assert_eq!(stats.count, 1);
+		assert_eq!(stats.avg, -95.0);
+	}
+
+	#[test]
+	fn signal_stats_window() {
+		let mut history = SignalHistory::new(3);
+		history.push(sample(300, -120.0, None));
+		history.push(sample(100, -100.0, None));
+		history.push(sample(5, -90.0, None));
+
+		// the sample older than the window is ignored
+		let stats = history.stats(Duration::from_secs(200), rsrp).unwrap();
+		assert_eq!(stats.min, -100.0);
+		assert_eq!(stats.count, 2);
+		assert!(history.stats(Duration::from_secs(1), rsrp).is_none());
+
+		// the oldest sample is dropped once the capacity is reached
+		history.push(sample(0, -70.0, None));
+		assert_eq!(history.len(), 3);
+		let stats = history.stats(Duration::from_secs(600), rsrp).unwrap();
+		assert_eq!(stats.min, -100.0);
+		assert_eq!(stats.max, -70.0);
+		assert_eq!(stats.avg, -260.0 / 3.0);
+		assert_eq!(history.latest().and_then(rsrp), Some(-70.0));
+
+		let mut empty = SignalHistory::new(0);
+		empty.push(sample(0, -70.0, None));
+		assert!(empty.is_empty());
+	}
+}
@@ -0,0 +1,228 @@
+//! List the multicast groups joined on each interface from `/proc/net/igmp`
+//! and `/proc/net/igmp6`.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::network::multicast::Memberships;
+//!
+//! let groups = Memberships::read().unwrap();
+//! for m in groups.entries() {
+//!     println!("{} joined {} ({} users)", m.interface, m.group, m.users);
+//! }
+//! ```
+
+use crate::util::{read_to_string, read_to_string_mut};
+#[cfg(feature = "async")]
+use crate::util::{read_to_string_async, read_to_string_mut_async};
+
+use std::io;
+use std::path::Path;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Read the ipv4 or ipv6 multicast group memberships.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Memberships {
+	ipv6: bool,
+	raw: String
+}
+
+impl Memberships {
+
+	fn path(ipv6: bool) -> &'static Path {
+		Path::new(match ipv6 {
+			false => "/proc/net/igmp",
+			true => "/proc/net/igmp6"
+		})
+	}
+
+	#[cfg(test)]
+	fn from_string(ipv6: bool, raw: String) -> Self {
+		Self {ipv6, raw}
+	}
+
+	/// Read the ipv4 memberships from /proc/net/igmp.
+	pub fn read() -> io::Result<Self> {
+		Ok(Self {
+			ipv6: false,
+			raw: read_to_string(Self::path(false))?
+		})
+	}
+
+	/// Read the ipv6 memberships from /proc/net/igmp6.
+	pub fn read_ipv6() -> io::Result<Self> {
+		Ok(Self {
+			ipv6: true,
+			raw: read_to_string(Self::path(true))?
+		})
+	}
+
+	/// Reloads information without allocating.
+	pub fn reload(&mut self) -> io::Result<()> {
+		read_to_string_mut(Self::path(self.ipv6), &mut self.raw)
+	}
+
+	/// Reads the ipv4 memberships without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		Ok(Self {
+			ipv6: false,
+			raw: read_to_string_async(Self::path(false)).await?
+		})
+	}
+
+	/// Reads the ipv6 memberships without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_ipv6_async() -> io::Result<Self> {
+		Ok(Self {
+			ipv6: true,
+			raw: read_to_string_async(Self::path(true)).await?
+		})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(Self::path(self.ipv6), &mut self.raw).await
+	}
+
+	/// Returns every membership, lines which cannot be parsed are skipped.
+	pub fn entries(&self) -> impl Iterator<Item=Membership> + '_ {
+		let v4 = (!self.ipv6).then(|| igmp_entries(&self.raw));
+		let v6 = self.ipv6.then(|| {
+			self.raw.lines().filter_map(Membership::from_v6_line)
+		});
+		v4.into_iter().flatten().chain(v6.into_iter().flatten())
+	}
+
+	/// Returns the memberships of an interface for example `eth0`.
+	pub fn by_interface<'a>(
+		&'a self,
+		name: &'a str
+	) -> impl Iterator<Item=Membership> + 'a {
+		self.entries().filter(move |m| m.interface == name)
+	}
+}
+
+/// A multicast group joined on an interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct Membership {
+	/// The index of the interface.
+	pub index: u32,
+	pub interface: String,
+	pub group: IpAddr,
+	/// How many sockets (or the kernel) joined the group.
+	pub users: u32,
+	/// The igmp version used on the link, `None` for ipv6.
+	pub igmp_version: Option<u8>
+}
+
+impl Membership {
+	fn from_v6_line(line: &str) -> Option<Self> {
+		let mut iter = line.split_whitespace();
+		let index = iter.next()?.parse().ok()?;
+		let interface = iter.next()?.to_string();
+		let group = u128::from_str_radix(iter.next()?, 16).ok()?;
+
+		Some(Self {
+			index,
+			interface,
+			group: Ipv6Addr::from(group).into(),
+			users: iter.next()?.parse().ok()?,
+			igmp_version: None
+		})
+	}
+}
+
+/// Parses /proc/net/igmp where the groups follow the line of their
+/// interface.
+fn igmp_entries(raw: &str) -> impl Iterator<Item=Membership> + '_ {
+	// (index, interface, igmp version)
+	let mut device: Option<(u32, &str, Option<u8>)> = None;
+
+	raw.lines()
+		// skip the header
+		.skip(1)
+		.filter_map(move |line| {
+			if !line.starts_with(char::is_whitespace) {
+				device = parse_igmp_device(line);
+				return None
+			}
+
+			let (index, interface, igmp_version) = device?;
+			let mut iter = line.split_whitespace();
+			// the address is printed in host byte order
+			let group = u32::from_str_radix(iter.next()?, 16).ok()?;
+
+			Some(Membership {
+				index,
+				interface: interface.to_string(),
+				group: Ipv4Addr::from(group.to_ne_bytes()).into(),
+				users: iter.next()?.parse().ok()?,
+				igmp_version
+			})
+		})
+}
+
+/// Parses a line like `2\teth0      :     3      V3`.
+fn parse_igmp_device(line: &str) -> Option<(u32, &str, Option<u8>)> {
+	let (device, rest) = line.split_once(':')?;
+	let mut iter = device.split_whitespace();
+	let index = iter.next()?.parse().ok()?;
+	let name = iter.next()?;
+	let version = rest.split_whitespace()
+		.nth(1)
+		.and_then(|v| v.strip_prefix('V')?.parse().ok());
+
+	Some((index, name, version))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn igmp() {
+		let groups = Memberships::from_string(false, "\
+Idx\tDevice    : Count Querier\tGroup    Users Timer\tReporter
+1\tlo        :     1      V3
+\t\t\t\t010000E0     1 0:00000000\t\t0
+4\teth0      :     2      V2
+\t\t\t\tFB0000E0     2 0:00000000\t\t1
+\t\t\t\t010000E0     1 0:00000000\t\t0
+".into());
+		let list: Vec<_> = groups.entries().collect();
+		assert_eq!(list.len(), 3);
+		assert_eq!(list[0].interface, "lo");
+		assert_eq!(list[0].group, "224.0.0.1".parse::<IpAddr>().unwrap());
+		assert_eq!(list[0].igmp_version, Some(3));
+		assert_eq!(list[1].index, 4);
+		assert_eq!(list[1].group, "224.0.0.251".parse::<IpAddr>().unwrap());
+		assert_eq!(list[1].users, 2);
+		assert_eq!(list[1].igmp_version, Some(2));
+		assert_eq!(groups.by_interface("eth0").count(), 2);
+	}
+
+	#[test]
+	fn igmp6() {
+		let groups = Memberships::from_string(true, "\
+1    lo              ff020000000000000000000000000001     1 0000000C 0
+4    eth0            ff0200000000000000000001ff000002     1 00000004 0
+4    eth0            ff0200000000000000000000000000fb     2 00000004 0
+".into());
+		let list: Vec<_> = groups.entries().collect();
+		assert_eq!(list.len(), 3);
+		assert_eq!(list[0].group, "ff02::1".parse::<IpAddr>().unwrap());
+		assert_eq!(list[1].group, "ff02::1:ff00:2".parse::<IpAddr>().unwrap());
+		assert_eq!(list[2].users, 2);
+		assert_eq!(list[2].igmp_version, None);
+		assert_eq!(groups.by_interface("eth0").count(), 2);
+	}
+}
@@ -0,0 +1,312 @@
+//! A minimal netlink client shared by the modules which talk to the kernel
+//! over netlink.
+
+use std::{io, mem};
+use std::fs::File;
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::os::raw::c_int;
+
+pub const NETLINK_ROUTE: c_int = 0;
+pub const NETLINK_SOCK_DIAG: c_int = 4;
+pub const NETLINK_GENERIC: c_int = 16;
+
+pub const NLM_F_REQUEST: u16 = 0x01;
+pub const NLM_F_MULTI: u16 = 0x02;
+pub const NLM_F_DUMP: u16 = 0x300;
+const NLMSG_ERROR: u16 = 2;
+const NLMSG_DONE: u16 = 3;
+pub const NLMSG_HDR_LEN: usize = 16;
+
+// see linux/genetlink.h
+const GENL_ID_CTRL: u16 = 0x10;
+const CTRL_CMD_GETFAMILY: u8 = 3;
+const CTRL_ATTR_FAMILY_ID: u16 = 1;
+const CTRL_ATTR_FAMILY_NAME: u16 = 2;
+pub const GENL_HDR_LEN: usize = 4;
+
+pub const BUFFER_SIZE: usize = 32 * 1024;
+
+#[derive(Debug)]
+pub struct Netlink {
+	file: File,
+	seq: u32
+}
+
+impl Netlink {
+	pub fn open(protocol: c_int) -> io::Result<Self> {
+		let fd = unsafe {
+			libc::socket(
+				libc::AF_NETLINK,
+				libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+				protocol
+			)
+		};
+		if fd < 0 {
+			return Err(io::Error::last_os_error())
+		}
+		// the file closes the socket on drop
+		Ok(Self {
+			file: unsafe { File::from_raw_fd(fd) },
+			seq: 0
+		})
+	}
+
+	/// Opens a socket which receives the messages sent to the multicast
+	/// groups.
+	pub fn subscribe(protocol: c_int, groups: u32) -> io::Result<Self> {
+		let me = Self::open(protocol)?;
+		let r = unsafe {
+			let mut addr: libc::sockaddr_nl = mem::zeroed();
+			addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+			addr.nl_groups = groups;
+			libc::bind(
+				me.file.as_raw_fd(),
+				&addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+				mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t
+			)
+		};
+		if r < 0 {
+			return Err(io::Error::last_os_error())
+		}
+		Ok(me)
+	}
+
+	/// Moves the socket into or out of nonblocking mode.
+	pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+		unsafe {
+			let fd = self.file.as_raw_fd();
+			let flags = libc::fcntl(fd, libc::F_GETFL);
+			if flags < 0 {
+				return Err(io::Error::last_os_error())
+			}
+
+			let flags = match nonblocking {
+				true => flags | libc::O_NONBLOCK,
+				false => flags & !libc::O_NONBLOCK
+			};
+			match libc::fcntl(fd, libc::F_SETFL, flags) {
+				-1 => Err(io::Error::last_os_error()),
+				_ => Ok(())
+			}
+		}
+	}
+
+	/// Receives one datagram and calls `f` with the type and the payload of
+	/// every message in it.
+	pub fn recv(
+		&mut self,
+		buf: &mut [u8],
+		mut f: impl FnMut(u16, &[u8])
+	) -> io::Result<()> {
+		let data = self.recv_datagram(buf)?;
+		parse_messages(data, &mut f).map(|_| ())
+	}
+
+	fn recv_datagram<'a>(&self, buf: &'a mut [u8]) -> io::Result<&'a [u8]> {
+		loop {
+			let len = unsafe {
+				libc::recv(
+					self.file.as_raw_fd(),
+					buf.as_mut_ptr() as *mut _,
+					buf.len(),
+					0
+				)
+			};
+			if len < 0 {
+				let e = io::Error::last_os_error();
+				if e.kind() == io::ErrorKind::Interrupted {
+					continue
+				}
+				return Err(e)
+			}
+			return Ok(&buf[..len as usize])
+		}
+	}
+
+	/// Sends a request and calls `f` with the type and the payload of every
+	/// message of the response.
+	pub fn request(
+		&mut self,
+		ty: u16,
+		flags: u16,
+		payload: &[u8],
+		mut f: impl FnMut(u16, &[u8])
+	) -> io::Result<()> {
+		self.seq = self.seq.wrapping_add(1);
+		let len = NLMSG_HDR_LEN + payload.len();
+		let mut req = Vec::with_capacity(len);
+		req.extend_from_slice(&(len as u32).to_ne_bytes());
+		req.extend_from_slice(&ty.to_ne_bytes());
+		req.extend_from_slice(&(NLM_F_REQUEST | flags).to_ne_bytes());
+		req.extend_from_slice(&self.seq.to_ne_bytes());
+		// the kernel fills in the port id
+		req.extend_from_slice(&0u32.to_ne_bytes());
+		req.extend_from_slice(payload);
+
+		let r = unsafe {
+			libc::send(self.file.as_raw_fd(), req.as_ptr() as *const _, len, 0)
+		};
+		if r < 0 {
+			return Err(io::Error::last_os_error())
+		}
+
+		let mut buf = vec![0u8; BUFFER_SIZE];
+		loop {
+			let data = self.recv_datagram(&mut buf)?;
+			if parse_messages(data, &mut f)? {
+				return Ok(())
+			}
+		}
+	}
+
+	/// Returns the id of a generic netlink family for example `nl80211`, or
+	/// `None` if the family is not registered (the module is not loaded).
+	pub fn genl_family(&mut self, name: &str) -> io::Result<Option<u16>> {
+		let mut payload = genl_header(CTRL_CMD_GETFAMILY, 1);
+		let mut name = name.as_bytes().to_vec();
+		name.push(0);
+		push_attr(&mut payload, CTRL_ATTR_FAMILY_NAME, &name);
+
+		let mut id = None;
+		let r = self.request(GENL_ID_CTRL, 0, &payload, |_, data| {
+			id = data.get(GENL_HDR_LEN..)
+				.and_then(|d| {
+					attributes(d).find(|(ty, _)| *ty == CTRL_ATTR_FAMILY_ID)
+				})
+				.and_then(|(_, v)| v.get(..2))
+				.map(|v| u16::from_ne_bytes([v[0], v[1]]));
+		});
+
+		match r {
+			Ok(()) => Ok(id),
+			Err(e) if e.raw_os_error() == Some(libc::ENOENT) => Ok(None),
+			Err(e) => Err(e)
+		}
+	}
+}
+
+impl AsRawFd for Netlink {
+	fn as_raw_fd(&self) -> RawFd {
+		self.file.as_raw_fd()
+	}
+}
+
+/// Parses the messages of one datagram, returns true if the response is
+/// complete.
+pub fn parse_messages(
+	mut data: &[u8],
+	f: &mut impl FnMut(u16, &[u8])
+) -> io::Result<bool> {
+	let mut done = true;
+	while data.len() >= NLMSG_HDR_LEN {
+		let len = u32::from_ne_bytes(data[..4].try_into().unwrap()) as usize;
+		let ty = u16::from_ne_bytes([data[4], data[5]]);
+		let flags = u16::from_ne_bytes([data[6], data[7]]);
+		if len < NLMSG_HDR_LEN || len > data.len() {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidData,
+				"truncated netlink message"
+			))
+		}
+		let payload = &data[NLMSG_HDR_LEN..len];
+
+		match ty {
+			NLMSG_DONE => return Ok(true),
+			NLMSG_ERROR => {
+				let errno = payload.get(..4)
+					.map(|b| i32::from_ne_bytes(b.try_into().unwrap()))
+					.unwrap_or(0);
+				if errno != 0 {
+					return Err(io::Error::from_raw_os_error(-errno))
+				}
+				// an acknowledgement
+				return Ok(true)
+			},
+			_ => {
+				// multipart messages are terminated by NLMSG_DONE
+				done = flags & NLM_F_MULTI == 0;
+				f(ty, payload);
+			}
+		}
+
+		data = data.get(align(len)..).unwrap_or(&[]);
+	}
+	Ok(done)
+}
+
+/// Builds struct genlmsghdr.
+pub fn genl_header(cmd: u8, version: u8) -> Vec<u8> {
+	vec![cmd, version, 0, 0]
+}
+
+/// Appends a struct nlattr with its value.
+pub fn push_attr(buf: &mut Vec<u8>, ty: u16, value: &[u8]) {
+	buf.extend_from_slice(&(4 + value.len() as u16).to_ne_bytes());
+	buf.extend_from_slice(&ty.to_ne_bytes());
+	buf.extend_from_slice(value);
+	buf.resize(align(buf.len()), 0);
+}
+
+/// Returns the type and the value of every attribute (struct rtattr or
+/// struct nlattr).
+pub fn attributes(mut data: &[u8]) -> impl Iterator<Item=(u16, &[u8])> {
+	std::iter::from_fn(move || {
+		let len = u16::from_ne_bytes([*data.first()?, *data.get(1)?]) as usize;
+		let ty = u16::from_ne_bytes([*data.get(2)?, *data.get(3)?]);
+		let value = data.get(4..len)?;
+		data = data.get(align(len)..).unwrap_or(&[]);
+		// the upper bits are flags
+		Some((ty & 0x3fff, value))
+	})
+}
+
+pub fn align(len: usize) -> usize {
+	(len + 3) & !3
+}
+
+pub fn c_string(b: &[u8]) -> String {
+	let end = b.iter().position(|b| *b == 0).unwrap_or(b.len());
+	String::from_utf8_lossy(&b[..end]).into_owned()
+}
+
+pub fn u32_attr(v: &[u8]) -> Option<u32> {
+	v.get(..4).map(|b| u32::from_ne_bytes(b.try_into().unwrap()))
+}
+
+pub fn u64_attr(v: &[u8]) -> Option<u64> {
+	v.get(..8).map(|b| u64::from_ne_bytes(b.try_into().unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn messages() {
+		let mut data = vec![];
+		let mut payload = genl_header(1, 1);
+		push_attr(&mut payload, 2, b"abc\0");
+		data.extend_from_slice(&((NLMSG_HDR_LEN + payload.len()) as u32).to_ne_bytes());
+		data.extend_from_slice(&20u16.to_ne_bytes());
+		data.extend_from_slice(&NLM_F_MULTI.to_ne_bytes());
+		data.resize(NLMSG_HDR_LEN, 0);
+		data.extend_from_slice(&payload);
+
+		let mut got = vec![];
+		let mut f = |ty, p: &[u8]| got.push((ty, p.to_vec()));
+		// a multipart message is not complete without NLMSG_DONE
+		assert!(!parse_messages(&data, &mut f).unwrap());
+
+		data.extend_from_slice(&(NLMSG_HDR_LEN as u32).to_ne_bytes());
+		data.extend_from_slice(&NLMSG_DONE.to_ne_bytes());
+		data.resize(data.len() + 10, 0);
+		assert!(parse_messages(&data, &mut f).unwrap());
+
+		assert_eq!(got.len(), 2);
+		assert_eq!(got[0].0, 20);
+		let attrs: Vec<_> = attributes(&got[0].1[GENL_HDR_LEN..]).collect();
+		assert_eq!(attrs.len(), 1);
+		assert_eq!(attrs[0].0, 2);
+		assert_eq!(c_string(attrs[0].1), "abc");
+	}
+}
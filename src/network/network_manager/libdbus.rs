@@ -0,0 +1,802 @@
+//! The NetworkManager using libdbus, every item is reexported in
+//! [`network_manager`](super).
+
+use super::{
+	DBUS_NAME, DBUS_PATH, PROPERTIES_INTERFACE, WIRELESS_INTERFACE,
+	ACCESS_POINT_INTERFACE, DeviceKind, DeviceState, CheckpointFlags,
+	RollbackResult, Capability, PermissionResult
+};
+
+use std::fmt;
+use std::time::{Duration, Instant};
+use std::sync::Arc;
+use std::net::Ipv4Addr;
+use std::collections::{HashMap, HashSet};
+
+use dbus::{Error, Path, Message};
+use dbus::blocking::{SyncConnection, Proxy};
+use dbus::arg::{RefArg, PropMap};
+use dbus::message::{MatchRule, MessageType};
+use dbus::blocking::stdintf::org_freedesktop_dbus::Properties;
+
+use nmdbus::NetworkManager as DbusNetworkManager;
+use nmdbus::device::Device as DeviceTrait;
+use nmdbus::device_modem::DeviceModem;
+use nmdbus::ip4config::IP4Config;
+
+const CHECKPOINT_INTERFACE: &str = "org.freedesktop.NetworkManager.Checkpoint";
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Clone)]
+struct Dbus {
+	conn: Arc<SyncConnection>,
+	timeout: Duration
+}
+
+impl Dbus {
+	fn connect() -> Result<Self, Error> {
+		SyncConnection::new_system()
+			.map(Arc::new)
+			.map(|conn| Self { conn, timeout: TIMEOUT })
+	}
+
+	fn proxy<'a, 'b>(
+		&'b self,
+		path: impl Into<Path<'a>>
+	) -> Proxy<'a, &'b SyncConnection> {
+		self.conn.with_proxy(DBUS_NAME, path, self.timeout)
+	}
+
+	/// Returns true if the service currently owns its name on the bus.
+	fn name_has_owner(&self) -> Result<bool, Error> {
+		self.conn.with_proxy(
+			"org.freedesktop.DBus",
+			"/org/freedesktop/DBus",
+			self.timeout
+		).method_call("org.freedesktop.DBus", "NameHasOwner", (DBUS_NAME,))
+			.map(|(owned,): (bool,)| owned)
+	}
+}
+
+#[derive(Clone)]
+pub struct NetworkManager {
+	dbus: Dbus
+}
+
+impl NetworkManager {
+	/// Connects to the system bus with the default timeout of 2 seconds.
+	pub fn connect() -> Result<Self, Error> {
+		Dbus::connect()
+			.map(|dbus| Self { dbus })
+	}
+
+	/// Creates a builder which allows to configure the timeout or to use an
+	/// existing connection.
+	pub fn builder() -> NetworkManagerBuilder {
+		NetworkManagerBuilder::new()
+	}
+
+	/// Creates a NetworkManager from an existing system bus connection.
+	///
+	/// This allows to share one connection between multiple managers.
+	pub fn from_connection(conn: Arc<SyncConnection>) -> Self {
+		Self {
+			dbus: Dbus { conn, timeout: TIMEOUT }
+		}
+	}
+
+	/// Returns true if NetworkManager is running.
+	///
+	/// Other calls fail with a generic dbus error if the service is missing,
+	/// this allows to check for it before and degrade gracefully.
+	pub fn is_available(&self) -> Result<bool, Error> {
+		self.dbus.name_has_owner()
+	}
+
+	pub fn devices(&self) -> Result<Vec<Device>, Error> {
+		let paths = self.dbus.proxy(DBUS_PATH).get_devices()?;
+		let devices = paths.into_iter()
+			.map(|path| {
+				Device {
+					dbus: self.dbus.clone(),
+					path
+				}
+			})
+			.collect();
+
+		Ok(devices)
+	}
+
+	/// The version of the running NetworkManager for example `1.42.4`.
+	pub fn version(&self) -> Result<String, Error> {
+		self.dbus.proxy(DBUS_PATH).get(DBUS_NAME, "Version")
+	}
+
+	/// The optional features the running NetworkManager supports.
+	pub fn capabilities(&self) -> Result<Vec<Capability>, Error> {
+		let caps: Vec<u32> = self.dbus.proxy(DBUS_PATH)
+			.get(DBUS_NAME, "Capabilities")?;
+		Ok(caps.into_iter().map(Into::into).collect())
+	}
+
+	/// Returns the permissions of the caller for example
+	/// `org.freedesktop.NetworkManager.wifi.scan`, which might require
+	/// authorization.
+	pub fn permissions(
+		&self
+	) -> Result<HashMap<String, PermissionResult>, Error> {
+		let (perms,): (HashMap<String, String>,) = self.dbus.proxy(DBUS_PATH)
+			.method_call(DBUS_NAME, "GetPermissions", ())?;
+		Ok(perms.into_iter().map(|(k, v)| (k, v.as_str().into())).collect())
+	}
+
+	/// Creates a checkpoint of the current configuration of the devices, if
+	/// `devices` is empty every device is included.
+	///
+	/// If the checkpoint is not destroyed before the rollback timeout passes
+	/// the configuration is rolled back automatically, a timeout of zero
+	/// disables the automatic rollback.
+	///
+	/// ## Example
+	/// ```no_run
+	/// # use std::time::Duration;
+	/// # use linux_info::network::network_manager::{
+	/// #     NetworkManager, CheckpointFlags
+	/// # };
+	/// let manager = NetworkManager::connect().unwrap();
+	/// let checkpoint = manager.checkpoint_create(
+	///     &[],
+	///     Duration::from_secs(30),
+	///     CheckpointFlags::new().with_delete_new_connections()
+	/// ).unwrap();
+	/// // make risky changes, if the connection is lost everything
+	/// // gets rolled back after 30 seconds
+	/// checkpoint.destroy().unwrap();
+	/// ```
+	pub fn checkpoint_create(
+		&self,
+		devices: &[Device],
+		rollback_timeout: Duration,
+		flags: CheckpointFlags
+	) -> Result<Checkpoint, Error> {
+		let devices: Vec<_> = devices.iter().map(|d| d.path.clone()).collect();
+		let (path,): (Path<'static>,) = self.dbus.proxy(DBUS_PATH)
+			.method_call(
+				DBUS_NAME,
+				"CheckpointCreate",
+				(devices, rollback_timeout.as_secs() as u32, u32::from(flags))
+			)?;
+
+		Ok(Checkpoint {
+			dbus: self.dbus.clone(),
+			path
+		})
+	}
+
+	/// Returns the checkpoints which currently exist.
+	pub fn checkpoints(&self) -> Result<Vec<Checkpoint>, Error> {
+		let paths: Vec<Path<'static>> = self.dbus.proxy(DBUS_PATH)
+			.get(DBUS_NAME, "Checkpoints")?;
+		let checkpoints = paths.into_iter()
+			.map(|path| {
+				Checkpoint {
+					dbus: self.dbus.clone(),
+					path
+				}
+			})
+			.collect();
+
+		Ok(checkpoints)
+	}
+}
+
+/// A builder to create a [`NetworkManager`].
+///
+/// ## Example
+/// ```no_run
+/// # use std::time::Duration;
+/// # use linux_info::network::network_manager::NetworkManager;
+/// let manager = NetworkManager::builder()
+///     .timeout(Duration::from_secs(5))
+///     .connect()
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct NetworkManagerBuilder {
+	conn: Option<Arc<SyncConnection>>,
+	timeout: Duration
+}
+
+impl NetworkManagerBuilder {
+	fn new() -> Self {
+		Self {
+			conn: None,
+			timeout: TIMEOUT
+		}
+	}
+
+	/// Sets the timeout used for every dbus call (default 2 seconds).
+	pub fn timeout(mut self, timeout: Duration) -> Self {
+		self.timeout = timeout;
+		self
+	}
+
+	/// Uses an existing connection instead of opening a new one to the
+	/// system bus.
+	pub fn connection(mut self, conn: Arc<SyncConnection>) -> Self {
+		self.conn = Some(conn);
+		self
+	}
+
+	/// Creates the NetworkManager, opening a new connection to the system bus if
+	/// no connection was set.
+	pub fn connect(self) -> Result<NetworkManager, Error> {
+		let conn = match self.conn {
+			Some(conn) => conn,
+			None => Arc::new(SyncConnection::new_system()?)
+		};
+
+		Ok(NetworkManager {
+			dbus: Dbus { conn, timeout: self.timeout }
+		})
+	}
+}
+
+#[derive(Clone)]
+pub struct Device {
+	dbus: Dbus,
+	path: Path<'static>
+}
+
+impl Device {
+	/// The path of the device as exposed by the udev property ID_PATH.  
+	/// Note that non-UTF-8 characters are backslash escaped.
+	/// Use g_strcompress() to obtain the true (non-UTF-8) string. 
+	pub fn path(&self) -> Result<String, Error> {
+		self.dbus.proxy(&self.path).path()
+	}
+
+	/// The name of the device's control (and often data) interface. Note that
+	/// non UTF-8 characters are backslash escaped, so the resulting name may
+	/// be longer then 15 characters. Use g_strcompress() to revert the
+	/// escaping.
+	pub fn interface(&self) -> Result<String, Error> {
+		self.dbus.proxy(&self.path).interface()
+	}
+
+	/// The driver handling the device. Non-UTF-8 sequences are backslash
+	/// escaped. Use g_strcompress() to revert. 
+	pub fn driver(&self) -> Result<String, Error> {
+		self.dbus.proxy(&self.path).driver()
+	}
+
+	/// The current state of the device. 
+	pub fn state(&self) -> Result<DeviceState, Error> {
+		DeviceTrait::state(&self.dbus.proxy(&self.path))
+			.map(Into::into)
+	}
+
+	/// The general type of the network device; ie Ethernet, Wi-Fi, etc.
+	pub fn kind(&self) -> Result<DeviceKind, Error> {
+		self.dbus.proxy(&self.path).device_type()
+			.map(Into::into)
+	}
+
+	/// Ipv4 Configuration of the device. Only valid when the device is in
+	/// DeviceState::Activated
+	pub fn ipv4_config(&self) -> Result<Ipv4Config, Error> {
+		self.dbus.proxy(&self.path).ip4_config()
+			.map(|path| Ipv4Config {
+				dbus: self.dbus.clone(),
+				path
+			})
+	}
+
+	/// The access point name the modem is connected to. Blank if disconnected.
+	pub fn modem_apn(&self) -> Result<String, Error> {
+		self.dbus.proxy(&self.path).apn()
+	}
+
+	/// The access points a Wi-Fi device currently sees, including hidden
+	/// ones.
+	pub fn access_points(&self) -> Result<Vec<AccessPoint>, Error> {
+		let (paths,): (Vec<Path<'static>>,) = self.dbus.proxy(&self.path)
+			.method_call(WIRELESS_INTERFACE, "GetAllAccessPoints", ())?;
+		let access_points = paths.into_iter()
+			.map(|path| {
+				AccessPoint {
+					dbus: self.dbus.clone(),
+					path
+				}
+			})
+			.collect();
+
+		Ok(access_points)
+	}
+
+	/// Watches the access points of a Wi-Fi device, see
+	/// [`AccessPointMonitor`].
+	pub fn watch_access_points(&self) -> Result<AccessPointMonitor, Error> {
+		AccessPointMonitor::new(self)
+	}
+}
+
+/// A Wi-Fi access point seen by a device.
+#[derive(Clone)]
+pub struct AccessPoint {
+	dbus: Dbus,
+	path: Path<'static>
+}
+
+impl AccessPoint {
+	/// The service set identifier, bytes which are not valid UTF-8 are
+	/// replaced.
+	pub fn ssid(&self) -> Result<String, Error> {
+		self.dbus.proxy(&self.path)
+			.get(ACCESS_POINT_INTERFACE, "Ssid")
+			.map(|b: Vec<u8>| String::from_utf8_lossy(&b).into_owned())
+	}
+
+	/// The hardware address (BSSID) of the access point.
+	pub fn hw_address(&self) -> Result<String, Error> {
+		self.dbus.proxy(&self.path).get(ACCESS_POINT_INTERFACE, "HwAddress")
+	}
+
+	/// The radio channel frequency in MHz.
+	pub fn frequency(&self) -> Result<u32, Error> {
+		self.dbus.proxy(&self.path).get(ACCESS_POINT_INTERFACE, "Frequency")
+	}
+
+	/// The maximum bitrate in kbit/s.
+	pub fn max_bitrate(&self) -> Result<u32, Error> {
+		self.dbus.proxy(&self.path).get(ACCESS_POINT_INTERFACE, "MaxBitrate")
+	}
+
+	/// The current signal quality in percent.
+	pub fn strength(&self) -> Result<u8, Error> {
+		self.dbus.proxy(&self.path).get(ACCESS_POINT_INTERFACE, "Strength")
+	}
+}
+
+impl PartialEq for AccessPoint {
+	fn eq(&self, other: &Self) -> bool {
+		self.path == other.path
+	}
+}
+
+impl Eq for AccessPoint {}
+
+impl fmt::Debug for AccessPoint {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_tuple("AccessPoint")
+			.field(&&*self.path)
+			.finish()
+	}
+}
+
+/// A change of the access points of a Wi-Fi device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccessPointEvent {
+	/// The device found a new access point.
+	Added(AccessPoint),
+	/// The access point disappeared, its properties can no longer be read.
+	Removed(AccessPoint),
+	/// The signal quality of an access point changed, in percent.
+	Strength(AccessPoint, u8)
+}
+
+/// Receives events when access points of a Wi-Fi device appear, disappear
+/// or their signal strength changes, so a signal meter does not need to
+/// rescan.
+///
+/// Uses a separate connection to the system bus.
+///
+/// ## Example
+/// ```no_run
+/// # use linux_info::network::network_manager::{
+/// #     NetworkManager, DeviceKind, AccessPointEvent
+/// # };
+/// let manager = NetworkManager::connect().unwrap();
+/// let wifi = manager.devices().unwrap()
+///     .into_iter()
+///     .find(|d| matches!(d.kind(), Ok(DeviceKind::Wifi)))
+///     .unwrap();
+///
+/// for event in wifi.watch_access_points().unwrap() {
+///     match event.unwrap() {
+///         AccessPointEvent::Strength(ap, strength) => {
+///             println!("{:?} {}%", ap.ssid(), strength);
+///         },
+///         e => println!("{:?}", e)
+///     }
+/// }
+/// ```
+pub struct AccessPointMonitor {
+	dbus: Dbus,
+	conn: SyncConnection,
+	device: Path<'static>,
+	known: HashSet<Path<'static>>
+}
+
+impl AccessPointMonitor {
+	fn new(device: &Device) -> Result<Self, Error> {
+		let conn = SyncConnection::new_system()?;
+		let rule = MatchRule::new()
+			.with_type(MessageType::Signal)
+			.with_sender(DBUS_NAME)
+			.with_namespaced_path(DBUS_PATH);
+		conn.add_match_no_cb(&rule.match_str())?;
+
+		// read the list after subscribing so no access point is missed
+		let known = device.access_points()?
+			.into_iter()
+			.map(|ap| ap.path)
+			.collect();
+
+		Ok(Self {
+			dbus: device.dbus.clone(),
+			conn,
+			device: device.path.clone(),
+			known
+		})
+	}
+
+	/// Returns the access points the device currently sees.
+	pub fn access_points(&self) -> Vec<AccessPoint> {
+		self.known.iter()
+			.map(|path| self.access_point(path.clone()))
+			.collect()
+	}
+
+	/// Waits for the next event.
+	pub fn recv(&mut self) -> Result<AccessPointEvent, Error> {
+		loop {
+			if let Some(ev) = self.recv_timeout(self.dbus.timeout)? {
+				return Ok(ev)
+			}
+		}
+	}
+
+	/// Waits at most `timeout` for the next event, returns `None` if no
+	/// event arrived in time.
+	pub fn recv_timeout(
+		&mut self,
+		timeout: Duration
+	) -> Result<Option<AccessPointEvent>, Error> {
+		let deadline = Instant::now() + timeout;
+		loop {
+			let left = deadline.saturating_duration_since(Instant::now());
+			let msg = match self.conn.channel().blocking_pop_message(left)? {
+				Some(msg) => msg,
+				None => return Ok(None)
+			};
+
+			if let Some(ev) = self.parse(&msg) {
+				return Ok(Some(ev))
+			}
+		}
+	}
+
+	fn access_point(&self, path: Path<'static>) -> AccessPoint {
+		AccessPoint {
+			dbus: self.dbus.clone(),
+			path
+		}
+	}
+
+	fn parse(&mut self, msg: &Message) -> Option<AccessPointEvent> {
+		let path = msg.path()?.into_static();
+		let interface = msg.interface()?;
+		let member = msg.member()?;
+
+		match (&*interface, &*member) {
+			(WIRELESS_INTERFACE, "AccessPointAdded") if path == self.device => {
+				let ap = msg.read1::<Path>().ok()?.into_static();
+				self.known.insert(ap.clone());
+				Some(AccessPointEvent::Added(self.access_point(ap)))
+			},
+			(WIRELESS_INTERFACE, "AccessPointRemoved") if path == self.device => {
+				let ap = msg.read1::<Path>().ok()?.into_static();
+				self.known.remove(&ap)
+					.then(|| AccessPointEvent::Removed(self.access_point(ap)))
+			},
+			(PROPERTIES_INTERFACE, "PropertiesChanged")
+				if self.known.contains(&path) =>
+			{
+				let (iface, changed): (&str, PropMap) = msg.read2().ok()?;
+				if iface != ACCESS_POINT_INTERFACE {
+					return None
+				}
+				let strength = changed.get("Strength")?.0.as_u64()?;
+				Some(AccessPointEvent::Strength(
+					self.access_point(path),
+					strength as u8
+				))
+			},
+			_ => None
+		}
+	}
+}
+
+impl Iterator for AccessPointMonitor {
+	type Item = Result<AccessPointEvent, Error>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		Some(self.recv())
+	}
+}
+
+#[derive(Clone)]
+pub struct Ipv4Config {
+	dbus: Dbus,
+	path: Path<'static>
+}
+
+impl Ipv4Config {
+	pub fn addresses(&self) -> Result<Vec<Ipv4Addr>, Error> {
+		let data = self.dbus.proxy(&self.path).address_data()?;
+		let addrs = data.into_iter()
+			.filter_map(|mut d| d.remove("address"))
+			.filter_map(|addr| {
+				addr.as_str()?
+					.parse().ok()
+			})
+			.collect();
+
+		Ok(addrs)
+	}
+}
+
+/// A checkpoint of the network configuration created by
+/// [`NetworkManager::checkpoint_create`].
+#[derive(Clone)]
+pub struct Checkpoint {
+	dbus: Dbus,
+	path: Path<'static>
+}
+
+impl Checkpoint {
+	/// The devices which are part of the checkpoint.
+	pub fn devices(&self) -> Result<Vec<Device>, Error> {
+		let paths: Vec<Path<'static>> = self.dbus.proxy(&self.path)
+			.get(CHECKPOINT_INTERFACE, "Devices")?;
+		let devices = paths.into_iter()
+			.map(|path| {
+				Device {
+					dbus: self.dbus.clone(),
+					path
+				}
+			})
+			.collect();
+
+		Ok(devices)
+	}
+
+	/// The timeout after which the configuration is rolled back, zero if
+	/// there is no automatic rollback.
+	pub fn rollback_timeout(&self) -> Result<Duration, Error> {
+		self.dbus.proxy(&self.path)
+			.get(CHECKPOINT_INTERFACE, "RollbackTimeout")
+			.map(|secs: u32| Duration::from_secs(secs.into()))
+	}
+
+	/// Restores the configuration of the checkpoint and destroys it.
+	///
+	/// Returns the result for every device path.
+	pub fn rollback(&self) -> Result<HashMap<String, RollbackResult>, Error> {
+		let (results,): (HashMap<String, u32>,) = self.dbus.proxy(DBUS_PATH)
+			.method_call(DBUS_NAME, "CheckpointRollback", (&self.path,))?;
+		Ok(results.into_iter().map(|(k, v)| (k, v.into())).collect())
+	}
+
+	/// Destroys the checkpoint without restoring its configuration, which
+	/// keeps the current configuration.
+	pub fn destroy(&self) -> Result<(), Error> {
+		self.dbus.proxy(DBUS_PATH)
+			.method_call(DBUS_NAME, "CheckpointDestroy", (&self.path,))
+	}
+
+	/// Resets the rollback timeout to `add` from now. Since: 1.12.
+	pub fn adjust_rollback_timeout(&self, add: Duration) -> Result<(), Error> {
+		self.dbus.proxy(DBUS_PATH).method_call(
+			DBUS_NAME,
+			"CheckpointAdjustRollbackTimeout",
+			(&self.path, add.as_secs() as u32)
+		)
+	}
+}
+
+/// Async version of [`NetworkManager`].
+///
+/// This does not use async dbus, every call runs the blocking libdbus call
+/// on tokio's blocking thread pool so the roundtrip does not stall the
+/// runtime but occupies a thread. Requires the `async` feature.
+#[cfg(all(feature = "async", feature = "dbus"))]
+#[cfg_attr(
+	docsrs,
+	doc(cfg(all(feature = "network-manager", feature = "async")))
+)]
+#[derive(Clone)]
+pub struct AsyncNetworkManager {
+	inner: NetworkManager
+}
+
+#[cfg(all(feature = "async", feature = "dbus"))]
+impl AsyncNetworkManager {
+	pub async fn connect() -> Result<Self, Error> {
+		crate::network::spawn_blocking(NetworkManager::connect).await
+			.map(|inner| Self { inner })
+	}
+
+	pub async fn devices(&self) -> Result<Vec<AsyncDevice>, Error> {
+		let inner = self.inner.clone();
+		let devices = crate::network::spawn_blocking(move || inner.devices()).await?;
+		Ok(devices.into_iter().map(|inner| AsyncDevice { inner }).collect())
+	}
+
+	async_fns!(NetworkManager {
+		fn is_available() -> bool;
+		fn version() -> String;
+		fn capabilities() -> Vec<Capability>;
+		fn permissions() -> HashMap<String, PermissionResult>;
+	});
+
+	/// See [`NetworkManager::checkpoint_create`].
+	pub async fn checkpoint_create(
+		&self,
+		devices: &[AsyncDevice],
+		rollback_timeout: Duration,
+		flags: CheckpointFlags
+	) -> Result<AsyncCheckpoint, Error> {
+		let inner = self.inner.clone();
+		let devices: Vec<_> = devices.iter().map(|d| d.inner.clone()).collect();
+		crate::network::spawn_blocking(move || {
+			inner.checkpoint_create(&devices, rollback_timeout, flags)
+		}).await
+			.map(|inner| AsyncCheckpoint { inner })
+	}
+
+	/// See [`NetworkManager::checkpoints`].
+	pub async fn checkpoints(&self) -> Result<Vec<AsyncCheckpoint>, Error> {
+		let inner = self.inner.clone();
+		let list = crate::network::spawn_blocking(move || inner.checkpoints()).await?;
+		Ok(list.into_iter().map(|inner| AsyncCheckpoint { inner }).collect())
+	}
+
+	/// Returns the blocking version.
+	pub fn blocking(&self) -> &NetworkManager {
+		&self.inner
+	}
+}
+
+#[cfg(all(feature = "async", feature = "dbus"))]
+impl From<NetworkManager> for AsyncNetworkManager {
+	fn from(inner: NetworkManager) -> Self {
+		Self { inner }
+	}
+}
+
+/// Async version of [`Device`].
+#[cfg(all(feature = "async", feature = "dbus"))]
+#[cfg_attr(
+	docsrs,
+	doc(cfg(all(feature = "network-manager", feature = "async")))
+)]
+#[derive(Clone)]
+pub struct AsyncDevice {
+	inner: Device
+}
+
+#[cfg(all(feature = "async", feature = "dbus"))]
+impl AsyncDevice {
+	async_fns!(Device {
+		fn path() -> String;
+		fn interface() -> String;
+		fn driver() -> String;
+		fn state() -> DeviceState;
+		fn kind() -> DeviceKind;
+		fn modem_apn() -> String;
+	});
+
+	/// See [`Device::ipv4_config`].
+	pub async fn ipv4_config(&self) -> Result<AsyncIpv4Config, Error> {
+		let inner = self.inner.clone();
+		crate::network::spawn_blocking(move || inner.ipv4_config()).await
+			.map(|inner| AsyncIpv4Config { inner })
+	}
+
+	/// See [`Device::access_points`].
+	pub async fn access_points(&self) -> Result<Vec<AsyncAccessPoint>, Error> {
+		let inner = self.inner.clone();
+		let list = crate::network::spawn_blocking(move || inner.access_points()).await?;
+		Ok(list.into_iter().map(|inner| AsyncAccessPoint { inner }).collect())
+	}
+
+	/// Returns the blocking version.
+	pub fn blocking(&self) -> &Device {
+		&self.inner
+	}
+}
+
+/// Async version of [`Ipv4Config`].
+#[cfg(all(feature = "async", feature = "dbus"))]
+#[cfg_attr(
+	docsrs,
+	doc(cfg(all(feature = "network-manager", feature = "async")))
+)]
+#[derive(Clone)]
+pub struct AsyncIpv4Config {
+	inner: Ipv4Config
+}
+
+#[cfg(all(feature = "async", feature = "dbus"))]
+impl AsyncIpv4Config {
+	async_fns!(Ipv4Config {
+		fn addresses() -> Vec<Ipv4Addr>;
+	});
+
+	/// Returns the blocking version.
+	pub fn blocking(&self) -> &Ipv4Config {
+		&self.inner
+	}
+}
+
+/// Async version of [`AccessPoint`].
+#[cfg(all(feature = "async", feature = "dbus"))]
+#[cfg_attr(
+	docsrs,
+	doc(cfg(all(feature = "network-manager", feature = "async")))
+)]
+#[derive(Clone)]
+pub struct AsyncAccessPoint {
+	inner: AccessPoint
+}
+
+#[cfg(all(feature = "async", feature = "dbus"))]
+impl AsyncAccessPoint {
+	async_fns!(AccessPoint {
+		fn ssid() -> String;
+		fn hw_address() -> String;
+		fn frequency() -> u32;
+		fn max_bitrate() -> u32;
+		fn strength() -> u8;
+	});
+
+	/// Returns the blocking version.
+	pub fn blocking(&self) -> &AccessPoint {
+		&self.inner
+	}
+}
+
+/// Async version of [`Checkpoint`].
+#[cfg(all(feature = "async", feature = "dbus"))]
+#[cfg_attr(
+	docsrs,
+	doc(cfg(all(feature = "network-manager", feature = "async")))
+)]
+#[derive(Clone)]
+pub struct AsyncCheckpoint {
+	inner: Checkpoint
+}
+
+#[cfg(all(feature = "async", feature = "dbus"))]
+impl AsyncCheckpoint {
+	async_fns!(Checkpoint {
+		fn rollback_timeout() -> Duration;
+		fn rollback() -> HashMap<String, RollbackResult>;
+		fn destroy() -> ();
+		fn adjust_rollback_timeout(add: Duration) -> ();
+	});
+
+	/// See [`Checkpoint::devices`].
+	pub async fn devices(&self) -> Result<Vec<AsyncDevice>, Error> {
+		let inner = self.inner.clone();
+		let devices = crate::network::spawn_blocking(move || inner.devices()).await?;
+		Ok(devices.into_iter().map(|inner| AsyncDevice { inner }).collect())
+	}
+
+	/// Returns the blocking version.
+	pub fn blocking(&self) -> &Checkpoint {
+		&self.inner
+	}
+}
@@ -0,0 +1,543 @@
+//! The NetworkManager using the pure rust zbus backend.
+//!
+//! Has the same api as the libdbus version in
+//! [`network_manager`](super) except that calls don't have a timeout.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::network::network_manager::zbus::NetworkManager;
+//!
+//! let manager = NetworkManager::connect().unwrap();
+//! for device in manager.devices().unwrap() {
+//!     println!("{:?} {:?}", device.interface(), device.state());
+//! }
+//! ```
+
+use super::{
+	DBUS_NAME, DBUS_PATH, PROPERTIES_INTERFACE, WIRELESS_INTERFACE,
+	ACCESS_POINT_INTERFACE, DeviceKind, DeviceState, Capability,
+	PermissionResult, CheckpointFlags, RollbackResult
+};
+
+use std::fmt;
+use std::time::Duration;
+
+use std::net::Ipv4Addr;
+use std::collections::{HashMap, HashSet};
+
+use zbus1::{Error, Message, MatchRule};
+use zbus1::message::Type as MessageType;
+use zbus1::blocking::{Connection, Proxy, ProxyBuilder, MessageIterator};
+use zbus1::blocking::fdo::DBusProxy;
+use zbus1::names::BusName;
+use zbus1::proxy::CacheProperties;
+use zbus1::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue};
+
+const DEVICE_INTERFACE: &str = "org.freedesktop.NetworkManager.Device";
+const MODEM_INTERFACE: &str = "org.freedesktop.NetworkManager.Device.Modem";
+const IP4_CONFIG_INTERFACE: &str = "org.freedesktop.NetworkManager.IP4Config";
+const CHECKPOINT_INTERFACE: &str = "org.freedesktop.NetworkManager.Checkpoint";
+
+#[derive(Clone)]
+struct Dbus {
+	conn: Connection
+}
+
+impl Dbus {
+	fn connect() -> Result<Self, Error> {
+		Connection::system()
+			.map(|conn| Self { conn })
+	}
+
+	fn proxy<'a>(
+		&self,
+		path: ObjectPath<'a>,
+		interface: &'a str
+	) -> Result<Proxy<'a>, Error> {
+		ProxyBuilder::new(&self.conn)
+			.destination(DBUS_NAME)?
+			.path(path)?
+			.interface(interface)?
+			.cache_properties(CacheProperties::No)
+			.build()
+	}
+}
+
+#[derive(Clone)]
+pub struct NetworkManager {
+	dbus: Dbus
+}
+
+impl NetworkManager {
+	/// Connects to the system bus.
+	pub fn connect() -> Result<Self, Error> {
+		Dbus::connect()
+			.map(|dbus| Self { dbus })
+	}
+
+	/// Creates a NetworkManager from an existing system bus connection.
+	///
+	/// This allows to share one connection between multiple managers.
+	pub fn from_connection(conn: Connection) -> Self {
+		Self {
+			dbus: Dbus { conn }
+		}
+	}
+
+	fn proxy(&self) -> Result<Proxy<'static>, Error> {
+		self.dbus.proxy(
+			ObjectPath::from_static_str_unchecked(DBUS_PATH),
+			DBUS_NAME
+		)
+	}
+
+	/// Returns true if NetworkManager is running.
+	///
+	/// Other calls fail with a generic dbus error if the service is missing,
+	/// this allows to check for it before and degrade gracefully.
+	pub fn is_available(&self) -> Result<bool, Error> {
+		let name = BusName::try_from(DBUS_NAME)?;
+		DBusProxy::new(&self.dbus.conn)?
+			.name_has_owner(name)
+			.map_err(Into::into)
+	}
+
+	pub fn devices(&self) -> Result<Vec<Device>, Error> {
+		let paths: Vec<OwnedObjectPath> = self.proxy()?
+			.call("GetDevices", &())?;
+
+		let devices = paths.into_iter()
+			.map(|path| {
+				Device {
+					dbus: self.dbus.clone(),
+					path
+				}
+			})
+			.collect();
+
+		Ok(devices)
+	}
+
+	/// The version of the running NetworkManager for example `1.42.4`.
+	pub fn version(&self) -> Result<String, Error> {
+		self.proxy()?.get_property("Version")
+	}
+
+	/// The optional features the running NetworkManager supports.
+	pub fn capabilities(&self) -> Result<Vec<Capability>, Error> {
+		let caps: Vec<u32> = self.proxy()?.get_property("Capabilities")?;
+		Ok(caps.into_iter().map(Into::into).collect())
+	}
+
+	/// Returns the permissions of the caller for example
+	/// `org.freedesktop.NetworkManager.wifi.scan`, which might require
+	/// authorization.
+	pub fn permissions(
+		&self
+	) -> Result<HashMap<String, PermissionResult>, Error> {
+		let perms: HashMap<String, String> = self.proxy()?
+			.call("GetPermissions", &())?;
+		Ok(perms.into_iter().map(|(k, v)| (k, v.as_str().into())).collect())
+	}
+
+	/// Creates a checkpoint of the current configuration of the devices, if
+	/// `devices` is empty every device is included.
+	///
+	/// If the checkpoint is not destroyed before the rollback timeout passes
+	/// the configuration is rolled back automatically, a timeout of zero
+	/// disables the automatic rollback.
+	pub fn checkpoint_create(
+		&self,
+		devices: &[Device],
+		rollback_timeout: Duration,
+		flags: CheckpointFlags
+	) -> Result<Checkpoint, Error> {
+		let devices: Vec<_> = devices.iter().map(|d| d.path.clone()).collect();
+		let path: OwnedObjectPath = self.proxy()?.call(
+			"CheckpointCreate",
+			&(devices, rollback_timeout.as_secs() as u32, u32::from(flags))
+		)?;
+
+		Ok(Checkpoint {
+			dbus: self.dbus.clone(),
+			path
+		})
+	}
+
+	/// Returns the checkpoints which currently exist.
+	pub fn checkpoints(&self) -> Result<Vec<Checkpoint>, Error> {
+		let paths: Vec<OwnedObjectPath> = self.proxy()?
+			.get_property("Checkpoints")?;
+		let checkpoints = paths.into_iter()
+			.map(|path| {
+				Checkpoint {
+					dbus: self.dbus.clone(),
+					path
+				}
+			})
+			.collect();
+
+		Ok(checkpoints)
+	}
+}
+
+#[derive(Clone)]
+pub struct Device {
+	dbus: Dbus,
+	path: OwnedObjectPath
+}
+
+impl Device {
+	fn get<R>(&self, interface: &str, name: &str) -> Result<R, Error>
+	where
+		R: TryFrom<OwnedValue>,
+		R::Error: Into<Error>
+	{
+		self.dbus.proxy(self.path.as_ref(), interface)?
+			.get_property(name)
+	}
+
+	/// The path of the device as exposed by the udev property ID_PATH.
+	/// Note that non-UTF-8 characters are backslash escaped.
+	/// Use g_strcompress() to obtain the true (non-UTF-8) string.
+	pub fn path(&self) -> Result<String, Error> {
+		self.get(DEVICE_INTERFACE, "Path")
+	}
+
+	/// The name of the device's control (and often data) interface. Note that
+	/// non UTF-8 characters are backslash escaped, so the resulting name may
+	/// be longer then 15 characters. Use g_strcompress() to revert the
+	/// escaping.
+	pub fn interface(&self) -> Result<String, Error> {
+		self.get(DEVICE_INTERFACE, "Interface")
+	}
+
+	/// The driver handling the device. Non-UTF-8 sequences are backslash
+	/// escaped. Use g_strcompress() to revert.
+	pub fn driver(&self) -> Result<String, Error> {
+		self.get(DEVICE_INTERFACE, "Driver")
+	}
+
+	/// The current state of the device.
+	pub fn state(&self) -> Result<DeviceState, Error> {
+		self.get::<u32>(DEVICE_INTERFACE, "State")
+			.map(Into::into)
+	}
+
+	/// The general type of the network device; ie Ethernet, Wi-Fi, etc.
+	pub fn kind(&self) -> Result<DeviceKind, Error> {
+		self.get::<u32>(DEVICE_INTERFACE, "DeviceType")
+			.map(Into::into)
+	}
+
+	/// Ipv4 Configuration of the device. Only valid when the device is in
+	/// DeviceState::Activated
+	pub fn ipv4_config(&self) -> Result<Ipv4Config, Error> {
+		self.get(DEVICE_INTERFACE, "Ip4Config")
+			.map(|path| Ipv4Config {
+				dbus: self.dbus.clone(),
+				path
+			})
+	}
+
+	/// The access point name the modem is connected to. Blank if disconnected.
+	pub fn modem_apn(&self) -> Result<String, Error> {
+		self.get(MODEM_INTERFACE, "Apn")
+	}
+
+	/// The access points a Wi-Fi device currently sees, including hidden
+	/// ones.
+	pub fn access_points(&self) -> Result<Vec<AccessPoint>, Error> {
+		let paths: Vec<OwnedObjectPath> = self.dbus
+			.proxy(self.path.as_ref(), WIRELESS_INTERFACE)?
+			.call("GetAllAccessPoints", &())?;
+		let access_points = paths.into_iter()
+			.map(|path| {
+				AccessPoint {
+					dbus: self.dbus.clone(),
+					path
+				}
+			})
+			.collect();
+
+		Ok(access_points)
+	}
+
+	/// Watches the access points of a Wi-Fi device, see
+	/// [`AccessPointMonitor`].
+	pub fn watch_access_points(&self) -> Result<AccessPointMonitor, Error> {
+		AccessPointMonitor::new(self)
+	}
+}
+
+/// A Wi-Fi access point seen by a device.
+#[derive(Clone)]
+pub struct AccessPoint {
+	dbus: Dbus,
+	path: OwnedObjectPath
+}
+
+impl AccessPoint {
+	fn get<R>(&self, name: &str) -> Result<R, Error>
+	where
+		R: TryFrom<OwnedValue>,
+		R::Error: Into<Error>
+	{
+		self.dbus.proxy(self.path.as_ref(), ACCESS_POINT_INTERFACE)?
+			.get_property(name)
+	}
+
+	/// The service set identifier, bytes which are not valid UTF-8 are
+	/// replaced.
+	pub fn ssid(&self) -> Result<String, Error> {
+		self.get("Ssid")
+			.map(|b: Vec<u8>| String::from_utf8_lossy(&b).into_owned())
+	}
+
+	/// The hardware address (BSSID) of the access point.
+	pub fn hw_address(&self) -> Result<String, Error> {
+		self.get("HwAddress")
+	}
+
+	/// The radio channel frequency in MHz.
+	pub fn frequency(&self) -> Result<u32, Error> {
+		self.get("Frequency")
+	}
+
+	/// The maximum bitrate in kbit/s.
+	pub fn max_bitrate(&self) -> Result<u32, Error> {
+		self.get("MaxBitrate")
+	}
+
+	/// The current signal quality in percent.
+	pub fn strength(&self) -> Result<u8, Error> {
+		self.get("Strength")
+	}
+}
+
+impl PartialEq for AccessPoint {
+	fn eq(&self, other: &Self) -> bool {
+		self.path == other.path
+	}
+}
+
+impl Eq for AccessPoint {}
+
+impl fmt::Debug for AccessPoint {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_tuple("AccessPoint")
+			.field(&self.path.as_str())
+			.finish()
+	}
+}
+
+/// A change of the access points of a Wi-Fi device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccessPointEvent {
+	/// The device found a new access point.
+	Added(AccessPoint),
+	/// The access point disappeared, its properties can no longer be read.
+	Removed(AccessPoint),
+	/// The signal quality of an access point changed, in percent.
+	Strength(AccessPoint, u8)
+}
+
+/// Receives events when access points of a Wi-Fi device appear, disappear
+/// or their signal strength changes, so a signal meter does not need to
+/// rescan.
+pub struct AccessPointMonitor {
+	dbus: Dbus,
+	iter: MessageIterator,
+	device: OwnedObjectPath,
+	known: HashSet<OwnedObjectPath>
+}
+
+impl AccessPointMonitor {
+	fn new(device: &Device) -> Result<Self, Error> {
+		let rule = MatchRule::builder()
+			.msg_type(MessageType::Signal)
+			.sender(DBUS_NAME)?
+			.path_namespace(DBUS_PATH)?
+			.build();
+		let iter = MessageIterator::for_match_rule(
+			rule,
+			&device.dbus.conn,
+			None
+		)?;
+
+		// read the list after subscribing so no access point is missed
+		let known = device.access_points()?
+			.into_iter()
+			.map(|ap| ap.path)
+			.collect();
+
+		Ok(Self {
+			dbus: device.dbus.clone(),
+			iter,
+			device: device.path.clone(),
+			known
+		})
+	}
+
+	/// Returns the access points the device currently sees.
+	pub fn access_points(&self) -> Vec<AccessPoint> {
+		self.known.iter()
+			.map(|path| self.access_point(path.clone()))
+			.collect()
+	}
+
+	/// Waits for the next event.
+	pub fn recv(&mut self) -> Result<AccessPointEvent, Error> {
+		loop {
+			let msg = match self.iter.next() {
+				Some(msg) => msg?,
+				None => return Err(Error::Failure("connection closed".into()))
+			};
+
+			if let Some(ev) = self.parse(&msg) {
+				return Ok(ev)
+			}
+		}
+	}
+
+	fn access_point(&self, path: OwnedObjectPath) -> AccessPoint {
+		AccessPoint {
+			dbus: self.dbus.clone(),
+			path
+		}
+	}
+
+	fn parse(&mut self, msg: &Message) -> Option<AccessPointEvent> {
+		let header = msg.header();
+		let path = OwnedObjectPath::from(header.path()?.clone());
+		let interface = header.interface()?.as_str();
+		let member = header.member()?.as_str();
+		let body = msg.body();
+
+		match (interface, member) {
+			(WIRELESS_INTERFACE, "AccessPointAdded") if path == self.device => {
+				let ap: OwnedObjectPath = body.deserialize().ok()?;
+				self.known.insert(ap.clone());
+				Some(AccessPointEvent::Added(self.access_point(ap)))
+			},
+			(WIRELESS_INTERFACE, "AccessPointRemoved") if path == self.device => {
+				let ap: OwnedObjectPath = body.deserialize().ok()?;
+				self.known.remove(&ap)
+					.then(|| AccessPointEvent::Removed(self.access_point(ap)))
+			},
+			(PROPERTIES_INTERFACE, "PropertiesChanged")
+				if self.known.contains(&path) =>
+			{
+				let (iface, mut changed, _): (
+					String,
+					HashMap<String, OwnedValue>,
+					Vec<String>
+				) = body.deserialize().ok()?;
+				if iface != ACCESS_POINT_INTERFACE {
+					return None
+				}
+				let strength = u8::try_from(changed.remove("Strength")?).ok()?;
+				Some(AccessPointEvent::Strength(
+					self.access_point(path),
+					strength
+				))
+			},
+			_ => None
+		}
+	}
+}
+
+impl Iterator for AccessPointMonitor {
+	type Item = Result<AccessPointEvent, Error>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		Some(self.recv())
+	}
+}
+
+#[derive(Clone)]
+pub struct Ipv4Config {
+	dbus: Dbus,
+	path: OwnedObjectPath
+}
+
+impl Ipv4Config {
+	pub fn addresses(&self) -> Result<Vec<Ipv4Addr>, Error> {
+		let data: Vec<HashMap<String, OwnedValue>> = self.dbus
+			.proxy(self.path.as_ref(), IP4_CONFIG_INTERFACE)?
+			.get_property("AddressData")?;
+
+		let addrs = data.into_iter()
+			.filter_map(|mut d| d.remove("address"))
+			.filter_map(|addr| {
+				String::try_from(addr).ok()?
+					.parse().ok()
+			})
+			.collect();
+
+		Ok(addrs)
+	}
+}
+
+#[derive(Clone)]
+pub struct Checkpoint {
+	dbus: Dbus,
+	path: OwnedObjectPath
+}
+
+impl Checkpoint {
+	fn manager(&self) -> Result<Proxy<'static>, Error> {
+		self.dbus.proxy(
+			ObjectPath::from_static_str_unchecked(DBUS_PATH),
+			DBUS_NAME
+		)
+	}
+
+	/// The devices which are part of the checkpoint.
+	pub fn devices(&self) -> Result<Vec<Device>, Error> {
+		let paths: Vec<OwnedObjectPath> = self.dbus
+			.proxy(self.path.as_ref(), CHECKPOINT_INTERFACE)?
+			.get_property("Devices")?;
+		let devices = paths.into_iter()
+			.map(|path| {
+				Device {
+					dbus: self.dbus.clone(),
+					path
+				}
+			})
+			.collect();
+
+		Ok(devices)
+	}
+
+	/// The timeout after which the configuration is rolled back, zero if
+	/// there is no automatic rollback.
+	pub fn rollback_timeout(&self) -> Result<Duration, Error> {
+		self.dbus.proxy(self.path.as_ref(), CHECKPOINT_INTERFACE)?
+			.get_property("RollbackTimeout")
+			.map(|secs: u32| Duration::from_secs(secs.into()))
+	}
+
+	/// Restores the configuration of the checkpoint and destroys it.
+	///
+	/// Returns the result for every device path.
+	pub fn rollback(&self) -> Result<HashMap<String, RollbackResult>, Error> {
+		let results: HashMap<String, u32> = self.manager()?
+			.call("CheckpointRollback", &(&self.path,))?;
+		Ok(results.into_iter().map(|(k, v)| (k, v.into())).collect())
+	}
+
+	/// Destroys the checkpoint without restoring its configuration, which
+	/// keeps the current configuration.
+	pub fn destroy(&self) -> Result<(), Error> {
+		self.manager()?.call("CheckpointDestroy", &(&self.path,))
+	}
+
+	/// Resets the rollback timeout to `add` from now. Since: 1.12.
+	pub fn adjust_rollback_timeout(&self, add: Duration) -> Result<(), Error> {
+		self.manager()?.call(
+			"CheckpointAdjustRollbackTimeout",
+			&(&self.path, add.as_secs() as u32)
+		)
+	}
+}
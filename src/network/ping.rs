@@ -0,0 +1,265 @@
+//! Send an icmp echo request and measure the round trip time.
+//!
+//! Uses an unprivileged icmp datagram socket which requires the group of
+//! the process to be in `net.ipv4.ping_group_range`, if that is not the case
+//! a raw socket is used which requires `CAP_NET_RAW`.
+
+use std::{io, mem};
+use std::fs::File;
+use std::net::{IpAddr, ToSocketAddrs};
+use std::time::{Duration, Instant};
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::os::raw::c_int;
+
+const ICMP_ECHO_REQUEST: u8 = 8;
+const ICMP_ECHO_REPLY: u8 = 0;
+const ICMPV6_ECHO_REQUEST: u8 = 128;
+const ICMPV6_ECHO_REPLY: u8 = 129;
+const PAYLOAD: &[u8] = b"linux-info ping";
+
+/// Sends an icmp echo request to a host and returns the round trip time.
+///
+/// The host can be a name or an ip address, if a name resolves to multiple
+/// addresses the first one is used. Returns an error of the kind
+/// `TimedOut` if no reply arrives within the timeout.
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use linux_info::network::ping;
+///
+/// let rtt = ping("1.1.1.1", Duration::from_secs(1)).unwrap();
+/// println!("rtt {:?}", rtt);
+/// ```
+pub fn ping(host: &str, timeout: Duration) -> io::Result<Duration> {
+	let addr = (host, 0).to_socket_addrs()?
+		.next()
+		.ok_or_else(|| io::Error::new(
+			io::ErrorKind::NotFound,
+			"host has no address"
+		))?;
+	ping_addr(addr.ip(), timeout)
+}
+
+/// Sends an icmp echo request to an address and returns the round trip
+/// time.
+///
+/// See [`ping`].
+pub fn ping_addr(ip: IpAddr, timeout: Duration) -> io::Result<Duration> {
+	let (socket, raw) = IcmpSocket::open(ip)?;
+	// datagram sockets replace the identifier with the local port
+	let ident = (std::process::id() & 0xffff) as u16;
+	let seq = 1;
+
+	socket.connect(ip)?;
+	let start = Instant::now();
+	socket.send(&echo_request(ip.is_ipv4(), ident, seq))?;
+
+	let mut buf = [0u8; 1024];
+	loop {
+		let left = timeout.checked_sub(start.elapsed())
+			.ok_or_else(timed_out)?;
+		socket.wait_readable(left)?;
+
+		let len = socket.recv(&mut buf)?;
+		let mut packet = &buf[..len];
+		// raw ipv4 sockets include the ip header
+		if raw && ip.is_ipv4() {
+			let header = packet.first().map(|b| (b & 0x0f) as usize * 4)
+				.unwrap_or(0);
+			packet = packet.get(header..).unwrap_or(&[]);
+		}
+
+		if is_echo_reply(packet, ip.is_ipv4(), (!raw).then(|| ident), seq) {
+			return Ok(start.elapsed())
+		}
+	}
+}
+
+fn timed_out() -> io::Error {
+	io::Error::new(io::ErrorKind::TimedOut, "no echo reply received")
+}
+
+struct IcmpSocket {
+	file: File
+}
+
+impl IcmpSocket {
+	/// Returns the socket and true if it is a raw socket.
+	fn open(ip: IpAddr) -> io::Result<(Self, bool)> {
+		let (domain, protocol) = match ip {
+			IpAddr::V4(_) => (libc::AF_INET, libc::IPPROTO_ICMP),
+			IpAddr::V6(_) => (libc::AF_INET6, libc::IPPROTO_ICMPV6)
+		};
+
+		match Self::new(domain, libc::SOCK_DGRAM, protocol) {
+			Ok(s) => Ok((s, false)),
+			Err(e) if matches!(
+				e.raw_os_error(),
+				Some(libc::EACCES) | Some(libc::EPERM)
+			) => {
+				Self::new(domain, libc::SOCK_RAW, protocol).map(|s| (s, true))
+			},
+			Err(e) => Err(e)
+		}
+	}
+
+	fn new(domain: c_int, ty: c_int, protocol: c_int) -> io::Result<Self> {
+		let fd = unsafe {
+			libc::socket(domain, ty | libc::SOCK_CLOEXEC, protocol)
+		};
+		if fd < 0 {
+			return Err(io::Error::last_os_error())
+		}
+		// the file closes the socket on drop
+		Ok(Self { file: unsafe { File::from_raw_fd(fd) } })
+	}
+
+	fn connect(&self, ip: IpAddr) -> io::Result<()> {
+		let r = match ip {
+			IpAddr::V4(ip) => unsafe {
+				let mut addr: libc::sockaddr_in = mem::zeroed();
+				addr.sin_family = libc::AF_INET as _;
+				addr.sin_addr.s_addr = u32::from_ne_bytes(ip.octets());
+				libc::connect(
+					self.file.as_raw_fd(),
+					&addr as *const _ as *const libc::sockaddr,
+					mem::size_of_val(&addr) as _
+				)
+			},
+			IpAddr::V6(ip) => unsafe {
+				let mut addr: libc::sockaddr_in6 = mem::zeroed();
+				addr.sin6_family = libc::AF_INET6 as _;
+				addr.sin6_addr.s6_addr = ip.octets();
+				libc::connect(
+					self.file.as_raw_fd(),
+					&addr as *const _ as *const libc::sockaddr,
+					mem::size_of_val(&addr) as _
+				)
+			}
+		};
+		match r {
+			-1 => Err(io::Error::last_os_error()),
+			_ => Ok(())
+		}
+	}
+
+	fn send(&self, packet: &[u8]) -> io::Result<()> {
+		let r = unsafe {
+			libc::send(
+				self.file.as_raw_fd(),
+				packet.as_ptr() as *const _,
+				packet.len(),
+				0
+			)
+		};
+		match r {
+			-1 => Err(io::Error::last_os_error()),
+			_ => Ok(())
+		}
+	}
+
+	fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+		let r = unsafe {
+			libc::recv(
+				self.file.as_raw_fd(),
+				buf.as_mut_ptr() as *mut _,
+				buf.len(),
+				0
+			)
+		};
+		match r {
+			-1 => Err(io::Error::last_os_error()),
+			n => Ok(n as usize)
+		}
+	}
+
+	fn wait_readable(&self, timeout: Duration) -> io::Result<()> {
+		let mut pfd = libc::pollfd {
+			fd: self.file.as_raw_fd(),
+			events: libc::POLLIN,
+			revents: 0
+		};
+		let timeout = timeout.as_millis().min(c_int::MAX as u128);
+
+		loop {
+			let r = unsafe { libc::poll(&mut pfd, 1, timeout as c_int) };
+			match r {
+				-1 => {
+					let e = io::Error::last_os_error();
+					if e.kind() != io::ErrorKind::Interrupted {
+						return Err(e)
+					}
+				},
+				0 => return Err(timed_out()),
+				_ => return Ok(())
+			}
+		}
+	}
+}
+
+/// Builds an echo request, the kernel calculates the checksum for icmpv6.
+fn echo_request(v4: bool, ident: u16, seq: u16) -> Vec<u8> {
+	let ty = if v4 { ICMP_ECHO_REQUEST } else { ICMPV6_ECHO_REQUEST };
+	let mut packet = vec![ty, 0, 0, 0];
+	packet.extend_from_slice(&ident.to_be_bytes());
+	packet.extend_from_slice(&seq.to_be_bytes());
+	packet.extend_from_slice(PAYLOAD);
+
+	if v4 {
+		let sum = checksum(&packet);
+		packet[2..4].copy_from_slice(&sum.to_be_bytes());
+	}
+	packet
+}
+
+/// Returns true if the packet is the reply to our request, the identifier
+/// is only checked if it is known (raw sockets).
+fn is_echo_reply(
+	packet: &[u8],
+	v4: bool,
+	ident: Option<u16>,
+	seq: u16
+) -> bool {
+	let ty = if v4 { ICMP_ECHO_REPLY } else { ICMPV6_ECHO_REPLY };
+	if packet.len() < 8 || packet[0] != ty {
+		return false
+	}
+
+	let ident_matches = ident.map(|i| packet[4..6] == i.to_be_bytes())
+		.unwrap_or(true);
+	ident_matches && packet[6..8] == seq.to_be_bytes()
+}
+
+/// The internet checksum (rfc 1071).
+fn checksum(data: &[u8]) -> u16 {
+	let mut sum = data.chunks(2)
+		.map(|c| u32::from(u16::from_be_bytes([c[0], *c.get(1).unwrap_or(&0)])))
+		.sum::<u32>();
+	while sum >> 16 != 0 {
+		sum = (sum & 0xffff) + (sum >> 16);
+	}
+	!(sum as u16)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn echo_packet() {
+		let packet = echo_request(true, 0x1234, 1);
+		assert_eq!(&packet[..2], [8, 0]);
+		assert_eq!(&packet[4..8], [0x12, 0x34, 0, 1]);
+		// a packet including its checksum sums up to zero
+		assert_eq!(checksum(&packet), 0);
+
+		let mut reply = packet.clone();
+		reply[0] = ICMP_ECHO_REPLY;
+		assert!(is_echo_reply(&reply, true, Some(0x1234), 1));
+		assert!(is_echo_reply(&reply, true, None, 1));
+		assert!(!is_echo_reply(&reply, true, Some(0x4321), 1));
+		assert!(!is_echo_reply(&reply, true, None, 2));
+		assert!(!is_echo_reply(&packet, true, None, 1));
+		assert!(!is_echo_reply(&reply, false, None, 1));
+	}
+}
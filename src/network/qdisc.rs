@@ -0,0 +1,200 @@
+//! Get the queueing disciplines (qdiscs) of every interface and their drop
+//! counters using rtnetlink, like `tc -s qdisc` does.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::network::qdisc::qdiscs;
+//!
+//! for q in qdiscs().unwrap() {
+//!     println!(
+//!         "{} {} dropped {} overlimits {} backlog {}",
+//!         q.interface, q.kind, q.drops, q.overlimits, q.backlog
+//!     );
+//! }
+//! ```
+
+use super::interfaces::Interface;
+use super::netlink::{
+	Netlink, NETLINK_ROUTE, NLM_F_DUMP, attributes, c_string, u32_attr,
+	u64_attr
+};
+
+use std::io;
+
+// see linux/rtnetlink.h
+const RTM_NEWQDISC: u16 = 36;
+const RTM_GETQDISC: u16 = 38;
+/// The size of struct tcmsg.
+const TCMSG_LEN: usize = 20;
+const TC_H_ROOT: u32 = 0xffff_ffff;
+
+const TCA_KIND: u16 = 1;
+const TCA_STATS2: u16 = 7;
+
+// see linux/gen_stats.h
+const TCA_STATS_BASIC: u16 = 1;
+const TCA_STATS_QUEUE: u16 = 3;
+const TCA_STATS_PKT64: u16 = 8;
+
+/// A queueing discipline with its counters.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct Qdisc {
+	/// The index of the interface.
+	pub index: u32,
+	pub interface: String,
+	/// The type of the qdisc for example `fq_codel` or `mq`.
+	pub kind: String,
+	pub handle: u32,
+	/// The handle of the parent, `0xffffffff` for the root qdisc.
+	pub parent: u32,
+	pub bytes: u64,
+	pub packets: u64,
+	/// The packets dropped, because the queue was full or by the active
+	/// queue management.
+	pub drops: u32,
+	pub requeues: u32,
+	/// How often the qdisc was over its limit, for example a shaper delaying
+	/// packets.
+	pub overlimits: u32,
+	/// The packets currently in the queue.
+	pub qlen: u32,
+	/// The bytes currently in the queue.
+	pub backlog: u32
+}
+
+impl Qdisc {
+	/// Parses struct tcmsg followed by attributes.
+	fn parse(ty: u16, data: &[u8]) -> Option<Self> {
+		if ty != RTM_NEWQDISC || data.len() < TCMSG_LEN {
+			return None
+		}
+		let u32_at = |i: usize| u32_attr(&data[i..]);
+
+		let mut q = Self {
+			index: u32_at(4)?,
+			handle: u32_at(8)?,
+			parent: u32_at(12)?,
+			..Default::default()
+		};
+		for (ty, value) in attributes(&data[TCMSG_LEN..]) {
+			match ty {
+				TCA_KIND => q.kind = c_string(value),
+				TCA_STATS2 => q.parse_stats(value),
+				_ => {}
+			}
+		}
+
+		Some(q)
+	}
+
+	fn parse_stats(&mut self, data: &[u8]) {
+		for (ty, value) in attributes(data) {
+			match ty {
+				// struct gnet_stats_basic
+				TCA_STATS_BASIC => {
+					self.bytes = u64_attr(value).unwrap_or(0);
+					// only set if the 64 bit counter is missing
+					if self.packets == 0 {
+						self.packets = value.get(8..)
+							.and_then(u32_attr)
+							.unwrap_or(0) as u64;
+					}
+				},
+				TCA_STATS_PKT64 => self.packets = u64_attr(value).unwrap_or(0),
+				// struct gnet_stats_queue
+				TCA_STATS_QUEUE => {
+					let field = |i: usize| {
+						value.get(i * 4..).and_then(u32_attr).unwrap_or(0)
+					};
+					self.qlen = field(0);
+					self.backlog = field(1);
+					self.drops = field(2);
+					self.requeues = field(3);
+					self.overlimits = field(4);
+				},
+				_ => {}
+			}
+		}
+	}
+
+	/// Returns true if this qdisc is attached directly to the interface,
+	/// for multiqueue devices (`mq`) its counters are the sum of all queues.
+	pub fn is_root(&self) -> bool {
+		self.parent == TC_H_ROOT
+	}
+}
+
+/// Returns the qdiscs of every interface.
+pub fn qdiscs() -> io::Result<Vec<Qdisc>> {
+	let mut netlink = Netlink::open(NETLINK_ROUTE)?;
+	let mut list = vec![];
+	netlink.request(RTM_GETQDISC, NLM_F_DUMP, &[0; TCMSG_LEN], |ty, data| {
+		list.extend(Qdisc::parse(ty, data));
+	})?;
+
+	let interfaces = Interface::all()?;
+	for q in &mut list {
+		q.interface = interfaces.iter()
+			.find(|i| i.index().ok() == Some(q.index))
+			.and_then(Interface::name)
+			.unwrap_or_default()
+			.into();
+	}
+	Ok(list)
+}
+
+/// Returns the qdiscs of an interface, the root qdisc is first.
+pub fn by_interface(interface: &Interface) -> io::Result<Vec<Qdisc>> {
+	let index = interface.index()?;
+	let mut list = qdiscs()?;
+	list.retain(|q| q.index == index);
+	list.sort_by_key(|q| !q.is_root());
+	Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::network::netlink::push_attr;
+
+	#[test]
+	fn qdisc_message() {
+		let mut msg = vec![0u8; 4];
+		msg.extend_from_slice(&2u32.to_ne_bytes());
+		msg.extend_from_slice(&0x8001_0000u32.to_ne_bytes());
+		msg.extend_from_slice(&TC_H_ROOT.to_ne_bytes());
+		msg.extend_from_slice(&2u32.to_ne_bytes());
+		push_attr(&mut msg, TCA_KIND, b"fq_codel\0");
+
+		let mut basic = 123456u64.to_ne_bytes().to_vec();
+		basic.extend_from_slice(&789u32.to_ne_bytes());
+		let mut queue = vec![];
+		for v in [1u32, 1514, 42, 3, 7] {
+			queue.extend_from_slice(&v.to_ne_bytes());
+		}
+		let mut stats = vec![];
+		push_attr(&mut stats, TCA_STATS_BASIC, &basic);
+		push_attr(&mut stats, TCA_STATS_QUEUE, &queue);
+		push_attr(&mut msg, TCA_STATS2, &stats);
+
+		let q = Qdisc::parse(RTM_NEWQDISC, &msg).unwrap();
+		assert_eq!(q.index, 2);
+		assert_eq!(q.kind, "fq_codel");
+		assert_eq!(q.handle, 0x8001_0000);
+		assert!(q.is_root());
+		assert_eq!(q.bytes, 123456);
+		assert_eq!(q.packets, 789);
+		assert_eq!(q.qlen, 1);
+		assert_eq!(q.backlog, 1514);
+		assert_eq!(q.drops, 42);
+		assert_eq!(q.requeues, 3);
+		assert_eq!(q.overlimits, 7);
+
+		assert!(Qdisc::parse(RTM_NEWQDISC, &msg[..12]).is_none());
+	}
+}
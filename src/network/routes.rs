@@ -0,0 +1,311 @@
+//! Read the kernel routing table from `/proc/net/route` and
+//! `/proc/net/ipv6_route`.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::network::{default_interface, primary_ip};
+//!
+//! let iface = default_interface().unwrap();
+//! println!("{:?} {:?}", iface.as_ref().and_then(|i| i.name()), primary_ip());
+//! ```
+
+use super::interfaces::Interface;
+use crate::util::{read_to_string, read_to_string_mut, interface_addresses};
+#[cfg(feature = "async")]
+use crate::util::{read_to_string_async, read_to_string_mut_async};
+
+use std::io;
+use std::path::Path;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+
+// see linux/route.h
+const RTF_UP: u32 = 0x0001;
+const RTF_GATEWAY: u32 = 0x0002;
+const RTF_REJECT: u32 = 0x0200;
+
+/// Read the ipv4 or ipv6 routing table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Routes {
+	ipv6: bool,
+	raw: String
+}
+
+impl Routes {
+
+	fn path(ipv6: bool) -> &'static Path {
+		Path::new(match ipv6 {
+			false => "/proc/net/route",
+			true => "/proc/net/ipv6_route"
+		})
+	}
+
+	#[cfg(test)]
+	fn from_string(ipv6: bool, raw: String) -> Self {
+		Self {ipv6, raw}
+	}
+
+	/// Read the ipv4 routing table from /proc/net/route.
+	pub fn read() -> io::Result<Self> {
+		Ok(Self {
+			ipv6: false,
+			raw: read_to_string(Self::path(false))?
+		})
+	}
+
+	/// Read the ipv6 routing table from /proc/net/ipv6_route.
+	pub fn read_ipv6() -> io::Result<Self> {
+		Ok(Self {
+			ipv6: true,
+			raw: read_to_string(Self::path(true))?
+		})
+	}
+
+	/// Reloads information without allocating.
+	pub fn reload(&mut self) -> io::Result<()> {
+		read_to_string_mut(Self::path(self.ipv6), &mut self.raw)
+	}
+
+	/// Reads the ipv4 table without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		Ok(Self {
+			ipv6: false,
+			raw: read_to_string_async(Self::path(false)).await?
+		})
+	}
+
+	/// Reads the ipv6 table without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_ipv6_async() -> io::Result<Self> {
+		Ok(Self {
+			ipv6: true,
+			raw: read_to_string_async(Self::path(true)).await?
+		})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(Self::path(self.ipv6), &mut self.raw).await
+	}
+
+	/// Returns every route, lines which cannot be parsed are skipped.
+	pub fn entries(&self) -> impl Iterator<Item=Route> + '_ {
+		let ipv6 = self.ipv6;
+		self.raw.lines()
+			// the ipv4 table has a header
+			.skip(if ipv6 { 0 } else { 1 })
+			.filter_map(move |l| match ipv6 {
+				false => Route::from_v4_line(l),
+				true => Route::from_v6_line(l)
+			})
+	}
+
+	/// Returns the usable default route with the lowest metric.
+	pub fn default_route(&self) -> Option<Route> {
+		self.entries()
+			.filter(|r| r.is_default() && r.is_usable())
+			.min_by_key(|r| r.metric)
+	}
+}
+
+/// A route from the routing table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct Route {
+	/// The interface the traffic is sent through.
+	pub interface: String,
+	pub destination: IpAddr,
+	/// The length of the destination prefix, zero for the default route.
+	pub prefix_len: u8,
+	/// The next hop, `None` if the destination is directly reachable.
+	pub gateway: Option<IpAddr>,
+	pub metric: u32,
+	/// The `RTF_*` flags.
+	pub flags: u32
+}
+
+impl Route {
+	fn from_v4_line(line: &str) -> Option<Self> {
+		let mut iter = line.split_whitespace();
+		let interface = iter.next()?.to_string();
+		let destination = parse_v4(iter.next()?)?;
+		let gateway = parse_v4(iter.next()?)?;
+		let flags = u32::from_str_radix(iter.next()?, 16).ok()?;
+		let mut iter = iter.skip(2);
+		let metric = iter.next()?.parse().ok()?;
+		let mask = parse_v4(iter.next()?)?;
+
+		Some(Self {
+			interface,
+			destination: destination.into(),
+			prefix_len: u32::from(mask).count_ones() as u8,
+			gateway: (flags & RTF_GATEWAY != 0).then(|| gateway.into()),
+			metric,
+			flags
+		})
+	}
+
+	fn from_v6_line(line: &str) -> Option<Self> {
+		let mut iter = line.split_whitespace();
+		let destination = parse_v6(iter.next()?)?;
+		let prefix_len = u8::from_str_radix(iter.next()?, 16).ok()?;
+		// source and source prefix
+		let mut iter = iter.skip(2);
+		let gateway = parse_v6(iter.next()?)?;
+		let metric = u32::from_str_radix(iter.next()?, 16).ok()?;
+		let mut iter = iter.skip(2);
+		let flags = u32::from_str_radix(iter.next()?, 16).ok()?;
+
+		Some(Self {
+			interface: iter.next()?.to_string(),
+			destination: destination.into(),
+			prefix_len,
+			gateway: (flags & RTF_GATEWAY != 0).then(|| gateway.into()),
+			metric,
+			flags
+		})
+	}
+
+	/// Returns true if this route matches every destination.
+	pub fn is_default(&self) -> bool {
+		self.prefix_len == 0
+	}
+
+	/// Returns true if the route is up and does not reject traffic.
+	pub fn is_usable(&self) -> bool {
+		self.flags & RTF_UP != 0 && self.flags & RTF_REJECT == 0
+	}
+}
+
+/// Parses an address like `010200C0`, printed in host byte order.
+fn parse_v4(s: &str) -> Option<Ipv4Addr> {
+	let n = u32::from_str_radix(s, 16).ok()?;
+	Some(n.to_ne_bytes().into())
+}
+
+/// Parses an address like `fe800000000000000000000000000001`.
+fn parse_v6(s: &str) -> Option<Ipv6Addr> {
+	u128::from_str_radix(s, 16).ok().map(Ipv6Addr::from)
+}
+
+/// Returns the default route, ipv4 is preferred over ipv6.
+fn default_route() -> io::Result<Option<Route>> {
+	let route = Routes::read()?.default_route();
+	if route.is_some() {
+		return Ok(route)
+	}
+
+	match Routes::read_ipv6() {
+		Ok(r) => Ok(r.default_route()),
+		// ipv6 is disabled
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+		Err(e) => Err(e)
+	}
+}
+
+/// Returns the interface outbound traffic is sent through, which is the
+/// interface of the default route with the lowest metric.
+///
+/// Returns `None` if there is no default route.
+pub fn default_interface() -> io::Result<Option<Interface>> {
+	Ok(default_route()?.and_then(|r| Interface::by_name(&r.interface)))
+}
+
+/// Returns the local address outbound traffic uses.
+///
+/// Asks the kernel which source address it would select for a destination
+/// routed through the default route (no packets are sent), if that fails
+/// the first address of the default interface is returned. Behind a nat
+/// this is not the public address.
+///
+/// Returns `None` if there is no default route.
+pub fn primary_ip() -> io::Result<Option<IpAddr>> {
+	let route = match default_route()? {
+		Some(r) => r,
+		None => return Ok(None)
+	};
+
+	// documentation addresses which are never local
+	let target: IpAddr = match route.destination {
+		IpAddr::V4(_) => Ipv4Addr::new(192, 0, 2, 1).into(),
+		IpAddr::V6(_) => Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1).into()
+	};
+	if let Some(ip) = source_addr(target) {
+		return Ok(Some(ip))
+	}
+
+	Ok(interface_addresses()?
+		.into_iter()
+		.find(|(name, ip)| {
+			*name == route.interface &&
+			ip.is_ipv4() == target.is_ipv4()
+		})
+		.map(|(_, ip)| ip))
+}
+
+/// Returns the source address the kernel would use to reach the target.
+fn source_addr(target: IpAddr) -> Option<IpAddr> {
+	let bind: SocketAddr = match target {
+		IpAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+		IpAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into()
+	};
+	let socket = UdpSocket::bind(bind).ok()?;
+	// connecting an udp socket only selects the route
+	socket.connect((target, 53)).ok()?;
+	let ip = socket.local_addr().ok()?.ip();
+	(!ip.is_unspecified()).then(|| ip)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn routes() {
+		let routes = Routes::from_string(false, "\
+Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT
+wlan0\t00000000\t0101A8C0\t0003\t0\t0\t600\t00000000\t0\t0\t0
+eth0\t00000000\t010200C0\t0003\t0\t0\t100\t00000000\t0\t0\t0
+eth0\t000200C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0
+".into());
+		let list: Vec<_> = routes.entries().collect();
+		assert_eq!(list.len(), 3);
+		assert_eq!(list[2].destination, "192.0.2.0".parse::<IpAddr>().unwrap());
+		assert_eq!(list[2].prefix_len, 24);
+		assert_eq!(list[2].gateway, None);
+		let default = routes.default_route().unwrap();
+		assert_eq!(default.interface, "eth0");
+		assert_eq!(default.gateway, Some("192.0.2.1".parse().unwrap()));
+		assert_eq!(default.metric, 100);
+	}
+
+	#[test]
+	fn ipv6_routes() {
+		let routes = Routes::from_string(true, "\
+fd000000000000000000000000000000 40 00000000000000000000000000000000 00 00000000000000000000000000000000 00000100 00000001 00000000 00000001     eth0
+00000000000000000000000000000000 00 00000000000000000000000000000000 00 fd000000000000000000000000000001 00000400 00000001 00000000 00000003     eth0
+00000000000000000000000000000001 80 00000000000000000000000000000000 00 00000000000000000000000000000000 00000000 00000003 00000000 80200001       lo
+00000000000000000000000000000000 00 00000000000000000000000000000000 00 00000000000000000000000000000000 ffffffff 00000001 00000000 00200200       lo
+".into());
+		let list: Vec<_> = routes.entries().collect();
+		assert_eq!(list.len(), 4);
+		assert_eq!(list[0].destination, "fd00::".parse::<IpAddr>().unwrap());
+		assert_eq!(list[0].prefix_len, 64);
+		assert_eq!(list[0].metric, 256);
+		assert_eq!(list[2].prefix_len, 128);
+		// the unreachable default route is skipped
+		assert!(!list[3].is_usable());
+		let default = routes.default_route().unwrap();
+		assert_eq!(default.interface, "eth0");
+		assert_eq!(default.gateway, Some("fd00::1".parse().unwrap()));
+		assert_eq!(default.metric, 1024);
+	}
+}
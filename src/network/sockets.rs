@@ -0,0 +1,371 @@
+//! Read the tcp and udp socket tables from `/proc/net`.
+//!
+//! Use [`Process::sockets`](crate::process::Process::sockets) to get the
+//! sockets of a process.
+//!
+//! Use [`inet_diag`](super::inet_diag) to get the `TCP_INFO` (rtt, congestion
+//! window) of tcp sockets.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::network::sockets::{Sockets, Protocol, TcpState};
+//!
+//! let tcp = Sockets::read(Protocol::Tcp).unwrap();
+//! for socket in tcp.entries().filter(|s| s.state == TcpState::Listen) {
+//!     println!("listening on {}", socket.local);
+//! }
+//! ```
+
+use crate::util::{read_to_string, read_to_string_mut};
+use crate::process::{Process, processes_by_socket};
+#[cfg(feature = "async")]
+use crate::util::{read_to_string_async, read_to_string_mut_async};
+
+use std::io;
+use std::path::Path;
+use std::net::{SocketAddr, IpAddr, Ipv4Addr, Ipv6Addr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", rename_all = "snake_case")
+)]
+pub enum Protocol {
+	Tcp,
+	Tcp6,
+	Udp,
+	Udp6
+}
+
+impl Protocol {
+	pub const ALL: [Self; 4] = [Self::Tcp, Self::Tcp6, Self::Udp, Self::Udp6];
+
+	fn path(&self) -> &'static Path {
+		Path::new(match self {
+			Self::Tcp => "/proc/net/tcp",
+			Self::Tcp6 => "/proc/net/tcp6",
+			Self::Udp => "/proc/net/udp",
+			Self::Udp6 => "/proc/net/udp6"
+		})
+	}
+
+	pub fn is_tcp(&self) -> bool {
+		matches!(self, Self::Tcp | Self::Tcp6)
+	}
+}
+
+/// The state of a socket, udp sockets use `Established` if they are
+/// connected and `Close` otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", rename_all = "snake_case")
+)]
+pub enum TcpState {
+	Established,
+	SynSent,
+	SynRecv,
+	FinWait1,
+	FinWait2,
+	TimeWait,
+	Close,
+	CloseWait,
+	LastAck,
+	Listen,
+	Closing,
+	NewSynRecv,
+	Unknown(u8)
+}
+
+impl From<u8> for TcpState {
+	fn from(n: u8) -> Self {
+		match n {
+			1 => Self::Established,
+			2 => Self::SynSent,
+			3 => Self::SynRecv,
+			4 => Self::FinWait1,
+			5 => Self::FinWait2,
+			6 => Self::TimeWait,
+			7 => Self::Close,
+			8 => Self::CloseWait,
+			9 => Self::LastAck,
+			10 => Self::Listen,
+			11 => Self::Closing,
+			12 => Self::NewSynRecv,
+			n => Self::Unknown(n)
+		}
+	}
+}
+
+/// Read a socket table for example /proc/net/tcp.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sockets {
+	protocol: Protocol,
+	raw: String
+}
+
+impl Sockets {
+
+	#[cfg(test)]
+	fn from_string(protocol: Protocol, raw: String) -> Self {
+		Self {protocol, raw}
+	}
+
+	/// Read the socket table of a protocol.
+	pub fn read(protocol: Protocol) -> io::Result<Self> {
+		Ok(Self {
+			protocol,
+			raw: read_to_string(protocol.path())?
+		})
+	}
+
+	/// Reads every socket table, tables which don't exist (for example if
+	/// ipv6 is disabled) are skipped.
+	pub fn read_all() -> io::Result<Vec<Self>> {
+		let mut list = vec![];
+		for protocol in Protocol::ALL {
+			match Self::read(protocol) {
+				Ok(s) => list.push(s),
+				Err(e) if e.kind() == io::ErrorKind::NotFound => {},
+				Err(e) => return Err(e)
+			}
+		}
+		Ok(list)
+	}
+
+	/// Reloads information without allocating.
+	pub fn reload(&mut self) -> io::Result<()> {
+		read_to_string_mut(self.protocol.path(), &mut self.raw)
+	}
+
+	/// Reads the file without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async(protocol: Protocol) -> io::Result<Self> {
+		Ok(Self {
+			protocol,
+			raw: read_to_string_async(protocol.path()).await?
+		})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(self.protocol.path(), &mut self.raw).await
+	}
+
+	pub fn protocol(&self) -> Protocol {
+		self.protocol
+	}
+
+	/// Returns every socket, lines which cannot be parsed are skipped.
+	pub fn entries(&self) -> impl Iterator<Item=Socket> + '_ {
+		self.raw.lines()
+			// skip the header
+			.skip(1)
+			.filter_map(move |l| Socket::from_line(self.protocol, l))
+	}
+
+	/// Returns the socket with the given inode.
+	pub fn by_inode(&self, inode: u64) -> Option<Socket> {
+		self.entries().find(|s| s.inode == inode)
+	}
+}
+
+/// A socket from the socket table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct Socket {
+	pub protocol: Protocol,
+	pub local: SocketAddr,
+	/// The peer, `0.0.0.0:0` or `[::]:0` if not connected.
+	pub remote: SocketAddr,
+	pub state: TcpState,
+	/// Bytes in the send queue.
+	pub tx_queue: u64,
+	/// Bytes in the receive queue.
+	pub rx_queue: u64,
+	/// The user which owns the socket.
+	pub uid: u32,
+	/// The inode which links the socket to a file descriptor of a process,
+	/// zero for sockets in `TimeWait`.
+	pub inode: u64
+}
+
+impl Socket {
+	fn from_line(protocol: Protocol, line: &str) -> Option<Self> {
+		let mut iter = line.split_whitespace();
+		// slot
+		iter.next()?;
+		let local = parse_addr(iter.next()?)?;
+		let remote = parse_addr(iter.next()?)?;
+		let state = u8::from_str_radix(iter.next()?, 16).ok()?;
+		let (tx, rx) = iter.next()?.split_once(':')?;
+		let mut iter = iter.skip(2);
+
+		Some(Self {
+			protocol,
+			local,
+			remote,
+			state: state.into(),
+			tx_queue: u64::from_str_radix(tx, 16).ok()?,
+			rx_queue: u64::from_str_radix(rx, 16).ok()?,
+			uid: iter.next()?.parse().ok()?,
+			inode: iter.nth(1)?.parse().ok()?
+		})
+	}
+
+	/// Returns true if this is a listening tcp socket or an unconnected udp
+	/// socket.
+	pub fn is_listening(&self) -> bool {
+		match self.protocol.is_tcp() {
+			true => self.state == TcpState::Listen,
+			false => self.state == TcpState::Close && self.remote.port() == 0
+		}
+	}
+}
+
+/// Parses an address like `0100007F:0035`.
+///
+/// The kernel prints the address as 32 bit words in host byte order.
+fn parse_addr(s: &str) -> Option<SocketAddr> {
+	let (ip, port) = s.split_once(':')?;
+	let port = u16::from_str_radix(port, 16).ok()?;
+
+	let mut bytes = [0u8; 16];
+	let words = ip.len() / 8;
+	if !matches!(words, 1 | 4) || ip.len() % 8 != 0 {
+		return None
+	}
+	for i in 0..words {
+		let word = u32::from_str_radix(ip.get(i * 8..i * 8 + 8)?, 16).ok()?;
+		bytes[i * 4..i * 4 + 4].copy_from_slice(&word.to_ne_bytes());
+	}
+
+	let ip = match words {
+		1 => IpAddr::V4(Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3])),
+		_ => IpAddr::V6(Ipv6Addr::from(bytes))
+	};
+	Some(SocketAddr::new(ip, port))
+}
+
+/// A tcp or udp socket which accepts connections or datagrams.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct ListeningSocket {
+	pub protocol: Protocol,
+	/// The bind address, `0.0.0.0` or `::` means every address.
+	pub address: SocketAddr,
+	/// The user which owns the socket.
+	pub uid: u32,
+	/// The process which has the socket open, `None` if it can't be read
+	/// (without root usually the processes of other users).
+	pub pid: Option<u32>,
+	/// The name of the process.
+	pub process: Option<String>
+}
+
+/// Returns every listening tcp and unconnected udp socket with the process
+/// which owns it, like `ss -ltunp`.
+///
+/// A socket shared between multiple processes (for example forked
+/// workers) is returned once per process.
+///
+/// ```no_run
+/// use linux_info::network::sockets::listening;
+///
+/// for socket in listening().unwrap() {
+///     println!(
+///         "{:?} {} {}",
+///         socket.protocol,
+///         socket.address,
+///         socket.process.as_deref().unwrap_or("-")
+///     );
+/// }
+/// ```
+pub fn listening() -> io::Result<Vec<ListeningSocket>> {
+	let by_inode = processes_by_socket()?;
+
+	let mut list = vec![];
+	for table in Sockets::read_all()? {
+		for socket in table.entries().filter(Socket::is_listening) {
+			let processes = by_inode.get(&socket.inode)
+				.map(|p| p.as_slice())
+				.unwrap_or(&[]);
+			let listening = |process: Option<&Process>| ListeningSocket {
+				protocol: socket.protocol,
+				address: socket.local,
+				uid: socket.uid,
+				pid: process.map(Process::pid),
+				process: process.and_then(|p| p.name().ok())
+			};
+
+			if processes.is_empty() {
+				list.push(listening(None));
+			}
+			list.extend(processes.iter().map(|p| listening(Some(p))));
+		}
+	}
+
+	list.sort_by_key(|s| (s.address.port(), s.protocol.is_tcp()));
+	Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn word(ip: [u8; 4]) -> String {
+		format!("{:08X}", u32::from_ne_bytes(ip))
+	}
+
+	#[test]
+	fn tcp() {
+		let raw = format!("\
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: {}:0035 00000000:0000 0A 00000000:00000000 00:00000000 00000000   101        0 20370 1 0000000000000000 100 0 0 10 0
+   1: {}:A2C4 {}:01BB 01 00000010:00000002 02:00000AF4 00000000  1000        0 771236 2 0000000000000000 20 4 30 10 -1
+", word([127, 0, 0, 53]), word([192, 168, 1, 10]), word([140, 82, 121, 4]));
+		let tcp = Sockets::from_string(Protocol::Tcp, raw);
+		let list: Vec<_> = tcp.entries().collect();
+		assert_eq!(list.len(), 2);
+		assert_eq!(list[0].local, "127.0.0.53:53".parse().unwrap());
+		assert_eq!(list[0].state, TcpState::Listen);
+		assert_eq!(list[0].uid, 101);
+		assert_eq!(list[0].inode, 20370);
+		assert_eq!(list[1].remote, "140.82.121.4:443".parse().unwrap());
+		assert_eq!(list[1].state, TcpState::Established);
+		assert_eq!(list[1].tx_queue, 16);
+		assert_eq!(list[1].rx_queue, 2);
+		assert_eq!(tcp.by_inode(771236).unwrap().local.port(), 41668);
+		assert!(list[0].is_listening());
+		assert!(!list[1].is_listening());
+	}
+
+	#[test]
+	fn tcp6() {
+		let ip = Ipv6Addr::LOCALHOST.octets();
+		let addr: String = ip.chunks(4)
+			.map(|c| word([c[0], c[1], c[2], c[3]]))
+			.collect();
+		let raw = format!("\
+  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: {}:1F90 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 55012 1 0000000000000000 100 0 0 10 0
+", addr);
+		let tcp = Sockets::from_string(Protocol::Tcp6, raw);
+		let socket = tcp.entries().next().unwrap();
+		assert_eq!(socket.local, "[::1]:8080".parse().unwrap());
+		assert_eq!(socket.remote, "[::]:0".parse().unwrap());
+	}
+}
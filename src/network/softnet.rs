@@ -0,0 +1,165 @@
+//! Read the per cpu packet processing counters from
+//! `/proc/net/softnet_stat`.
+//!
+//! A rising `dropped` counter means the backlog queue was full
+//! (`net.core.netdev_max_backlog`), a rising `time_squeeze` means the budget
+//! (`net.core.netdev_budget`) ran out before all packets were processed.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::network::softnet::Softnet;
+//!
+//! let softnet = Softnet::read().unwrap();
+//! for cpu in softnet.cpus() {
+//!     println!(
+//!         "cpu {} processed {} dropped {} squeezed {}",
+//!         cpu.cpu, cpu.processed, cpu.dropped, cpu.time_squeeze
+//!     );
+//! }
+//! ```
+
+use crate::util::{read_to_string, read_to_string_mut};
+#[cfg(feature = "async")]
+use crate::util::{read_to_string_async, read_to_string_mut_async};
+
+use std::io;
+use std::path::Path;
+
+/// Read the softnet counters of every cpu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Softnet {
+	raw: String
+}
+
+impl Softnet {
+
+	fn path() -> &'static Path {
+		Path::new("/proc/net/softnet_stat")
+	}
+
+	#[cfg(test)]
+	fn from_string(raw: String) -> Self {
+		Self {raw}
+	}
+
+	/// Read the counters from /proc/net/softnet_stat.
+	pub fn read() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string(Self::path())?
+		})
+	}
+
+	/// Reloads information without allocating.
+	pub fn reload(&mut self) -> io::Result<()> {
+		read_to_string_mut(Self::path(), &mut self.raw)
+	}
+
+	/// Reads the counters without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string_async(Self::path()).await?
+		})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(Self::path(), &mut self.raw).await
+	}
+
+	/// Returns the counters of every online cpu, lines which cannot be
+	/// parsed are skipped.
+	pub fn cpus(&self) -> impl Iterator<Item=SoftnetCpu> + '_ {
+		self.raw.lines()
+			.enumerate()
+			.filter_map(|(i, line)| SoftnetCpu::from_line(i as u32, line))
+	}
+
+	/// The packets dropped on all cpus.
+	pub fn dropped(&self) -> u64 {
+		self.cpus().map(|c| c.dropped as u64).sum()
+	}
+
+	/// How often the processing ran out of budget on all cpus.
+	pub fn time_squeeze(&self) -> u64 {
+		self.cpus().map(|c| c.time_squeeze as u64).sum()
+	}
+}
+
+/// The counters of one cpu, they are 32 bit and wrap around.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct SoftnetCpu {
+	/// The cpu id, kernels before 5.10 do not report it, then the line
+	/// number is used which is wrong if cpus are offline.
+	pub cpu: u32,
+	/// The packets processed.
+	pub processed: u32,
+	/// The packets dropped because the backlog queue was full.
+	pub dropped: u32,
+	/// How often the processing stopped before all work was done.
+	pub time_squeeze: u32,
+	/// How often this cpu was woken up to process packets steered by rps.
+	pub received_rps: u32,
+	/// The packets dropped by the flow limit.
+	pub flow_limit_count: u32,
+	/// The packets currently waiting in the backlog, requires Linux 5.10.
+	pub backlog_len: Option<u32>
+}
+
+impl SoftnetCpu {
+	fn from_line(line_index: u32, line: &str) -> Option<Self> {
+		let values = line.split_whitespace()
+			.map(|v| u32::from_str_radix(v, 16).ok())
+			.collect::<Option<Vec<_>>>()?;
+		let get = |i: usize| values.get(i).copied();
+
+		Some(Self {
+			cpu: get(12).unwrap_or(line_index),
+			processed: get(0)?,
+			dropped: get(1)?,
+			time_squeeze: get(2)?,
+			received_rps: get(9).unwrap_or(0),
+			flow_limit_count: get(10).unwrap_or(0),
+			backlog_len: get(11)
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn softnet_stat() {
+		let softnet = Softnet::from_string("\
+0004d4a3 00000000 00000012 00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000
+00015a10 00000003 00000001 00000000 00000000 00000000 00000000 00000000 00000000 00000017 00000000 00000002 00000002
+".into());
+		let cpus: Vec<_> = softnet.cpus().collect();
+		assert_eq!(cpus.len(), 2);
+		assert_eq!(cpus[0].processed, 0x4d4a3);
+		assert_eq!(cpus[0].time_squeeze, 0x12);
+		assert_eq!(cpus[1].cpu, 2);
+		assert_eq!(cpus[1].received_rps, 0x17);
+		assert_eq!(cpus[1].backlog_len, Some(2));
+		assert_eq!(softnet.dropped(), 3);
+		assert_eq!(softnet.time_squeeze(), 0x13);
+
+		// before 5.10 the cpu id is missing
+		let old = Softnet::from_string("\
+00000010 00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000
+00000020 00000001 00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000 00000000
+".into());
+		let cpus: Vec<_> = old.cpus().collect();
+		assert_eq!(cpus[1].cpu, 1);
+		assert_eq!(cpus[1].backlog_len, None);
+	}
+}
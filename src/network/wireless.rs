@@ -0,0 +1,187 @@
+//! Get the wireless regulatory domain and channel survey data using nl80211.
+//!
+//! Requires the `cfg80211` kernel module, the survey also requires a driver
+//! which supports it (most access point capable drivers do).
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::network::interfaces::Interface;
+//! use linux_info::network::wireless::{regulatory_domain, survey};
+//!
+//! println!("country {:?}", regulatory_domain().unwrap());
+//!
+//! let wlan = Interface::by_name("wlan0").unwrap();
+//! for channel in survey(&wlan).unwrap() {
+//!     println!(
+//!         "{} MHz noise {:?} dBm busy {:?}",
+//!         channel.frequency, channel.noise, channel.busy_ratio()
+//!     );
+//! }
+//! ```
+
+use super::interfaces::Interface;
+use super::netlink::{
+	Netlink, NETLINK_GENERIC, NLM_F_DUMP, GENL_HDR_LEN, genl_header,
+	push_attr, attributes, c_string, u32_attr, u64_attr
+};
+
+use std::io;
+use std::time::Duration;
+
+// see linux/nl80211.h
+const NL80211_CMD_GET_REG: u8 = 31;
+const NL80211_CMD_GET_SURVEY: u8 = 50;
+const NL80211_ATTR_IFINDEX: u16 = 3;
+const NL80211_ATTR_REG_ALPHA2: u16 = 33;
+const NL80211_ATTR_SURVEY_INFO: u16 = 84;
+
+const NL80211_SURVEY_INFO_FREQUENCY: u16 = 1;
+const NL80211_SURVEY_INFO_NOISE: u16 = 2;
+const NL80211_SURVEY_INFO_IN_USE: u16 = 3;
+const NL80211_SURVEY_INFO_TIME: u16 = 4;
+const NL80211_SURVEY_INFO_TIME_BUSY: u16 = 5;
+const NL80211_SURVEY_INFO_TIME_EXT_BUSY: u16 = 6;
+const NL80211_SURVEY_INFO_TIME_RX: u16 = 7;
+const NL80211_SURVEY_INFO_TIME_TX: u16 = 8;
+
+fn nl80211() -> io::Result<Option<(Netlink, u16)>> {
+	let mut netlink = Netlink::open(NETLINK_GENERIC)?;
+	Ok(netlink.genl_family("nl80211")?.map(|id| (netlink, id)))
+}
+
+/// Returns the global regulatory domain as an iso 3166 country code for
+/// example `DE`, `00` means the world domain.
+///
+/// Returns `None` if cfg80211 is not loaded.
+pub fn regulatory_domain() -> io::Result<Option<String>> {
+	let (mut netlink, family) = match nl80211()? {
+		Some(n) => n,
+		None => return Ok(None)
+	};
+
+	let mut alpha2 = None;
+	netlink.request(
+		family,
+		0,
+		&genl_header(NL80211_CMD_GET_REG, 0),
+		|_, data| {
+			alpha2 = data.get(GENL_HDR_LEN..)
+				.and_then(|d| {
+					attributes(d).find(|(ty, _)| *ty == NL80211_ATTR_REG_ALPHA2)
+				})
+				.map(|(_, v)| c_string(v));
+		}
+	)?;
+	Ok(alpha2)
+}
+
+/// The survey data of a channel, the times are counted since the driver
+/// started collecting them.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct ChannelSurvey {
+	/// The center frequency in MHz.
+	pub frequency: u32,
+	/// The noise level in dBm.
+	pub noise: Option<i8>,
+	/// True if this is the channel the interface currently uses.
+	pub in_use: bool,
+	/// The time the radio was on the channel.
+	pub active_time: Option<Duration>,
+	/// The time the channel was sensed busy.
+	pub busy_time: Option<Duration>,
+	/// The time the extension channel was sensed busy.
+	pub ext_busy_time: Option<Duration>,
+	/// The time spent receiving.
+	pub rx_time: Option<Duration>,
+	/// The time spent transmitting.
+	pub tx_time: Option<Duration>
+}
+
+impl ChannelSurvey {
+	fn parse(data: &[u8]) -> Option<Self> {
+		let info = attributes(data)
+			.find(|(ty, _)| *ty == NL80211_ATTR_SURVEY_INFO)?.1;
+
+		let mut survey = Self::default();
+		for (ty, v) in attributes(info) {
+			let millis = || u64_attr(v).map(Duration::from_millis);
+			match ty {
+				NL80211_SURVEY_INFO_FREQUENCY => survey.frequency = u32_attr(v)?,
+				NL80211_SURVEY_INFO_NOISE => {
+					survey.noise = v.first().map(|n| *n as i8);
+				},
+				NL80211_SURVEY_INFO_IN_USE => survey.in_use = true,
+				NL80211_SURVEY_INFO_TIME => survey.active_time = millis(),
+				NL80211_SURVEY_INFO_TIME_BUSY => survey.busy_time = millis(),
+				NL80211_SURVEY_INFO_TIME_EXT_BUSY => {
+					survey.ext_busy_time = millis();
+				},
+				NL80211_SURVEY_INFO_TIME_RX => survey.rx_time = millis(),
+				NL80211_SURVEY_INFO_TIME_TX => survey.tx_time = millis(),
+				_ => {}
+			}
+		}
+
+		(survey.frequency != 0).then(|| survey)
+	}
+
+	/// Returns how much of the active time the channel was busy, from 0 to
+	/// 1.
+	pub fn busy_ratio(&self) -> Option<f64> {
+		let active = self.active_time?.as_secs_f64();
+		let busy = self.busy_time?.as_secs_f64();
+		(active > 0.0).then(|| (busy / active).min(1.0))
+	}
+}
+
+/// Returns the survey data of every channel of a wireless interface.
+///
+/// Returns an empty list if cfg80211 is not loaded, returns an error if the
+/// interface is not wireless or the driver does not support surveys.
+pub fn survey(interface: &Interface) -> io::Result<Vec<ChannelSurvey>> {
+	let index = interface.index()?;
+	let (mut netlink, family) = match nl80211()? {
+		Some(n) => n,
+		None => return Ok(vec![])
+	};
+
+	let mut payload = genl_header(NL80211_CMD_GET_SURVEY, 0);
+	push_attr(&mut payload, NL80211_ATTR_IFINDEX, &index.to_ne_bytes());
+
+	let mut list = vec![];
+	netlink.request(family, NLM_F_DUMP, &payload, |_, data| {
+		list.extend(data.get(GENL_HDR_LEN..).and_then(ChannelSurvey::parse));
+	})?;
+	Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn survey_info() {
+		let mut info = vec![];
+		push_attr(&mut info, NL80211_SURVEY_INFO_FREQUENCY, &2412u32.to_ne_bytes());
+		push_attr(&mut info, NL80211_SURVEY_INFO_NOISE, &[(-95i8) as u8]);
+		push_attr(&mut info, NL80211_SURVEY_INFO_IN_USE, &[]);
+		push_attr(&mut info, NL80211_SURVEY_INFO_TIME, &1000u64.to_ne_bytes());
+		push_attr(&mut info, NL80211_SURVEY_INFO_TIME_BUSY, &250u64.to_ne_bytes());
+		let mut data = vec![];
+		push_attr(&mut data, NL80211_ATTR_IFINDEX, &3u32.to_ne_bytes());
+		push_attr(&mut data, NL80211_ATTR_SURVEY_INFO, &info);
+
+		let survey = ChannelSurvey::parse(&data).unwrap();
+		assert_eq!(survey.frequency, 2412);
+		assert_eq!(survey.noise, Some(-95));
+		assert!(survey.in_use);
+		assert_eq!(survey.active_time, Some(Duration::from_secs(1)));
+		assert_eq!(survey.rx_time, None);
+		assert_eq!(survey.busy_ratio(), Some(0.25));
+	}
+}
@@ -0,0 +1,223 @@
+//! Connect to wpa_supplicant, useful on systems without NetworkManager.
+
+use std::time::Duration;
+use std::sync::Arc;
+
+use dbus::{Error, Path};
+use dbus::arg::{self, PropMap, Variant};
+use dbus::blocking::{SyncConnection, Proxy};
+use dbus::blocking::stdintf::org_freedesktop_dbus::Properties;
+
+const DBUS_NAME: &str = "fi.w1.wpa_supplicant1";
+const DBUS_PATH: &str = "/fi/w1/wpa_supplicant1";
+const INTERFACE: &str = "fi.w1.wpa_supplicant1.Interface";
+const BSS_INTERFACE: &str = "fi.w1.wpa_supplicant1.BSS";
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Clone)]
+struct Dbus {
+	conn: Arc<SyncConnection>,
+	timeout: Duration
+}
+
+impl Dbus {
+	fn connect() -> Result<Self, Error> {
+		SyncConnection::new_system()
+			.map(Arc::new)
+			.map(|conn| Self { conn, timeout: TIMEOUT })
+	}
+
+	fn proxy<'a, 'b>(
+		&'b self,
+		path: impl Into<Path<'a>>
+	) -> Proxy<'a, &'b SyncConnection> {
+		self.conn.with_proxy(DBUS_NAME, path, self.timeout)
+	}
+}
+
+#[derive(Clone)]
+pub struct WpaSupplicant {
+	dbus: Dbus
+}
+
+impl WpaSupplicant {
+	/// Connects to the system bus with the default timeout of 2 seconds.
+	pub fn connect() -> Result<Self, Error> {
+		Dbus::connect()
+			.map(|dbus| Self { dbus })
+	}
+
+	/// Creates a WpaSupplicant from an existing system bus connection.
+	pub fn from_connection(conn: Arc<SyncConnection>) -> Self {
+		Self {
+			dbus: Dbus { conn, timeout: TIMEOUT }
+		}
+	}
+
+	/// Returns all interfaces controlled by wpa_supplicant.
+	pub fn interfaces(&self) -> Result<Vec<Interface>, Error> {
+		let paths: Vec<Path<'static>> = self.dbus.proxy(DBUS_PATH)
+			.get(DBUS_NAME, "Interfaces")?;
+
+		Ok(paths.into_iter()
+			.map(|path| Interface {
+				dbus: self.dbus.clone(),
+				path
+			})
+			.collect())
+	}
+}
+
+#[derive(Clone)]
+pub struct Interface {
+	dbus: Dbus,
+	path: Path<'static>
+}
+
+impl Interface {
+	fn get<R>(&self, name: &str) -> Result<R, Error>
+	where R: for<'b> arg::Get<'b> + 'static {
+		self.dbus.proxy(&self.path).get(INTERFACE, name)
+	}
+
+	/// The dbus object path for example `/fi/w1/wpa_supplicant1/Interfaces/0`.
+	pub fn object_path(&self) -> &str {
+		&self.path
+	}
+
+	/// The name of the network interface for example `wlan0`.
+	pub fn ifname(&self) -> Result<String, Error> {
+		self.get("Ifname")
+	}
+
+	/// The name of the driver used by the interface for example `nl80211`.
+	pub fn driver(&self) -> Result<String, Error> {
+		self.get("Driver")
+	}
+
+	/// The state of the interface.
+	pub fn state(&self) -> Result<InterfaceState, Error> {
+		self.get::<String>("State")
+			.map(|s| InterfaceState::from_str(&s))
+	}
+
+	/// Returns the BSS the interface is currently connected to.
+	pub fn current_bss(&self) -> Result<Option<Bss>, Error> {
+		let path: Path<'static> = self.get("CurrentBSS")?;
+		// wpa_supplicant returns "/" if there is no current bss
+		if &*path == "/" {
+			return Ok(None)
+		}
+
+		Ok(Some(Bss {
+			dbus: self.dbus.clone(),
+			path
+		}))
+	}
+
+	/// Returns the results of the last scan.
+	pub fn scan_results(&self) -> Result<Vec<Bss>, Error> {
+		let paths: Vec<Path<'static>> = self.get("BSSs")?;
+
+		Ok(paths.into_iter()
+			.map(|path| Bss {
+				dbus: self.dbus.clone(),
+				path
+			})
+			.collect())
+	}
+
+	/// Triggers an active scan. The results will be available in
+	/// `scan_results` after the scan completed.
+	pub fn scan(&self) -> Result<(), Error> {
+		let mut args = PropMap::new();
+		args.insert("Type".into(), Variant(Box::new("active".to_string())));
+
+		self.dbus.proxy(&self.path).method_call(INTERFACE, "Scan", (args,))
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub enum InterfaceState {
+	Disconnected,
+	Inactive,
+	Scanning,
+	Authenticating,
+	Associating,
+	Associated,
+	FourWayHandshake,
+	GroupHandshake,
+	Completed,
+	Unknown
+}
+
+impl InterfaceState {
+	fn from_str(s: &str) -> Self {
+		match s {
+			"disconnected" => Self::Disconnected,
+			"inactive" => Self::Inactive,
+			"scanning" => Self::Scanning,
+			"authenticating" => Self::Authenticating,
+			"associating" => Self::Associating,
+			"associated" => Self::Associated,
+			"4way_handshake" => Self::FourWayHandshake,
+			"group_handshake" => Self::GroupHandshake,
+			"completed" => Self::Completed,
+			_ => Self::Unknown
+		}
+	}
+}
+
+/// A basic service set, essentially an access point.
+#[derive(Clone)]
+pub struct Bss {
+	dbus: Dbus,
+	path: Path<'static>
+}
+
+impl Bss {
+	fn get<R>(&self, name: &str) -> Result<R, Error>
+	where R: for<'b> arg::Get<'b> + 'static {
+		self.dbus.proxy(&self.path).get(BSS_INTERFACE, name)
+	}
+
+	/// The dbus object path.
+	pub fn object_path(&self) -> &str {
+		&self.path
+	}
+
+	/// The ssid of the network, not necessarily valid utf8.
+	pub fn ssid_raw(&self) -> Result<Vec<u8>, Error> {
+		self.get("SSID")
+	}
+
+	/// The ssid of the network, invalid utf8 is replaced.
+	pub fn ssid(&self) -> Result<String, Error> {
+		self.ssid_raw()
+			.map(|s| String::from_utf8_lossy(&s).into_owned())
+	}
+
+	/// The bssid formatted as `00:11:22:33:44:55`.
+	pub fn bssid(&self) -> Result<String, Error> {
+		let bssid: Vec<u8> = self.get("BSSID")?;
+		let parts: Vec<_> = bssid.iter()
+			.map(|b| format!("{:02x}", b))
+			.collect();
+		Ok(parts.join(":"))
+	}
+
+	/// The signal strength in dBm.
+	pub fn signal(&self) -> Result<i16, Error> {
+		self.get("Signal")
+	}
+
+	/// The frequency in MHz.
+	pub fn frequency(&self) -> Result<u16, Error> {
+		self.get("Frequency")
+	}
+}
@@ -0,0 +1,213 @@
+//! Identify cellular (WWAN) hardware from sysfs, which works without
+//! ModemManager running.
+//!
+//! A modem is found through its network interface (`/sys/class/net/wwan0`
+//! or an interface the driver marked as wwan, like `qmi_wwan` or
+//! `cdc_mbim` do) and through its control ports in `/sys/class/wwan`.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::network::wwan::WwanDevice;
+//!
+//! for modem in WwanDevice::all().unwrap() {
+//!     println!(
+//!         "{:?} {:?} driver {:?} usb {:04x?}",
+//!         modem.interfaces(), modem.ports(), modem.driver(),
+//!         modem.usb_ids()
+//!     );
+//! }
+//! ```
+
+use crate::util::read_sys_string;
+
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+
+const NET_PATH: &str = "/sys/class/net";
+const WWAN_PATH: &str = "/sys/class/wwan";
+
+/// A cellular modem, the path points to the usb device or the pci function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WwanDevice {
+	path: PathBuf,
+	interfaces: Vec<String>,
+	ports: Vec<String>,
+	driver: Option<String>
+}
+
+impl WwanDevice {
+	/// Returns every modem which has a wwan network interface or wwan
+	/// control ports.
+	pub fn all() -> io::Result<Vec<Self>> {
+		let mut list: Vec<Self> = vec![];
+
+		for (entry, name) in class_entries(NET_PATH)? {
+			if !is_wwan_interface(&entry, &name) {
+				continue
+			}
+			let root = match hardware_root(&entry) {
+				Some(r) => r,
+				None => continue
+			};
+			let driver = driver_of(&entry.join("device"));
+			let modem = Self::entry(&mut list, root);
+			modem.interfaces.push(name);
+			if modem.driver.is_none() {
+				modem.driver = driver;
+			}
+		}
+
+		for (entry, name) in class_entries(WWAN_PATH)? {
+			let root = match hardware_root(&entry) {
+				Some(r) => r,
+				None => continue
+			};
+			let modem = Self::entry(&mut list, root);
+			// skip the wwan device itself for example wwan0
+			let is_device = name.strip_prefix("wwan")
+				.map(|n| n.bytes().all(|b| b.is_ascii_digit()))
+				.unwrap_or(false);
+			if !is_device {
+				modem.ports.push(name);
+			}
+		}
+
+		for modem in &mut list {
+			if modem.driver.is_none() {
+				modem.driver = driver_of(&modem.path);
+			}
+			modem.interfaces.sort();
+			modem.ports.sort();
+		}
+		list.sort_by(|a, b| a.path.cmp(&b.path));
+		Ok(list)
+	}
+
+	fn entry(list: &mut Vec<Self>, path: PathBuf) -> &mut Self {
+		match list.iter().position(|m| m.path == path) {
+			Some(i) => &mut list[i],
+			None => {
+				list.push(Self {
+					path,
+					interfaces: vec![],
+					ports: vec![],
+					driver: None
+				});
+				list.last_mut().unwrap()
+			}
+		}
+	}
+
+	/// The sysfs path of the usb device or the pci function.
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// The network interfaces of the modem for example `wwan0`.
+	pub fn interfaces(&self) -> &[String] {
+		&self.interfaces
+	}
+
+	/// The control ports of the modem for example `wwan0at0` or
+	/// `wwan0mbim0`, requires a kernel with the wwan subsystem (5.13).
+	pub fn ports(&self) -> &[String] {
+		&self.ports
+	}
+
+	/// The driver of the network interface for example `qmi_wwan`,
+	/// `cdc_mbim` or `mhi_net`, or if the modem has no network interface
+	/// the driver of the device.
+	pub fn driver(&self) -> Option<&str> {
+		self.driver.as_deref()
+	}
+
+	/// Returns true if the modem is connected over usb.
+	pub fn is_usb(&self) -> bool {
+		self.path.join("idVendor").is_file()
+	}
+
+	/// The usb vendor and product id, `None` if the modem is not connected
+	/// over usb.
+	pub fn usb_ids(&self) -> io::Result<Option<(u16, u16)>> {
+		if !self.is_usb() {
+			return Ok(None)
+		}
+
+		Ok(Some((
+			read_hex(self.path.join("idVendor"))?,
+			read_hex(self.path.join("idProduct"))?
+		)))
+	}
+
+	/// The pci vendor and device id, `None` if the modem is not a pci
+	/// device (like most M.2 modems using MHI).
+	pub fn pci_ids(&self) -> io::Result<Option<(u16, u16)>> {
+		if self.is_usb() {
+			return Ok(None)
+		}
+
+		Ok(Some((
+			read_hex(self.path.join("vendor"))?,
+			read_hex(self.path.join("device"))?
+		)))
+	}
+
+	/// The manufacturer reported by a usb modem.
+	pub fn manufacturer(&self) -> Option<String> {
+		read_sys_string(self.path.join("manufacturer")).ok()
+	}
+
+	/// The product name reported by a usb modem.
+	pub fn product(&self) -> Option<String> {
+		read_sys_string(self.path.join("product")).ok()
+	}
+}
+
+/// Returns the path and name of every entry in a sysfs class.
+fn class_entries(class: &str) -> io::Result<Vec<(PathBuf, String)>> {
+	let dir = match fs::read_dir(class) {
+		Ok(d) => d,
+		Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+		Err(e) => return Err(e)
+	};
+
+	let mut list = vec![];
+	for entry in dir {
+		let entry = entry?;
+		if let Some(name) = entry.file_name().to_str() {
+			list.push((entry.path(), name.to_string()));
+		}
+	}
+	Ok(list)
+}
+
+fn is_wwan_interface(path: &Path, name: &str) -> bool {
+	name.starts_with("wwan") ||
+	read_sys_string(path.join("uevent"))
+		.map(|u| u.lines().any(|l| l == "DEVTYPE=wwan"))
+		.unwrap_or(false)
+}
+
+/// Walks up from a class device to the usb device or the pci function it
+/// belongs to.
+fn hardware_root(class_entry: &Path) -> Option<PathBuf> {
+	let path = fs::canonicalize(class_entry).ok()?;
+	path.ancestors()
+		.skip(1)
+		.find(|p| {
+			p.join("idVendor").is_file() ||
+			(p.join("vendor").is_file() && p.join("device").is_file())
+		})
+		.map(Path::to_path_buf)
+}
+
+fn driver_of(device: &Path) -> Option<String> {
+	let link = fs::read_link(device.join("driver")).ok()?;
+	link.file_name()?.to_str().map(Into::into)
+}
+
+fn read_hex(path: impl AsRef<Path>) -> io::Result<u16> {
+	let s = read_sys_string(path)?;
+	u16::from_str_radix(s.trim_start_matches("0x"), 16)
+		.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,291 @@
+//! Get exports and statistics of the kernel nfs server.
+//!
+//! Most files only exist if the nfs server is running.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::nfs::{Exports, NfsdStats};
+//!
+//! for export in Exports::read().unwrap().entries() {
+//!     println!("{} {} {:?}", export.path, export.client, export.options);
+//! }
+//!
+//! let stats = NfsdStats::read().unwrap();
+//! println!("read {:?} written {:?}", stats.read_bytes(), stats.written_bytes());
+//! ```
+
+use crate::util::{
+	read_to_string, read_to_string_mut, read_sys_value, unescape_octal
+};
+#[cfg(feature = "async")]
+use crate::util::{read_to_string_async, read_to_string_mut_async};
+use crate::unit::DataSize;
+
+use std::io;
+use std::borrow::Cow;
+use std::path::Path;
+
+const EXPORT_PATHS: [&str; 2] = ["/proc/fs/nfsd/exports", "/var/lib/nfs/etab"];
+
+/// The names of the nfs v3 operations in the order of /proc/net/rpc/nfsd.
+const V3_OPS: [&str; 22] = [
+	"null", "getattr", "setattr", "lookup", "access", "readlink", "read",
+	"write", "create", "mkdir", "symlink", "mknod", "remove", "rmdir",
+	"rename", "link", "readdir", "readdirplus", "fsstat", "fsinfo",
+	"pathconf", "commit"
+];
+
+/// Returns the amount of nfsd threads from /proc/fs/nfsd/threads.
+///
+/// Zero means the server is stopped.
+pub fn threads() -> io::Result<usize> {
+	read_sys_value("/proc/fs/nfsd/threads")
+}
+
+/// Read the exported paths from /proc/fs/nfsd/exports or
+/// /var/lib/nfs/etab.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Exports {
+	raw: String
+}
+
+impl Exports {
+
+	#[cfg(test)]
+	fn from_string(raw: String) -> Self {
+		Self {raw}
+	}
+
+	/// Reads /proc/fs/nfsd/exports, if it is not readable (requires root)
+	/// /var/lib/nfs/etab is used.
+	pub fn read() -> io::Result<Self> {
+		let mut raw = String::new();
+		Self::read_mut(&mut raw)?;
+		Ok(Self {raw})
+	}
+
+	/// Reloads information without allocating.
+	pub fn reload(&mut self) -> io::Result<()> {
+		Self::read_mut(&mut self.raw)
+	}
+
+	fn read_mut(raw: &mut String) -> io::Result<()> {
+		read_to_string_mut(EXPORT_PATHS[0], raw)
+			.or_else(|_| read_to_string_mut(EXPORT_PATHS[1], raw))
+	}
+
+	/// Reads the file without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		let raw = match read_to_string_async(EXPORT_PATHS[0]).await {
+			Ok(raw) => raw,
+			Err(_) => read_to_string_async(EXPORT_PATHS[1]).await?
+		};
+		Ok(Self {raw})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		match read_to_string_mut_async(EXPORT_PATHS[0], &mut self.raw).await {
+			Ok(()) => Ok(()),
+			Err(_) => {
+				read_to_string_mut_async(EXPORT_PATHS[1], &mut self.raw).await
+			}
+		}
+	}
+
+	/// Returns every export, a path exported to multiple clients is
+	/// returned once per client.
+	pub fn entries(&self) -> impl Iterator<Item=Export<'_>> {
+		self.raw.lines()
+			.filter(|l| !l.starts_with('#'))
+			.flat_map(|line| {
+				let mut parts = line.split_whitespace();
+				let path = parts.next().map(unescape_octal);
+				parts.filter_map(move |client| {
+					Export::new(path.clone()?, client)
+				})
+			})
+	}
+}
+
+/// A path exported to a client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Export<'a> {
+	pub path: Cow<'a, str>,
+	/// The client, for example `*`, `192.168.1.0/24` or `host.example.com`.
+	pub client: &'a str,
+	/// The options like `rw` or `no_root_squash`.
+	pub options: Vec<&'a str>
+}
+
+impl<'a> Export<'a> {
+	fn new(path: Cow<'a, str>, client: &'a str) -> Option<Self> {
+		let (client, options) = match client.split_once('(') {
+			Some((client, opts)) => {
+				(client, opts.strip_suffix(')')?.split(',').collect())
+			},
+			None => (client, vec![])
+		};
+
+		Some(Self { path, client, options })
+	}
+
+	/// Returns true if the export is writable.
+	pub fn is_writable(&self) -> bool {
+		self.options.contains(&"rw")
+	}
+}
+
+/// Read nfs server statistics from /proc/net/rpc/nfsd.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NfsdStats {
+	raw: String
+}
+
+impl NfsdStats {
+
+	fn path() -> &'static Path {
+		Path::new("/proc/net/rpc/nfsd")
+	}
+
+	#[cfg(test)]
+	fn from_string(raw: String) -> Self {
+		Self {raw}
+	}
+
+	/// Read nfs server statistics from /proc/net/rpc/nfsd.
+	pub fn read() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string(Self::path())?
+		})
+	}
+
+	/// Reloads information without allocating.
+	pub fn reload(&mut self) -> io::Result<()> {
+		read_to_string_mut(Self::path(), &mut self.raw)
+	}
+
+	/// Reads the file without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string_async(Self::path()).await?
+		})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(Self::path(), &mut self.raw).await
+	}
+
+	/// Returns the numbers of a line, for example `io` or `proc4ops`.
+	pub fn values(&self, key: &str) -> Option<impl Iterator<Item=u64> + '_> {
+		let line = self.raw.lines()
+			.find(|l| l.split(' ').next() == Some(key))?;
+		Some(line.split_whitespace()
+			.skip(1)
+			.filter_map(|v| v.parse().ok()))
+	}
+
+	fn nth(&self, key: &str, n: usize) -> Option<u64> {
+		self.values(key)?.nth(n)
+	}
+
+	/// The bytes read from disk by the server.
+	pub fn read_bytes(&self) -> Option<DataSize> {
+		self.nth("io", 0).map(|b| DataSize::from_bytes(b.into()))
+	}
+
+	/// The bytes written to disk by the server.
+	pub fn written_bytes(&self) -> Option<DataSize> {
+		self.nth("io", 1).map(|b| DataSize::from_bytes(b.into()))
+	}
+
+	/// The amount of threads.
+	pub fn threads(&self) -> Option<u64> {
+		self.nth("th", 0)
+	}
+
+	/// The amount of rpc calls received.
+	pub fn rpc_calls(&self) -> Option<u64> {
+		self.nth("rpc", 0)
+	}
+
+	/// The amount of rpc calls which were rejected because they were
+	/// malformed or not authorized.
+	pub fn rpc_bad_calls(&self) -> Option<u64> {
+		self.nth("rpc", 1)
+	}
+
+	/// Returns the counter of every operation of a version, for example
+	/// `proc3` or `proc4ops`.
+	///
+	/// The first value of the line (the amount of counters) is skipped.
+	pub fn ops(&self, key: &str) -> Option<Vec<u64>> {
+		self.values(key).map(|v| v.skip(1).collect())
+	}
+
+	/// Returns the nfs v3 operations with their names.
+	pub fn v3_ops(&self) -> Option<Vec<(&'static str, u64)>> {
+		let ops = self.ops("proc3")?;
+		Some(V3_OPS.iter().copied().zip(ops).collect())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn exports() {
+		let exports = Exports::from_string("\
+# Version 1.1
+# Path Client(Flags) # IPs
+/srv/nfs\t192.168.1.0/24(rw,sync,wdelay,no_root_squash)
+/srv/my\\040share\t*(ro,sync) host.local
+".into());
+		let list: Vec<_> = exports.entries().collect();
+		assert_eq!(list.len(), 3);
+		assert_eq!(list[0].path, "/srv/nfs");
+		assert_eq!(list[0].client, "192.168.1.0/24");
+		assert_eq!(list[0].options, ["rw", "sync", "wdelay", "no_root_squash"]);
+		assert!(list[0].is_writable());
+		assert_eq!(list[1].path, "/srv/my share");
+		assert_eq!(list[1].client, "*");
+		assert!(!list[1].is_writable());
+		assert_eq!(list[2].client, "host.local");
+		assert!(list[2].options.is_empty());
+	}
+
+	#[test]
+	fn nfsd_stats() {
+		let stats = NfsdStats::from_string("\
+rc 0 4096 152
+io 1048576 2048
+th 8 0 0.000 0.000 0.000 0.000 0.000 0.000 0.000 0.000 0.000 0.000
+ra 32 0 0 0 0 0 0 0 0 0 0 0
+net 4248 0 4248 8
+rpc 4248 2 0 2 0
+proc3 22 2 150 0 40 38 0 1000 30 0 0 0 0 0 0 0 0 0 12 2 2 0 10
+proc4 2 2 1200
+".into());
+		assert_eq!(stats.read_bytes().unwrap().as_bytes(), 1048576);
+		assert_eq!(stats.written_bytes().unwrap().as_bytes(), 2048);
+		assert_eq!(stats.threads(), Some(8));
+		assert_eq!(stats.rpc_calls(), Some(4248));
+		assert_eq!(stats.rpc_bad_calls(), Some(2));
+		assert_eq!(stats.ops("proc4").unwrap(), [2, 1200]);
+		let v3 = stats.v3_ops().unwrap();
+		assert_eq!(v3.len(), 22);
+		assert_eq!(v3[1], ("getattr", 150));
+		assert_eq!(v3[6], ("read", 1000));
+		assert!(stats.ops("proc2").is_none());
+	}
+}
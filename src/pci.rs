@@ -0,0 +1,523 @@
+//! List pci devices from `/sys/bus/pci/devices` with their pcie link state,
+//! AER error counters and SR-IOV virtual functions.
+//!
+//! For passthrough setups [`iommu_enabled`] and [`IommuGroup`] show how the
+//! devices are isolated and if they are bound to `vfio-pci`.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::pci::PciDevice;
+//!
+//! for dev in PciDevice::all().unwrap() {
+//!     let link = match dev.link().unwrap() {
+//!         Some(l) => l,
+//!         None => continue
+//!     };
+//!     if link.is_degraded() {
+//!         println!(
+//!             "{:?} runs at {:?} GT/s x{} instead of {:?} GT/s x{}",
+//!             dev.address(), link.current_speed, link.current_width,
+//!             link.max_speed, link.max_width
+//!         );
+//!     }
+//! }
+//! ```
+
+use crate::util::{read_sys_string, read_sys_value, read_sys_opt};
+
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+
+const PCI_PATH: &str = "/sys/bus/pci/devices";
+const IOMMU_PATH: &str = "/sys/class/iommu";
+const IOMMU_GROUPS_PATH: &str = "/sys/kernel/iommu_groups";
+const VFIO_DRIVER: &str = "vfio-pci";
+
+/// A pci device for example `/sys/bus/pci/devices/0000:01:00.0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PciDevice {
+	path: PathBuf
+}
+
+impl PciDevice {
+	/// Returns all pci devices.
+	pub fn all() -> io::Result<Vec<Self>> {
+		let dir = match fs::read_dir(PCI_PATH) {
+			Ok(d) => d,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+			Err(e) => return Err(e)
+		};
+
+		let mut list = dir.map(|e| e.map(|e| Self::from_path(e.path())))
+			.collect::<io::Result<Vec<_>>>()?;
+		list.sort_by(|a, b| a.path.cmp(&b.path));
+		Ok(list)
+	}
+
+	/// Returns the device with an address like `0000:01:00.0`.
+	pub fn by_address(address: &str) -> Option<Self> {
+		let path = Path::new(PCI_PATH).join(address);
+		path.is_dir().then(|| Self::from_path(path))
+	}
+
+	/// Returns the pci device a sysfs device belongs to, for example
+	/// `/sys/class/drm/card0/device` or `/sys/class/nvme/nvme0`.
+	pub fn of_device(device: impl AsRef<Path>) -> Option<Self> {
+		let path = fs::canonicalize(device).ok()?;
+		path.ancestors()
+			.find(|p| is_pci(p))
+			.map(Self::from_path)
+	}
+
+	/// Creates a device from a path like
+	/// `/sys/bus/pci/devices/0000:01:00.0`.
+	pub fn from_path(path: impl Into<PathBuf>) -> Self {
+		Self { path: path.into() }
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// The address of the device for example `0000:01:00.0`.
+	pub fn address(&self) -> Option<&str> {
+		self.path.file_name()?.to_str()
+	}
+
+	/// The pci vendor id for example `0x10de`.
+	pub fn vendor_id(&self) -> io::Result<u16> {
+		read_hex(self.path.join("vendor")).map(|v| v as u16)
+	}
+
+	/// The pci device id.
+	pub fn device_id(&self) -> io::Result<u16> {
+		read_hex(self.path.join("device")).map(|v| v as u16)
+	}
+
+	/// The class code for example `0x010802` for a nvme controller.
+	pub fn class(&self) -> io::Result<u32> {
+		read_hex(self.path.join("class"))
+	}
+
+	/// The kernel driver for example `nvme`, `None` if no driver is bound.
+	pub fn driver(&self) -> Option<String> {
+		let link = fs::read_link(self.path.join("driver")).ok()?;
+		link.file_name()?.to_str().map(Into::into)
+	}
+
+	/// The driver which is forced to bind to this device, set with
+	/// `driver_override` for example to bind `vfio-pci`.
+	pub fn driver_override(&self) -> Option<String> {
+		read_sys_string(self.path.join("driver_override")).ok()
+			.filter(|d| d != "(null)")
+	}
+
+	/// Returns true if the device is bound to `vfio-pci` and can be passed
+	/// through to a virtual machine.
+	pub fn is_vfio(&self) -> bool {
+		self.driver().map(|d| d == VFIO_DRIVER).unwrap_or(false)
+	}
+
+	/// The iommu group of the device, `None` if the iommu is disabled.
+	pub fn iommu_group(&self) -> Option<IommuGroup> {
+		let link = fs::read_link(self.path.join("iommu_group")).ok()?;
+		let id = link.file_name()?.to_str()?.parse().ok()?;
+		Some(IommuGroup::from_id(id))
+	}
+
+	/// The network interfaces of the device for example `enp1s0f0`.
+	pub fn net_interfaces(&self) -> io::Result<Vec<String>> {
+		let dir = match fs::read_dir(self.path.join("net")) {
+			Ok(d) => d,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+			Err(e) => return Err(e)
+		};
+
+		let mut list = vec![];
+		for entry in dir {
+			if let Some(name) = entry?.file_name().to_str() {
+				list.push(name.to_string());
+			}
+		}
+		list.sort();
+		Ok(list)
+	}
+
+	/// The SR-IOV capability of a physical function, `None` if the device
+	/// does not support SR-IOV.
+	pub fn sriov(&self) -> io::Result<Option<Sriov>> {
+		let total_vfs = match read_sys_opt(self.path.join("sriov_totalvfs"))? {
+			Some(t) => t,
+			None => return Ok(None)
+		};
+
+		Ok(Some(Sriov {
+			total_vfs,
+			num_vfs: read_sys_value(self.path.join("sriov_numvfs"))?
+		}))
+	}
+
+	/// The enabled virtual functions of this physical function.
+	pub fn virtual_functions(&self) -> io::Result<Vec<PciDevice>> {
+		let mut list = vec![];
+		for entry in fs::read_dir(&self.path)? {
+			let entry = entry?;
+			// the links are named virtfn0, virtfn1 ...
+			let index: u32 = match entry.file_name().to_str()
+				.and_then(|n| n.strip_prefix("virtfn"))
+				.and_then(|n| n.parse().ok())
+			{
+				Some(i) => i,
+				None => continue
+			};
+			list.push((index, PciDevice::from_path(fs::canonicalize(
+				entry.path()
+			)?)));
+		}
+		list.sort_by_key(|(i, _)| *i);
+		Ok(list.into_iter().map(|(_, d)| d).collect())
+	}
+
+	/// The physical function if this is a virtual function.
+	pub fn physical_function(&self) -> Option<PciDevice> {
+		fs::canonicalize(self.path.join("physfn")).ok()
+			.map(PciDevice::from_path)
+	}
+
+	/// Returns true if this is a SR-IOV virtual function.
+	pub fn is_virtual_function(&self) -> bool {
+		self.path.join("physfn").exists()
+	}
+
+	/// The bridge or root port this device is connected to.
+	pub fn parent(&self) -> Option<Self> {
+		let path = fs::canonicalize(&self.path).ok()?;
+		let parent = path.parent()?;
+		is_pci(parent).then(|| Self::from_path(parent))
+	}
+
+	/// The negotiated and the maximum link speed and width.
+	///
+	/// Returns `None` for devices which are not pcie.
+	pub fn link(&self) -> io::Result<Option<PcieLink>> {
+		let current_speed = match read_sys_string(
+			self.path.join("current_link_speed")
+		) {
+			Ok(s) => parse_speed(&s),
+			Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+			Err(e) => return Err(e)
+		};
+
+		Ok(Some(PcieLink {
+			current_speed,
+			current_width: read_sys_value(
+				self.path.join("current_link_width")
+			)?,
+			max_speed: parse_speed(
+				&read_sys_string(self.path.join("max_link_speed"))?
+			),
+			max_width: read_sys_value(self.path.join("max_link_width"))?
+		}))
+	}
+
+	/// The AER error counters since boot, requires Linux 5.1 and a device
+	/// which supports advanced error reporting.
+	pub fn aer(&self) -> io::Result<Option<AerCounters>> {
+		let correctable = match read_sys_string(
+			self.path.join("aer_dev_correctable")
+		) {
+			Ok(s) => AerErrors::parse(&s),
+			Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+			Err(e) => return Err(e)
+		};
+
+		Ok(Some(AerCounters {
+			correctable,
+			nonfatal: AerErrors::parse(
+				&read_sys_string(self.path.join("aer_dev_nonfatal"))?
+			),
+			fatal: AerErrors::parse(
+				&read_sys_string(self.path.join("aer_dev_fatal"))?
+			)
+		}))
+	}
+}
+
+/// The SR-IOV state of a physical function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct Sriov {
+	/// How many virtual functions the device supports.
+	pub total_vfs: u32,
+	/// How many virtual functions are enabled, changed by writing to
+	/// `sriov_numvfs`.
+	pub num_vfs: u32
+}
+
+/// Returns true if an iommu (intel vt-d or amd-vi) is enabled, this
+/// requires the firmware to enable it and for intel `intel_iommu=on` on
+/// older kernels.
+pub fn iommu_enabled() -> io::Result<bool> {
+	match fs::read_dir(IOMMU_PATH) {
+		Ok(mut d) => Ok(d.next().is_some()),
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+		Err(e) => Err(e)
+	}
+}
+
+/// The smallest set of devices the iommu can isolate, every device of a
+/// group needs to be passed through together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IommuGroup {
+	id: u32
+}
+
+impl IommuGroup {
+	/// Returns every iommu group, empty if the iommu is disabled.
+	pub fn all() -> io::Result<Vec<Self>> {
+		let dir = match fs::read_dir(IOMMU_GROUPS_PATH) {
+			Ok(d) => d,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+			Err(e) => return Err(e)
+		};
+
+		let mut list = vec![];
+		for entry in dir {
+			let entry = entry?;
+			if let Some(id) = entry.file_name().to_str()
+				.and_then(|n| n.parse().ok())
+			{
+				list.push(Self::from_id(id));
+			}
+		}
+		list.sort_by_key(|g| g.id);
+		Ok(list)
+	}
+
+	pub fn from_id(id: u32) -> Self {
+		Self { id }
+	}
+
+	pub fn id(&self) -> u32 {
+		self.id
+	}
+
+	/// The path for example `/sys/kernel/iommu_groups/12`.
+	pub fn path(&self) -> PathBuf {
+		Path::new(IOMMU_GROUPS_PATH).join(self.id.to_string())
+	}
+
+	/// How the iommu translates dma for this group, `identity` means it
+	/// is bypassed (`iommu=pt`), `DMA` or `DMA-FQ` that it is translated.
+	pub fn kind(&self) -> io::Result<String> {
+		read_sys_string(self.path().join("type"))
+	}
+
+	/// Returns the pci devices in this group.
+	pub fn devices(&self) -> io::Result<Vec<PciDevice>> {
+		let mut list = vec![];
+		for entry in fs::read_dir(self.path().join("devices"))? {
+			let path = entry?.path();
+			if is_pci(&path) {
+				list.push(PciDevice::from_path(path));
+			}
+		}
+		list.sort_by(|a, b| a.path.cmp(&b.path));
+		Ok(list)
+	}
+
+	/// Returns true if every endpoint in the group is bound to `vfio-pci`
+	/// or has no driver, which is required to pass the group through.
+	/// Bridges are ignored since they stay with the host.
+	pub fn is_vfio_ready(&self) -> io::Result<bool> {
+		for dev in self.devices()? {
+			// 0x06 is the class of bridges
+			if dev.class()? >> 16 == 0x06 {
+				continue
+			}
+			if dev.driver().map(|d| d != VFIO_DRIVER).unwrap_or(false) {
+				return Ok(false)
+			}
+		}
+		Ok(true)
+	}
+}
+
+fn is_pci(path: &Path) -> bool {
+	fs::read_link(path.join("subsystem"))
+		.map(|s| s.file_name().map(|n| n == "pci").unwrap_or(false))
+		.unwrap_or(false) &&
+	path.join("config").is_file()
+}
+
+fn read_hex(path: impl AsRef<Path>) -> io::Result<u32> {
+	let s = read_sys_string(path)?;
+	u32::from_str_radix(s.trim_start_matches("0x"), 16)
+		.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Parses a speed like `8.0 GT/s PCIe` or `2.5 GT/s`.
+fn parse_speed(s: &str) -> Option<f64> {
+	s.split_whitespace().next()?.parse().ok()
+}
+
+/// The state of a pcie link.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct PcieLink {
+	/// The negotiated speed in GT/s, `None` if unknown.
+	pub current_speed: Option<f64>,
+	/// The negotiated number of lanes, 0 if the link is down.
+	pub current_width: u8,
+	/// The maximum speed the device supports in GT/s.
+	pub max_speed: Option<f64>,
+	/// The maximum number of lanes the device supports.
+	pub max_width: u8
+}
+
+impl PcieLink {
+	/// The pcie generation of the negotiated speed, for example 4 for
+	/// 16 GT/s.
+	pub fn current_generation(&self) -> Option<u8> {
+		self.current_speed.and_then(generation)
+	}
+
+	/// The pcie generation of the maximum speed.
+	pub fn max_generation(&self) -> Option<u8> {
+		self.max_speed.and_then(generation)
+	}
+
+	/// Returns true if the link runs slower or with fewer lanes than the
+	/// device supports.
+	///
+	/// This can also be the case if the slot or the bridge does not support
+	/// more (see [`PciDevice::parent`]) or if the device lowers the speed
+	/// to save power while idle, which gpus do.
+	pub fn is_degraded(&self) -> bool {
+		let slower = match (self.current_speed, self.max_speed) {
+			(Some(c), Some(m)) => c < m,
+			_ => false
+		};
+		slower || self.current_width < self.max_width
+	}
+}
+
+fn generation(speed: f64) -> Option<u8> {
+	[2.5, 5.0, 8.0, 16.0, 32.0, 64.0].iter()
+		.position(|s| (speed - s).abs() < 0.1)
+		.map(|i| i as u8 + 1)
+}
+
+/// The AER errors of a device, grouped by severity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct AerCounters {
+	/// Errors which were corrected by the hardware, a rising count points
+	/// to a bad link (like `RxErr` or `BadTLP`).
+	pub correctable: AerErrors,
+	/// Errors where a transaction failed but the link still works.
+	pub nonfatal: AerErrors,
+	/// Errors which make the link unreliable.
+	pub fatal: AerErrors
+}
+
+impl AerCounters {
+	/// Returns the sum of all errors.
+	pub fn total(&self) -> u64 {
+		self.correctable.total + self.nonfatal.total + self.fatal.total
+	}
+}
+
+/// The error counters of one severity.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct AerErrors {
+	pub total: u64,
+	/// Every error type with its count, for example `("RxErr", 2)`.
+	pub counters: Vec<(String, u64)>
+}
+
+impl AerErrors {
+	fn parse(s: &str) -> Self {
+		let mut errors = Self::default();
+		for line in s.lines() {
+			let (name, count) = match line.split_once(' ')
+				.and_then(|(n, c)| Some((n, c.trim().parse().ok()?)))
+			{
+				Some(e) => e,
+				None => continue
+			};
+
+			if name.starts_with("TOTAL_ERR_") {
+				errors.total = count;
+			} else {
+				errors.counters.push((name.to_string(), count));
+			}
+		}
+		errors
+	}
+
+	/// Returns the count of an error type for example `BadTLP`.
+	pub fn get(&self, name: &str) -> Option<u64> {
+		self.counters.iter()
+			.find(|(n, _)| n == name)
+			.map(|(_, c)| *c)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn aer_errors() {
+		let errors = AerErrors::parse("\
+RxErr 3
+BadTLP 1
+BadDLLP 0
+Rollover 0
+Timeout 0
+NonFatalErr 0
+CorrIntErr 0
+HeaderOF 0
+TOTAL_ERR_COR 4
+");
+		assert_eq!(errors.total, 4);
+		assert_eq!(errors.counters.len(), 8);
+		assert_eq!(errors.get("RxErr"), Some(3));
+		assert_eq!(errors.get("BadTLP"), Some(1));
+	}
+
+	#[test]
+	fn link_speed() {
+		assert_eq!(parse_speed("8.0 GT/s PCIe"), Some(8.0));
+		assert_eq!(parse_speed("2.5 GT/s"), Some(2.5));
+		assert_eq!(parse_speed("Unknown"), None);
+
+		let link = PcieLink {
+			current_speed: Some(8.0),
+			current_width: 4,
+			max_speed: Some(16.0),
+			max_width: 4
+		};
+		assert_eq!(link.current_generation(), Some(3));
+		assert_eq!(link.max_generation(), Some(4));
+		assert!(link.is_degraded());
+	}
+}
@@ -0,0 +1,10 @@
+//! Get power information (batteries, ups, power supplies, sleep states, lid
+//! and dock).
+
+pub mod platform;
+pub mod sleep;
+pub mod wakeup;
+
+#[cfg(any(feature = "upower", feature = "zbus"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "upower", feature = "zbus"))))]
+pub mod upower;
@@ -0,0 +1,395 @@
+//! Get the lid, ac adapter, dock and usb-c state of a laptop, so power
+//! decisions can be made from one api.
+//!
+//! The lid is read from `/proc/acpi/button/lid` and falls back to the input
+//! switches, which requires access to `/dev/input/event*` (root or the
+//! `input` group).
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::power::platform::PlatformState;
+//!
+//! let state = PlatformState::read().unwrap();
+//! println!(
+//!     "lid {:?} ac {:?} docked {:?} external display {}",
+//!     state.lid, state.ac_online, state.docked,
+//!     state.usb_c_display
+//! );
+//! ```
+
+use crate::util::{read_sys_string, read_sys_value};
+
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+use std::os::unix::io::AsRawFd;
+
+const ACPI_LID_PATH: &str = "/proc/acpi/button/lid";
+const INPUT_PATH: &str = "/sys/class/input";
+const POWER_SUPPLY_PATH: &str = "/sys/class/power_supply";
+const PLATFORM_PATH: &str = "/sys/devices/platform";
+const TYPEC_PATH: &str = "/sys/class/typec";
+
+// see linux/input-event-codes.h
+const SW_LID: u32 = 0x00;
+const SW_DOCK: u32 = 0x05;
+/// EVIOCGSW(8) from linux/input.h
+const EVIOCGSW: u32 = (2 << 30) | (8 << 16) | ((b'E' as u32) << 8) | 0x1b;
+
+/// The svid of the DisplayPort alternate mode.
+const SVID_DISPLAYPORT: u16 = 0xff01;
+/// The svid of the Thunderbolt alternate mode.
+const SVID_THUNDERBOLT: u16 = 0x8087;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub enum LidState {
+	Open,
+	Closed
+}
+
+/// The state of the lid, `None` if there is no lid or it cannot be read.
+pub fn lid_state() -> io::Result<Option<LidState>> {
+	if let Some(state) = acpi_lid()? {
+		return Ok(Some(state))
+	}
+
+	Ok(input_switch(SW_LID)?.map(|closed| match closed {
+		true => LidState::Closed,
+		false => LidState::Open
+	}))
+}
+
+/// Reads `/proc/acpi/button/lid/*/state` which contains a line like
+/// `state:      open`.
+fn acpi_lid() -> io::Result<Option<LidState>> {
+	let dir = match fs::read_dir(ACPI_LID_PATH) {
+		Ok(d) => d,
+		Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+		Err(e) => return Err(e)
+	};
+
+	for entry in dir {
+		let raw = read_sys_string(entry?.path().join("state"))?;
+		if let Some(state) = parse_acpi_lid(&raw) {
+			return Ok(Some(state))
+		}
+	}
+	Ok(None)
+}
+
+fn parse_acpi_lid(raw: &str) -> Option<LidState> {
+	let (_, state) = raw.split_once(':')?;
+	match state.trim() {
+		"open" => Some(LidState::Open),
+		"closed" => Some(LidState::Closed),
+		_ => None
+	}
+}
+
+/// Returns the state of a switch of the first input device which has it,
+/// `None` if no device has it or the device cannot be opened.
+fn input_switch(code: u32) -> io::Result<Option<bool>> {
+	let dir = match fs::read_dir(INPUT_PATH) {
+		Ok(d) => d,
+		Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+		Err(e) => return Err(e)
+	};
+
+	for entry in dir {
+		let path = entry?.path();
+		// the bitmask of the switches the device has
+		let caps = match read_sys_string(path.join("capabilities/sw")) {
+			Ok(c) => c,
+			Err(_) => continue
+		};
+		let has_switch = caps.split_whitespace().last()
+			.and_then(|c| u64::from_str_radix(c, 16).ok())
+			.map(|c| c & (1 << code) != 0)
+			.unwrap_or(false);
+		if !has_switch {
+			continue
+		}
+
+		let event = match event_device(&path)? {
+			Some(e) => e,
+			None => continue
+		};
+		match read_switches(&event) {
+			Ok(state) => return Ok(Some(state & (1 << code) != 0)),
+			Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {},
+			Err(e) => return Err(e)
+		}
+	}
+	Ok(None)
+}
+
+/// Returns the `/dev/input/eventN` of an input device.
+fn event_device(input: &Path) -> io::Result<Option<PathBuf>> {
+	for entry in fs::read_dir(input)? {
+		let entry = entry?;
+		if let Some(name) = entry.file_name().to_str() {
+			if name.starts_with("event") {
+				return Ok(Some(Path::new("/dev/input").join(name)))
+			}
+		}
+	}
+	Ok(None)
+}
+
+fn read_switches(event: &Path) -> io::Result<u64> {
+	let file = fs::File::open(event)?;
+	let mut buf = [0u8; 8];
+	let r = unsafe {
+		libc::ioctl(file.as_raw_fd(), EVIOCGSW as _, buf.as_mut_ptr())
+	};
+	if r < 0 {
+		return Err(io::Error::last_os_error())
+	}
+	Ok(u64::from_le_bytes(buf))
+}
+
+/// Returns true if an ac adapter or a usb-c charger is connected, `None`
+/// if the machine reports no external power supply (most desktops).
+pub fn ac_online() -> io::Result<Option<bool>> {
+	let dir = match fs::read_dir(POWER_SUPPLY_PATH) {
+		Ok(d) => d,
+		Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+		Err(e) => return Err(e)
+	};
+
+	let mut online = None;
+	for entry in dir {
+		let path = entry?.path();
+		let kind = read_sys_string(path.join("type"))?;
+		// usb supplies are usb-c chargers (ucsi)
+		if kind != "Mains" && kind != "USB" {
+			continue
+		}
+		let is_online = read_sys_value::<u8>(path.join("online"))
+			.map(|o| o != 0)
+			.unwrap_or(false);
+		online = Some(online.unwrap_or(false) || is_online);
+	}
+	Ok(online)
+}
+
+/// Returns true if the laptop is in a docking station, from the acpi dock
+/// driver or the dock switch.
+///
+/// Usb-c docks are not docking stations in that sense, see
+/// [`TypecPort`] for them.
+pub fn docked() -> io::Result<Option<bool>> {
+	let dir = match fs::read_dir(PLATFORM_PATH) {
+		Ok(d) => d,
+		Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+		Err(e) => return Err(e)
+	};
+
+	let mut docked = None;
+	for entry in dir {
+		let entry = entry?;
+		let is_dock = entry.file_name().to_str()
+			.map(|n| n.starts_with("dock."))
+			.unwrap_or(false);
+		if !is_dock {
+			continue
+		}
+		let d = read_sys_value::<u8>(entry.path().join("docked"))? != 0;
+		docked = Some(docked.unwrap_or(false) || d);
+	}
+
+	match docked {
+		Some(d) => Ok(Some(d)),
+		None => input_switch(SW_DOCK)
+	}
+}
+
+/// A usb-c port for example `/sys/class/typec/port0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypecPort {
+	path: PathBuf
+}
+
+impl TypecPort {
+	/// Returns all usb-c ports the kernel manages (ucsi or tcpm).
+	pub fn all() -> io::Result<Vec<Self>> {
+		let dir = match fs::read_dir(TYPEC_PATH) {
+			Ok(d) => d,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+			Err(e) => return Err(e)
+		};
+
+		let mut list = vec![];
+		for entry in dir {
+			let entry = entry?;
+			// partners and cables are listed as port0-partner
+			let is_port = entry.file_name().to_str()
+				.map(|n| n.starts_with("port") && !n.contains('-'))
+				.unwrap_or(false);
+			if is_port {
+				list.push(Self::from_path(entry.path()));
+			}
+		}
+		list.sort_by(|a, b| a.path.cmp(&b.path));
+		Ok(list)
+	}
+
+	/// Creates a port from a path like `/sys/class/typec/port0`.
+	pub fn from_path(path: impl Into<PathBuf>) -> Self {
+		Self { path: path.into() }
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// The name of the port for example `port0`.
+	pub fn name(&self) -> Option<&str> {
+		self.path.file_name()?.to_str()
+	}
+
+	/// The current data role, `host` or `device`.
+	pub fn data_role(&self) -> io::Result<String> {
+		read_sys_string(self.path.join("data_role"))
+			.map(|r| selected(&r).into())
+	}
+
+	/// The current power role, `source` or `sink`, sink means the port
+	/// charges the laptop.
+	pub fn power_role(&self) -> io::Result<String> {
+		read_sys_string(self.path.join("power_role"))
+			.map(|r| selected(&r).into())
+	}
+
+	fn partner(&self) -> Option<PathBuf> {
+		let name = self.name()?;
+		let partner = self.path.with_file_name(format!("{}-partner", name));
+		partner.is_dir().then(|| partner)
+	}
+
+	/// Returns true if something is connected to the port.
+	pub fn is_connected(&self) -> bool {
+		self.partner().is_some()
+	}
+
+	/// The alternate modes the connected device supports.
+	pub fn alt_modes(&self) -> io::Result<Vec<AltMode>> {
+		let partner = match self.partner() {
+			Some(p) => p,
+			None => return Ok(vec![])
+		};
+
+		let mut list = vec![];
+		for entry in fs::read_dir(partner)? {
+			let path = entry?.path();
+			// alternate modes are named like port0-partner.0
+			let svid = match read_sys_string(path.join("svid")) {
+				Ok(s) => s,
+				Err(_) => continue
+			};
+			let svid = u16::from_str_radix(&svid, 16)
+				.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+			list.push(AltMode {
+				svid,
+				mode: read_sys_value(path.join("mode"))?,
+				active: read_sys_string(path.join("active"))? == "yes"
+			});
+		}
+		list.sort_by_key(|m| (m.svid, m.mode));
+		Ok(list)
+	}
+}
+
+/// Returns the value in brackets from a file like `[host] device`.
+fn selected(raw: &str) -> &str {
+	raw.split_whitespace()
+		.find_map(|r| r.strip_prefix('[')?.strip_suffix(']'))
+		.unwrap_or(raw)
+}
+
+/// An alternate mode of a usb-c partner, which carries another protocol
+/// over the usb-c cable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct AltMode {
+	/// The standard or vendor id, for example `0xff01` for DisplayPort.
+	pub svid: u16,
+	pub mode: u32,
+	/// True if the mode was entered.
+	pub active: bool
+}
+
+impl AltMode {
+	pub fn is_displayport(&self) -> bool {
+		self.svid == SVID_DISPLAYPORT
+	}
+
+	pub fn is_thunderbolt(&self) -> bool {
+		self.svid == SVID_THUNDERBOLT
+	}
+}
+
+/// The lid, power and dock state in one call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct PlatformState {
+	pub lid: Option<LidState>,
+	pub ac_online: Option<bool>,
+	pub docked: Option<bool>,
+	/// True if a usb-c port is in DisplayPort or Thunderbolt mode, which
+	/// mostly means an external display or usb-c dock is connected.
+	pub usb_c_display: bool
+}
+
+impl PlatformState {
+	pub fn read() -> io::Result<Self> {
+		let mut usb_c_display = false;
+		for port in TypecPort::all()? {
+			usb_c_display |= port.alt_modes()?.iter().any(|m| {
+				m.active && (m.is_displayport() || m.is_thunderbolt())
+			});
+		}
+
+		Ok(Self {
+			lid: lid_state()?,
+			ac_online: ac_online()?,
+			docked: docked()?,
+			usb_c_display
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn lid() {
+		assert_eq!(parse_acpi_lid("state:      open"), Some(LidState::Open));
+		assert_eq!(
+			parse_acpi_lid("state:      closed"),
+			Some(LidState::Closed)
+		);
+		assert_eq!(parse_acpi_lid("state:      unsupported"), None);
+	}
+
+	#[test]
+	fn roles() {
+		assert_eq!(selected("[host] device"), "host");
+		assert_eq!(selected("source [sink]"), "sink");
+		assert_eq!(selected("sink"), "sink");
+	}
+}
@@ -0,0 +1,156 @@
+//! Get the supported sleep states from /sys/power.
+
+use crate::util::{read_to_string, read_to_string_mut};
+#[cfg(feature = "async")]
+use crate::util::{read_to_string_async, read_to_string_mut_async};
+
+use std::io;
+use std::path::Path;
+
+/// Read the supported sleep states from /sys/power/state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SleepStates {
+	raw: String
+}
+
+impl SleepStates {
+	fn path() -> &'static Path {
+		Path::new("/sys/power/state")
+	}
+
+	#[cfg(test)]
+	fn from_string(raw: String) -> Self {
+		Self {raw}
+	}
+
+	/// Reads the file /sys/power/state.
+	pub fn read() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string(Self::path())?
+		})
+	}
+
+	/// Reloads information without allocating.
+	pub fn reload(&mut self) -> io::Result<()> {
+		read_to_string_mut(Self::path(), &mut self.raw)
+	}
+
+	/// Reads the file without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string_async(Self::path()).await?
+		})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(Self::path(), &mut self.raw).await
+	}
+
+	/// All supported states for example `freeze`, `mem` and `disk`.
+	pub fn states(&self) -> impl Iterator<Item=&str> {
+		self.raw.split_whitespace()
+	}
+
+	/// Returns true if suspend to ram is supported.
+	pub fn supports_mem(&self) -> bool {
+		self.states().any(|s| s == "mem")
+	}
+
+	/// Returns true if hibernation is supported.
+	pub fn supports_disk(&self) -> bool {
+		self.states().any(|s| s == "disk")
+	}
+
+	/// Returns true if suspend to idle is supported.
+	pub fn supports_freeze(&self) -> bool {
+		self.states().any(|s| s == "freeze")
+	}
+}
+
+/// Read the variants of suspend to ram from /sys/power/mem_sleep.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemSleep {
+	raw: String
+}
+
+impl MemSleep {
+	fn path() -> &'static Path {
+		Path::new("/sys/power/mem_sleep")
+	}
+
+	#[cfg(test)]
+	fn from_string(raw: String) -> Self {
+		Self {raw}
+	}
+
+	/// Reads the file /sys/power/mem_sleep.
+	pub fn read() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string(Self::path())?
+		})
+	}
+
+	/// Reloads information without allocating.
+	pub fn reload(&mut self) -> io::Result<()> {
+		read_to_string_mut(Self::path(), &mut self.raw)
+	}
+
+	/// Reads the file without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string_async(Self::path()).await?
+		})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(Self::path(), &mut self.raw).await
+	}
+
+	/// All supported variants for example `s2idle`, `shallow` and `deep`.
+	pub fn modes(&self) -> impl Iterator<Item=&str> {
+		self.raw.split_whitespace()
+			.map(|m| m.trim_start_matches('[').trim_end_matches(']'))
+	}
+
+	/// The variant which is used when suspending to ram.
+	pub fn current(&self) -> Option<&str> {
+		self.raw.split_whitespace()
+			.find(|m| m.starts_with('['))
+			.map(|m| m.trim_start_matches('[').trim_end_matches(']'))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sleep_states() {
+		let states = SleepStates::from_string("freeze mem disk\n".into());
+		assert!(states.supports_mem());
+		assert!(states.supports_disk());
+		assert!(states.supports_freeze());
+
+		let states = SleepStates::from_string("freeze\n".into());
+		assert!(!states.supports_mem());
+		assert_eq!(states.states().count(), 1);
+	}
+
+	#[test]
+	fn mem_sleep() {
+		let mem = MemSleep::from_string("s2idle [deep]\n".into());
+		let modes: Vec<_> = mem.modes().collect();
+		assert_eq!(modes, ["s2idle", "deep"]);
+		assert_eq!(mem.current(), Some("deep"));
+	}
+}
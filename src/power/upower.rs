@@ -0,0 +1,307 @@
+//! Connect to UPower, which lists batteries, ups and wireless peripherals.
+//!
+//! The `upower` feature uses libdbus, the `zbus` feature enables the pure
+//! rust backend in [`zbus`](self::zbus) with the same api.
+
+use std::time::Duration;
+#[cfg(feature = "upower")]
+use std::sync::Arc;
+
+#[cfg(feature = "upower")]
+use dbus::{Error, Path};
+#[cfg(feature = "upower")]
+use dbus::arg;
+#[cfg(feature = "upower")]
+use dbus::blocking::{SyncConnection, Proxy};
+#[cfg(feature = "upower")]
+use dbus::blocking::stdintf::org_freedesktop_dbus::Properties;
+
+#[cfg(feature = "zbus")]
+#[cfg_attr(docsrs, doc(cfg(feature = "zbus")))]
+pub mod zbus;
+
+const DBUS_NAME: &str = "org.freedesktop.UPower";
+const DBUS_PATH: &str = "/org/freedesktop/UPower";
+const DEVICE_INTERFACE: &str = "org.freedesktop.UPower.Device";
+#[cfg(feature = "upower")]
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+#[cfg(feature = "upower")]
+#[derive(Clone)]
+struct Dbus {
+	conn: Arc<SyncConnection>,
+	timeout: Duration
+}
+
+#[cfg(feature = "upower")]
+impl Dbus {
+	fn connect() -> Result<Self, Error> {
+		SyncConnection::new_system()
+			.map(Arc::new)
+			.map(|conn| Self { conn, timeout: TIMEOUT })
+	}
+
+	fn proxy<'a, 'b>(
+		&'b self,
+		path: impl Into<Path<'a>>
+	) -> Proxy<'a, &'b SyncConnection> {
+		self.conn.with_proxy(DBUS_NAME, path, self.timeout)
+	}
+}
+
+#[cfg(feature = "upower")]
+#[cfg_attr(docsrs, doc(cfg(feature = "upower")))]
+#[derive(Clone)]
+pub struct UPower {
+	dbus: Dbus
+}
+
+#[cfg(feature = "upower")]
+impl UPower {
+	/// Connects to the system bus with the default timeout of 2 seconds.
+	pub fn connect() -> Result<Self, Error> {
+		Dbus::connect()
+			.map(|dbus| Self { dbus })
+	}
+
+	/// Creates a UPower from an existing system bus connection.
+	pub fn from_connection(conn: Arc<SyncConnection>) -> Self {
+		Self {
+			dbus: Dbus { conn, timeout: TIMEOUT }
+		}
+	}
+
+	/// Returns true if the system is running on battery power.
+	pub fn on_battery(&self) -> Result<bool, Error> {
+		self.dbus.proxy(DBUS_PATH).get(DBUS_NAME, "OnBattery")
+	}
+
+	/// Returns all power devices known to UPower.
+	pub fn devices(&self) -> Result<Vec<Device>, Error> {
+		let (paths,): (Vec<Path<'static>>,) = self.dbus.proxy(DBUS_PATH)
+			.method_call(DBUS_NAME, "EnumerateDevices", ())?;
+
+		Ok(paths.into_iter()
+			.map(|path| Device {
+				dbus: self.dbus.clone(),
+				path
+			})
+			.collect())
+	}
+
+	/// Returns the composite device which is used by desktop environments
+	/// to display the overall battery state.
+	pub fn display_device(&self) -> Result<Device, Error> {
+		let (path,): (Path<'static>,) = self.dbus.proxy(DBUS_PATH)
+			.method_call(DBUS_NAME, "GetDisplayDevice", ())?;
+
+		Ok(Device {
+			dbus: self.dbus.clone(),
+			path
+		})
+	}
+}
+
+#[cfg(feature = "upower")]
+#[cfg_attr(docsrs, doc(cfg(feature = "upower")))]
+#[derive(Clone)]
+pub struct Device {
+	dbus: Dbus,
+	path: Path<'static>
+}
+
+#[cfg(feature = "upower")]
+impl Device {
+	fn get<R>(&self, name: &str) -> Result<R, Error>
+	where R: for<'b> arg::Get<'b> + 'static {
+		self.dbus.proxy(&self.path).get(DEVICE_INTERFACE, name)
+	}
+
+	/// The dbus object path for example
+	/// `/org/freedesktop/UPower/devices/battery_BAT0`.
+	pub fn object_path(&self) -> &str {
+		&self.path
+	}
+
+	/// The sysfs path or another identifier of the device.
+	pub fn native_path(&self) -> Result<String, Error> {
+		self.get("NativePath")
+	}
+
+	pub fn vendor(&self) -> Result<String, Error> {
+		self.get("Vendor")
+	}
+
+	pub fn model(&self) -> Result<String, Error> {
+		self.get("Model")
+	}
+
+	pub fn serial(&self) -> Result<String, Error> {
+		self.get("Serial")
+	}
+
+	pub fn kind(&self) -> Result<DeviceKind, Error> {
+		self.get::<u32>("Type")
+			.map(Into::into)
+	}
+
+	/// If the device is used to supply the system as a whole.
+	///
+	/// Returns false for wireless peripherals.
+	pub fn power_supply(&self) -> Result<bool, Error> {
+		self.get("PowerSupply")
+	}
+
+	/// If the device is a line power device, whether it is online.
+	pub fn online(&self) -> Result<bool, Error> {
+		self.get("Online")
+	}
+
+	/// If the device is a battery, whether it is present.
+	pub fn is_present(&self) -> Result<bool, Error> {
+		self.get("IsPresent")
+	}
+
+	pub fn state(&self) -> Result<DeviceState, Error> {
+		self.get::<u32>("State")
+			.map(Into::into)
+	}
+
+	/// The amount of energy left in percent (0 - 100).
+	pub fn percentage(&self) -> Result<f64, Error> {
+		self.get("Percentage")
+	}
+
+	/// The amount of energy currently available in Wh.
+	pub fn energy(&self) -> Result<f64, Error> {
+		self.get("Energy")
+	}
+
+	/// The amount of energy when the device is fully charged in Wh.
+	pub fn energy_full(&self) -> Result<f64, Error> {
+		self.get("EnergyFull")
+	}
+
+	/// The amount of energy the device was designed to hold in Wh.
+	pub fn energy_full_design(&self) -> Result<f64, Error> {
+		self.get("EnergyFullDesign")
+	}
+
+	/// The rate at which energy is drained (or charged) in W.
+	pub fn energy_rate(&self) -> Result<f64, Error> {
+		self.get("EnergyRate")
+	}
+
+	/// The voltage in V.
+	pub fn voltage(&self) -> Result<f64, Error> {
+		self.get("Voltage")
+	}
+
+	/// The estimated time until the device is empty.
+	///
+	/// Returns `None` if the value is not known (not discharging).
+	pub fn time_to_empty(&self) -> Result<Option<Duration>, Error> {
+		self.get::<i64>("TimeToEmpty")
+			.map(secs_to_duration)
+	}
+
+	/// The estimated time until the device is fully charged.
+	///
+	/// Returns `None` if the value is not known (not charging).
+	pub fn time_to_full(&self) -> Result<Option<Duration>, Error> {
+		self.get::<i64>("TimeToFull")
+			.map(secs_to_duration)
+	}
+
+	/// The capacity of the battery compared to its design capacity in
+	/// percent (0 - 100).
+	pub fn capacity(&self) -> Result<f64, Error> {
+		self.get("Capacity")
+	}
+
+	/// The temperature of the device in degrees Celsius.
+	pub fn temperature(&self) -> Result<f64, Error> {
+		self.get("Temperature")
+	}
+}
+
+fn secs_to_duration(secs: i64) -> Option<Duration> {
+	(secs > 0).then(|| Duration::from_secs(secs as u64))
+}
+
+dbus_enum! {
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+	#[cfg_attr(
+		feature = "serde",
+		derive(serde1::Serialize, serde1::Deserialize),
+		serde(crate = "serde1")
+	)]
+	pub enum DeviceKind: u32 {
+		/// unknown device (0), or a device type which is not known to this
+		/// crate.
+		Unknown,
+		LinePower = 1,
+		Battery = 2,
+		Ups = 3,
+		Monitor = 4,
+		Mouse = 5,
+		Keyboard = 6,
+		Pda = 7,
+		Phone = 8,
+		MediaPlayer = 9,
+		Tablet = 10,
+		Computer = 11,
+		GamingInput = 12,
+		Pen = 13,
+		Touchpad = 14,
+		Modem = 15,
+		Network = 16,
+		Headset = 17,
+		Speakers = 18,
+		Headphones = 19,
+		Video = 20,
+		OtherAudio = 21,
+		RemoteControl = 22,
+		Printer = 23,
+		Scanner = 24,
+		Camera = 25,
+		Wearable = 26,
+		Toy = 27,
+		BluetoothGeneric = 28
+	}
+}
+
+dbus_enum! {
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+	#[cfg_attr(
+		feature = "serde",
+		derive(serde1::Serialize, serde1::Deserialize),
+		serde(crate = "serde1")
+	)]
+	pub enum DeviceState: u32 {
+		/// the state is unknown (0), or a state which is not known to this
+		/// crate.
+		Unknown,
+		Charging = 1,
+		Discharging = 2,
+		Empty = 3,
+		FullyCharged = 4,
+		PendingCharge = 5,
+		PendingDischarge = 6
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn unknown_values() {
+		assert_eq!(DeviceKind::from(2), DeviceKind::Battery);
+		assert_eq!(DeviceKind::from(0), DeviceKind::Unknown(0));
+		assert_eq!(DeviceKind::from(29), DeviceKind::Unknown(29));
+		assert_eq!(DeviceKind::from(29).value(), 29);
+		assert_eq!(DeviceState::from(4), DeviceState::FullyCharged);
+		assert_eq!(u32::from(DeviceState::from(7)), 7);
+	}
+}
@@ -0,0 +1,229 @@
+//! UPower using the pure rust zbus backend.
+//!
+//! Has the same api as the libdbus version in [`upower`](super) except that
+//! calls don't have a timeout.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::power::upower::zbus::UPower;
+//!
+//! let upower = UPower::connect().unwrap();
+//! for device in upower.devices().unwrap() {
+//!     println!("{:?} {:?}", device.kind(), device.percentage());
+//! }
+//! ```
+
+use super::{
+	DBUS_NAME, DBUS_PATH, DEVICE_INTERFACE, DeviceKind, DeviceState,
+	secs_to_duration
+};
+
+use std::time::Duration;
+
+use zbus1::Error;
+use zbus1::blocking::{Connection, Proxy, ProxyBuilder};
+use zbus1::proxy::CacheProperties;
+use zbus1::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue};
+
+#[derive(Clone)]
+struct Dbus {
+	conn: Connection
+}
+
+impl Dbus {
+	fn connect() -> Result<Self, Error> {
+		Connection::system()
+			.map(|conn| Self { conn })
+	}
+
+	fn proxy<'a>(
+		&self,
+		path: ObjectPath<'a>,
+		interface: &'a str
+	) -> Result<Proxy<'a>, Error> {
+		ProxyBuilder::new(&self.conn)
+			.destination(DBUS_NAME)?
+			.path(path)?
+			.interface(interface)?
+			.cache_properties(CacheProperties::No)
+			.build()
+	}
+}
+
+#[derive(Clone)]
+pub struct UPower {
+	dbus: Dbus
+}
+
+impl UPower {
+	/// Connects to the system bus.
+	pub fn connect() -> Result<Self, Error> {
+		Dbus::connect()
+			.map(|dbus| Self { dbus })
+	}
+
+	/// Creates a UPower from an existing system bus connection.
+	pub fn from_connection(conn: Connection) -> Self {
+		Self {
+			dbus: Dbus { conn }
+		}
+	}
+
+	fn proxy(&self) -> Result<Proxy<'_>, Error> {
+		self.dbus.proxy(ObjectPath::from_static_str_unchecked(DBUS_PATH), DBUS_NAME)
+	}
+
+	/// Returns true if the system is running on battery power.
+	pub fn on_battery(&self) -> Result<bool, Error> {
+		self.proxy()?.get_property("OnBattery")
+	}
+
+	/// Returns all power devices known to UPower.
+	pub fn devices(&self) -> Result<Vec<Device>, Error> {
+		let paths: Vec<OwnedObjectPath> = self.proxy()?
+			.call("EnumerateDevices", &())?;
+
+		Ok(paths.into_iter()
+			.map(|path| Device {
+				dbus: self.dbus.clone(),
+				path
+			})
+			.collect())
+	}
+
+	/// Returns the composite device which is used by desktop environments
+	/// to display the overall battery state.
+	pub fn display_device(&self) -> Result<Device, Error> {
+		let path: OwnedObjectPath = self.proxy()?
+			.call("GetDisplayDevice", &())?;
+
+		Ok(Device {
+			dbus: self.dbus.clone(),
+			path
+		})
+	}
+}
+
+#[derive(Clone)]
+pub struct Device {
+	dbus: Dbus,
+	path: OwnedObjectPath
+}
+
+impl Device {
+	fn get<R>(&self, name: &str) -> Result<R, Error>
+	where
+		R: TryFrom<OwnedValue>,
+		R::Error: Into<Error>
+	{
+		self.dbus.proxy(self.path.as_ref(), DEVICE_INTERFACE)?
+			.get_property(name)
+	}
+
+	/// The dbus object path for example
+	/// `/org/freedesktop/UPower/devices/battery_BAT0`.
+	pub fn object_path(&self) -> &str {
+		self.path.as_str()
+	}
+
+	/// The sysfs path or another identifier of the device.
+	pub fn native_path(&self) -> Result<String, Error> {
+		self.get("NativePath")
+	}
+
+	pub fn vendor(&self) -> Result<String, Error> {
+		self.get("Vendor")
+	}
+
+	pub fn model(&self) -> Result<String, Error> {
+		self.get("Model")
+	}
+
+	pub fn serial(&self) -> Result<String, Error> {
+		self.get("Serial")
+	}
+
+	pub fn kind(&self) -> Result<DeviceKind, Error> {
+		self.get::<u32>("Type")
+			.map(Into::into)
+	}
+
+	/// If the device is used to supply the system as a whole.
+	///
+	/// Returns false for wireless peripherals.
+	pub fn power_supply(&self) -> Result<bool, Error> {
+		self.get("PowerSupply")
+	}
+
+	/// If the device is a line power device, whether it is online.
+	pub fn online(&self) -> Result<bool, Error> {
+		self.get("Online")
+	}
+
+	/// If the device is a battery, whether it is present.
+	pub fn is_present(&self) -> Result<bool, Error> {
+		self.get("IsPresent")
+	}
+
+	pub fn state(&self) -> Result<DeviceState, Error> {
+		self.get::<u32>("State")
+			.map(Into::into)
+	}
+
+	/// The amount of energy left in percent (0 - 100).
+	pub fn percentage(&self) -> Result<f64, Error> {
+		self.get("Percentage")
+	}
+
+	/// The amount of energy currently available in Wh.
+	pub fn energy(&self) -> Result<f64, Error> {
+		self.get("Energy")
+	}
+
+	/// The amount of energy when the device is fully charged in Wh.
+	pub fn energy_full(&self) -> Result<f64, Error> {
+		self.get("EnergyFull")
+	}
+
+	/// The amount of energy the device was designed to hold in Wh.
+	pub fn energy_full_design(&self) -> Result<f64, Error> {
+		self.get("EnergyFullDesign")
+	}
+
+	/// The rate at which energy is drained (or charged) in W.
+	pub fn energy_rate(&self) -> Result<f64, Error> {
+		self.get("EnergyRate")
+	}
+
+	/// The voltage in V.
+	pub fn voltage(&self) -> Result<f64, Error> {
+		self.get("Voltage")
+	}
+
+	/// The estimated time until the device is empty.
+	///
+	/// Returns `None` if the value is not known (not discharging).
+	pub fn time_to_empty(&self) -> Result<Option<Duration>, Error> {
+		self.get::<i64>("TimeToEmpty")
+			.map(secs_to_duration)
+	}
+
+	/// The estimated time until the device is fully charged.
+	///
+	/// Returns `None` if the value is not known (not charging).
+	pub fn time_to_full(&self) -> Result<Option<Duration>, Error> {
+		self.get::<i64>("TimeToFull")
+			.map(secs_to_duration)
+	}
+
+	/// The capacity of the battery compared to its design capacity in
+	/// percent (0 - 100).
+	pub fn capacity(&self) -> Result<f64, Error> {
+		self.get("Capacity")
+	}
+
+	/// The temperature of the device in degrees Celsius.
+	pub fn temperature(&self) -> Result<f64, Error> {
+		self.get("Temperature")
+	}
+}
@@ -0,0 +1,122 @@
+//! Get the wakeup sources from /sys/class/wakeup, to find out what keeps a
+//! device awake or woke it up.
+
+use crate::util::{read_sys_string, read_sys_value};
+
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const WAKEUP_PATH: &str = "/sys/class/wakeup";
+
+/// A wakeup source for example `/sys/class/wakeup/wakeup0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WakeupSource {
+	path: PathBuf
+}
+
+impl WakeupSource {
+	/// Returns all wakeup sources.
+	///
+	/// Returns an empty list if the kernel does not expose them (before
+	/// linux 5.4).
+	pub fn all() -> io::Result<Vec<Self>> {
+		let dir = match fs::read_dir(WAKEUP_PATH) {
+			Ok(d) => d,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+			Err(e) => return Err(e)
+		};
+
+		let mut list = dir.map(|e| e.map(|e| Self::from_path(e.path())))
+			.collect::<io::Result<Vec<_>>>()?;
+		list.sort_by(|a, b| a.path.cmp(&b.path));
+		Ok(list)
+	}
+
+	/// Returns all wakeup sources which are currently active, which means
+	/// they prevent the system from suspending.
+	pub fn active() -> io::Result<Vec<Self>> {
+		let mut list = vec![];
+		for source in Self::all()? {
+			if source.is_active()? {
+				list.push(source);
+			}
+		}
+		Ok(list)
+	}
+
+	/// Creates a wakeup source from a path like `/sys/class/wakeup/wakeup0`.
+	pub fn from_path(path: impl Into<PathBuf>) -> Self {
+		Self { path: path.into() }
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// The name of the wakeup source for example `PNP0C0D:00` or
+	/// `alarmtimer`.
+	pub fn name(&self) -> io::Result<String> {
+		read_sys_string(self.path.join("name"))
+	}
+
+	fn count(&self, name: &str) -> io::Result<u64> {
+		read_sys_value(self.path.join(name))
+	}
+
+	fn millis(&self, name: &str) -> io::Result<Duration> {
+		self.count(name)
+			.map(Duration::from_millis)
+	}
+
+	/// How many times the wakeup source was activated.
+	pub fn active_count(&self) -> io::Result<u64> {
+		self.count("active_count")
+	}
+
+	/// How many events were signaled.
+	pub fn event_count(&self) -> io::Result<u64> {
+		self.count("event_count")
+	}
+
+	/// How many times the wakeup source aborted a suspend.
+	pub fn wakeup_count(&self) -> io::Result<u64> {
+		self.count("wakeup_count")
+	}
+
+	/// How many times the timeout of the wakeup source expired.
+	pub fn expire_count(&self) -> io::Result<u64> {
+		self.count("expire_count")
+	}
+
+	/// How long the wakeup source is active, zero if it is not active.
+	pub fn active_time(&self) -> io::Result<Duration> {
+		self.millis("active_time_ms")
+	}
+
+	/// Returns true if the wakeup source currently prevents a suspend.
+	pub fn is_active(&self) -> io::Result<bool> {
+		self.active_time()
+			.map(|t| t > Duration::ZERO)
+	}
+
+	/// How long the wakeup source was active in total.
+	pub fn total_time(&self) -> io::Result<Duration> {
+		self.millis("total_time_ms")
+	}
+
+	/// The longest time the wakeup source was active.
+	pub fn max_time(&self) -> io::Result<Duration> {
+		self.millis("max_time_ms")
+	}
+
+	/// How long the wakeup source prevented the system from suspending.
+	pub fn prevent_suspend_time(&self) -> io::Result<Duration> {
+		self.millis("prevent_suspend_time_ms")
+	}
+
+	/// The monotonic time of the last change.
+	pub fn last_change(&self) -> io::Result<Duration> {
+		self.millis("last_change_ms")
+	}
+}
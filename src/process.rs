@@ -0,0 +1,348 @@
+//! Get information about processes from `/proc/[pid]`.
+//!
+//! Most values of processes owned by other users can only be read as root.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::process::Process;
+//!
+//! let process = Process::current();
+//! for socket in process.sockets().unwrap() {
+//!     println!("{:?} {} -> {}", socket.protocol, socket.local, socket.remote);
+//! }
+//! ```
+//!
+//! To see which users use the most memory or cpu time, [`users`] sums the
+//! resources of their processes.
+
+use crate::util::{read_to_string, read_sys_string};
+use crate::error::Error;
+use crate::network::sockets::{Sockets, Socket};
+use crate::unit::DataSize;
+use crate::system::{clock_ticks, page_size};
+
+use std::{fs, io};
+use std::path::PathBuf;
+use std::time::Duration;
+use std::collections::{HashMap, BTreeMap};
+
+/// A process for example `/proc/1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Process {
+	pid: u32
+}
+
+impl Process {
+	/// Returns all processes.
+	pub fn all() -> io::Result<Vec<Self>> {
+		let mut list = vec![];
+		for entry in fs::read_dir("/proc")? {
+			let pid = entry?.file_name().to_str()
+				.and_then(|n| n.parse().ok());
+			if let Some(pid) = pid {
+				list.push(Self::from_pid(pid));
+			}
+		}
+		list.sort();
+		Ok(list)
+	}
+
+	/// Returns the calling process.
+	pub fn current() -> Self {
+		Self::from_pid(std::process::id())
+	}
+
+	pub fn from_pid(pid: u32) -> Self {
+		Self { pid }
+	}
+
+	pub fn pid(&self) -> u32 {
+		self.pid
+	}
+
+	pub fn path(&self) -> PathBuf {
+		PathBuf::from(format!("/proc/{}", self.pid))
+	}
+
+	/// Returns true if the process is still running.
+	pub fn exists(&self) -> bool {
+		self.path().exists()
+	}
+
+	/// The name of the process, truncated to 15 bytes by the kernel.
+	pub fn name(&self) -> io::Result<String> {
+		read_sys_string(self.path().join("comm"))
+	}
+
+	/// The command line arguments, empty for kernel threads.
+	pub fn cmdline(&self) -> io::Result<Vec<String>> {
+		let raw = fs::read(self.path().join("cmdline"))?;
+		Ok(raw.split(|b| *b == 0)
+			.filter(|a| !a.is_empty())
+			.map(|a| String::from_utf8_lossy(a).into_owned())
+			.collect())
+	}
+
+	/// The real user id.
+	pub fn uid(&self) -> io::Result<u32> {
+		let path = self.path().join("status");
+		read_to_string(&path)?
+			.lines()
+			.find_map(|l| l.strip_prefix("Uid:"))
+			.and_then(|l| l.split_whitespace().next()?.parse().ok())
+			.ok_or_else(|| Error::missing_field(path, "Uid").into())
+	}
+
+	/// The memory the process has in ram (resident set size).
+	pub fn rss(&self) -> io::Result<DataSize> {
+		let path = self.path().join("statm");
+		let pages: u64 = read_to_string(&path)?
+			.split_whitespace()
+			.nth(1)
+			.and_then(|p| p.parse().ok())
+			.ok_or_else(|| Error::missing_field(path, "resident"))?;
+		Ok(DataSize::from_bytes((pages * page_size()?).into()))
+	}
+
+	/// The cpu time the process spent in user and kernel mode.
+	pub fn cpu_time(&self) -> io::Result<Duration> {
+		let ticks = self.stat()?.cpu_ticks();
+		Ok(Duration::from_secs_f64(ticks as f64 / clock_ticks()? as f64))
+	}
+
+	/// The number of threads.
+	pub fn threads(&self) -> io::Result<u64> {
+		self.stat().map(|s| s.threads)
+	}
+
+	fn stat(&self) -> io::Result<ProcessStat> {
+		let path = self.path().join("stat");
+		ProcessStat::parse(&read_to_string(&path)?)
+			.ok_or_else(|| Error::missing_field(path, "utime").into())
+	}
+
+	/// The number of open file descriptors.
+	///
+	/// Requires permission to read the file descriptors of the process.
+	pub fn fd_count(&self) -> io::Result<usize> {
+		Ok(fs::read_dir(self.path().join("fd"))?.count())
+	}
+
+	/// Returns the inodes of every socket the process has open.
+	///
+	/// Requires permission to read the file descriptors of the process.
+	pub fn socket_inodes(&self) -> io::Result<Vec<u64>> {
+		let mut inodes = vec![];
+		for entry in fs::read_dir(self.path().join("fd"))? {
+			// the fd might have been closed in the meantime
+			let link = match fs::read_link(entry?.path()) {
+				Ok(l) => l,
+				Err(_) => continue
+			};
+			if let Some(inode) = link.to_str().and_then(socket_inode) {
+				inodes.push(inode);
+			}
+		}
+		Ok(inodes)
+	}
+
+	/// Returns the tcp and udp sockets of the process.
+	///
+	/// Unix sockets are not included.
+	pub fn sockets(&self) -> io::Result<Vec<Socket>> {
+		let inodes = self.socket_inodes()?;
+		Ok(Sockets::read_all()?
+			.iter()
+			.flat_map(Sockets::entries)
+			.filter(|s| s.inode != 0 && inodes.contains(&s.inode))
+			.collect())
+	}
+}
+
+/// The fields of `/proc/[pid]/stat` which are needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ProcessStat {
+	utime: u64,
+	stime: u64,
+	threads: u64
+}
+
+impl ProcessStat {
+	fn parse(raw: &str) -> Option<Self> {
+		// the name can contain spaces and parentheses, so the fields start
+		// after the last `)` with the state as field 3
+		let mut fields = raw.rsplit_once(')')?.1.split_whitespace();
+		let mut field = |n: usize| -> Option<u64> {
+			fields.nth(n)?.parse().ok()
+		};
+		let utime = field(14 - 3)?;
+		let stime = field(0)?;
+		let threads = field(20 - 16)?;
+		Some(Self { utime, stime, threads })
+	}
+
+	fn cpu_ticks(&self) -> u64 {
+		self.utime + self.stime
+	}
+}
+
+/// The resources every process of a user uses together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct UserSummary {
+	pub uid: u32,
+	pub processes: u64,
+	pub threads: u64,
+	/// The sum of the resident set sizes, memory shared between processes
+	/// is counted multiple times.
+	pub rss: DataSize,
+	/// The cpu time the processes used since they were started, processes
+	/// which already exited are not included.
+	pub cpu_time: Duration,
+	/// The open file descriptors of the processes which could be read.
+	pub fds: u64,
+	/// How many processes had file descriptors which could not be read,
+	/// without root usually every process of another user.
+	pub unreadable_fds: u64
+}
+
+impl UserSummary {
+	fn new(uid: u32) -> Self {
+		Self {
+			uid,
+			processes: 0,
+			threads: 0,
+			rss: DataSize::from_bytes(0),
+			cpu_time: Duration::ZERO,
+			fds: 0,
+			unreadable_fds: 0
+		}
+	}
+}
+
+/// Groups every process by its user and sums the resources they use,
+/// sorted by uid.
+///
+/// Processes which exit while they are read are skipped.
+pub fn users() -> io::Result<Vec<UserSummary>> {
+	let ticks = clock_ticks()?;
+
+	let mut users: BTreeMap<u32, UserSummary> = BTreeMap::new();
+	for process in Process::all()? {
+		let read = || -> io::Result<_> {
+			Ok((process.uid()?, process.stat()?, process.rss()?))
+		};
+		let (uid, stat, rss) = match read() {
+			Ok(r) => r,
+			Err(_) => continue
+		};
+
+		let user = users.entry(uid).or_insert_with(|| UserSummary::new(uid));
+		user.processes += 1;
+		user.threads += stat.threads;
+		user.rss += rss;
+		user.cpu_time += Duration::from_secs_f64(
+			stat.cpu_ticks() as f64 / ticks as f64
+		);
+		match process.fd_count() {
+			Ok(c) => user.fds += c as u64,
+			Err(_) => user.unreadable_fds += 1
+		}
+	}
+	Ok(users.into_values().collect())
+}
+
+/// Returns the processes which have a socket open by the inode of the
+/// socket, processes which can't be read are skipped.
+pub(crate) fn processes_by_socket() -> io::Result<HashMap<u64, Vec<Process>>> {
+	let mut by_inode: HashMap<u64, Vec<Process>> = HashMap::new();
+	for process in Process::all()? {
+		let inodes = match process.socket_inodes() {
+			Ok(i) => i,
+			Err(_) => continue
+		};
+		for inode in inodes {
+			by_inode.entry(inode).or_default().push(process);
+		}
+	}
+	Ok(by_inode)
+}
+
+/// Parses a link like `socket:[12345]`.
+fn socket_inode(link: &str) -> Option<u64> {
+	link.strip_prefix("socket:[")?
+		.strip_suffix(']')?
+		.parse().ok()
+}
+
+/// A socket with the process which has it open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct ProcessSocket {
+	pub pid: u32,
+	pub name: Option<String>,
+	pub socket: Socket
+}
+
+/// Returns every tcp and udp socket with the process which has it open.
+///
+/// This is best effort: processes whose file descriptors can't be read
+/// (without root usually every process of another user) are skipped, a
+/// socket shared by multiple processes is returned once per process and
+/// sockets without a process (for example in `TimeWait`) are not
+/// returned.
+pub fn sockets() -> io::Result<Vec<ProcessSocket>> {
+	let by_inode = processes_by_socket()?;
+
+	let mut list = vec![];
+	for table in Sockets::read_all()? {
+		for socket in table.entries() {
+			let processes = match by_inode.get(&socket.inode) {
+				Some(p) if socket.inode != 0 => p,
+				_ => continue
+			};
+			for process in processes {
+				list.push(ProcessSocket {
+					pid: process.pid,
+					name: process.name().ok(),
+					socket: socket.clone()
+				});
+			}
+		}
+	}
+	Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn inode_link() {
+		assert_eq!(socket_inode("socket:[771236]"), Some(771236));
+		assert_eq!(socket_inode("pipe:[1234]"), None);
+		assert_eq!(socket_inode("/dev/null"), None);
+	}
+
+	#[test]
+	fn stat() {
+		let stat = ProcessStat::parse(
+			"1234 (tmux: server) S 1 1234 1234 0 -1 4194368 2381 0 0 0 \
+			512 97 0 0 20 0 3 0 8214 12390400 1024 18446744073709551615 1 1 \
+			0 0 0 0 0 3674116 134433283 0 0 0 17 5 0 0 0 0 0 0 0 0 0 0 0 0 0"
+		).unwrap();
+		assert_eq!(stat.utime, 512);
+		assert_eq!(stat.stime, 97);
+		assert_eq!(stat.threads, 3);
+		assert_eq!(stat.cpu_ticks(), 609);
+		assert!(ProcessStat::parse("1234 (sh").is_none());
+	}
+}
@@ -0,0 +1,143 @@
+//! List ptp hardware clocks from `/sys/class/ptp`.
+
+use crate::util::{read_sys_string, read_sys_value};
+
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+
+const PTP_PATH: &str = "/sys/class/ptp";
+
+/// A ptp clock for example `/sys/class/ptp/ptp0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PtpClock {
+	path: PathBuf
+}
+
+impl PtpClock {
+	/// Returns all ptp clocks.
+	pub fn all() -> io::Result<Vec<Self>> {
+		let dir = match fs::read_dir(PTP_PATH) {
+			Ok(d) => d,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+			Err(e) => return Err(e)
+		};
+
+		let mut list = dir.map(|e| e.map(|e| Self::from_path(e.path())))
+			.collect::<io::Result<Vec<_>>>()?;
+		list.sort_by(|a, b| a.path.cmp(&b.path));
+		Ok(list)
+	}
+
+	/// Creates a ptp clock from a path like `/sys/class/ptp/ptp0`.
+	pub fn from_path(path: impl Into<PathBuf>) -> Self {
+		Self { path: path.into() }
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// The name of the device for example `ptp0`.
+	pub fn name(&self) -> Option<&str> {
+		self.path.file_name()?.to_str()
+	}
+
+	/// The character device for example `/dev/ptp0`.
+	pub fn dev_path(&self) -> Option<PathBuf> {
+		self.name()
+			.map(|n| Path::new("/dev").join(n))
+	}
+
+	/// The name of the clock, usually the driver for example `igb` or
+	/// `ice-0000:17:00.0-clk`.
+	pub fn clock_name(&self) -> io::Result<String> {
+		read_sys_string(self.path.join("clock_name"))
+	}
+
+	/// The maximum frequency adjustment in parts per billion.
+	pub fn max_adjustment(&self) -> io::Result<i64> {
+		read_sys_value(self.path.join("max_adjustment"))
+	}
+
+	/// The number of programmable alarms.
+	pub fn alarms(&self) -> io::Result<u32> {
+		read_sys_value(self.path.join("n_alarms"))
+	}
+
+	/// The number of external time stamp channels.
+	pub fn external_timestamps(&self) -> io::Result<u32> {
+		read_sys_value(self.path.join("n_external_timestamps"))
+	}
+
+	/// The number of programmable periodic output signals.
+	pub fn periodic_outputs(&self) -> io::Result<u32> {
+		read_sys_value(self.path.join("n_periodic_outputs"))
+	}
+
+	/// The number of programmable pins.
+	pub fn programmable_pins(&self) -> io::Result<u32> {
+		read_sys_value(self.path.join("n_programmable_pins"))
+	}
+
+	/// Returns true if the clock can generate a pulse per second signal.
+	pub fn pps_available(&self) -> io::Result<bool> {
+		read_sys_value::<u8>(self.path.join("pps_available"))
+			.map(|v| v == 1)
+	}
+
+	/// Returns the network interfaces this clock belongs to, for example
+	/// `eth0`.
+	///
+	/// Virtual clocks (for example `kvm`) don't have a network interface.
+	pub fn interfaces(&self) -> io::Result<Vec<String>> {
+		let dir = match fs::read_dir(self.path.join("device/net")) {
+			Ok(d) => d,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+			Err(e) => return Err(e)
+		};
+
+		let mut list = vec![];
+		for entry in dir {
+			if let Some(name) = entry?.file_name().to_str() {
+				list.push(name.to_string());
+			}
+		}
+		list.sort();
+		Ok(list)
+	}
+
+	/// Reads all values at once.
+	pub fn info(&self) -> io::Result<PtpClockInfo> {
+		Ok(PtpClockInfo {
+			name: self.name().unwrap_or_default().to_string(),
+			clock_name: self.clock_name()?,
+			max_adjustment: self.max_adjustment()?,
+			alarms: self.alarms()?,
+			external_timestamps: self.external_timestamps()?,
+			periodic_outputs: self.periodic_outputs()?,
+			programmable_pins: self.programmable_pins()?,
+			pps_available: self.pps_available()?,
+			interfaces: self.interfaces()?
+		})
+	}
+}
+
+/// All values of a ptp clock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct PtpClockInfo {
+	pub name: String,
+	pub clock_name: String,
+	/// Parts per billion
+	pub max_adjustment: i64,
+	pub alarms: u32,
+	pub external_timestamps: u32,
+	pub periodic_outputs: u32,
+	pub programmable_pins: u32,
+	pub pps_available: bool,
+	pub interfaces: Vec<String>
+}
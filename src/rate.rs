@@ -0,0 +1,194 @@
+//! Turn monotonically increasing kernel counters into rates per second.
+//!
+//! Counters like the bytes in /proc/net/dev or the sectors in
+//! /proc/diskstats only ever increase, until they wrap around. A [`Rate`] is
+//! computed from two readings of such a counter and the time between them.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::rate::CounterSample;
+//! use linux_info::network::interfaces::NetDev;
+//! use std::{thread, time::Duration};
+//!
+//! let read = || {
+//!     let dev = NetDev::read().unwrap();
+//!     CounterSample::new(dev.by_name("eth0").unwrap().rx_bytes)
+//! };
+//!
+//! let earlier = read();
+//! thread::sleep(Duration::from_secs(1));
+//! let rate = read().rate(&earlier);
+//! println!("receiving {}/s", rate.as_data_size());
+//! ```
+
+use crate::unit::DataSize;
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// A counter exposed by the kernel.
+pub trait Counter: Copy {
+	/// Returns how much the counter increased since `earlier`, assuming it
+	/// wrapped around at most once or was reset to zero.
+	fn delta(self, earlier: Self) -> u64;
+}
+
+impl Counter for u32 {
+	fn delta(self, earlier: Self) -> u64 {
+		self.wrapping_sub(earlier).into()
+	}
+}
+
+impl Counter for u64 {
+	fn delta(self, earlier: Self) -> u64 {
+		if self >= earlier {
+			return self - earlier
+		}
+
+		// 32bit kernels expose `unsigned long` counters which wrap at
+		// u32::MAX, a 64bit counter never wraps so it must have been reset
+		match (u32::try_from(self), u32::try_from(earlier)) {
+			(Ok(n), Ok(e)) => n.delta(e),
+			_ => self
+		}
+	}
+}
+
+/// A value together with the time it was read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CounterSample<T> {
+	pub value: T,
+	pub taken_at: Instant
+}
+
+impl<T> CounterSample<T> {
+	/// Creates a sample taken now.
+	pub fn new(value: T) -> Self {
+		Self::at(value, Instant::now())
+	}
+
+	pub fn at(value: T, taken_at: Instant) -> Self {
+		Self { value, taken_at }
+	}
+
+	/// The time passed since the earlier sample.
+	pub fn elapsed_since(&self, earlier: &Self) -> Duration {
+		self.taken_at.saturating_duration_since(earlier.taken_at)
+	}
+
+	/// Computes the rate of a counter contained in both samples.
+	///
+	/// ```
+	/// # use linux_info::rate::CounterSample;
+	/// # use std::time::{Duration, Instant};
+	/// let now = Instant::now();
+	/// let a = CounterSample::at((10u64, 0u64), now);
+	/// let b = CounterSample::at((30u64, 5u64), now + Duration::from_secs(2));
+	/// assert_eq!(b.rate_by(&a, |v| v.0).per_second(), 10.0);
+	/// ```
+	pub fn rate_by<C, F>(&self, earlier: &Self, f: F) -> Rate
+	where
+		C: Counter,
+		F: Fn(&T) -> C
+	{
+		Rate::from_counters(f(&self.value), f(&earlier.value), self.elapsed_since(earlier))
+	}
+}
+
+impl<C: Counter> CounterSample<C> {
+	/// Computes the rate between the earlier sample and this one.
+	pub fn rate(&self, earlier: &Self) -> Rate {
+		self.rate_by(earlier, |c| *c)
+	}
+}
+
+/// A change per second.
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", transparent)
+)]
+pub struct Rate {
+	per_second: f64
+}
+
+impl Rate {
+	pub const fn new(per_second: f64) -> Self {
+		Self { per_second }
+	}
+
+	/// Creates a rate from a difference over the elapsed time.
+	///
+	/// Returns a rate of zero if no time elapsed.
+	pub fn from_delta(delta: u64, elapsed: Duration) -> Self {
+		let secs = elapsed.as_secs_f64();
+		if secs == 0.0 {
+			return Self::default()
+		}
+		Self::new(delta as f64 / secs)
+	}
+
+	/// Creates a rate from two readings of a counter.
+	pub fn from_counters<C: Counter>(
+		current: C,
+		earlier: C,
+		elapsed: Duration
+	) -> Self {
+		Self::from_delta(current.delta(earlier), elapsed)
+	}
+
+	pub fn per_second(&self) -> f64 {
+		self.per_second
+	}
+
+	pub fn per_minute(&self) -> f64 {
+		self.per_second * 60.0
+	}
+
+	/// Returns the rate multiplied by `factor`, for example the sector size.
+	pub fn scale(&self, factor: f64) -> Self {
+		Self::new(self.per_second * factor)
+	}
+
+	/// Interprets the rate as bytes per second.
+	pub fn as_data_size(&self) -> DataSize {
+		DataSize::from_bytes(self.per_second.max(0.0).round() as u128)
+	}
+}
+
+impl fmt::Display for Rate {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		fmt::Display::fmt(&self.per_second, f)?;
+		f.write_str("/s")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn wrap() {
+		assert_eq!(10u64.delta(4), 6);
+		assert_eq!(5u32.delta(u32::MAX - 4), 10);
+		// a 32bit counter stored in a u64
+		assert_eq!(5u64.delta(u64::from(u32::MAX) - 4), 10);
+		// a reset 64bit counter
+		assert_eq!(5u64.delta(u64::MAX - 4), 5);
+		assert_eq!(5u64.delta(u64::from(u32::MAX) + 10), 5);
+	}
+
+	#[test]
+	fn rates() {
+		let now = Instant::now();
+		let a = CounterSample::at(100u64, now);
+		let b = CounterSample::at(400u64, now + Duration::from_millis(1500));
+		assert_eq!(b.rate(&a).per_second(), 200.0);
+		assert_eq!(b.rate(&a).per_minute(), 12000.0);
+		assert_eq!(b.rate(&a).scale(512.0).as_data_size().as_bytes(), 102400);
+		// no time elapsed
+		assert_eq!(a.rate(&a), Rate::default());
+		assert_eq!(Rate::new(1.5).to_string(), "1.5/s");
+	}
+}
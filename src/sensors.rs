@@ -0,0 +1,734 @@
+//! Read hardware sensors exposed by hwmon in `/sys/class/hwmon`.
+//!
+//! Values are converted from the sysfs units (millidegree, microwatt) into
+//! degree Celsius and watt.
+//!
+//! [`SensorChip`] describes a chip with its bus and the mainboard it is on,
+//! so the output is self-describing for example `it8688 on X570 AORUS`.
+
+use crate::util::{read_sys_string, read_sys_value, read_sys_opt, SysDir};
+use crate::error::Error;
+
+use std::{fs, io, fmt};
+use std::path::{Path, PathBuf};
+
+const HWMON_PATH: &str = "/sys/class/hwmon";
+const DMI_PATH: &str = "/sys/class/dmi/id";
+
+/// A hwmon chip, for example `k10temp` or `amdgpu`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hwmon {
+	path: PathBuf
+}
+
+impl Hwmon {
+	/// Returns all hwmon chips.
+	pub fn all() -> io::Result<Vec<Self>> {
+		let mut list: Vec<_> = fs::read_dir(HWMON_PATH)?
+			.map(|e| e.map(|e| Self::from_path(e.path())))
+			.collect::<io::Result<_>>()?;
+		list.sort_by(|a, b| a.path.cmp(&b.path));
+		Ok(list)
+	}
+
+	/// Returns the first hwmon chip in `{path}/hwmon/`, which is where
+	/// devices (for example a gpu) expose their sensors.
+	pub fn from_device(path: impl AsRef<Path>) -> io::Result<Option<Self>> {
+		let dir = match fs::read_dir(path.as_ref().join("hwmon")) {
+			Ok(d) => d,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+			Err(e) => return Err(e)
+		};
+
+		let mut paths = dir.map(|e| e.map(|e| e.path()))
+			.collect::<io::Result<Vec<_>>>()?;
+		paths.sort();
+		Ok(paths.into_iter().next().map(Self::from_path))
+	}
+
+	/// Creates a hwmon from a path like `/sys/class/hwmon/hwmon0`.
+	pub fn from_path(path: impl Into<PathBuf>) -> Self {
+		Self { path: path.into() }
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// The name of the chip for example `k10temp`.
+	pub fn name(&self) -> io::Result<String> {
+		read_sys_string(self.path.join("name"))
+	}
+
+	/// Returns all temperature sensors.
+	///
+	/// Channels which can't be read right now (some chips return `EIO` or
+	/// `ENODATA` for unconnected sensors) are skipped.
+	pub fn temperatures(&self) -> io::Result<Vec<Temperature>> {
+		self.channels("temp")?
+			.into_iter()
+			.filter_map(|index| {
+				let value = self.milli("temp", index, "input").transpose()?;
+				Some(value.and_then(|celsius| Ok(Temperature {
+					index,
+					label: self.label("temp", index)?,
+					celsius,
+					max: self.milli("temp", index, "max")?,
+					crit: self.milli("temp", index, "crit")?
+				})))
+			})
+			.collect()
+	}
+
+	/// Returns all fans, fans which can't be read are skipped.
+	pub fn fans(&self) -> io::Result<Vec<Fan>> {
+		self.channels("fan")?
+			.into_iter()
+			.filter_map(|index| {
+				let rpm = read_channel(self.attr("fan", index, "input"))
+					.transpose()?;
+				Some(rpm.and_then(|rpm| Ok(Fan {
+					index,
+					label: self.label("fan", index)?,
+					rpm
+				})))
+			})
+			.collect()
+	}
+
+	/// Returns all power sensors.
+	///
+	/// Uses `powerN_average` and falls back to `powerN_input`, sensors which
+	/// can't be read are skipped.
+	pub fn power(&self) -> io::Result<Vec<Power>> {
+		self.channels("power")?
+			.into_iter()
+			.filter_map(|index| {
+				let watts = self.micro("power", index, "average")
+					.and_then(|w| match w {
+						Some(w) => Ok(Some(w)),
+						None => self.micro("power", index, "input")
+					})
+					.transpose()?;
+				Some(watts.and_then(|watts| Ok(Power {
+					index,
+					label: self.label("power", index)?,
+					watts
+				})))
+			})
+			.collect()
+	}
+
+	/// Returns true if a chassis intrusion was detected, `None` if the chip
+	/// has no intrusion detection (mostly Super I/O chips have one).
+	///
+	/// The alarm stays set until 0 is written to `intrusionN_alarm`.
+	pub fn intrusion_detected(&self) -> io::Result<Option<bool>> {
+		let mut detected = None;
+		for index in self.channels("intrusion")? {
+			let alarm: Option<u8> = read_sys_opt(
+				self.attr("intrusion", index, "alarm")
+			)?;
+			if let Some(alarm) = alarm {
+				detected = Some(detected.unwrap_or(false) || alarm != 0);
+			}
+		}
+		Ok(detected)
+	}
+
+	/// The bus the chip is connected to.
+	pub fn bus(&self) -> HwmonBus {
+		let device = match fs::canonicalize(self.path.join("device")) {
+			Ok(d) => d,
+			Err(_) => return HwmonBus::Virtual
+		};
+		let subsystem = fs::read_link(device.join("subsystem")).ok();
+		let subsystem = subsystem.as_ref()
+			.and_then(|s| s.file_name())
+			.and_then(|s| s.to_str())
+			.unwrap_or("");
+		let name = device.file_name()
+			.and_then(|n| n.to_str())
+			.unwrap_or("");
+
+		match subsystem {
+			"platform" => HwmonBus::Platform,
+			"isa" => HwmonBus::Isa,
+			"pci" => HwmonBus::Pci(name.into()),
+			// the hwmon of an nvme drive belongs to the nvme controller
+			// (nvme0), which links to its pci device unless it uses nvme
+			// over fabrics
+			"nvme" => fs::canonicalize(device.join("device")).ok()
+				.filter(|p| {
+					fs::read_link(p.join("subsystem"))
+						.map_or(false, |s| s.ends_with("pci"))
+				})
+				.and_then(|p| p.file_name()?.to_str().map(String::from))
+				.map(HwmonBus::Pci)
+				.unwrap_or_else(|| HwmonBus::Other(subsystem.into())),
+			// i2c devices are named like 0-0018
+			"i2c" => name.split_once('-')
+				.and_then(|(bus, addr)| Some(HwmonBus::I2c {
+					bus: bus.parse().ok()?,
+					address: u16::from_str_radix(addr, 16).ok()?
+				}))
+				.unwrap_or_else(|| HwmonBus::Other(subsystem.into())),
+			s => HwmonBus::Other(s.into())
+		}
+	}
+
+	/// Returns all pwm outputs which control the fans.
+	pub fn pwms(&self) -> io::Result<Vec<Pwm>> {
+		self.channels("pwm")
+			.map(|list| list.into_iter().map(|i| self.pwm(i)).collect())
+	}
+
+	/// Reads the temperatures, fans and power sensors, see [`HwmonReader`]
+	/// to sample them repeatedly.
+	pub fn reader(&self) -> io::Result<HwmonReader> {
+		HwmonReader::new(self)
+	}
+
+	/// Returns the pwm output with the given index, without checking if it
+	/// exists.
+	pub fn pwm(&self, index: u32) -> Pwm {
+		Pwm {
+			path: self.path.clone(),
+			index
+		}
+	}
+
+	fn attr(&self, kind: &str, index: u32, attr: &str) -> PathBuf {
+		self.path.join(format!("{}{}_{}", kind, index, attr))
+	}
+
+	fn label(&self, kind: &str, index: u32) -> io::Result<Option<String>> {
+		read_sys_opt(self.attr(kind, index, "label"))
+	}
+
+	fn milli(
+		&self,
+		kind: &str,
+		index: u32,
+		attr: &str
+	) -> io::Result<Option<f64>> {
+		read_channel::<i64>(self.attr(kind, index, attr))
+			.map(|v| v.map(|v| v as f64 / 1_000.0))
+	}
+
+	fn micro(
+		&self,
+		kind: &str,
+		index: u32,
+		attr: &str
+	) -> io::Result<Option<f64>> {
+		read_channel::<u64>(self.attr(kind, index, attr))
+			.map(|v| v.map(|v| v as f64 / 1_000_000.0))
+	}
+
+	/// Returns the sorted indexes of all channels of the given kind.
+	fn channels(&self, kind: &str) -> io::Result<Vec<u32>> {
+		let mut list = vec![];
+		for entry in fs::read_dir(&self.path)? {
+			let entry = entry?;
+			let name = entry.file_name();
+			if let Some(index) = name.to_str()
+				.and_then(|n| parse_channel(n, kind))
+			{
+				list.push(index);
+			}
+		}
+		list.sort_unstable();
+		list.dedup();
+		Ok(list)
+	}
+}
+
+/// Reads the sensor values of a hwmon chip repeatedly.
+///
+/// The channels and labels are read once. [`reload`](Self::reload) only
+/// reads the current values, with the directory kept open and without
+/// allocating.
+#[derive(Debug)]
+pub struct HwmonReader {
+	dir: SysDir,
+	temperatures: Vec<Temperature>,
+	fans: Vec<Fan>,
+	power: Vec<Power>,
+	/// The attribute with the value of every temperature, fan and power
+	/// sensor in this order.
+	inputs: Vec<String>
+}
+
+impl HwmonReader {
+	fn new(hwmon: &Hwmon) -> io::Result<Self> {
+		let temperatures = hwmon.temperatures()?;
+		let fans = hwmon.fans()?;
+		let power = hwmon.power()?;
+
+		let mut inputs: Vec<_> = temperatures.iter()
+			.map(|t| format!("temp{}_input", t.index))
+			.chain(fans.iter().map(|f| format!("fan{}_input", f.index)))
+			.collect();
+		for p in &power {
+			let average = format!("power{}_average", p.index);
+			if hwmon.path.join(&average).exists() {
+				inputs.push(average);
+			} else {
+				inputs.push(format!("power{}_input", p.index));
+			}
+		}
+
+		Ok(Self {
+			dir: SysDir::open(&hwmon.path)?,
+			temperatures,
+			fans,
+			power,
+			inputs
+		})
+	}
+
+	pub fn path(&self) -> &Path {
+		self.dir.path()
+	}
+
+	/// Reads the current values.
+	///
+	/// A sensor which can't be read right now keeps its previous value.
+	pub fn reload(&mut self) -> io::Result<()> {
+		let dir = &mut self.dir;
+		let mut inputs = self.inputs.iter();
+		let mut read = |attr: &str| -> io::Result<Option<i64>> {
+			match dir.read_value(attr) {
+				Ok(v) => Ok(Some(v)),
+				Err(e) if is_unavailable(&e) => Ok(None),
+				Err(e) => Err(e)
+			}
+		};
+
+		for temp in &mut self.temperatures {
+			if let Some(milli) = read(inputs.next().unwrap())? {
+				temp.celsius = milli as f64 / 1_000.0;
+			}
+		}
+		for fan in &mut self.fans {
+			if let Some(rpm) = read(inputs.next().unwrap())? {
+				fan.rpm = rpm as u32;
+			}
+		}
+		for power in &mut self.power {
+			if let Some(micro) = read(inputs.next().unwrap())? {
+				power.watts = micro as f64 / 1_000_000.0;
+			}
+		}
+		Ok(())
+	}
+
+	pub fn temperatures(&self) -> &[Temperature] {
+		&self.temperatures
+	}
+
+	pub fn fans(&self) -> &[Fan] {
+		&self.fans
+	}
+
+	pub fn power(&self) -> &[Power] {
+		&self.power
+	}
+}
+
+/// Where a hwmon chip is connected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub enum HwmonBus {
+	/// A platform device, like Super I/O chips (`it87`, `nct6775`) or
+	/// `coretemp`.
+	Platform,
+	Isa,
+	/// A pci device with its address, for example a gpu or nvme drive.
+	///
+	/// nvme drives use the address of their pci controller, nvme over
+	/// fabrics is `Other("nvme")`.
+	Pci(String),
+	/// A chip on the i2c or smbus, for example the temperature sensors of
+	/// memory modules (`jc42`, `spd5118`).
+	I2c {
+		bus: u32,
+		address: u16
+	},
+	/// The chip has no device, for example `acpitz`.
+	Virtual,
+	Other(String)
+}
+
+impl HwmonBus {
+	/// Returns true if the chip is part of the mainboard (Super I/O chips
+	/// and smbus sensors).
+	pub fn is_on_board(&self) -> bool {
+		matches!(self, Self::Platform | Self::Isa | Self::I2c { .. })
+	}
+}
+
+/// The mainboard from `/sys/class/dmi/id`, only available on machines with
+/// dmi (x86).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct Board {
+	/// For example `Gigabyte Technology Co., Ltd.`.
+	pub vendor: Option<String>,
+	/// For example `X570 AORUS ELITE`.
+	pub name: String
+}
+
+impl Board {
+	/// Reads the board, returns `None` if the machine has no dmi.
+	pub fn read() -> io::Result<Option<Self>> {
+		let path = Path::new(DMI_PATH);
+		let name = match read_sys_opt::<String>(path.join("board_name"))? {
+			Some(n) if !n.is_empty() => n,
+			_ => return Ok(None)
+		};
+
+		Ok(Some(Self {
+			vendor: read_sys_opt::<String>(path.join("board_vendor"))?
+				.filter(|v| !v.is_empty()),
+			name
+		}))
+	}
+}
+
+/// A hwmon chip with the context needed to describe it, for example
+/// `it8688 on X570 AORUS ELITE`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct SensorChip {
+	/// The name of the chip for example `it8688` or `k10temp`.
+	pub name: String,
+	pub bus: HwmonBus,
+	/// The mainboard, only set for chips which are part of it.
+	pub board: Option<Board>,
+	/// The path of the hwmon for example `/sys/class/hwmon/hwmon3`.
+	pub path: PathBuf
+}
+
+impl SensorChip {
+	/// Returns every hwmon chip with its bus and the board it is on.
+	pub fn all() -> io::Result<Vec<Self>> {
+		let board = Board::read()?;
+		Hwmon::all()?
+			.into_iter()
+			.map(|hwmon| {
+				let bus = hwmon.bus();
+				Ok(Self {
+					name: hwmon.name()?,
+					board: board.clone().filter(|_| bus.is_on_board()),
+					bus,
+					path: hwmon.path
+				})
+			})
+			.collect()
+	}
+
+	pub fn hwmon(&self) -> Hwmon {
+		Hwmon::from_path(self.path.clone())
+	}
+}
+
+impl fmt::Display for SensorChip {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(&self.name)?;
+		match &self.bus {
+			HwmonBus::I2c { bus, address } => {
+				write!(f, " on smbus {}-{:04x}", bus, address)?;
+			},
+			HwmonBus::Pci(addr) => write!(f, " on pci {}", addr)?,
+			_ => {}
+		}
+		match &self.board {
+			Some(board) => write!(f, " on {}", board.name),
+			None => Ok(())
+		}
+	}
+}
+
+/// Like [`read_sys_opt`] but also returns `None` if the sensor can't be read
+/// right now.
+fn read_channel<T>(path: PathBuf) -> io::Result<Option<T>>
+where T: std::str::FromStr {
+	match read_sys_opt(path) {
+		Err(e) if is_unavailable(&e) => Ok(None),
+		r => r
+	}
+}
+
+/// Drivers return these errors for sensors which are not connected or
+/// currently not readable, for example nvme drives in a low power state.
+fn is_unavailable(e: &io::Error) -> bool {
+	matches!(
+		Error::raw_os_error_of(e),
+		Some(libc::EIO | libc::ENODATA | libc::EAGAIN | libc::ENXIO)
+	)
+}
+
+/// Parses files like `temp1_input` or `pwm1` and returns the index.
+fn parse_channel(name: &str, kind: &str) -> Option<u32> {
+	let rest = name.strip_prefix(kind)?;
+	let index = match rest.split_once('_') {
+		Some((index, _)) => index,
+		None => rest
+	};
+	index.parse().ok()
+}
+
+/// A pwm output of a hwmon chip (`pwmN`), which controls the speed of a fan.
+///
+/// Changing values requires the `fan-control` feature and root permissions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pwm {
+	path: PathBuf,
+	index: u32
+}
+
+impl Pwm {
+	pub fn index(&self) -> u32 {
+		self.index
+	}
+
+	fn file(&self, suffix: &str) -> PathBuf {
+		self.path.join(format!("pwm{}{}", self.index, suffix))
+	}
+
+	/// The current duty cycle (0 - 255).
+	pub fn value(&self) -> io::Result<u8> {
+		read_sys_value(self.file(""))
+	}
+
+	/// The current duty cycle in percent (0 - 100).
+	pub fn percent(&self) -> io::Result<f64> {
+		self.value()
+			.map(|v| v as f64 / 255.0 * 100.0)
+	}
+
+	/// How the fan is controlled.
+	pub fn enable(&self) -> io::Result<PwmEnable> {
+		read_sys_value::<u8>(self.file("_enable"))
+			.map(PwmEnable::from_u8)
+	}
+
+	/// Returns `None` if the chip does not support changing the mode.
+	pub fn mode(&self) -> io::Result<Option<PwmMode>> {
+		read_sys_opt::<u8>(self.file("_mode"))
+			.map(|m| m.map(PwmMode::from_u8))
+	}
+
+	/// Sets the duty cycle (0 - 255).
+	///
+	/// This only has an effect if the pwm is in manual mode, see
+	/// [`Pwm::set_enable`].
+	#[cfg(feature = "fan-control")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "fan-control")))]
+	pub fn set_value(&self, value: u8) -> io::Result<()> {
+		fs::write(self.file(""), value.to_string())
+	}
+
+	/// Sets the duty cycle in percent (0 - 100).
+	#[cfg(feature = "fan-control")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "fan-control")))]
+	pub fn set_percent(&self, percent: f64) -> io::Result<()> {
+		let value = (percent.clamp(0.0, 100.0) / 100.0 * 255.0).round();
+		self.set_value(value as u8)
+	}
+
+	/// Changes how the fan is controlled.
+	///
+	/// Make sure to set the mode back to automatic when your program exits.
+	#[cfg(feature = "fan-control")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "fan-control")))]
+	pub fn set_enable(&self, enable: PwmEnable) -> io::Result<()> {
+		fs::write(self.file("_enable"), enable.as_u8().to_string())
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub enum PwmEnable {
+	/// No fan speed control, the fan runs at full speed.
+	FullSpeed,
+	/// Manual control via [`Pwm::value`].
+	Manual,
+	/// Automatic control by the chip, the meaning of the value depends on
+	/// the driver (usually 2).
+	Automatic(u8)
+}
+
+impl PwmEnable {
+	pub fn from_u8(num: u8) -> Self {
+		match num {
+			0 => Self::FullSpeed,
+			1 => Self::Manual,
+			n => Self::Automatic(n)
+		}
+	}
+
+	pub fn as_u8(&self) -> u8 {
+		match self {
+			Self::FullSpeed => 0,
+			Self::Manual => 1,
+			Self::Automatic(n) => *n
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub enum PwmMode {
+	/// Direct current mode
+	Dc,
+	/// Pulse width modulation
+	Pwm
+}
+
+impl PwmMode {
+	pub fn from_u8(num: u8) -> Self {
+		match num {
+			0 => Self::Dc,
+			_ => Self::Pwm
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct Temperature {
+	pub index: u32,
+	pub label: Option<String>,
+	/// Degree Celsius
+	pub celsius: f64,
+	pub max: Option<f64>,
+	pub crit: Option<f64>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct Fan {
+	pub index: u32,
+	pub label: Option<String>,
+	/// Revolutions per minute
+	pub rpm: u32
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct Power {
+	pub index: u32,
+	pub label: Option<String>,
+	pub watts: f64
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn channel_names() {
+		assert_eq!(parse_channel("temp1_input", "temp"), Some(1));
+		assert_eq!(parse_channel("temp12_crit", "temp"), Some(12));
+		assert_eq!(parse_channel("fan2_label", "temp"), None);
+		assert_eq!(parse_channel("pwm1", "pwm"), Some(1));
+		assert_eq!(parse_channel("pwm2_enable", "pwm"), Some(2));
+		assert_eq!(parse_channel("pwmconfig", "pwm"), None);
+		assert_eq!(parse_channel("power1_cap_max", "power"), Some(1));
+		assert_eq!(parse_channel("intrusion0_alarm", "intrusion"), Some(0));
+	}
+
+	#[test]
+	fn unavailable_channels() {
+		let err = |n| io::Error::from_raw_os_error(n);
+		assert!(is_unavailable(&err(libc::EIO)));
+		assert!(is_unavailable(&err(libc::ENODATA)));
+		assert!(!is_unavailable(&err(libc::EACCES)));
+		assert!(!is_unavailable(&io::ErrorKind::InvalidData.into()));
+	}
+
+	#[test]
+	fn chip_display() {
+		let board = Board {
+			vendor: None,
+			name: "X570 AORUS ELITE".into()
+		};
+		let mut chip = SensorChip {
+			name: "it8688".into(),
+			bus: HwmonBus::Platform,
+			board: Some(board.clone()),
+			path: "/sys/class/hwmon/hwmon3".into()
+		};
+		assert_eq!(chip.to_string(), "it8688 on X570 AORUS ELITE");
+
+		chip.name = "jc42".into();
+		chip.bus = HwmonBus::I2c { bus: 0, address: 0x18 };
+		assert_eq!(chip.to_string(), "jc42 on smbus 0-0018 on X570 AORUS ELITE");
+
+		chip.name = "amdgpu".into();
+		chip.bus = HwmonBus::Pci("0000:0b:00.0".into());
+		chip.board = None;
+		assert_eq!(chip.to_string(), "amdgpu on pci 0000:0b:00.0");
+	}
+
+	#[test]
+	fn nvme_bus() {
+		use std::os::unix::fs::symlink;
+
+		let dir = std::env::temp_dir()
+			.join(format!("linux-info-nvme-bus-{}", std::process::id()));
+		let pci = dir.join("devices/0000:01:00.0");
+		let nvme = pci.join("nvme/nvme0");
+		let hwmon = nvme.join("hwmon0");
+		fs::create_dir_all(&hwmon).unwrap();
+		fs::create_dir_all(dir.join("bus/pci")).unwrap();
+		fs::create_dir_all(dir.join("class/nvme")).unwrap();
+		symlink(dir.join("bus/pci"), pci.join("subsystem")).unwrap();
+		symlink(dir.join("class/nvme"), nvme.join("subsystem")).unwrap();
+		symlink(&nvme, hwmon.join("device")).unwrap();
+
+		// nvme over fabrics has no pci device
+		let bus = Hwmon::from_path(&hwmon).bus();
+		assert_eq!(bus, HwmonBus::Other("nvme".into()));
+
+		symlink(&pci, nvme.join("device")).unwrap();
+		let bus = Hwmon::from_path(&hwmon).bus();
+		fs::remove_dir_all(&dir).unwrap();
+		assert_eq!(bus, HwmonBus::Pci("0000:01:00.0".into()));
+	}
+}
@@ -0,0 +1,572 @@
+//! Collect cpu, memory, uptime, load average, mounts, disks, network
+//! interfaces and optionally bios information in a single call.
+//!
+//! A section which cannot be read is `None` and the reason is stored in
+//! [`Snapshot::errors`], the remaining sections are still collected.
+//!
+//! ## Example
+//! ```
+//! use linux_info::snapshot::{Snapshot, SnapshotOptions};
+//!
+//! let snapshot = Snapshot::collect(SnapshotOptions::default());
+//! for error in &snapshot.errors {
+//!     eprintln!("{}: {}", error.section, error.message);
+//! }
+//! ```
+
+use crate::cpu::{Cpu, OwnedCpuEntry};
+use crate::memory::Memory;
+use crate::system::{Uptime, LoadAvg};
+use crate::storage::{MountPoints, OwnedMountPoint, DiskStats, DiskStat};
+use crate::network::interfaces::{Interface, NetDev, NetDevEntry};
+use crate::unit::DataSize;
+use crate::error::Error;
+use crate::diff::{Change, diff_by_key};
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+use crate::bios::{Bios, OwnedBiosInfo, OwnedSystemInfo};
+
+use std::{io, fmt};
+use std::time::{Duration, SystemTime};
+
+/// Selects which sections get collected.
+///
+/// By default every section except bios is collected, since reading the
+/// bios tables usually requires root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapshotOptions {
+	pub cpu: bool,
+	pub memory: bool,
+	pub uptime: bool,
+	pub load_avg: bool,
+	pub mounts: bool,
+	pub disks: bool,
+	pub interfaces: bool,
+	/// Only has an effect on x86_64 and aarch64.
+	pub bios: bool
+}
+
+impl SnapshotOptions {
+	/// Collects every section including bios.
+	pub fn all() -> Self {
+		Self {
+			bios: true,
+			..Self::default()
+		}
+	}
+}
+
+impl Default for SnapshotOptions {
+	fn default() -> Self {
+		Self {
+			cpu: true,
+			memory: true,
+			uptime: true,
+			load_avg: true,
+			mounts: true,
+			disks: true,
+			interfaces: true,
+			bios: false
+		}
+	}
+}
+
+/// The state of the whole system at one point in time.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct Snapshot {
+	/// When the collection started.
+	pub taken_at: SystemTime,
+	/// Every entry from /proc/cpuinfo.
+	pub cpu: Option<Vec<OwnedCpuEntry>>,
+	pub memory: Option<MemorySnapshot>,
+	pub uptime: Option<Duration>,
+	pub load_avg: Option<LoadAvgSnapshot>,
+	pub mounts: Option<Vec<OwnedMountPoint>>,
+	pub disks: Option<Vec<DiskStat>>,
+	pub interfaces: Option<Vec<InterfaceSnapshot>>,
+	#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+	pub bios: Option<BiosSnapshot>,
+	/// The errors of every section which could not be collected.
+	pub errors: Vec<SectionError>
+}
+
+impl Snapshot {
+	/// Collects every section enabled in the options.
+	///
+	/// This never fails, errors are stored per section.
+	pub fn collect(options: SnapshotOptions) -> Self {
+		let mut errors = vec![];
+		let taken_at = SystemTime::now();
+
+		let cpu = options.cpu.then(|| capture(&mut errors, Section::Cpu, || {
+			let cpu = Cpu::read()?;
+			Ok(cpu.entries().map(|e| e.to_owned_entry()).collect())
+		})).flatten();
+
+		let memory = options.memory.then(|| capture(
+			&mut errors,
+			Section::Memory,
+			|| Memory::read().map(|m| MemorySnapshot::from_memory(&m))
+		)).flatten();
+
+		let uptime = options.uptime.then(|| capture(
+			&mut errors,
+			Section::Uptime,
+			|| Uptime::read()?.uptime()
+				.ok_or_else(|| Error::missing_field("/proc/uptime", "uptime").into())
+		)).flatten();
+
+		let load_avg = options.load_avg.then(|| capture(
+			&mut errors,
+			Section::LoadAvg,
+			|| {
+				let (one, five, fifteen) = LoadAvg::read()?.average()
+					.ok_or_else(|| Error::parse(
+						"/proc/loadavg",
+						Some(1),
+						"load average"
+					))?;
+				Ok(LoadAvgSnapshot { one, five, fifteen })
+			}
+		)).flatten();
+
+		let mounts = options.mounts.then(|| capture(
+			&mut errors,
+			Section::Mounts,
+			|| {
+				let points = MountPoints::read()?;
+				Ok(points.points().map(|p| p.to_owned_point()).collect())
+			}
+		)).flatten();
+
+		let disks = options.disks.then(|| capture(
+			&mut errors,
+			Section::Disks,
+			|| Ok(DiskStats::read()?.entries().collect())
+		)).flatten();
+
+		let interfaces = options.interfaces.then(|| capture(
+			&mut errors,
+			Section::Interfaces,
+			InterfaceSnapshot::all
+		)).flatten();
+
+		#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+		let bios = options.bios.then(|| capture(
+			&mut errors,
+			Section::Bios,
+			|| Bios::read().map(|b| BiosSnapshot::from_bios(&b))
+		)).flatten();
+
+		Self {
+			taken_at,
+			cpu,
+			memory,
+			uptime,
+			load_avg,
+			mounts,
+			disks,
+			interfaces,
+			#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+			bios,
+			errors
+		}
+	}
+
+	/// Returns true if every enabled section could be collected.
+	pub fn is_complete(&self) -> bool {
+		self.errors.is_empty()
+	}
+
+	/// Returns what changed compared to a newer snapshot.
+	///
+	/// Sections which are missing in either snapshot are skipped.
+	pub fn diff(&self, newer: &Self) -> SnapshotDiff {
+		SnapshotDiff {
+			cpu: diff_sections(&self.cpu, &newer.cpu, |list| {
+				list.iter().cloned().enumerate().collect()
+			}),
+			memory: diff_sections(&self.memory, &newer.memory, |m| {
+				m.values().into_iter()
+					.filter_map(|(k, v)| Some((k.to_string(), v?)))
+					.collect()
+			}),
+			mounts: diff_sections(&self.mounts, &newer.mounts, |list| {
+				list.iter()
+					.map(|p| (p.mount_point.clone().unwrap_or_default(), p.clone()))
+					.collect()
+			}),
+			disks: diff_sections(&self.disks, &newer.disks, |list| {
+				list.iter().map(|d| (d.name.clone(), d.clone())).collect()
+			}),
+			interfaces: diff_sections(
+				&self.interfaces,
+				&newer.interfaces,
+				|list| list.iter().map(|i| (i.name.clone(), i.clone())).collect()
+			)
+		}
+	}
+}
+
+fn diff_sections<T, K, V>(
+	old: &Option<T>,
+	new: &Option<T>,
+	f: impl Fn(&T) -> Vec<(K, V)>
+) -> Vec<Change<K, V>>
+where
+	K: PartialEq,
+	V: PartialEq
+{
+	match (old, new) {
+		(Some(old), Some(new)) => diff_by_key(f(old), f(new)),
+		_ => vec![]
+	}
+}
+
+/// What changed between two snapshots, see [`Snapshot::diff`].
+///
+/// The uptime and load average are not compared since they change all the
+/// time.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct SnapshotDiff {
+	/// Cpu entries by their index.
+	pub cpu: Vec<Change<usize, OwnedCpuEntry>>,
+	/// Memory values by the field name of [`MemorySnapshot`].
+	pub memory: Vec<Change<String, DataSize>>,
+	/// Mounts by their mount point.
+	pub mounts: Vec<Change<String, OwnedMountPoint>>,
+	/// Disks by their name.
+	pub disks: Vec<Change<String, DiskStat>>,
+	/// Interfaces by their name.
+	pub interfaces: Vec<Change<String, InterfaceSnapshot>>
+}
+
+impl SnapshotDiff {
+	/// Returns true if nothing changed.
+	pub fn is_empty(&self) -> bool {
+		self.cpu.is_empty() &&
+		self.memory.is_empty() &&
+		self.mounts.is_empty() &&
+		self.disks.is_empty() &&
+		self.interfaces.is_empty()
+	}
+}
+
+fn capture<T>(
+	errors: &mut Vec<SectionError>,
+	section: Section,
+	f: impl FnOnce() -> io::Result<T>
+) -> Option<T> {
+	match f() {
+		Ok(v) => Some(v),
+		Err(e) => {
+			errors.push(SectionError {
+				section,
+				kind: e.kind().into(),
+				message: e.to_string()
+			});
+			None
+		}
+	}
+}
+
+/// The most important values from /proc/meminfo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct MemorySnapshot {
+	pub total: Option<DataSize>,
+	pub free: Option<DataSize>,
+	pub available: Option<DataSize>,
+	pub swap_total: Option<DataSize>,
+	pub swap_free: Option<DataSize>
+}
+
+impl MemorySnapshot {
+	pub(crate) fn from_memory(mem: &Memory) -> Self {
+		Self {
+			total: mem.total_memory(),
+			free: mem.free_memory(),
+			available: mem.available_memory(),
+			swap_total: mem.size_value("SwapTotal"),
+			swap_free: mem.size_value("SwapFree")
+		}
+	}
+
+	fn values(&self) -> [(&'static str, Option<DataSize>); 5] {
+		[
+			("total", self.total),
+			("free", self.free),
+			("available", self.available),
+			("swap_total", self.swap_total),
+			("swap_free", self.swap_free)
+		]
+	}
+}
+
+/// The load average over 1, 5 and 15 minutes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct LoadAvgSnapshot {
+	pub one: f32,
+	pub five: f32,
+	pub fifteen: f32
+}
+
+/// A network interface with its traffic counters.
+///
+/// Values which can't be read for this interface are `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct InterfaceSnapshot {
+	pub name: String,
+	pub mac_address: Option<String>,
+	pub mtu: Option<u32>,
+	pub operstate: Option<String>,
+	pub counters: Option<NetDevEntry>
+}
+
+impl InterfaceSnapshot {
+	fn all() -> io::Result<Vec<Self>> {
+		let net_dev = NetDev::read()?;
+
+		let list = Interface::all()?.into_iter()
+			.filter_map(|iface| {
+				let name = iface.name()?.to_string();
+				Some(Self {
+					mac_address: iface.mac_address().ok(),
+					mtu: iface.mtu().ok(),
+					operstate: iface.operstate().ok(),
+					counters: net_dev.by_name(&name),
+					name
+				})
+			})
+			.collect();
+
+		Ok(list)
+	}
+}
+
+/// The bios and system information from the dmi tables.
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct BiosSnapshot {
+	pub bios_info: Option<OwnedBiosInfo>,
+	pub system_info: Option<OwnedSystemInfo>
+}
+
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+impl BiosSnapshot {
+	fn from_bios(bios: &Bios) -> Self {
+		Self {
+			bios_info: bios.bios_info().map(|i| i.to_owned_info()),
+			system_info: bios.system_info().map(|i| i.to_owned_info())
+		}
+	}
+}
+
+/// A section of the snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", rename_all = "snake_case")
+)]
+pub enum Section {
+	Cpu,
+	Memory,
+	Uptime,
+	LoadAvg,
+	Mounts,
+	Disks,
+	Interfaces,
+	Bios
+}
+
+impl fmt::Display for Section {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			Self::Cpu => "cpu",
+			Self::Memory => "memory",
+			Self::Uptime => "uptime",
+			Self::LoadAvg => "load_avg",
+			Self::Mounts => "mounts",
+			Self::Disks => "disks",
+			Self::Interfaces => "interfaces",
+			Self::Bios => "bios"
+		})
+	}
+}
+
+/// The reason a section could not be collected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct SectionError {
+	pub section: Section,
+	pub kind: ErrorKind,
+	pub message: String
+}
+
+/// A simplified [`io::ErrorKind`] which can be serialized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", rename_all = "snake_case")
+)]
+pub enum ErrorKind {
+	NotFound,
+	PermissionDenied,
+	InvalidData,
+	Other
+}
+
+impl From<io::ErrorKind> for ErrorKind {
+	fn from(kind: io::ErrorKind) -> Self {
+		match kind {
+			io::ErrorKind::NotFound => Self::NotFound,
+			io::ErrorKind::PermissionDenied => Self::PermissionDenied,
+			io::ErrorKind::InvalidData => Self::InvalidData,
+			_ => Self::Other
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::diff::ChangeKind;
+
+	fn snapshot(
+		memory: Option<MemorySnapshot>,
+		disks: &str,
+		interfaces: Vec<InterfaceSnapshot>
+	) -> Snapshot {
+		Snapshot {
+			taken_at: SystemTime::UNIX_EPOCH,
+			cpu: None,
+			memory,
+			uptime: None,
+			load_avg: None,
+			mounts: None,
+			disks: Some(DiskStats::from_string(disks.into()).entries().collect()),
+			interfaces: Some(interfaces),
+			#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+			bios: None,
+			errors: vec![]
+		}
+	}
+
+	fn memory(free: f64) -> MemorySnapshot {
+		MemorySnapshot {
+			total: Some(DataSize::from_kb(32853280.0)),
+			free: Some(DataSize::from_kb(free)),
+			available: None,
+			swap_total: None,
+			swap_free: None
+		}
+	}
+
+	fn interface(name: &str, rx_bytes: u64) -> InterfaceSnapshot {
+		InterfaceSnapshot {
+			name: name.into(),
+			mac_address: None,
+			mtu: Some(1500),
+			operstate: Some("up".into()),
+			counters: Some(NetDevEntry {
+				name: name.into(),
+				rx_bytes,
+				..Default::default()
+			})
+		}
+	}
+
+	#[test]
+	fn diff() {
+		let earlier = snapshot(
+			Some(memory(919776.0)),
+			"8 0 sda 4137 1210 332950 2346 111 41 1202 254 0 1560 2600\n\
+			 8 16 sdb 100 0 800 10 0 0 0 0 0 10 10\n",
+			vec![interface("lo", 100), interface("eth0", 1000)]
+		);
+		let snapshot = snapshot(
+			Some(memory(819776.0)),
+			"8 0 sda 4237 1210 334950 2346 121 41 1402 254 0 2060 2600\n",
+			vec![interface("lo", 100), interface("wg0", 0)]
+		);
+		let diff = earlier.diff(&snapshot);
+		assert!(diff.cpu.is_empty());
+		assert!(diff.mounts.is_empty());
+
+		assert_eq!(diff.memory.len(), 1);
+		assert_eq!(diff.memory[0].key, "free");
+		assert_eq!(diff.memory[0].kind(), ChangeKind::Changed);
+		assert_eq!(diff.memory[0].new, Some(DataSize::from_kb(819776.0)));
+
+		let disks: Vec<_> = diff.disks.iter()
+			.map(|c| (c.key.as_str(), c.kind()))
+			.collect();
+		assert_eq!(disks, [
+			("sda", ChangeKind::Changed),
+			("sdb", ChangeKind::Removed)
+		]);
+
+		let interfaces: Vec<_> = diff.interfaces.iter()
+			.map(|c| (c.key.as_str(), c.kind()))
+			.collect();
+		assert_eq!(interfaces, [
+			("wg0", ChangeKind::Added),
+			("eth0", ChangeKind::Removed)
+		]);
+
+		assert!(snapshot.diff(&snapshot).is_empty());
+	}
+
+	#[test]
+	fn diff_missing_section() {
+		let disks = "8 0 sda 4137 1210 332950 2346 111 41 1202 254 0 1560 2600\n";
+		let earlier = snapshot(None, disks, vec![interface("lo", 100)]);
+		let snapshot = snapshot(
+			Some(memory(819776.0)),
+			disks,
+			vec![interface("lo", 200)]
+		);
+		let diff = earlier.diff(&snapshot);
+		// memory is only in the newer snapshot and gets skipped
+		assert!(diff.memory.is_empty());
+		assert!(diff.disks.is_empty());
+		assert_eq!(diff.interfaces.len(), 1);
+		assert_eq!(diff.interfaces[0].kind(), ChangeKind::Changed);
+		let old = diff.interfaces[0].old.as_ref().unwrap();
+		assert_eq!(old.counters.as_ref().unwrap().rx_bytes, 100);
+	}
+}
@@ -0,0 +1,2468 @@
+//! get information about drives and raids.
+
+pub mod verity;
+pub mod network_block;
+
+use crate::util::{
+	read_to_string, read_to_string_mut, blkdev_sector_size, line_of,
+	unescape_octal, read_sys_value, SysDir
+};
+use crate::error::{self, Error};
+#[cfg(feature = "async")]
+use crate::util::{read_to_string_async, read_to_string_mut_async};
+use crate::unit::DataSize;
+use crate::rate::Rate;
+use crate::diff::{Change, diff_by_key};
+use crate::monitor::Source;
+use crate::system::online_cpus;
+
+use std::path::{Path, PathBuf};
+use std::borrow::Cow;
+use std::{fs, io};
+use std::convert::TryInto;
+use std::time::Duration;
+use std::thread;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, Condvar};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::os::unix::fs::MetadataExt;
+
+use byte_parser::{StrParser, ParseIterator, parse_iter};
+
+#[cfg(feature = "serde")]
+use serde1::ser::{Serialize, Serializer, SerializeStruct};
+
+
+/// Read partitions from /proc/partitions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Partitions {
+	raw: String
+}
+
+impl Partitions {
+
+	fn path() -> &'static Path {
+		Path::new("/proc/partitions")
+	}
+
+	#[cfg(test)]
+	fn from_string(raw: String) -> Self {
+		Self {raw}
+	}
+
+	/// Read partitions from /proc/partitions.
+	pub fn read() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string(Self::path())?
+		})
+	}
+
+	/// Reloads information without allocating.
+	pub fn reload(&mut self) -> io::Result<()> {
+		read_to_string_mut(Self::path(), &mut self.raw)
+	}
+
+	/// Reads the file without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string_async(Self::path()).await?
+		})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(Self::path(), &mut self.raw).await
+	}
+
+	pub fn entries<'a>(&'a self) -> impl Iterator<Item=PartitionEntry<'a>> {
+		self.raw.trim()
+			.split('\n')
+			.skip(2)// skip headers
+			.map(PartitionEntry::from_str)
+	}
+
+	/// Like [`Partitions::entries`] but returns an error naming the line and
+	/// field if any entry is incomplete.
+	pub fn try_entries(&self) -> error::Result<Vec<OwnedPartitionEntry>> {
+		self.entries()
+			.map(|e| {
+				let line = Some(line_of(&self.raw, e.raw));
+				let err = |field: &str| Error::parse_field(
+					Self::path(),
+					line,
+					field,
+					format!("{:?}", e.raw.trim())
+				);
+
+				Ok(OwnedPartitionEntry {
+					major: Some(e.major().ok_or_else(|| err("major"))?),
+					minor: Some(e.minor().ok_or_else(|| err("minor"))?),
+					blocks: Some(e.blocks().ok_or_else(|| err("blocks"))?),
+					name: Some(e.name().ok_or_else(|| err("name"))?.into())
+				})
+			})
+			.collect()
+	}
+
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionEntry<'a> {
+	raw: &'a str
+}
+
+impl<'a> PartitionEntry<'a> {
+
+	fn from_str(raw: &'a str) -> Self {
+		Self {raw}
+	}
+
+	/// returns every key and valu ein the cpu info
+	pub fn values(&self) -> impl Iterator<Item=&'a str> {
+		self.raw.split(' ')
+			.map(str::trim)
+			.filter(|s| !s.is_empty())
+	}
+
+	/// Returns the major value.
+	pub fn major(&self) -> Option<usize> {
+		self.values().nth(0)?
+			.parse().ok()
+	}
+
+	/// Returns the minor value.
+	pub fn minor(&self) -> Option<usize> {
+		self.values().nth(1)?
+			.parse().ok()
+	}
+
+	/// Returns the blocks value.
+	pub fn blocks(&self) -> Option<usize> {
+		self.values().nth(2)?
+			.parse().ok()
+	}
+
+	/// Returns the name value.
+	pub fn name(&self) -> Option<&'a str> {
+		self.values().nth(3)
+	}
+
+	/// Parses every value into an entry which does not borrow from
+	/// [`Partitions`].
+	pub fn to_owned_entry(&self) -> OwnedPartitionEntry {
+		OwnedPartitionEntry {
+			major: self.major(),
+			minor: self.minor(),
+			blocks: self.blocks(),
+			name: self.name().map(Into::into)
+		}
+	}
+
+}
+
+/// An owned and parsed version of [`PartitionEntry`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct OwnedPartitionEntry {
+	pub major: Option<usize>,
+	pub minor: Option<usize>,
+	pub blocks: Option<usize>,
+	pub name: Option<String>
+}
+
+/// Read mount points from /proc/self/mountinfo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountPoints {
+	raw: String
+}
+
+impl MountPoints {
+
+	fn path() -> &'static Path {
+		Path::new("/proc/self/mountinfo")
+	}
+
+	#[cfg(test)]
+	fn from_string(raw: String) -> Self {
+		Self {raw}
+	}
+
+	/// Read mount points from /proc/self/mountinfo.
+	pub fn read() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string(Self::path())?
+		})
+	}
+
+	/// Reloads information without allocating.
+	pub fn reload(&mut self) -> io::Result<()> {
+		read_to_string_mut(Self::path(), &mut self.raw)
+	}
+
+	/// Reads the file without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string_async(Self::path()).await?
+		})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(Self::path(), &mut self.raw).await
+	}
+
+	/// Get the mount points.
+	pub fn points<'a>(&'a self) -> impl Iterator<Item=MountPoint<'a>> {
+		self.raw.trim()
+			.split('\n')
+			.map(MountPoint::from_str)
+	}
+
+	/// Like [`MountPoints::points`] but returns an error naming the line and
+	/// field if any mount point is incomplete.
+	pub fn try_points(&self) -> error::Result<Vec<OwnedMountPoint>> {
+		self.points()
+			.map(|p| {
+				let owned = p.to_owned_point();
+				let missing = [
+					("mount_id", owned.mount_id.is_none()),
+					("parent_id", owned.parent_id.is_none()),
+					("major", owned.major.is_none()),
+					("minor", owned.minor.is_none()),
+					("root", owned.root.is_none()),
+					("mount_point", owned.mount_point.is_none()),
+					("mount_options", owned.mount_options.is_none()),
+					("filesystem_type", owned.filesystem_type.is_none()),
+					("mount_source", owned.mount_source.is_none()),
+					("super_options", owned.super_options.is_none())
+				];
+
+				match missing.iter().find(|(_, m)| *m) {
+					Some((field, _)) => Err(Error::parse_field(
+						Self::path(),
+						Some(line_of(&self.raw, p.raw)),
+						*field,
+						format!("{:?}", p.raw)
+					)),
+					None => Ok(owned)
+				}
+			})
+			.collect()
+	}
+
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountPoint<'a> {
+	raw: &'a str
+}
+
+impl<'a> MountPoint<'a> {
+
+	fn from_str(raw: &'a str) -> Self {
+		Self {raw}
+	}
+
+	/// Returns every value separated by a space.
+	#[inline]
+	pub fn values(&self) -> impl Iterator<Item=&'a str> {
+		self.raw.split(' ')
+	}
+
+	/// A unique ID for the mount (may be reused after umount).
+	pub fn mount_id(&self) -> Option<usize> {
+		self.values().nth(0)?
+			.parse().ok()
+	}
+
+	/// The ID of the parent mount (or of self for
+	/// the root of this mount namespace's mount tree).
+	pub fn parent_id(&self) -> Option<usize> {
+		self.values().nth(1)?
+			.parse().ok()
+	}
+
+	/// major:minor: the value of st_dev for files on this filesystem.
+	#[inline]
+	pub fn major_minor(&self) -> Option<&'a str> {
+		self.values().nth(2)
+	}
+
+	/// Gets the major value.
+	pub fn major(&self) -> Option<usize> {
+		self.major_minor()?
+			.split(':')
+			.nth(0)?
+			.parse().ok()
+	}
+
+	/// Gets the minor value.
+	pub fn minor(&self) -> Option<usize> {
+		self.major_minor()?
+			.split(':')
+			.nth(1)?
+			.parse().ok()
+	}
+
+	/// the pathname of the directory in the filesystem
+	/// which forms the root of this mount.
+	///
+	/// Octal escapes like `\040` are replaced.
+	pub fn root(&self) -> Option<Cow<'a, str>> {
+		self.values().nth(3)
+			.map(unescape_octal)
+	}
+
+	/// The pathname of the mount point relative
+	/// to the process's root directory.
+	///
+	/// Octal escapes like `\040` are replaced.
+	pub fn mount_point(&self) -> Option<Cow<'a, str>> {
+		self.values().nth(4)
+			.map(unescape_octal)
+	}
+
+	/// Per-mount options.
+	pub fn mount_options(&self) -> Option<&'a str> {
+		self.values().nth(5)
+	}
+
+	/// Currently, the possible optional fields are `shared`, `master`,
+	/// `propagate_from`, and `unbindable`.
+	pub fn optional_fields(&self) -> impl Iterator<Item=(&'a str, Option<&'a str>)> {
+		self.values().skip(6)
+			.take_while(|&i| i != "-")
+			.map(|opt| {
+				let mut iters = opt.split(':');
+				(
+					iters.next().unwrap(),
+					iters.next()
+					// TODO: update when https://github.com/rust-lang/rust/issues/77998 gets closed
+					// Some(iters.as_str()).filter(str::is_empty)
+				)
+			})
+	}
+
+	fn after_separator(&self) -> impl Iterator<Item=&'a str> {
+		self.values().skip(5)
+			.skip_while(|&i| i != "-")
+			.skip(1)// skip separator
+	}
+
+	/// The filesystem type in the form "type[.subtype]".
+	pub fn filesystem_type(&self) -> Option<&'a str> {
+		// maybe parse subtype?
+		self.after_separator().nth(0)
+	}
+
+	// Filesystem-specific information if available.  
+	// Returns none if its the same as filesystem_type
+	/// Filesystem-specific information.  
+	/// df command uses this information as Filesystem.
+	///
+	/// Octal escapes like `\040` are replaced.
+	pub fn mount_source(&self) -> Option<Cow<'a, str>> {
+		self.after_separator().nth(1)
+			.map(unescape_octal)
+		// let src = self.after_separator().nth(1)?;
+		// match self.filesystem_type() {
+		// 	Some(fst) if fst == src => None,
+		// 	_ => Some(src)
+		// }
+	}
+
+	/// Per-superblock options.
+	pub fn super_options(&self) -> Option<&'a str> {
+		self.after_separator().nth(2)
+	}
+
+	/// Returns the layers if this is an overlay mount.
+	pub fn overlay(&self) -> Option<OverlayLayers> {
+		if self.filesystem_type()? != "overlay" {
+			return None
+		}
+		Some(OverlayLayers::from_options(self.super_options()?))
+	}
+
+	/// Returns information about the fuse connection if this is a fuse
+	/// mount.
+	pub fn fuse(&self) -> Option<FuseMount> {
+		FuseMount::new(
+			self.filesystem_type()?,
+			self.major()?,
+			self.minor()?,
+			self.super_options()?
+		)
+	}
+
+	/// Returns true if the mount or the filesystem is read only.
+	pub fn is_read_only(&self) -> bool {
+		is_read_only(self.mount_options(), self.super_options())
+	}
+
+	/// What the filesystem does on errors, for example `remount-ro`,
+	/// `continue` or `panic`.
+	pub fn errors_behavior(&self) -> Option<&'a str> {
+		option_value(self.super_options()?, "errors")
+	}
+
+	/// Returns the filesystem statistics of this mount point.
+	///
+	/// Returns an error if the mount point could not be parsed.
+	pub fn stats(&self) -> io::Result<FsStat> {
+		let mount_point = self.mount_point()
+			.ok_or_else(missing_mount_point)?;
+		FsStat::read(&*mount_point)
+	}
+
+	/// Parses every value into a mount point which does not borrow from
+	/// [`MountPoints`].
+	pub fn to_owned_point(&self) -> OwnedMountPoint {
+		let s = |s: Option<&str>| s.map(String::from);
+		let c = |s: Option<Cow<str>>| s.map(Cow::into_owned);
+
+		OwnedMountPoint {
+			mount_id: self.mount_id(),
+			parent_id: self.parent_id(),
+			major: self.major(),
+			minor: self.minor(),
+			root: c(self.root()),
+			mount_point: c(self.mount_point()),
+			mount_options: s(self.mount_options()),
+			optional_fields: self.optional_fields()
+				.map(|(k, v)| (k.into(), s(v)))
+				.collect(),
+			filesystem_type: s(self.filesystem_type()),
+			mount_source: c(self.mount_source()),
+			super_options: s(self.super_options())
+		}
+	}
+
+}
+
+/// An owned and parsed version of [`MountPoint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct OwnedMountPoint {
+	pub mount_id: Option<usize>,
+	pub parent_id: Option<usize>,
+	pub major: Option<usize>,
+	pub minor: Option<usize>,
+	pub root: Option<String>,
+	pub mount_point: Option<String>,
+	pub mount_options: Option<String>,
+	pub optional_fields: Vec<(String, Option<String>)>,
+	pub filesystem_type: Option<String>,
+	pub mount_source: Option<String>,
+	pub super_options: Option<String>
+}
+
+impl OwnedMountPoint {
+	/// Returns the filesystem statistics of this mount point.
+	///
+	/// Returns an error if the mount point is not known.
+	pub fn stats(&self) -> io::Result<FsStat> {
+		let mount_point = self.mount_point.as_deref()
+			.ok_or_else(missing_mount_point)?;
+		FsStat::read(mount_point)
+	}
+
+	/// Returns the layers if this is an overlay mount.
+	pub fn overlay(&self) -> Option<OverlayLayers> {
+		if self.filesystem_type.as_deref()? != "overlay" {
+			return None
+		}
+		Some(OverlayLayers::from_options(self.super_options.as_deref()?))
+	}
+
+	/// Returns information about the fuse connection if this is a fuse
+	/// mount.
+	pub fn fuse(&self) -> Option<FuseMount> {
+		FuseMount::new(
+			self.filesystem_type.as_deref()?,
+			self.major?,
+			self.minor?,
+			self.super_options.as_deref()?
+		)
+	}
+
+	/// Returns true if the mount or the filesystem is read only.
+	pub fn is_read_only(&self) -> bool {
+		is_read_only(
+			self.mount_options.as_deref(),
+			self.super_options.as_deref()
+		)
+	}
+
+	/// What the filesystem does on errors, for example `remount-ro`,
+	/// `continue` or `panic`.
+	pub fn errors_behavior(&self) -> Option<&str> {
+		option_value(self.super_options.as_deref()?, "errors")
+	}
+
+	/// Returns the amount of errors ext4 detected on this filesystem.
+	///
+	/// Returns `None` if this is not an ext4 filesystem.
+	pub fn ext4_error_count(&self) -> Option<io::Result<u64>> {
+		if self.filesystem_type.as_deref()? != "ext4" {
+			return None
+		}
+
+		// /dev/mapper/root is a link to /dev/dm-0
+		let dev = fs::canonicalize(self.mount_source.as_deref()?).ok()?;
+		let name = dev.file_name()?;
+		Some(read_sys_value(
+			Path::new("/sys/fs/ext4").join(name).join("errors_count")
+		))
+	}
+}
+
+fn is_read_only(
+	mount_options: Option<&str>,
+	super_options: Option<&str>
+) -> bool {
+	[mount_options, super_options].iter()
+		.flatten()
+		.any(|o| o.split(',').any(|o| o == "ro"))
+}
+
+fn option_value<'a>(options: &'a str, key: &str) -> Option<&'a str> {
+	options.split(',')
+		.filter_map(|o| o.split_once('='))
+		.find(|(k, _)| *k == key)
+		.map(|(_, v)| v)
+}
+
+/// A change reported by a [`MountWatcher`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct MountEvent {
+	pub kind: MountEventKind,
+	/// The mount after the change or before it was unmounted.
+	pub mount: OwnedMountPoint
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", rename_all = "snake_case")
+)]
+pub enum MountEventKind {
+	Mounted,
+	Unmounted,
+	/// The mount was remounted read only.
+	///
+	/// `errors` is true if ext4 reports errors, which means the kernel
+	/// probably remounted it because of an error. `errors=remount-ro` alone
+	/// is not enough since it is the default for many root filesystems.
+	RemountedReadOnly { errors: bool },
+	RemountedReadWrite
+}
+
+/// Compares /proc/self/mountinfo on every check and reports mounts which
+/// were added, removed or changed between read only and read write.
+///
+/// Silent read only remounts because of filesystem errors are a common
+/// failure, see [`MountEventKind::RemountedReadOnly`].
+///
+/// ## Example
+/// ```no_run
+/// use linux_info::storage::MountWatcher;
+/// use linux_info::monitor::Monitor;
+/// use std::time::Duration;
+///
+/// let mut monitor = Monitor::new();
+/// let events = monitor.add(MountWatcher::new().unwrap(), Duration::from_secs(10));
+/// for sample in events.iter() {
+///     for event in sample.unwrap().value {
+///         println!("{:?} {:?}", event.kind, event.mount.mount_point);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MountWatcher {
+	mounts: MountPoints,
+	previous: Vec<OwnedMountPoint>
+}
+
+impl MountWatcher {
+	/// Reads the current mounts, only changes after this are reported.
+	pub fn new() -> io::Result<Self> {
+		let mounts = MountPoints::read()?;
+		let previous = mounts.points().map(|p| p.to_owned_point()).collect();
+		Ok(Self { mounts, previous })
+	}
+
+	/// Reads /proc/self/mountinfo and returns what changed since the last
+	/// check.
+	pub fn check(&mut self) -> io::Result<Vec<MountEvent>> {
+		self.mounts.reload()?;
+		let current = self.mounts.points()
+			.map(|p| p.to_owned_point())
+			.collect();
+		let previous = std::mem::replace(&mut self.previous, current);
+		Ok(mount_events(previous, &self.previous))
+	}
+}
+
+impl Source for MountWatcher {
+	type Value = Vec<MountEvent>;
+
+	fn sample(&mut self) -> io::Result<Self::Value> {
+		self.check()
+	}
+}
+
+fn mount_events(
+	previous: Vec<OwnedMountPoint>,
+	current: &[OwnedMountPoint]
+) -> Vec<MountEvent> {
+	let mut events = vec![];
+
+	for mount in current {
+		let prev = previous.iter()
+			.find(|p| p.mount_id == mount.mount_id);
+		let prev_ro = match prev {
+			Some(p) => p.is_read_only(),
+			None => {
+				events.push(MountEvent {
+					kind: MountEventKind::Mounted,
+					mount: mount.clone()
+				});
+				continue
+			}
+		};
+
+		let kind = match (prev_ro, mount.is_read_only()) {
+			(false, true) => {
+				let errors = matches!(
+					mount.ext4_error_count(),
+					Some(Ok(c)) if c > 0
+				);
+				MountEventKind::RemountedReadOnly { errors }
+			},
+			(true, false) => MountEventKind::RemountedReadWrite,
+			_ => continue
+		};
+		events.push(MountEvent { kind, mount: mount.clone() });
+	}
+
+	events.extend(previous.into_iter()
+		.filter(|p| !current.iter().any(|c| c.mount_id == p.mount_id))
+		.map(|mount| MountEvent {
+			kind: MountEventKind::Unmounted,
+			mount
+		}));
+
+	events
+}
+
+fn missing_mount_point() -> io::Error {
+	Error::missing_field(MountPoints::path(), "mount_point").into()
+}
+
+/// The directories an overlay mount is made of.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct OverlayLayers {
+	/// The read only layers, the topmost layer comes first.
+	pub lower: Vec<PathBuf>,
+	/// The writable layer, `None` for read only mounts.
+	pub upper: Option<PathBuf>,
+	pub work: Option<PathBuf>
+}
+
+impl OverlayLayers {
+	/// Parses the super options of an overlay mount like
+	/// `rw,lowerdir=/l1:/l2,upperdir=/u,workdir=/w`.
+	///
+	/// Supports the `lowerdir+` and `datadir+` options used by newer
+	/// kernels to list every layer separately.
+	pub fn from_options(options: &str) -> Self {
+		let mut layers = Self::default();
+		for opt in split_unescaped(options, ',') {
+			let (key, value) = match opt.split_once('=') {
+				Some(kv) => kv,
+				None => continue
+			};
+
+			match key {
+				"lowerdir" => layers.lower.extend(
+					split_unescaped(value, ':')
+						// data only layers are separated by ::
+						.filter(|l| !l.is_empty())
+						.map(overlay_path)
+				),
+				"lowerdir+" | "datadir+" => {
+					layers.lower.push(overlay_path(value))
+				},
+				"upperdir" => layers.upper = Some(overlay_path(value)),
+				"workdir" => layers.work = Some(overlay_path(value)),
+				_ => {}
+			}
+		}
+		layers
+	}
+
+	/// Returns the ids of the container image layers, for the lower layers
+	/// followed by the upper layer.
+	///
+	/// Works with the directory layout of docker (`overlay2/<id>/diff` and
+	/// the shortened `overlay2/l/<id>`) and containerd
+	/// (`snapshots/<id>/fs`).
+	pub fn layer_ids(&self) -> impl Iterator<Item=&str> {
+		self.lower.iter()
+			.chain(self.upper.as_ref())
+			.filter_map(|p| layer_id(p))
+	}
+}
+
+/// Decodes the octal escapes of mountinfo and then the backslashes
+/// overlayfs uses to escape `:` and `,` in a path.
+fn overlay_path(s: &str) -> PathBuf {
+	let mut path = String::with_capacity(s.len());
+	let unescaped = unescape_octal(s);
+	let mut chars = unescaped.chars();
+	while let Some(c) = chars.next() {
+		match c {
+			'\\' => path.extend(chars.next()),
+			c => path.push(c)
+		}
+	}
+	path.into()
+}
+
+/// Splits at `sep` if it is not escaped with a backslash.
+///
+/// mountinfo escapes a backslash as `\134`, so `sep` is escaped if an odd
+/// number of `\134` precede it.
+fn split_unescaped(s: &str, sep: char) -> impl Iterator<Item=&str> {
+	let mut parts = vec![];
+	let mut start = 0;
+	for (i, c) in s.char_indices() {
+		if c != sep {
+			continue
+		}
+
+		let mut before = &s[start..i];
+		let mut escapes = 0;
+		while let Some(b) = before.strip_suffix("\\134") {
+			before = b;
+			escapes += 1;
+		}
+
+		if escapes % 2 == 0 {
+			parts.push(&s[start..i]);
+			start = i + sep.len_utf8();
+		}
+	}
+	parts.push(&s[start..]);
+	parts.into_iter()
+}
+
+fn layer_id(path: &Path) -> Option<&str> {
+	let name = path.file_name()?.to_str()?;
+	let parent = path.parent()?;
+	match name {
+		"diff" | "fs" => parent.file_name()?.to_str(),
+		_ if parent.file_name()? == "l" => Some(name),
+		_ => None
+	}
+}
+
+/// A fuse mount and its connection in /sys/fs/fuse/connections.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct FuseMount {
+	/// The connection id, the device number of the mount as the kernel
+	/// encodes it (`major << 20 | minor`).
+	pub connection: usize,
+	/// The subtype for example `sshfs` for `fuse.sshfs`.
+	pub subtype: Option<String>,
+	/// The user which mounted the filesystem.
+	pub user_id: Option<u32>,
+	/// The group which mounted the filesystem.
+	pub group_id: Option<u32>
+}
+
+impl FuseMount {
+	fn new(
+		fs_type: &str,
+		major: usize,
+		minor: usize,
+		options: &str
+	) -> Option<Self> {
+		let subtype = match fs_type {
+			"fuse" | "fuseblk" => None,
+			t => Some(t.strip_prefix("fuse.")?.to_string())
+		};
+
+		let option = |key: &str| {
+			options.split(',')
+				.filter_map(|o| o.split_once('='))
+				.find(|(k, _)| *k == key)
+				.and_then(|(_, v)| v.parse().ok())
+		};
+
+		Some(Self {
+			// fusectl uses the kernel internal dev_t which differs from
+			// the one userspace sees
+			connection: major << 20 | minor,
+			subtype,
+			user_id: option("user_id"),
+			group_id: option("group_id")
+		})
+	}
+
+	/// The directory of the connection in /sys/fs/fuse/connections.
+	///
+	/// Requires fusectl to be mounted.
+	pub fn connection_path(&self) -> PathBuf {
+		Path::new("/sys/fs/fuse/connections")
+			.join(self.connection.to_string())
+	}
+
+	/// The amount of requests waiting to be handled by the daemon.
+	///
+	/// A value which stays above zero indicates a stuck daemon.
+	pub fn waiting(&self) -> io::Result<usize> {
+		read_sys_value(self.connection_path().join("waiting"))
+	}
+
+	/// Returns the pids of processes owned by [`FuseMount::user_id`] which
+	/// have /dev/fuse open.
+	///
+	/// The kernel does not expose which process serves a connection, so
+	/// this might return more than the daemon of this mount. Processes of
+	/// other users can only be inspected as root.
+	pub fn daemon_candidates(&self) -> io::Result<Vec<u32>> {
+		let mut pids = vec![];
+		for entry in fs::read_dir("/proc")? {
+			let entry = entry?;
+			let pid: u32 = match entry.file_name().to_str()
+				.and_then(|n| n.parse().ok())
+			{
+				Some(p) => p,
+				None => continue
+			};
+
+			if self.user_id.is_some() && process_uid(pid) != self.user_id {
+				continue
+			}
+
+			let fds = match fs::read_dir(entry.path().join("fd")) {
+				Ok(fds) => fds,
+				Err(_) => continue
+			};
+			let has_fuse = fds.filter_map(Result::ok)
+				.filter_map(|fd| fs::read_link(fd.path()).ok())
+				.any(|link| link == Path::new("/dev/fuse"));
+
+			if has_fuse {
+				pids.push(pid);
+			}
+		}
+		Ok(pids)
+	}
+}
+
+fn process_uid(pid: u32) -> Option<u32> {
+	read_to_string(format!("/proc/{}/status", pid)).ok()?
+		.lines()
+		.find_map(|l| l.strip_prefix("Uid:"))?
+		.split_whitespace()
+		.next()?
+		.parse().ok()
+}
+
+/// Filesystem statistics
+#[derive(Clone)]
+pub struct FsStat {
+	raw: libc::statfs
+}
+
+impl FsStat {
+
+	/// Reads filesystems staticstics for a given
+	/// file descriptor.
+	pub fn read(path: impl AsRef<Path>) -> io::Result<Self> {
+		crate::util::statfs(path)
+			.map(|raw| Self { raw })
+	}
+
+	/// Returns `true` if the total blocks is bigger than zero.
+	pub fn has_blocks(&self) -> bool {
+		self.total_blocks()
+			.map(|b| b > 0)
+			.unwrap_or(false)
+	}
+
+	/// The block size in bytes used for this filesystem.
+	pub fn block_size(&self) -> Option<usize> {
+		self.raw.f_bsize.try_into().ok()
+	}
+
+	/// The total block count.
+	pub fn total_blocks(&self) -> Option<usize> {
+		self.raw.f_blocks.try_into().ok()
+	}
+
+	/// The blocks that are still free may not all
+	/// be accessible to unprivileged users.
+	pub fn free_blocks(&self) -> Option<usize> {
+		self.raw.f_bfree.try_into().ok()
+	}
+
+	/// The blocks that are free and accessible to unprivileged
+	/// users.
+	pub fn available_blocks(&self) -> Option<usize> {
+		self.raw.f_bavail.try_into().ok()
+	}
+
+	/// The blocks that are already used.
+	pub fn used_blocks(&self) -> Option<usize> {
+		Some(self.total_blocks()? - self.free_blocks()?)
+	}
+
+	/// The size of the filesystem.
+	pub fn total(&self) -> Option<DataSize> {
+		DataSize::from_size_bytes(self.total_blocks()? * self.block_size()?)
+	}
+
+	/// The size of the free space.
+	pub fn free(&self) -> Option<DataSize> {
+		DataSize::from_size_bytes(self.free_blocks()? * self.block_size()?)
+	}
+
+	/// The size of the available space to unprivileged
+	/// users.
+	pub fn available(&self) -> Option<DataSize> {
+		DataSize::from_size_bytes(self.available_blocks()? * self.block_size()?)
+	}
+
+	/// The size of the space that is currently
+	/// used.
+	pub fn used(&self) -> Option<DataSize> {
+		DataSize::from_size_bytes(self.used_blocks()? * self.block_size()?)
+	}
+
+	/// The total inode count.
+	pub fn total_inodes(&self) -> Option<usize> {
+		self.raw.f_files.try_into().ok()
+	}
+
+	/// The inodes that are still free.
+	pub fn free_inodes(&self) -> Option<usize> {
+		self.raw.f_ffree.try_into().ok()
+	}
+
+	/// The inodes that are already used.
+	pub fn used_inodes(&self) -> Option<usize> {
+		Some(self.total_inodes()? - self.free_inodes()?)
+	}
+
+	/// The used space compared to the space usable by unprivileged users
+	/// (0 - 1), like the `Use%` column of `df`.
+	///
+	/// Returns `None` if the filesystem has no blocks.
+	pub fn used_ratio(&self) -> Option<f64> {
+		let used = self.used_blocks()?;
+		let usable = used + self.available_blocks()?;
+		ratio(used, usable)
+	}
+
+	/// The used inodes compared to the total (0 - 1).
+	///
+	/// Returns `None` if the filesystem has no inodes, for example btrfs.
+	pub fn inodes_used_ratio(&self) -> Option<f64> {
+		ratio(self.used_inodes()?, self.total_inodes()?)
+	}
+
+}
+
+fn ratio(part: usize, total: usize) -> Option<f64> {
+	match total {
+		0 => None,
+		t => Some(part as f64 / t as f64)
+	}
+}
+
+/// Thresholds (0 - 1) at which a [`FsUsageWatcher`] reports a breach.
+///
+/// `None` disables the check.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct FsThresholds {
+	pub space: Option<f64>,
+	pub inodes: Option<f64>
+}
+
+/// Uses 90% space and 95% inodes.
+impl Default for FsThresholds {
+	fn default() -> Self {
+		Self {
+			space: Some(0.9),
+			inodes: Some(0.95)
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", rename_all = "snake_case")
+)]
+pub enum FsResource {
+	Space,
+	Inodes
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", rename_all = "snake_case")
+)]
+pub enum FsUsageEventKind {
+	/// The usage reached the threshold.
+	Breached,
+	/// The usage is below the threshold again.
+	Recovered
+}
+
+/// Returned by a [`FsUsageWatcher`] if a threshold was crossed.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct FsUsageEvent {
+	pub mount_point: PathBuf,
+	pub resource: FsResource,
+	pub kind: FsUsageEventKind,
+	/// The used ratio (0 - 1).
+	pub used: f64
+}
+
+/// Checks the usage of mount points against thresholds.
+///
+/// An event is only returned when a threshold is crossed, not on every
+/// check. Mount points which cannot be read (for example because they were
+/// unmounted) are skipped.
+///
+/// ## Example
+/// ```no_run
+/// use linux_info::storage::{FsUsageWatcher, FsThresholds};
+/// use linux_info::monitor::Monitor;
+/// use std::time::Duration;
+///
+/// let watcher = FsUsageWatcher::new(["/", "/home"], FsThresholds::default());
+/// let mut monitor = Monitor::new();
+/// let events = monitor.add(watcher, Duration::from_secs(60));
+/// for sample in events.iter() {
+///     for event in sample.unwrap().value {
+///         println!("{:?}", event);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FsUsageWatcher {
+	thresholds: FsThresholds,
+	// the mount point and if space and inodes are breached
+	mounts: Vec<(PathBuf, [bool; 2])>
+}
+
+impl FsUsageWatcher {
+	pub fn new<I, P>(mount_points: I, thresholds: FsThresholds) -> Self
+	where
+		I: IntoIterator<Item=P>,
+		P: Into<PathBuf>
+	{
+		Self {
+			thresholds,
+			mounts: mount_points.into_iter()
+				.map(|p| (p.into(), [false; 2]))
+				.collect()
+		}
+	}
+
+	/// Watches every mount point from /proc/self/mountinfo which has
+	/// blocks.
+	pub fn all(thresholds: FsThresholds) -> io::Result<Self> {
+		let mounts = MountPoints::read()?;
+		let points: Vec<_> = mounts.points()
+			.filter(|p| p.stats().map(|s| s.has_blocks()).unwrap_or(false))
+			.filter_map(|p| p.mount_point().map(Cow::into_owned))
+			.collect();
+		Ok(Self::new(points, thresholds))
+	}
+
+	pub fn thresholds(&self) -> FsThresholds {
+		self.thresholds
+	}
+
+	/// Reads the usage of every mount point and returns the thresholds
+	/// which were crossed since the last check.
+	pub fn check(&mut self) -> Vec<FsUsageEvent> {
+		let mut events = vec![];
+		for i in 0..self.mounts.len() {
+			let stat = match FsStat::read(&self.mounts[i].0) {
+				Ok(s) => s,
+				Err(_) => continue
+			};
+
+			self.update(i, FsResource::Space, stat.used_ratio(), &mut events);
+			self.update(
+				i,
+				FsResource::Inodes,
+				stat.inodes_used_ratio(),
+				&mut events
+			);
+		}
+		events
+	}
+
+	fn update(
+		&mut self,
+		mount: usize,
+		resource: FsResource,
+		used: Option<f64>,
+		events: &mut Vec<FsUsageEvent>
+	) {
+		let (threshold, idx) = match resource {
+			FsResource::Space => (self.thresholds.space, 0),
+			FsResource::Inodes => (self.thresholds.inodes, 1)
+		};
+		let (threshold, used) = match (threshold, used) {
+			(Some(t), Some(u)) => (t, u),
+			_ => return
+		};
+
+		let (path, breached) = &mut self.mounts[mount];
+		let now = used >= threshold;
+		if now == breached[idx] {
+			return
+		}
+		breached[idx] = now;
+
+		events.push(FsUsageEvent {
+			mount_point: path.clone(),
+			resource,
+			kind: if now {
+				FsUsageEventKind::Breached
+			} else {
+				FsUsageEventKind::Recovered
+			},
+			used
+		});
+	}
+}
+
+impl Source for FsUsageWatcher {
+	type Value = Vec<FsUsageEvent>;
+
+	fn sample(&mut self) -> io::Result<Self::Value> {
+		Ok(self.check())
+	}
+}
+
+/// Options for [`dir_size`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirSizeOptions {
+	/// Directories deeper than this are not counted, `0` only counts the
+	/// entries directly in the path. `None` counts everything.
+	pub max_depth: Option<usize>,
+	/// Don't descend into directories on other filesystems (like `du -x`).
+	pub same_filesystem: bool,
+	/// Count the file length instead of the allocated blocks (like
+	/// `du --apparent-size`).
+	pub apparent_size: bool,
+	/// The amount of threads used.
+	pub threads: usize
+}
+
+/// Counts everything with one thread per online cpu.
+impl Default for DirSizeOptions {
+	fn default() -> Self {
+		Self {
+			max_depth: None,
+			same_filesystem: false,
+			apparent_size: false,
+			threads: online_cpus().unwrap_or(4)
+		}
+	}
+}
+
+/// Calculates the disk usage of a directory like `du -s`.
+///
+/// Directories are read in parallel, hard links are only counted once and
+/// symlinks are not followed. Entries which cannot be read (for example
+/// because of missing permissions) are skipped.
+///
+/// ```no_run
+/// use linux_info::storage::{dir_size, DirSizeOptions};
+///
+/// let size = dir_size("/var/log", &DirSizeOptions {
+///     same_filesystem: true,
+///     ..Default::default()
+/// }).unwrap();
+/// println!("{:#.1}", size);
+/// ```
+pub fn dir_size(
+	path: impl AsRef<Path>,
+	options: &DirSizeOptions
+) -> io::Result<DataSize> {
+	let path = path.as_ref();
+	let meta = fs::symlink_metadata(path)?;
+
+	let walker = Arc::new(DirWalker {
+		options: options.clone(),
+		dev: meta.dev(),
+		queue: Mutex::new(DirQueue {
+			dirs: vec![],
+			active: 0
+		}),
+		cond: Condvar::new(),
+		bytes: AtomicU64::new(0),
+		links: Mutex::new(HashSet::new())
+	});
+
+	walker.count(&meta);
+	if meta.is_dir() {
+		walker.queue.lock().unwrap().dirs.push((path.to_path_buf(), 0));
+
+		let threads: Vec<_> = (1..options.threads.max(1))
+			.map(|_| {
+				let walker = walker.clone();
+				thread::spawn(move || walker.work())
+			})
+			.collect();
+		walker.work();
+		for thread in threads {
+			let _ = thread.join();
+		}
+	}
+
+	Ok(DataSize::from_bytes(walker.bytes.load(Ordering::Relaxed).into()))
+}
+
+struct DirQueue {
+	dirs: Vec<(PathBuf, usize)>,
+	// directories which are currently read
+	active: usize
+}
+
+struct DirWalker {
+	options: DirSizeOptions,
+	dev: u64,
+	queue: Mutex<DirQueue>,
+	cond: Condvar,
+	bytes: AtomicU64,
+	// (dev, inode) of files with multiple hard links
+	links: Mutex<HashSet<(u64, u64)>>
+}
+
+impl DirWalker {
+	fn count(&self, meta: &fs::Metadata) {
+		if !meta.is_dir() && meta.nlink() > 1 {
+			let mut links = self.links.lock().unwrap();
+			if !links.insert((meta.dev(), meta.ino())) {
+				return
+			}
+		}
+
+		let bytes = if self.options.apparent_size {
+			meta.len()
+		} else {
+			meta.blocks() * 512
+		};
+		self.bytes.fetch_add(bytes, Ordering::Relaxed);
+	}
+
+	fn work(&self) {
+		loop {
+			let (dir, depth) = {
+				let mut queue = self.queue.lock().unwrap();
+				loop {
+					if let Some(dir) = queue.dirs.pop() {
+						queue.active += 1;
+						break dir
+					}
+					// nobody can add more directories
+					if queue.active == 0 {
+						return
+					}
+					queue = self.cond.wait(queue).unwrap();
+				}
+			};
+
+			let descend = self.options.max_depth
+				.map(|max| depth < max)
+				.unwrap_or(true);
+			let mut subdirs = vec![];
+
+			for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+				// does not follow symlinks
+				let meta = match entry.metadata() {
+					Ok(m) => m,
+					Err(_) => continue
+				};
+				self.count(&meta);
+
+				let same_fs = !self.options.same_filesystem ||
+					meta.dev() == self.dev;
+				if meta.is_dir() && descend && same_fs {
+					subdirs.push((entry.path(), depth + 1));
+				}
+			}
+
+			let mut queue = self.queue.lock().unwrap();
+			queue.dirs.extend(subdirs);
+			queue.active -= 1;
+			self.cond.notify_all();
+		}
+	}
+}
+
+/// Read mount points from /proc/mdstat.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Raids {
+	raw: String
+}
+
+impl Raids {
+
+	fn path() -> &'static Path {
+		Path::new("/proc/mdstat")
+	}
+
+	#[cfg(test)]
+	fn from_string(raw: String) -> Self {
+		Self {raw}
+	}
+
+	/// Read raid devices from /proc/mdstat.
+	pub fn read() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string(Self::path())?
+		})
+	}
+
+	/// Reloads information without allocating.
+	pub fn reload(&mut self) -> io::Result<()> {
+		read_to_string_mut(Self::path(), &mut self.raw)
+	}
+
+	/// Reads the file without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string_async(Self::path()).await?
+		})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(Self::path(), &mut self.raw).await
+	}
+
+	/// Returns all listed devices in /proc/mdstat.
+	pub fn raids(&self) -> impl Iterator<Item=Raid<'_>> {
+		let mut first_line = false;
+		parse_iter(
+			StrParser::new(self.raw.trim()),
+			move |parser| {
+				if !first_line {
+					parser.consume_while_byte_fn(|&b| b != b'\n');
+					// remove newline
+					parser.advance();
+					first_line = true;
+				}
+				parser.peek()?;
+				let key = parser.record()
+					.while_byte_fn(|&b| b != b':')
+					.consume_to_str()
+					.trim();
+
+				if key == "unused devices" {
+					return None
+				}
+				// remove colon
+				parser.advance();
+
+				let mut parser = parser.record();
+				let mut one = false;
+
+				loop {
+
+					if one && matches!(parser.peek(), Some(b'\n')) {
+						// finished
+						let s = parser.to_str().trim();
+						parser.advance();
+						return Some(Raid::from_str(key, s))
+					}
+
+					if one {
+						one = false;
+						continue
+					}
+
+					match parser.next() {
+						Some(b'\n') => one = true,
+						None => {
+							// The end
+							return Some(Raid::from_str(key, parser.to_str().trim()))
+						},
+						_ => {}
+					}
+
+				}
+			}
+		)
+	}
+
+}
+
+// https://raid.wiki.kernel.org/index.php/Mdstat
+/// A raid device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Raid<'a> {
+	name: &'a str,
+	raw: &'a str
+}
+
+impl<'a> Raid<'a> {
+
+	fn from_str(name: &'a str, raw: &'a str) -> Self {
+		Self {name, raw}
+	}
+
+	/// Returns every line and their values with out the name.
+	#[inline]
+	pub fn values(&self) -> impl Iterator<Item=impl Iterator<Item=&'a str>> {
+		self.raw.split('\n')
+			.map(str::trim)
+			.map(|l| l.split(' '))
+	}
+
+	/// The name of the raid for example `md0`.
+	pub fn name(&self) -> &'a str {
+		self.name
+	}
+
+	/// The state of the current device.
+	pub fn state(&self) -> Option<&'a str> {
+		self.values()
+			.nth(0)?
+			.nth(0)
+	}
+
+	fn line(&self, line: usize) -> impl Iterator<Item=&'a str> {
+		let mut iter = self.values().nth(line);
+		std::iter::from_fn(move || iter.as_mut()?.next())
+	}
+
+	/// Returns the kind of raid device.  
+	/// Maybe in the future will return an enum.
+	pub fn kind(&self) -> Option<&'a str> {
+		self.line(0).nth(1)
+	}
+
+	/// Returns all devices (id, name) in this raid array.
+	pub fn devices(&self) -> impl Iterator<Item=(usize, &'a str)> {
+		self.line(0)
+			.skip(2)
+			.filter_map(|dev| {
+				let mut split = dev.split(&['[', ']'][..]);
+				let name = split.next()?;
+				Some((
+					split.next()?.parse().ok()?,
+					name
+				))
+			})
+	}
+
+	/// Returns all usable blocks.
+	pub fn usable_blocks(&self) -> Option<usize> {
+		self.line(1)
+			.nth(0)?
+			.parse().ok()
+	}
+
+	/// The amount of devices that are currently used. Should
+	/// be `raid.used_devices()? == raid.devices().count()`.
+	pub fn used_devices(&self) -> Option<usize> {
+		self.line(1)
+			.find(|l| l.starts_with('['))?
+			.split('/')
+			.nth(0)?
+			.strip_prefix('[')?
+			.parse().ok()
+	}
+
+	/// The amount of devices that would be ideal for this
+	/// array configuration.
+	pub fn ideal_devices(&self) -> Option<usize> {
+		self.line(1)
+			.find(|l| l.starts_with('['))?
+			.split('/')
+			.nth(1)?
+			.strip_suffix(']')?
+			.parse().ok()
+	}
+
+	/// Returns the progress line if there is any, for example:  
+	/// `[==>..................]  recovery = 12.6% (37043392/292945152) finish=127.5min speed=33440K/sec`
+	pub fn progress(&self) -> Option<&'a str> {
+		let l = self.raw.split('\n')
+			.nth(2)?
+			.trim();
+		l.starts_with('[')
+			.then(|| l)
+	}
+
+	/// Returns filesystem statistics to this raid array.
+	pub fn stats(&self) -> io::Result<FsStat> {
+		FsStat::read(format!("/dev/{}", self.name()))
+	}
+
+	/// Parses every value into a raid which does not borrow from [`Raids`].
+	pub fn to_owned_raid(&self) -> OwnedRaid {
+		OwnedRaid {
+			name: self.name().into(),
+			state: self.state().map(Into::into),
+			kind: self.kind().map(Into::into),
+			devices: self.devices()
+				.map(|(id, name)| (id, name.into()))
+				.collect(),
+			usable_blocks: self.usable_blocks(),
+			used_devices: self.used_devices(),
+			ideal_devices: self.ideal_devices(),
+			progress: self.progress().map(Into::into)
+		}
+	}
+
+}
+
+/// An owned and parsed version of [`Raid`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct OwnedRaid {
+	pub name: String,
+	pub state: Option<String>,
+	pub kind: Option<String>,
+	/// (id, name)
+	pub devices: Vec<(usize, String)>,
+	pub usable_blocks: Option<usize>,
+	pub used_devices: Option<usize>,
+	pub ideal_devices: Option<usize>,
+	pub progress: Option<String>
+}
+
+impl OwnedRaid {
+	/// Returns filesystem statistics to this raid array.
+	pub fn stats(&self) -> io::Result<FsStat> {
+		FsStat::read(format!("/dev/{}", self.name))
+	}
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Partitions {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where S: Serializer {
+		serializer.collect_seq(self.entries())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for PartitionEntry<'_> {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where S: Serializer {
+		self.to_owned_entry().serialize(serializer)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for MountPoints {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where S: Serializer {
+		serializer.collect_seq(self.points())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for MountPoint<'_> {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where S: Serializer {
+		self.to_owned_point().serialize(serializer)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for FsStat {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where S: Serializer {
+		let mut s = serializer.serialize_struct("FsStat", 9)?;
+		s.serialize_field("block_size", &self.block_size())?;
+		s.serialize_field("total_blocks", &self.total_blocks())?;
+		s.serialize_field("free_blocks", &self.free_blocks())?;
+		s.serialize_field("available_blocks", &self.available_blocks())?;
+		s.serialize_field("used_blocks", &self.used_blocks())?;
+		s.serialize_field("total", &self.total())?;
+		s.serialize_field("free", &self.free())?;
+		s.serialize_field("available", &self.available())?;
+		s.serialize_field("used", &self.used())?;
+		s.end()
+	}
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Raids {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where S: Serializer {
+		serializer.collect_seq(self.raids())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Raid<'_> {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where S: Serializer {
+		self.to_owned_raid().serialize(serializer)
+	}
+}
+
+/// Read disk statistics from /proc/diskstats.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiskStats {
+	raw: String
+}
+
+impl DiskStats {
+
+	fn path() -> &'static Path {
+		Path::new("/proc/diskstats")
+	}
+
+	#[cfg(test)]
+	pub(crate) fn from_string(raw: String) -> Self {
+		Self {raw}
+	}
+
+	/// Read disk statistics from /proc/diskstats.
+	pub fn read() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string(Self::path())?
+		})
+	}
+
+	/// Reloads information without allocating.
+	pub fn reload(&mut self) -> io::Result<()> {
+		read_to_string_mut(Self::path(), &mut self.raw)
+	}
+
+	/// Reads the file without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string_async(Self::path()).await?
+		})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(Self::path(), &mut self.raw).await
+	}
+
+	/// Returns the statistics of every device.
+	pub fn entries(&self) -> impl Iterator<Item=DiskStat> + '_ {
+		self.raw.lines()
+			.filter_map(DiskStat::from_line)
+	}
+
+	/// Returns the statistics of a device by its name for example `sda`.
+	pub fn by_name(&self, name: &str) -> Option<DiskStat> {
+		self.entries()
+			.find(|e| e.name == name)
+	}
+
+	/// Returns every device which was added, removed or whose statistics
+	/// changed compared to the newer reading.
+	pub fn diff(&self, newer: &Self) -> Vec<Change<String, DiskStat>> {
+		diff_by_key(
+			self.entries().map(|e| (e.name.clone(), e)),
+			newer.entries().map(|e| (e.name.clone(), e))
+		)
+	}
+
+}
+
+/// The statistics of a block device.
+///
+/// A sector is always 512 bytes, regardless of the sector size of the
+/// device.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct DiskStat {
+	pub major: usize,
+	pub minor: usize,
+	pub name: String,
+	pub reads_completed: u64,
+	pub reads_merged: u64,
+	pub sectors_read: u64,
+	/// milliseconds spent reading
+	pub time_reading: u64,
+	pub writes_completed: u64,
+	pub writes_merged: u64,
+	pub sectors_written: u64,
+	/// milliseconds spent writing
+	pub time_writing: u64,
+	pub ios_in_progress: u64,
+	/// milliseconds spent doing I/Os
+	pub time_io: u64,
+	/// weighted milliseconds spent doing I/Os
+	pub weighted_time_io: u64,
+	/// Since linux 4.18
+	pub discards_completed: Option<u64>,
+	pub discards_merged: Option<u64>,
+	pub sectors_discarded: Option<u64>,
+	pub time_discarding: Option<u64>,
+	/// Since linux 5.5
+	pub flushes_completed: Option<u64>,
+	pub time_flushing: Option<u64>
+}
+
+impl DiskStat {
+
+	fn from_line(line: &str) -> Option<Self> {
+		let mut iter = line.split_whitespace();
+		let major = iter.next()?.parse().ok()?;
+		let minor = iter.next()?.parse().ok()?;
+		let name = iter.next()?.to_string();
+
+		let mut stat = Self { major, minor, name, ..Default::default() };
+		stat.parse_counters(iter)?;
+		Some(stat)
+	}
+
+	/// Parses the counters which follow the name in /proc/diskstats, which
+	/// is also the format of `/sys/class/block/{name}/stat`.
+	fn parse_counters<'a>(
+		&mut self,
+		mut iter: impl Iterator<Item=&'a str>
+	) -> Option<()> {
+		let mut num = || -> Option<u64> { iter.next()?.parse().ok() };
+
+		self.reads_completed = num()?;
+		self.reads_merged = num()?;
+		self.sectors_read = num()?;
+		self.time_reading = num()?;
+		self.writes_completed = num()?;
+		self.writes_merged = num()?;
+		self.sectors_written = num()?;
+		self.time_writing = num()?;
+		self.ios_in_progress = num()?;
+		self.time_io = num()?;
+		self.weighted_time_io = num()?;
+		self.discards_completed = num();
+		self.discards_merged = num();
+		self.sectors_discarded = num();
+		self.time_discarding = num();
+		self.flushes_completed = num();
+		self.time_flushing = num();
+		Some(())
+	}
+
+	/// The amount of data read.
+	pub fn read(&self) -> DataSize {
+		DataSize::from_size_bytes(u128::from(self.sectors_read) * 512).unwrap()
+	}
+
+	/// The amount of data written.
+	pub fn written(&self) -> DataSize {
+		DataSize::from_size_bytes(u128::from(self.sectors_written) * 512).unwrap()
+	}
+
+	/// Computes the throughput since an earlier reading of the same disk.
+	pub fn rates(&self, earlier: &Self, elapsed: Duration) -> DiskStatRates {
+		let rate = |n, e| Rate::from_counters(n, e, elapsed);
+
+		DiskStatRates {
+			reads: rate(self.reads_completed, earlier.reads_completed),
+			writes: rate(self.writes_completed, earlier.writes_completed),
+			read_bytes: rate(self.sectors_read, earlier.sectors_read)
+				.scale(512.0),
+			written_bytes: rate(self.sectors_written, earlier.sectors_written)
+				.scale(512.0),
+			// time_io is in milliseconds
+			busy: (rate(self.time_io, earlier.time_io).per_second() / 1000.0)
+				.min(1.0)
+		}
+	}
+
+}
+
+/// The throughput of a disk between two [`DiskStat`]s.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct DiskStatRates {
+	pub reads: Rate,
+	pub writes: Rate,
+	pub read_bytes: Rate,
+	pub written_bytes: Rate,
+	/// The fraction of time the disk was busy from 0 to 1.
+	pub busy: f64
+}
+
+/// The statistics of one block device from `/sys/class/block/{name}/stat`.
+///
+/// The directory stays open and the buffers are reused, so
+/// [`reload`](Self::reload) does not allocate. The values are the same as
+/// in /proc/diskstats, without reading the lines of every other device.
+#[derive(Debug)]
+pub struct BlockStatistics {
+	dir: SysDir,
+	stat: DiskStat
+}
+
+impl BlockStatistics {
+	/// Opens the statistics of a block device or partition by its name, for
+	/// example `sda` or `nvme0n1p1`.
+	pub fn open(name: &str) -> io::Result<Self> {
+		Self::from_path(Path::new("/sys/class/block").join(name))
+	}
+
+	/// Opens the statistics from a path like `/sys/block/sda`.
+	pub fn from_path(path: impl Into<PathBuf>) -> io::Result<Self> {
+		let mut dir = SysDir::open(path)?;
+		let name = dir.path().file_name()
+			.and_then(|n| n.to_str())
+			.unwrap_or_default()
+			.to_string();
+		// the device number like 8:0
+		let (major, minor) = dir.read_string("dev")?
+			.split_once(':')
+			.and_then(|(ma, mi)| Some((ma.parse().ok()?, mi.parse().ok()?)))
+			.ok_or_else(|| {
+				Error::parse(dir.path().join("dev"), None, "device number")
+			})?;
+
+		let mut stats = Self {
+			dir,
+			stat: DiskStat { major, minor, name, ..Default::default() }
+		};
+		stats.reload()?;
+		Ok(stats)
+	}
+
+	pub fn path(&self) -> &Path {
+		self.dir.path()
+	}
+
+	/// Reads the statistics again.
+	pub fn reload(&mut self) -> io::Result<()> {
+		let raw = self.dir.read_string("stat")?;
+		match self.stat.parse_counters(raw.split_whitespace()) {
+			Some(()) => Ok(()),
+			None => Err(Error::parse(
+				self.dir.path().join("stat"),
+				None,
+				"counters"
+			).into())
+		}
+	}
+
+	/// The statistics of the last reading.
+	pub fn stat(&self) -> &DiskStat {
+		&self.stat
+	}
+}
+
+/// Returns the sector size for a given path.
+/// 
+/// This uses the ioctl call `BLKSSZGET`.
+pub fn sector_size(path: impl AsRef<Path>) -> io::Result<u64> {
+	blkdev_sector_size(fs::File::open(path)?)
+}
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::diff::ChangeKind;
+
+	fn partitions() -> Partitions {
+		Partitions::from_string("\
+major minor  #blocks  name
+
+   7        0     142152 loop0
+   7        1     101528 loop1
+ 259        0  500107608 nvme0n1
+ 259        1     510976 nvme0n1p1\n\
+		".into())
+	}
+
+	fn cmp_entry(major: usize, minor: usize, blocks: usize, name: &str, e: &PartitionEntry<'_>) {
+		assert_eq!(e.major().unwrap(), major);
+		assert_eq!(e.minor().unwrap(), minor);
+		assert_eq!(e.blocks().unwrap(), blocks);
+		assert_eq!(e.name().unwrap(), name);
+	}
+
+	#[test]
+	fn all_partitions() {
+		let part = partitions();
+		let mut e = part.entries();
+		println!("e: {:?}", part.entries().collect::<Vec<_>>());
+		cmp_entry(7, 0, 142152, "loop0", &e.next().unwrap());
+		cmp_entry(7, 1, 101528, "loop1", &e.next().unwrap());
+		cmp_entry(259, 0, 500107608, "nvme0n1", &e.next().unwrap());
+		cmp_entry(259, 1, 510976, "nvme0n1p1", &e.next().unwrap());
+		assert!(e.next().is_none());
+	}
+
+	fn mount_points() -> MountPoints {
+		MountPoints::from_string("\
+26 29 0:5 / /dev rw,nosuid,noexec,relatime shared:2 - devtmpfs udev rw,size=8123832k,nr_inodes=2030958,mode=755
+27 26 0:24 / /dev/pts rw,nosuid,noexec,relatime shared:3 - devpts devpts rw,gid=5,mode=620,ptmxmode=000
+35 33 0:30 / /sys/fs/cgroup/systemd rw,nosuid,nodev,noexec,relatime shared:11 other - cgroup cgroup rw,xattr,name=systemd
+2509 28 0:25 /snapd/ns /run/snapd/ns rw,nosuid,nodev,noexec,relatime - tmpfs tmpfs rw,size=1631264k,mode=755
+2893 2509 0:4 mnt:[4026532961] /run/snapd/ns/snap-store.mnt rw - nsfs nsfs rw
+3012 29 8:17 / /media/my\\040drive rw,relatime - ext4 /dev/sdb1 rw\n\
+		".into())
+	}
+
+	fn cmp_point(
+		mount_id: usize,
+		parent_id: usize,
+		major_minor: &str,
+		root: &str,
+		mount_point: &str,
+		mount_options: &str,
+		optional_fields: &[(&str, Option<&str>)],
+		filesystem_type: &str,
+		mount_source: &str,
+		super_options: &str,
+		point: &MountPoint<'_>
+	) {
+		assert_eq!(point.mount_id().unwrap(), mount_id);
+		assert_eq!(point.parent_id().unwrap(), parent_id);
+		assert_eq!(point.major_minor().unwrap(), major_minor);
+		assert_eq!(point.root().unwrap(), root);
+		assert_eq!(point.mount_point().unwrap(), mount_point);
+		assert_eq!(point.mount_options().unwrap(), mount_options);
+		assert_eq!(point.optional_fields().collect::<Vec<_>>(), optional_fields);
+		assert_eq!(point.filesystem_type().unwrap(), filesystem_type);
+		assert_eq!(point.mount_source().unwrap(), mount_source);
+		assert_eq!(point.super_options().unwrap(), super_options);
+	}
+
+	#[test]
+	fn all_mount_points() {
+		let mt = mount_points();
+		let mut mt = mt.points();
+		cmp_point(
+			26, 29, "0:5", "/", "/dev", "rw,nosuid,noexec,relatime",
+			&[("shared", Some("2"))], "devtmpfs", "udev",
+			"rw,size=8123832k,nr_inodes=2030958,mode=755",
+			&mt.next().unwrap()
+		);
+		cmp_point(
+			27, 26, "0:24", "/", "/dev/pts", "rw,nosuid,noexec,relatime",
+			&[("shared", Some("3"))], "devpts", "devpts",
+			"rw,gid=5,mode=620,ptmxmode=000",
+			&mt.next().unwrap()
+		);
+		cmp_point(
+			35, 33, "0:30", "/", "/sys/fs/cgroup/systemd", "rw,nosuid,nodev,noexec,relatime",
+			&[("shared", Some("11")), ("other", None)], "cgroup", "cgroup", "rw,xattr,name=systemd",
+			&mt.next().unwrap()
+		);
+		cmp_point(
+			2509, 28, "0:25", "/snapd/ns", "/run/snapd/ns", "rw,nosuid,nodev,noexec,relatime",
+			&[], "tmpfs", "tmpfs", "rw,size=1631264k,mode=755",
+			&mt.next().unwrap()
+		);
+		cmp_point(
+			2893, 2509, "0:4", "mnt:[4026532961]", "/run/snapd/ns/snap-store.mnt", "rw",
+			&[], "nsfs", "nsfs", "rw",
+			&mt.next().unwrap()
+		);
+		cmp_point(
+			3012, 29, "8:17", "/", "/media/my drive", "rw,relatime",
+			&[], "ext4", "/dev/sdb1", "rw",
+			&mt.next().unwrap()
+		);
+	}
+
+	#[test]
+	fn owned_partition() {
+		let part = partitions();
+		let owned = part.entries().nth(2).unwrap().to_owned_entry();
+		assert_eq!(owned, OwnedPartitionEntry {
+			major: Some(259),
+			minor: Some(0),
+			blocks: Some(500107608),
+			name: Some("nvme0n1".into())
+		});
+	}
+
+	#[test]
+	fn owned_mount_point() {
+		let mt = mount_points();
+		let owned = mt.points().nth(2).unwrap().to_owned_point();
+		assert_eq!(owned.mount_id, Some(35));
+		assert_eq!(owned.mount_point.as_deref(), Some("/sys/fs/cgroup/systemd"));
+		assert_eq!(owned.optional_fields, [
+			("shared".to_string(), Some("11".to_string())),
+			("other".to_string(), None)
+		]);
+		assert_eq!(owned.filesystem_type.as_deref(), Some("cgroup"));
+	}
+
+	#[test]
+	fn unescaped_mount_point() {
+		let mt = mount_points();
+		let owned = mt.points().nth(5).unwrap().to_owned_point();
+		assert_eq!(owned.mount_point.as_deref(), Some("/media/my drive"));
+
+		let mt = MountPoints::from_string("26 29 0:5 /".into());
+		let point = mt.points().next().unwrap();
+		let e = point.stats().err().unwrap();
+		assert_eq!(e.to_string(), "/proc/self/mountinfo: missing field mount_point");
+		assert!(point.to_owned_point().stats().is_err());
+	}
+
+	#[test]
+	fn fs_usage_watcher() {
+		let mut watcher = FsUsageWatcher::new(["/a"], FsThresholds {
+			space: Some(0.9),
+			inodes: None
+		});
+		let mut events = vec![];
+		watcher.update(0, FsResource::Space, Some(0.5), &mut events);
+		watcher.update(0, FsResource::Inodes, Some(1.0), &mut events);
+		assert!(events.is_empty());
+
+		watcher.update(0, FsResource::Space, Some(0.95), &mut events);
+		watcher.update(0, FsResource::Space, Some(0.96), &mut events);
+		assert_eq!(events, [FsUsageEvent {
+			mount_point: "/a".into(),
+			resource: FsResource::Space,
+			kind: FsUsageEventKind::Breached,
+			used: 0.95
+		}]);
+
+		events.clear();
+		watcher.update(0, FsResource::Space, Some(0.4), &mut events);
+		assert_eq!(events[0].kind, FsUsageEventKind::Recovered);
+	}
+
+	#[test]
+	fn overlay_layers() {
+		let mt = MountPoints::from_string("\
+1234 29 0:52 / /var/lib/docker/overlay2/abc/merged rw,relatime - overlay overlay rw,lowerdir=/var/lib/docker/overlay2/l/K3Q:/var/lib/docker/overlay2/l/Z7X,upperdir=/var/lib/docker/overlay2/abc/diff,workdir=/var/lib/docker/overlay2/abc/work
+".into());
+		let point = mt.points().next().unwrap();
+		let layers = point.overlay().unwrap();
+		assert_eq!(layers.lower, [
+			PathBuf::from("/var/lib/docker/overlay2/l/K3Q"),
+			PathBuf::from("/var/lib/docker/overlay2/l/Z7X")
+		]);
+		assert_eq!(layers.work.unwrap(), Path::new("/var/lib/docker/overlay2/abc/work"));
+		let layers = point.to_owned_point().overlay().unwrap();
+		assert_eq!(layers.layer_ids().collect::<Vec<_>>(), ["K3Q", "Z7X", "abc"]);
+
+		let layers = OverlayLayers::from_options(
+			"ro,lowerdir+=/snapshots/1/fs,lowerdir+=/a:b,datadir+=/data"
+		);
+		assert_eq!(layers.lower, [
+			PathBuf::from("/snapshots/1/fs"),
+			PathBuf::from("/a:b"),
+			PathBuf::from("/data")
+		]);
+		assert!(layers.upper.is_none());
+		assert_eq!(layers.layer_ids().collect::<Vec<_>>(), ["1"]);
+
+		assert!(mount_points().points().next().unwrap().overlay().is_none());
+
+		// mounted with lowerdir=/l/a\:b:/l/c\\:/l/d\,e and upperdir="/u v"
+		let mt = MountPoints::from_string("\
+1235 29 0:53 / /merged rw,relatime - overlay overlay rw,lowerdir=/l/a\\134:b:/l/c\\134\\134:/l/d\\134\\054e,upperdir=/u\\040v,workdir=/w
+".into());
+		let layers = mt.points().next().unwrap().overlay().unwrap();
+		assert_eq!(layers.lower, [
+			PathBuf::from("/l/a:b"),
+			PathBuf::from("/l/c\\"),
+			PathBuf::from("/l/d,e")
+		]);
+		assert_eq!(layers.upper.unwrap(), Path::new("/u v"));
+	}
+
+	#[test]
+	fn fuse_mount() {
+		let mt = MountPoints::from_string("\
+642 29 0:63 / /home/user/remote rw,nosuid,nodev,relatime - fuse.sshfs user@host:/ rw,user_id=1000,group_id=1000
+".into());
+		let fuse = mt.points().next().unwrap().fuse().unwrap();
+		assert_eq!(fuse, FuseMount {
+			connection: 63,
+			subtype: Some("sshfs".into()),
+			user_id: Some(1000),
+			group_id: Some(1000)
+		});
+		assert_eq!(fuse.connection_path(), Path::new("/sys/fs/fuse/connections/63"));
+		assert!(mount_points().points().next().unwrap().fuse().is_none());
+
+		let mt = MountPoints::from_string("\
+701 29 8:17 / /mnt/ntfs rw,relatime - fuseblk /dev/sdb1 rw,user_id=0,group_id=0,allow_other
+".into());
+		let fuse = mt.points().next().unwrap().fuse().unwrap();
+		assert_eq!(fuse.subtype, None);
+		assert_eq!(fuse.connection, 8388625);
+		assert_eq!(
+			fuse.connection_path(),
+			Path::new("/sys/fs/fuse/connections/8388625")
+		);
+	}
+
+	#[test]
+	fn dir_size_apparent() {
+		let dir = std::env::temp_dir()
+			.join(format!("linux-info-dir-size-{}", std::process::id()));
+		fs::create_dir_all(dir.join("a/b")).unwrap();
+		fs::write(dir.join("one"), [0; 100]).unwrap();
+		fs::write(dir.join("a/two"), [0; 200]).unwrap();
+		fs::write(dir.join("a/b/three"), [0; 300]).unwrap();
+		fs::hard_link(dir.join("one"), dir.join("a/link")).unwrap();
+
+		let dirs = |path: &Path| fs::symlink_metadata(path).unwrap().len();
+		let dir_bytes = dirs(&dir) + dirs(&dir.join("a")) + dirs(&dir.join("a/b"));
+
+		let options = DirSizeOptions {
+			apparent_size: true,
+			threads: 3,
+			..Default::default()
+		};
+		let size = dir_size(&dir, &options).unwrap();
+		assert_eq!(size.as_bytes() as u64, dir_bytes + 600);
+
+		let size = dir_size(&dir, &DirSizeOptions {
+			max_depth: Some(1),
+			..options
+		}).unwrap();
+		assert_eq!(size.as_bytes() as u64, dir_bytes + 300);
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn mount_events() {
+		let before = MountPoints::from_string("\
+26 29 0:5 / /dev rw,nosuid - devtmpfs udev rw
+30 1 8:1 / / rw,relatime - ext4 /dev/sda1 rw,errors=remount-ro
+31 1 8:2 / /data ro,relatime - xfs /dev/sda2 ro
+".into());
+		let after = MountPoints::from_string("\
+30 1 8:1 / / ro,relatime - ext4 /dev/sda1 ro,errors=remount-ro
+31 1 8:2 / /data rw,relatime - xfs /dev/sda2 rw
+40 1 8:3 / /mnt rw - vfat /dev/sda3 rw
+".into());
+		let owned = |m: &MountPoints| -> Vec<_> {
+			m.points().map(|p| p.to_owned_point()).collect()
+		};
+
+		let root = after.points().next().unwrap();
+		assert!(root.is_read_only());
+		assert_eq!(root.errors_behavior(), Some("remount-ro"));
+		assert!(!before.points().next().unwrap().is_read_only());
+
+		let events = super::mount_events(owned(&before), &owned(&after));
+		let kinds: Vec<_> = events.iter()
+			.map(|e| (e.kind, e.mount.mount_id.unwrap()))
+			.collect();
+		// an administrative remount of an errors=remount-ro filesystem
+		// without ext4 errors
+		assert_eq!(kinds, [
+			(MountEventKind::RemountedReadOnly { errors: false }, 30),
+			(MountEventKind::RemountedReadWrite, 31),
+			(MountEventKind::Mounted, 40),
+			(MountEventKind::Unmounted, 26)
+		]);
+	}
+
+	#[test]
+	fn strict_partitions() {
+		assert_eq!(partitions().try_entries().unwrap().len(), 4);
+
+		let part = Partitions::from_string("\
+major minor  #blocks  name
+
+   7        0     142152 loop0
+ 259        x  500107608 nvme0n1
+".into());
+		let e = part.try_entries().unwrap_err();
+		assert_eq!(
+			e.to_string(),
+			"/proc/partitions:4: failed to parse minor from \"259        x  500107608 nvme0n1\""
+		);
+	}
+
+	#[test]
+	fn strict_mount_points() {
+		assert_eq!(mount_points().try_points().unwrap().len(), 6);
+
+		let mt = MountPoints::from_string("\
+26 29 0:5 / /dev rw,nosuid,noexec,relatime shared:2 - devtmpfs udev rw
+27 26 0:24 / /dev/pts rw shared:3
+".into());
+		let e = mt.try_points().unwrap_err();
+		assert!(e.to_string().starts_with(
+			"/proc/self/mountinfo:2: failed to parse filesystem_type from "
+		));
+	}
+
+	#[test]
+	fn disk_stats() {
+		let stats = DiskStats::from_string("\
+ 259       0 nvme0n1 193461 48396 14093402 33807 1178290 622914 44367474 1019361 0 574340 1094596 0 0 0 0 117318 41427
+ 259       1 nvme0n1p1 263 1000 14886 50 2 0 2 0 0 88 50 0 0 0 0 0 0
+   8       0 sda 4137 1210 332950 2346 111 41 1202 254 0 1560 2600
+".into());
+		assert_eq!(stats.entries().count(), 3);
+		let nvme = stats.by_name("nvme0n1").unwrap();
+		assert_eq!(nvme.major, 259);
+		assert_eq!(nvme.reads_completed, 193461);
+		assert_eq!(nvme.sectors_written, 44367474);
+		assert_eq!(nvme.flushes_completed, Some(117318));
+		assert_eq!(nvme.time_flushing, Some(41427));
+		let sda = stats.by_name("sda").unwrap();
+		assert_eq!(sda.weighted_time_io, 2600);
+		assert_eq!(sda.discards_completed, None);
+	}
+
+	#[test]
+	fn block_statistics() {
+		let dir = std::env::temp_dir()
+			.join(format!("linux-info-block-stat-{}", std::process::id()));
+		let path = dir.join("sda");
+		fs::create_dir_all(&path).unwrap();
+		fs::write(path.join("dev"), "8:0\n").unwrap();
+		fs::write(
+			path.join("stat"),
+			"    4137 1210 332950 2346 111 41 1202 254 0 1560 2600\n"
+		).unwrap();
+
+		let mut stats = BlockStatistics::from_path(&path).unwrap();
+		let stat = stats.stat();
+		assert_eq!(stat.name, "sda");
+		assert_eq!((stat.major, stat.minor), (8, 0));
+		assert_eq!(stat.reads_completed, 4137);
+		assert_eq!(stat.weighted_time_io, 2600);
+		assert_eq!(stat.discards_completed, None);
+
+		fs::write(
+			path.join("stat"),
+			"4237 1210 334950 2346 121 41 1402 254 0 2060 2600 5 0 40 1 7 3\n"
+		).unwrap();
+		stats.reload().unwrap();
+		assert_eq!(stats.stat().reads_completed, 4237);
+		assert_eq!(stats.stat().sectors_discarded, Some(40));
+		assert_eq!(stats.stat().time_flushing, Some(3));
+
+		fs::write(path.join("stat"), "4237 x\n").unwrap();
+		let e = stats.reload().unwrap_err();
+		fs::remove_dir_all(&dir).unwrap();
+		assert!(e.to_string().ends_with("stat: failed to parse counters"));
+	}
+
+	#[test]
+	fn disk_stat_rates() {
+		let earlier = DiskStats::from_string("\
+   8       0 sda 4137 1210 332950 2346 111 41 1202 254 0 1560 2600
+".into());
+		let stats = DiskStats::from_string("\
+   8       0 sda 4237 1210 334950 2346 121 41 1402 254 0 2060 2600
+".into());
+		let rates = stats.by_name("sda").unwrap().rates(
+			&earlier.by_name("sda").unwrap(),
+			Duration::from_secs(1)
+		);
+		assert_eq!(rates.reads.per_second(), 100.0);
+		assert_eq!(rates.read_bytes.per_second(), 2000.0 * 512.0);
+		assert_eq!(rates.writes.per_second(), 10.0);
+		assert_eq!(rates.written_bytes.per_second(), 200.0 * 512.0);
+		assert_eq!(rates.busy, 0.5);
+	}
+
+	#[test]
+	fn raid_case_1() {
+		let raids = Raids::from_string("\
+Personalities : [raid1] [linear] [multipath] [raid0] [raid6] [raid5] [raid4] [raid10] 
+md10 : active raid1 sdd[0] sdc[1]
+      3906886464 blocks super 1.2 [2/2] [UU]
+      bitmap: 0/30 pages [0KB], 65536KB chunk
+
+md0 : active raid1 sdb[1] sda[0]
+      499975488 blocks super 1.2 [2/2] [UU]
+      bitmap: 3/4 pages [12KB], 65536KB chunk
+
+unused devices: <none>\n".into());
+		assert_eq!(raids.raids().count(), 2);
+		let first = raids.raids().next().unwrap();
+		assert_eq!(first.name(), "md10");
+		assert_eq!(first.used_devices().unwrap(), 2);
+		assert_eq!(first.ideal_devices().unwrap(), 2);
+		assert!(first.progress().is_none());
+		assert_eq!(first.devices().count(), first.used_devices().unwrap());
+	}
+
+	#[test]
+	fn raid_case_2() {
+		let raids = Raids::from_string("\
+Personalities : [raid1] [raid6] [raid5] [raid4]
+md127 : active raid5 sdh1[6] sdg1[4] sdf1[3] sde1[2] sdd1[1] sdc1[0]
+      1464725760 blocks level 5, 64k chunk, algorithm 2 [6/5] [UUUUU_]
+      [==>..................]  recovery = 12.6% (37043392/292945152) finish=127.5min speed=33440K/sec
+
+unused devices: <none>\n".into());
+		assert_eq!(raids.raids().count(), 1);
+		let first = raids.raids().next().unwrap();
+		let comp_dev: Vec<_> = first.devices().collect();
+		assert_eq!(comp_dev, [(6, "sdh1"), (4, "sdg1"), (3, "sdf1"), (2, "sde1"), (1, "sdd1"), (0, "sdc1")]);
+		assert_eq!(first.kind().unwrap(), "raid5");
+		assert_eq!(first.usable_blocks().unwrap(), 1464725760);
+		assert_eq!(first.used_devices().unwrap(), 6);
+		assert_eq!(first.ideal_devices().unwrap(), 5);
+		assert_eq!(first.progress().unwrap(), "[==>..................]  recovery = 12.6% (37043392/292945152) finish=127.5min speed=33440K/sec");
+		assert_eq!(first.devices().count(), first.used_devices().unwrap());
+	}
+
+	#[test]
+	fn raid_case_3() {
+		let raids = Raids::from_string("\
+Personalities : [linear] [raid0] [raid1] [raid5] [raid4] [raid6]
+md0 : active raid6 sdf1[0] sde1[1] sdd1[2] sdc1[3] sdb1[4] sda1[5] hdb1[6]
+      1225557760 blocks level 6, 256k chunk, algorithm 2 [7/7] [UUUUUUU]
+      bitmap: 0/234 pages [0KB], 512KB chunk
+
+unused devices: <none>\n".into());
+		assert_eq!(raids.raids().count(), 1);
+		let first = raids.raids().next().unwrap();
+		assert_eq!(first.devices().count(), first.used_devices().unwrap());
+	}
+
+
+	#[test]
+	fn disk_stats_diff() {
+		let earlier = DiskStats::from_string("\
+ 259       0 nvme0n1 193461 48396 14093402 33807 1178290 622914 44367474 1019361 0 574340 1094596 0 0 0 0 117318 41427
+   8       0 sda 4137 1210 332950 2346 111 41 1202 254 0 1560 2600
+   8      16 sdb 100 0 800 10 0 0 0 0 0 10 10
+".into());
+		let stats = DiskStats::from_string("\
+ 259       0 nvme0n1 193461 48396 14093402 33807 1178290 622914 44367474 1019361 0 574340 1094596 0 0 0 0 117318 41427
+   8       0 sda 4237 1210 334950 2346 121 41 1402 254 0 2060 2600
+ 253       0 dm-0 10 0 80 1 0 0 0 0 0 1 1
+".into());
+		let diff = earlier.diff(&stats);
+		let kinds: Vec<_> = diff.iter()
+			.map(|c| (c.key.as_str(), c.kind()))
+			.collect();
+		assert_eq!(kinds, [
+			("sda", ChangeKind::Changed),
+			("dm-0", ChangeKind::Added),
+			("sdb", ChangeKind::Removed)
+		]);
+		assert_eq!(diff[0].old.as_ref().unwrap().reads_completed, 4137);
+		assert_eq!(diff[0].new.as_ref().unwrap().reads_completed, 4237);
+		assert_eq!(diff[1].new.as_ref().unwrap().major, 253);
+		assert_eq!(diff[2].old.as_ref().unwrap().sectors_read, 800);
+	}
+}
+
+// get block number
+// /sys/block/<part>/dev   returns 7:0
+// uuid /sys/dev/block/7:0/dm/uuid
+
+/*
+Personalities : [raid1] [linear] [multipath] [raid0] [raid6] [raid5] [raid4] [raid10] 
+md10 : active raid1 sdd[0] sdc[1]
+      3906886464 blocks super 1.2 [2/2] [UU]
+      bitmap: 0/30 pages [0KB], 65536KB chunk
+
+md0 : active raid1 sdb[1] sda[0]
+      499975488 blocks super 1.2 [2/2] [UU]
+      bitmap: 3/4 pages [12KB], 65536KB chunk
+
+unused devices: <none>
+
+
+Personalities : [raid1] [raid6] [raid5] [raid4]
+md127 : active raid5 sdh1[6] sdg1[4] sdf1[3] sde1[2] sdd1[1] sdc1[0]
+      1464725760 blocks level 5, 64k chunk, algorithm 2 [6/5] [UUUUU_]
+      [==>..................]  recovery = 12.6% (37043392/292945152) finish=127.5min speed=33440K/sec
+
+unused devices: <none> 
+
+
+Personalities : [linear] [raid0] [raid1] [raid5] [raid4] [raid6]
+md0 : active raid6 sdf1[0] sde1[1] sdd1[2] sdc1[3] sdb1[4] sda1[5] hdb1[6]
+      1225557760 blocks level 6, 256k chunk, algorithm 2 [7/7] [UUUUUUU]
+      bitmap: 0/234 pages [0KB], 512KB chunk
+
+unused devices: <none>
+*/
\ No newline at end of file
@@ -0,0 +1,262 @@
+//! Get information about block devices backed by the network, ceph rbd in
+//! `/sys/bus/rbd/devices` and nbd in `/sys/block/nbd*`.
+//!
+//! Use [`RbdDevice::dev_path`] or [`NbdDevice::dev_path`] to find the mounts
+//! backed by them.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::storage::network_block::{RbdDevice, NbdDevice};
+//!
+//! for rbd in RbdDevice::all().unwrap() {
+//!     println!("{:?} {:?}/{:?}", rbd.dev_path(), rbd.pool(), rbd.image());
+//! }
+//! for nbd in NbdDevice::all().unwrap() {
+//!     println!("{:?} connected: {:?}", nbd.dev_path(), nbd.is_connected());
+//! }
+//! ```
+
+use crate::util::{read_sys_string, read_sys_value, read_sys_opt};
+use crate::unit::DataSize;
+
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+
+const RBD_PATH: &str = "/sys/bus/rbd/devices";
+const BLOCK_PATH: &str = "/sys/block";
+
+fn list<T>(
+	dir: &str,
+	filter: impl Fn(&str) -> bool,
+	f: impl Fn(PathBuf) -> T
+) -> io::Result<Vec<T>> {
+	let dir = match fs::read_dir(dir) {
+		Ok(d) => d,
+		Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+		Err(e) => return Err(e)
+	};
+
+	let mut paths = vec![];
+	for entry in dir {
+		let entry = entry?;
+		if entry.file_name().to_str().map(&filter).unwrap_or(false) {
+			paths.push(entry.path());
+		}
+	}
+	paths.sort();
+	Ok(paths.into_iter().map(f).collect())
+}
+
+/// A mapped ceph rbd image for example `/sys/bus/rbd/devices/0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RbdDevice {
+	path: PathBuf
+}
+
+impl RbdDevice {
+	/// Returns all mapped rbd images.
+	///
+	/// Returns an empty list if the rbd module is not loaded.
+	pub fn all() -> io::Result<Vec<Self>> {
+		list(RBD_PATH, |_| true, Self::from_path)
+	}
+
+	/// Creates a device from a path like `/sys/bus/rbd/devices/0`.
+	pub fn from_path(path: impl Into<PathBuf>) -> Self {
+		Self { path: path.into() }
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// The id of the device, the `0` of `/dev/rbd0`.
+	pub fn id(&self) -> Option<&str> {
+		self.path.file_name()?.to_str()
+	}
+
+	/// The block device for example `/dev/rbd0`.
+	pub fn dev_path(&self) -> Option<PathBuf> {
+		Some(format!("/dev/rbd{}", self.id()?).into())
+	}
+
+	/// The name of the pool containing the image.
+	pub fn pool(&self) -> io::Result<String> {
+		read_sys_string(self.path.join("pool"))
+	}
+
+	/// The namespace inside of the pool, `None` for the default namespace.
+	pub fn pool_namespace(&self) -> io::Result<Option<String>> {
+		read_sys_opt::<String>(self.path.join("pool_ns"))
+			.map(|n| n.filter(|n| !n.is_empty()))
+	}
+
+	/// The name of the image.
+	pub fn image(&self) -> io::Result<String> {
+		read_sys_string(self.path.join("name"))
+	}
+
+	/// The mapped snapshot or `-` if the image itself is mapped.
+	pub fn snapshot(&self) -> io::Result<String> {
+		read_sys_string(self.path.join("current_snap"))
+	}
+
+	/// The id of the ceph client for example `client4152`.
+	pub fn client_id(&self) -> io::Result<String> {
+		read_sys_string(self.path.join("client_id"))
+	}
+
+	/// The fsid of the ceph cluster.
+	pub fn cluster_fsid(&self) -> io::Result<String> {
+		read_sys_string(self.path.join("cluster_fsid"))
+	}
+
+	/// The size of the image.
+	pub fn size(&self) -> io::Result<DataSize> {
+		read_sys_value::<u64>(self.path.join("size"))
+			.map(|b| DataSize::from_bytes(b.into()))
+	}
+}
+
+/// A network block device for example `/sys/block/nbd0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NbdDevice {
+	path: PathBuf
+}
+
+impl NbdDevice {
+	/// Returns all nbd devices, connected or not.
+	///
+	/// Returns an empty list if the nbd module is not loaded.
+	pub fn all() -> io::Result<Vec<Self>> {
+		list(BLOCK_PATH, |n| n.starts_with("nbd"), Self::from_path)
+	}
+
+	/// Creates a device from a path like `/sys/block/nbd0`.
+	pub fn from_path(path: impl Into<PathBuf>) -> Self {
+		Self { path: path.into() }
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// The name for example `nbd0`.
+	pub fn name(&self) -> Option<&str> {
+		self.path.file_name()?.to_str()
+	}
+
+	/// The block device for example `/dev/nbd0`.
+	pub fn dev_path(&self) -> Option<PathBuf> {
+		Some(Path::new("/dev").join(self.name()?))
+	}
+
+	/// The pid of the process which handles the connection, `None` if the
+	/// device is not connected.
+	pub fn pid(&self) -> io::Result<Option<u32>> {
+		read_sys_opt(self.path.join("pid"))
+	}
+
+	/// Returns true if a client is connected to a server.
+	pub fn is_connected(&self) -> io::Result<bool> {
+		self.pid().map(|p| p.is_some())
+	}
+
+	/// The backend set by the client, for example the server address or the
+	/// image path used by qemu-nbd.
+	///
+	/// Requires a kernel with backend support (5.x) and a client setting it.
+	pub fn backend(&self) -> io::Result<Option<String>> {
+		read_sys_opt::<String>(self.path.join("backend"))
+			.map(|b| b.filter(|b| !b.is_empty()))
+	}
+
+	/// The size of the device, zero if not connected.
+	pub fn size(&self) -> io::Result<DataSize> {
+		// always in 512 byte sectors
+		read_sys_value::<u64>(self.path.join("size"))
+			.map(|s| DataSize::from_bytes(u128::from(s) * 512))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn temp_dir(name: &str) -> PathBuf {
+		let dir = std::env::temp_dir()
+			.join(format!("linux-info-{}-{}", name, std::process::id()));
+		fs::create_dir_all(&dir).unwrap();
+		dir
+	}
+
+	#[test]
+	fn rbd() {
+		let dir = temp_dir("rbd");
+		let path = dir.join("0");
+		fs::create_dir_all(&path).unwrap();
+		fs::write(path.join("pool"), "rbd\n").unwrap();
+		fs::write(path.join("pool_ns"), "\n").unwrap();
+		fs::write(path.join("name"), "vm-100-disk-0\n").unwrap();
+		fs::write(path.join("current_snap"), "-\n").unwrap();
+		fs::write(path.join("client_id"), "client4152\n").unwrap();
+		fs::write(path.join("size"), "10737418240\n").unwrap();
+
+		let rbd = RbdDevice::from_path(&path);
+		assert_eq!(rbd.id(), Some("0"));
+		assert_eq!(rbd.dev_path(), Some("/dev/rbd0".into()));
+		assert_eq!(rbd.pool().unwrap(), "rbd");
+		assert_eq!(rbd.pool_namespace().unwrap(), None);
+		assert_eq!(rbd.image().unwrap(), "vm-100-disk-0");
+		assert_eq!(rbd.snapshot().unwrap(), "-");
+		assert_eq!(rbd.client_id().unwrap(), "client4152");
+		assert_eq!(rbd.size().unwrap(), DataSize::from_bytes(10 << 30));
+
+		fs::write(path.join("pool_ns"), "tenant\n").unwrap();
+		assert_eq!(rbd.pool_namespace().unwrap().as_deref(), Some("tenant"));
+		// missing on old kernels
+		fs::remove_file(path.join("pool_ns")).unwrap();
+		assert_eq!(rbd.pool_namespace().unwrap(), None);
+		assert!(rbd.cluster_fsid().is_err());
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn nbd() {
+		let dir = temp_dir("nbd");
+		for name in ["nbd1", "nbd0", "sda"] {
+			fs::create_dir_all(dir.join(name)).unwrap();
+		}
+		let nbds = list(
+			dir.to_str().unwrap(),
+			|n| n.starts_with("nbd"),
+			NbdDevice::from_path
+		).unwrap();
+		let names: Vec<_> = nbds.iter().map(|n| n.name().unwrap()).collect();
+		assert_eq!(names, ["nbd0", "nbd1"]);
+
+		let nbd = &nbds[0];
+		assert_eq!(nbd.dev_path(), Some("/dev/nbd0".into()));
+		fs::write(nbd.path().join("size"), "0\n").unwrap();
+		// the pid file only exists while connected
+		assert_eq!(nbd.pid().unwrap(), None);
+		assert!(!nbd.is_connected().unwrap());
+		assert_eq!(nbd.backend().unwrap(), None);
+		assert_eq!(nbd.size().unwrap(), DataSize::from_bytes(0));
+
+		fs::write(nbd.path().join("pid"), "4242\n").unwrap();
+		fs::write(nbd.path().join("backend"), "192.168.1.2:10809\n").unwrap();
+		fs::write(nbd.path().join("size"), "2097152\n").unwrap();
+		assert_eq!(nbd.pid().unwrap(), Some(4242));
+		assert!(nbd.is_connected().unwrap());
+		let backend = nbd.backend().unwrap();
+		assert_eq!(backend.as_deref(), Some("192.168.1.2:10809"));
+		assert_eq!(nbd.size().unwrap(), DataSize::from_bytes(1 << 30));
+
+		fs::remove_dir_all(&dir).unwrap();
+		// the module is not loaded
+		let list = list(dir.to_str().unwrap(), |_| true, |p| p).unwrap();
+		assert!(list.is_empty());
+	}
+}
@@ -0,0 +1,372 @@
+//! Check if dm-verity and fs-verity protect the system.
+//!
+//! Reading the status of device mapper targets requires root.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::storage::verity::{DmDevice, file_verity};
+//!
+//! for dev in DmDevice::all().unwrap() {
+//!     println!("{:?} {:?}", dev.name(), dev.verity_status());
+//! }
+//!
+//! let verity = file_verity("/usr/bin/ls").unwrap();
+//! println!("fs-verity: {:?}", verity);
+//! ```
+
+use crate::util::read_sys_string;
+
+use std::{fs, io, mem, ptr};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::os::unix::io::AsRawFd;
+use std::os::raw::c_ulong;
+
+const BLOCK_PATH: &str = "/sys/block";
+const CONTROL_PATH: &str = "/dev/mapper/control";
+
+const DM_NAME_LEN: usize = 128;
+const DM_UUID_LEN: usize = 129;
+// see linux/dm-ioctl.h
+const DM_BUFFER_FULL_FLAG: u32 = 1 << 8;
+const DM_TABLE_STATUS_CMD: u32 = 12;
+
+#[repr(C)]
+struct DmIoctl {
+	version: [u32; 3],
+	data_size: u32,
+	data_start: u32,
+	target_count: u32,
+	open_count: i32,
+	flags: u32,
+	event_nr: u32,
+	padding: u32,
+	dev: u64,
+	name: [u8; DM_NAME_LEN],
+	uuid: [u8; DM_UUID_LEN],
+	data: [u8; 7]
+}
+
+/// The size of struct dm_target_spec.
+const TARGET_SPEC_LEN: usize = 40;
+
+const fn ioc(dir: u32, typ: u8, nr: u32, size: usize) -> c_ulong {
+	((dir << 30) | ((size as u32) << 16) | ((typ as u32) << 8) | nr) as c_ulong
+}
+
+const DM_TABLE_STATUS: c_ulong =
+	ioc(3, 0xfd, DM_TABLE_STATUS_CMD, mem::size_of::<DmIoctl>());
+// see linux/fsverity.h, the size of struct fsverity_digest
+const FS_IOC_MEASURE_VERITY: c_ulong = ioc(3, b'f', 134, 4);
+
+/// A device mapper device for example `/sys/block/dm-0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DmDevice {
+	path: PathBuf
+}
+
+impl DmDevice {
+	/// Returns all device mapper devices.
+	pub fn all() -> io::Result<Vec<Self>> {
+		let mut list = vec![];
+		for entry in fs::read_dir(BLOCK_PATH)? {
+			let entry = entry?;
+			let is_dm = entry.file_name().to_str()
+				.map(|n| n.starts_with("dm-"))
+				.unwrap_or(false);
+			if is_dm {
+				list.push(Self::from_path(entry.path()));
+			}
+		}
+		list.sort_by(|a, b| a.path.cmp(&b.path));
+		Ok(list)
+	}
+
+	/// Creates a device from a path like `/sys/block/dm-0`.
+	pub fn from_path(path: impl Into<PathBuf>) -> Self {
+		Self { path: path.into() }
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// The name in /dev/mapper for example `root`.
+	pub fn name(&self) -> io::Result<String> {
+		read_sys_string(self.path.join("dm/name"))
+	}
+
+	/// The uuid set by the creator, for example systemd uses
+	/// `CRYPT-VERITY-...` for verity devices.
+	pub fn uuid(&self) -> io::Result<String> {
+		read_sys_string(self.path.join("dm/uuid"))
+	}
+
+	/// Returns the targets and their status like `dmsetup status`.
+	///
+	/// Requires root.
+	pub fn targets(&self) -> io::Result<Vec<DmTarget>> {
+		let name = self.name()?;
+		let control = File::open(CONTROL_PATH)?;
+		table_status(&control, &name)
+	}
+
+	/// Returns the status of the first verity target or `None` if the
+	/// device has no verity target.
+	///
+	/// Requires root.
+	pub fn verity_status(&self) -> io::Result<Option<VerityStatus>> {
+		Ok(self.targets()?
+			.iter()
+			.find_map(DmTarget::verity_status))
+	}
+}
+
+/// A target of a device mapper device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct DmTarget {
+	/// The first sector of the target.
+	pub start: u64,
+	/// The length in sectors.
+	pub length: u64,
+	/// The type for example `verity`, `crypt` or `linear`.
+	pub target_type: String,
+	/// The status returned by the target.
+	pub status: String
+}
+
+impl DmTarget {
+	/// Returns the status if this is a verity target.
+	pub fn verity_status(&self) -> Option<VerityStatus> {
+		if self.target_type != "verity" {
+			return None
+		}
+
+		Some(match self.status.split_whitespace().next() {
+			Some("V") => VerityStatus::Verified,
+			Some("C") => VerityStatus::Corrupted,
+			_ => VerityStatus::Unknown(self.status.clone())
+		})
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub enum VerityStatus {
+	/// No corruption was detected.
+	Verified,
+	/// A block did not match its hash.
+	Corrupted,
+	Unknown(String)
+}
+
+fn table_status(control: &File, name: &str) -> io::Result<Vec<DmTarget>> {
+	let header = mem::size_of::<DmIoctl>();
+	let mut size = 16 * 1024;
+
+	loop {
+		let mut io = DmIoctl {
+			version: [4, 0, 0],
+			data_size: size as u32,
+			data_start: header as u32,
+			target_count: 0,
+			open_count: 0,
+			flags: 0,
+			event_nr: 0,
+			padding: 0,
+			dev: 0,
+			name: [0; DM_NAME_LEN],
+			uuid: [0; DM_UUID_LEN],
+			data: [0; 7]
+		};
+		let name = name.as_bytes();
+		if name.len() >= DM_NAME_LEN {
+			return Err(io::Error::new(
+				io::ErrorKind::InvalidInput,
+				"device mapper name too long"
+			))
+		}
+		io.name[..name.len()].copy_from_slice(name);
+
+		let mut buf = vec![0u8; size];
+		unsafe {
+			ptr::write_unaligned(buf.as_mut_ptr() as *mut DmIoctl, io);
+		}
+
+		let r = unsafe {
+			libc::ioctl(control.as_raw_fd(), DM_TABLE_STATUS as _, buf.as_mut_ptr())
+		};
+		if r < 0 {
+			return Err(io::Error::last_os_error())
+		}
+
+		let io = unsafe { ptr::read_unaligned(buf.as_ptr() as *const DmIoctl) };
+		if io.flags & DM_BUFFER_FULL_FLAG != 0 {
+			size *= 4;
+			continue
+		}
+
+		let data = buf.get(io.data_start as usize..)
+			.unwrap_or(&[]);
+		return Ok(parse_targets(data, io.target_count))
+	}
+}
+
+/// Parses the dm_target_spec structs each followed by a status string.
+fn parse_targets(data: &[u8], count: u32) -> Vec<DmTarget> {
+	let u64_at = |i: usize| {
+		data.get(i..i + 8)
+			.map(|b| u64::from_ne_bytes(b.try_into().unwrap()))
+	};
+	let u32_at = |i: usize| {
+		data.get(i..i + 4)
+			.map(|b| u32::from_ne_bytes(b.try_into().unwrap()))
+	};
+	let str_at = |i: usize, max: usize| -> Option<String> {
+		let bytes = data.get(i..)?;
+		let bytes = &bytes[..bytes.len().min(max)];
+		let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+		Some(String::from_utf8_lossy(&bytes[..end]).into_owned())
+	};
+
+	let mut targets = vec![];
+	let mut offset = 0;
+	for _ in 0..count {
+		let target = (|| Some(DmTarget {
+			start: u64_at(offset)?,
+			length: u64_at(offset + 8)?,
+			target_type: str_at(offset + 24, 16)?,
+			status: str_at(offset + TARGET_SPEC_LEN, usize::MAX)?
+		}))();
+		let next = u32_at(offset + 20);
+
+		match (target, next) {
+			(Some(target), Some(next)) => {
+				targets.push(target);
+				// next is relative to the start of the data
+				if next as usize <= offset {
+					break
+				}
+				offset = next as usize;
+			},
+			_ => break
+		}
+	}
+
+	targets
+}
+
+/// The hash algorithm used by fs-verity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub enum VerityAlgorithm {
+	Sha256,
+	Sha512,
+	Unknown(u16)
+}
+
+/// The fs-verity measurement of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct FileVerity {
+	pub algorithm: VerityAlgorithm,
+	/// The digest which covers the contents and the verity parameters.
+	pub digest: Vec<u8>
+}
+
+/// Returns the fs-verity digest of a file or `None` if fs-verity is not
+/// enabled for the file.
+///
+/// Returns an error if the filesystem does not support fs-verity.
+pub fn file_verity(path: impl AsRef<Path>) -> io::Result<Option<FileVerity>> {
+	const MAX_DIGEST: usize = 64;
+
+	let file = File::open(path)?;
+	let mut buf = [0u8; 4 + MAX_DIGEST];
+	buf[2..4].copy_from_slice(&(MAX_DIGEST as u16).to_ne_bytes());
+
+	let r = unsafe {
+		libc::ioctl(file.as_raw_fd(), FS_IOC_MEASURE_VERITY as _, buf.as_mut_ptr())
+	};
+	if r < 0 {
+		let e = io::Error::last_os_error();
+		return match e.raw_os_error() {
+			Some(libc::ENODATA) => Ok(None),
+			_ => Err(e)
+		}
+	}
+
+	let algorithm = match u16::from_ne_bytes([buf[0], buf[1]]) {
+		1 => VerityAlgorithm::Sha256,
+		2 => VerityAlgorithm::Sha512,
+		a => VerityAlgorithm::Unknown(a)
+	};
+	let len = (u16::from_ne_bytes([buf[2], buf[3]]) as usize).min(MAX_DIGEST);
+
+	Ok(Some(FileVerity {
+		algorithm,
+		digest: buf[4..4 + len].to_vec()
+	}))
+}
+
+/// Returns true if the kernel supports fs-verity for a filesystem type like
+/// `ext4` or `f2fs`.
+///
+/// The filesystem also needs to be created with the verity feature.
+pub fn fs_verity_supported(filesystem_type: &str) -> bool {
+	Path::new("/sys/fs")
+		.join(filesystem_type)
+		.join("features/verity")
+		.exists()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn spec(start: u64, len: u64, next: u32, ty: &str, status: &str) -> Vec<u8> {
+		let mut b = vec![];
+		b.extend_from_slice(&start.to_ne_bytes());
+		b.extend_from_slice(&len.to_ne_bytes());
+		b.extend_from_slice(&0i32.to_ne_bytes());
+		b.extend_from_slice(&next.to_ne_bytes());
+		let mut name = [0u8; 16];
+		name[..ty.len()].copy_from_slice(ty.as_bytes());
+		b.extend_from_slice(&name);
+		b.extend_from_slice(status.as_bytes());
+		b.push(0);
+		b
+	}
+
+	#[test]
+	fn targets() {
+		let mut data = spec(0, 1024, 48, "linear", "");
+		data.resize(48, 0);
+		data.extend(spec(1024, 2048, 0, "verity", "V"));
+
+		let targets = parse_targets(&data, 2);
+		assert_eq!(targets.len(), 2);
+		assert_eq!(targets[0].target_type, "linear");
+		assert_eq!(targets[0].verity_status(), None);
+		assert_eq!(targets[1].start, 1024);
+		assert_eq!(targets[1].length, 2048);
+		assert_eq!(targets[1].verity_status(), Some(VerityStatus::Verified));
+	}
+}
@@ -1,12 +1,24 @@
-//! get system information (uptime, hostname, os release, load average, usernames, groups).
-
-use crate::util::read_to_string_mut;
+//! get system information (uptime, hostname, os release, load average, interrupts, usernames, groups, time sync, machine fingerprint).
+
+use crate::util::{
+	read_to_string, read_to_string_mut, line_of, sysconf, canonical_name,
+	sha256, parse_cpu_list
+};
+use crate::error::{self, Error};
+use crate::diff::{Change, diff_by_key};
+use crate::unit::DataSize;
+use crate::network::interfaces::Interface;
+#[cfg(feature = "async")]
+use crate::util::{read_to_string_async, read_to_string_mut_async};
 
 use std::{fs, io};
-use std::path::Path;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 use std::ops::Sub;
 
+#[cfg(feature = "serde")]
+use serde1::ser::{Serialize, Serializer, SerializeStruct};
+
 /// Read uptime information from /proc/uptime.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Uptime {
@@ -26,7 +38,7 @@ impl Uptime {
 	/// Reads uptime from /proc/uptime.
 	pub fn read() -> io::Result<Self> {
 		Ok(Self {
-			raw: fs::read_to_string(Self::path())?
+			raw: read_to_string(Self::path())?
 		})
 	}
 
@@ -35,6 +47,22 @@ impl Uptime {
 		read_to_string_mut(Self::path(), &mut self.raw)
 	}
 
+	/// Reads the file without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string_async(Self::path()).await?
+		})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(Self::path(), &mut self.raw).await
+	}
+
 	/// Main method to get uptime values. Returns every entry.
 	pub fn all_infos<'a>(&'a self) -> impl Iterator<Item=Duration> + 'a {
 		self.raw.split(' ')
@@ -52,6 +80,55 @@ impl Uptime {
 	pub fn idletime(&self) -> Option<Duration> {
 		self.all_infos().nth(1)
 	}
+
+	/// Get the time when the system was booted.
+	///
+	/// Calculated from the current time so the value changes if the system
+	/// clock is adjusted.
+	pub fn booted_at(&self) -> Option<SystemTime> {
+		SystemTime::now().checked_sub(self.uptime()?)
+	}
+
+	/// Formats the uptime like the `uptime` command, for example
+	/// `3 days, 4:12`.
+	pub fn format_human(&self) -> Option<String> {
+		let secs = self.uptime()?.as_secs();
+		let days = secs / 86_400;
+		let hours = secs % 86_400 / 3_600;
+		let mins = secs % 3_600 / 60;
+
+		Some(match days {
+			0 => format!("{}:{:02}", hours, mins),
+			1 => format!("1 day, {}:{:02}", hours, mins),
+			d => format!("{} days, {}:{:02}", d, hours, mins)
+		})
+	}
+
+	/// Like [`Uptime::uptime`] but returns an error.
+	pub fn try_uptime(&self) -> error::Result<Duration> {
+		self.try_nth(0, "uptime")
+	}
+
+	/// Like [`Uptime::idletime`] but returns an error.
+	pub fn try_idletime(&self) -> error::Result<Duration> {
+		self.try_nth(1, "idletime")
+	}
+
+	fn try_nth(&self, nth: usize, field: &str) -> error::Result<Duration> {
+		let value = self.raw.split_whitespace()
+			.nth(nth)
+			.ok_or_else(|| Error::missing_field(Self::path(), field))?;
+
+		value.parse::<f64>().ok()
+			.filter(|v| v.is_finite() && *v >= 0.0)
+			.map(Duration::from_secs_f64)
+			.ok_or_else(|| Error::parse_field(
+				Self::path(),
+				Some(1),
+				field,
+				format!("{:?}", value)
+			))
+	}
 }
 
 /// Read the hostname from /proc/sys/kernel/hostname.
@@ -73,7 +150,7 @@ impl Hostname {
 	/// Reads hostname from /proc/sys/kernel/hostname.
 	pub fn read() -> io::Result<Self> {
 		Ok(Self {
-			raw: fs::read_to_string(Self::path())?
+			raw: read_to_string(Self::path())?
 		})
 	}
 
@@ -82,6 +159,22 @@ impl Hostname {
 		read_to_string_mut(Self::path(), &mut self.raw)
 	}
 
+	/// Reads the file without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string_async(Self::path()).await?
+		})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(Self::path(), &mut self.raw).await
+	}
+
 	/// Get hostname as str.
 	pub fn hostname(&self) -> &str {
 		self.raw.trim()
@@ -112,7 +205,7 @@ impl OsRelease {
 	/// Reads hostname from /proc/sys/kernel/osrelease.
 	pub fn read() -> io::Result<Self> {
 		Ok(Self {
-			raw: fs::read_to_string(Self::path())?
+			raw: read_to_string(Self::path())?
 		})
 	}
 
@@ -121,6 +214,22 @@ impl OsRelease {
 		read_to_string_mut(Self::path(), &mut self.raw)
 	}
 
+	/// Reads the file without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string_async(Self::path()).await?
+		})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(Self::path(), &mut self.raw).await
+	}
+
 	/// Get os release as str.
 	pub fn full_str(&self) -> &str {
 		self.raw.trim()
@@ -132,6 +241,370 @@ impl OsRelease {
 	}
 }
 
+/// Information to identify a host.
+///
+/// Only the hostname is required, the other values are read on a best
+/// effort basis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct HostInfo {
+	pub hostname: String,
+	/// The NIS domain name from /proc/sys/kernel/domainname.
+	pub domainname: Option<String>,
+	/// The fully qualified domain name, resolved via getaddrinfo.
+	pub fqdn: Option<String>,
+	/// The id from /etc/machine-id.
+	pub machine_id: Option<String>
+}
+
+impl HostInfo {
+	/// Reads the host information.
+	///
+	/// Resolving the fqdn might query dns.
+	pub fn read() -> io::Result<Self> {
+		let hostname = Hostname::read()?.hostname().to_string();
+
+		let domainname = read_to_string("/proc/sys/kernel/domainname").ok()
+			.and_then(|s| parse_domainname(&s));
+
+		let fqdn = canonical_name(&hostname);
+
+		Ok(Self { hostname, domainname, fqdn, machine_id: machine_id() })
+	}
+}
+
+fn machine_id() -> Option<String> {
+	["/etc/machine-id", "/var/lib/dbus/machine-id"].iter()
+		.filter_map(|p| read_to_string(p).ok())
+		.map(|s| s.trim().to_string())
+		.find(|s| !s.is_empty())
+}
+
+/// The values a [`machine_fingerprint`] is computed from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct MachineFingerprint {
+	/// The id from /etc/machine-id, changes if the os is reinstalled.
+	pub machine_id: Option<String>,
+	/// The uuid of the system from the firmware in lowercase, requires
+	/// root.
+	pub product_uuid: Option<String>,
+	/// The serial number of the mainboard, requires root.
+	pub board_serial: Option<String>,
+	/// The permanent address of the first onboard or pci network interface
+	/// in lowercase.
+	pub mac_address: Option<String>,
+	/// The value the hash was computed from.
+	pub source: FingerprintSource,
+	/// The hex encoded sha256 hash of the value `source` refers to.
+	pub hash: String
+}
+
+/// The values a [`MachineFingerprint`] can be computed from, ordered by
+/// priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1", rename_all = "snake_case")
+)]
+pub enum FingerprintSource {
+	MachineId,
+	ProductUuid,
+	BoardSerial,
+	MacAddress
+}
+
+impl FingerprintSource {
+	/// All sources, ordered by priority.
+	pub const ALL: [Self; 4] = [
+		Self::MachineId, Self::ProductUuid, Self::BoardSerial, Self::MacAddress
+	];
+
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::MachineId => "machine-id",
+			Self::ProductUuid => "product-uuid",
+			Self::BoardSerial => "board-serial",
+			Self::MacAddress => "mac"
+		}
+	}
+}
+
+impl MachineFingerprint {
+	/// The version of the composition, which is part of the hashed data.
+	pub const VERSION: u32 = 2;
+
+	/// Computes the fingerprint from the first value which is available, in
+	/// the order of [`FingerprintSource::ALL`].
+	///
+	/// Returns `None` if no value is available.
+	///
+	/// The hashed data is, without a trailing newline:
+	/// ```text
+	/// linux-info-fingerprint-v2
+	/// <source>=<value>
+	/// ```
+	/// where source is `machine-id`, `product-uuid`, `board-serial` or
+	/// `mac`.
+	pub fn new(
+		machine_id: Option<String>,
+		product_uuid: Option<String>,
+		board_serial: Option<String>,
+		mac_address: Option<String>
+	) -> Option<Self> {
+		let mut fingerprint = Self {
+			machine_id,
+			product_uuid,
+			board_serial,
+			mac_address,
+			source: FingerprintSource::MachineId,
+			hash: String::new()
+		};
+
+		let (source, hash) = FingerprintSource::ALL.iter()
+			.find_map(|s| Some((*s, fingerprint.component_hash(*s)?)))?;
+		fingerprint.source = source;
+		fingerprint.hash = hash;
+		Some(fingerprint)
+	}
+
+	/// Returns the value of the given source.
+	pub fn value(&self, source: FingerprintSource) -> Option<&str> {
+		match source {
+			FingerprintSource::MachineId => self.machine_id.as_deref(),
+			FingerprintSource::ProductUuid => self.product_uuid.as_deref(),
+			FingerprintSource::BoardSerial => self.board_serial.as_deref(),
+			FingerprintSource::MacAddress => self.mac_address.as_deref()
+		}
+	}
+
+	/// Returns the hash of a single value, to match hosts by another value
+	/// than the one with the highest priority.
+	pub fn component_hash(&self, source: FingerprintSource) -> Option<String> {
+		let data = format!(
+			"linux-info-fingerprint-v{}\n{}={}",
+			Self::VERSION,
+			source.as_str(),
+			self.value(source)?
+		);
+		let hash = sha256(data.as_bytes()).iter()
+			.map(|b| format!("{:02x}", b))
+			.collect();
+		Some(hash)
+	}
+}
+
+/// Returns a stable identifier of the machine, to recognize the same host in
+/// an inventory.
+///
+/// The hash is computed from a single value, the machine-id, the dmi product
+/// uuid, the board serial or the permanent address of the first onboard
+/// network interface, whichever is available first. See
+/// [`MachineFingerprint::new`] for the exact composition. Placeholder values
+/// firmwares write like `To be filled by O.E.M.` are ignored.
+///
+/// The machine-id can be read by every user, so the hash does not depend on
+/// the privileges unless the machine-id is missing. Reinstalling the os
+/// changes it, use [`MachineFingerprint::component_hash`] to match by the
+/// hardware values.
+///
+/// Returns an error with the kind `NotFound` if no value could be read.
+pub fn machine_fingerprint() -> io::Result<MachineFingerprint> {
+	let dmi = |name: &str| read_to_string(Path::new(DMI_PATH).join(name))
+		.ok()
+		.and_then(|v| filter_dmi_value(&v));
+
+	MachineFingerprint::new(
+		machine_id(),
+		dmi("product_uuid").map(|u| u.to_lowercase()),
+		dmi("board_serial"),
+		primary_mac_address()
+	).ok_or_else(|| io::Error::new(
+		io::ErrorKind::NotFound,
+		"no value to identify the machine found"
+	))
+}
+
+const DMI_PATH: &str = "/sys/class/dmi/id";
+
+/// Returns `None` for empty values and placeholders.
+fn filter_dmi_value(raw: &str) -> Option<String> {
+	let v = raw.trim();
+	let placeholder = v.is_empty() ||
+		v.chars().all(|c| c == '0' || c == '-') ||
+		v.chars().all(|c| c == 'F' || c == 'f' || c == '-') ||
+		[
+			"To be filled by O.E.M.", "Default string", "Not Specified",
+			"Not Applicable", "None", "System Serial Number", "Unknown",
+			"03000200-0400-0500-0006-000700080009"
+		].iter().any(|p| v.eq_ignore_ascii_case(p));
+
+	(!placeholder).then(|| v.to_string())
+}
+
+/// The address of the interface with the lowest name which is built in.
+///
+/// Usb and removable (thunderbolt) adapters are ignored so plugging in a
+/// dock does not change it, as are randomized or changed addresses.
+fn primary_mac_address() -> Option<String> {
+	Interface::all().ok()?
+		.iter()
+		// usb adapters and virtual interfaces have no pci device
+		.filter(|i| i.pci_device().is_some())
+		.filter(|i| {
+			let removable = read_to_string(i.path().join("device/removable"));
+			!matches!(removable, Ok(r) if r.trim() == "removable")
+		})
+		// 0 means the address is permanent (NET_ADDR_PERM)
+		.filter(|i| {
+			let assign_type = read_to_string(i.path().join("addr_assign_type"));
+			matches!(assign_type, Ok(t) if t.trim() == "0")
+		})
+		.filter_map(|i| i.mac_address().ok())
+		.map(|m| m.to_lowercase())
+		.find(|m| m.chars().any(|c| c != '0' && c != ':'))
+}
+
+/// The kernel returns `(none)` if no domain name is set.
+fn parse_domainname(raw: &str) -> Option<String> {
+	match raw.trim() {
+		"" | "(none)" => None,
+		s => Some(s.to_string())
+	}
+}
+
+/// Resource usage and limits of the current process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct SelfInfo {
+	/// The amount of open file descriptors.
+	pub open_fds: Option<usize>,
+	/// The soft limit of open file descriptors, `None` if unlimited.
+	pub fd_soft_limit: Option<u64>,
+	/// The hard limit of open file descriptors, `None` if unlimited.
+	pub fd_hard_limit: Option<u64>,
+	/// The resident set size.
+	pub rss: Option<DataSize>,
+	/// The memory limit of the cgroup the process belongs to, `None` if
+	/// there is no limit.
+	pub cgroup_memory_limit: Option<DataSize>
+}
+
+impl SelfInfo {
+	/// Returns how many file descriptors can still be opened before the
+	/// soft limit is reached.
+	pub fn fds_available(&self) -> Option<u64> {
+		let open = self.open_fds? as u64;
+		Some(self.fd_soft_limit?.saturating_sub(open))
+	}
+}
+
+/// Returns resource usage and limits of the calling process.
+///
+/// Only /proc/self/status is required, every other value is read on a best
+/// effort basis.
+pub fn self_info() -> io::Result<SelfInfo> {
+	let status = read_to_string("/proc/self/status")?;
+
+	// read_dir itself uses a file descriptor
+	let open_fds = fs::read_dir("/proc/self/fd").ok()
+		.map(|dir| dir.count().saturating_sub(1));
+
+	let (fd_soft_limit, fd_hard_limit) = match fd_limits() {
+		Some((soft, hard)) => (soft, hard),
+		None => (None, None)
+	};
+
+	let cgroup_memory_limit = read_to_string("/proc/self/cgroup").ok()
+		.and_then(|raw| cgroup_memory_file(&raw))
+		.and_then(|path| read_to_string(path).ok())
+		.and_then(|limit| parse_memory_limit(&limit));
+
+	Ok(SelfInfo {
+		open_fds,
+		fd_soft_limit,
+		fd_hard_limit,
+		rss: status_value(&status, "VmRSS")
+			.and_then(DataSize::from_kernel_str),
+		cgroup_memory_limit
+	})
+}
+
+// rlim_t is not u64 on every target
+#[allow(clippy::unnecessary_cast)]
+fn fd_limits() -> Option<(Option<u64>, Option<u64>)> {
+	let mut limit = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+	let r = unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) };
+	if r != 0 {
+		return None
+	}
+
+	let finite = |l: libc::rlim_t| {
+		Some(l).filter(|l| *l != libc::RLIM_INFINITY).map(|l| l as u64)
+	};
+	Some((finite(limit.rlim_cur), finite(limit.rlim_max)))
+}
+
+fn status_value<'a>(status: &'a str, key: &str) -> Option<&'a str> {
+	status.lines()
+		.filter_map(|l| l.split_once(':'))
+		.find(|(k, _)| *k == key)
+		.map(|(_, v)| v.trim())
+}
+
+/// Returns the directory of the cgroup which contains the files of a
+/// controller from /proc/self/cgroup and if it is a cgroup v2 directory.
+pub(crate) fn cgroup_dir(
+	raw: &str,
+	controller: &str
+) -> Option<(PathBuf, bool)> {
+	let mut unified = None;
+
+	for line in raw.lines() {
+		let mut parts = line.splitn(3, ':');
+		let (_, controllers, path) = (parts.next()?, parts.next()?, parts.next()?);
+		let path = path.trim_start_matches('/');
+
+		if controllers.split(',').any(|c| c == controller) {
+			return Some((Path::new("/sys/fs/cgroup")
+				.join(controller)
+				.join(path), false))
+		}
+
+		if controllers.is_empty() {
+			unified = Some((Path::new("/sys/fs/cgroup").join(path), true));
+		}
+	}
+
+	unified
+}
+
+/// Returns the file containing the memory limit from /proc/self/cgroup.
+pub(crate) fn cgroup_memory_file(raw: &str) -> Option<PathBuf> {
+	let (dir, v2) = cgroup_dir(raw, "memory")?;
+	Some(dir.join(if v2 { "memory.max" } else { "memory.limit_in_bytes" }))
+}
+
+/// cgroup v2 uses `max` for no limit, v1 a value close to `i64::MAX`.
+pub(crate) fn parse_memory_limit(raw: &str) -> Option<DataSize> {
+	raw.trim().parse::<u64>().ok()
+		.filter(|l| *l < 1 << 62)
+		.map(|l| DataSize::from_bytes(l.into()))
+}
+
 /// Read the load average from /proc/loadavg.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LoadAvg {
@@ -144,14 +617,14 @@ impl LoadAvg {
 	}
 
 	#[cfg(test)]
-	fn from_string(raw: String) -> Self {
+	pub(crate) fn from_string(raw: String) -> Self {
 		Self {raw}
 	}
 
 	/// Read load average from /proc/loadavg.
 	pub fn read() -> io::Result<Self> {
 		Ok(Self {
-			raw: fs::read_to_string(Self::path())?
+			raw: read_to_string(Self::path())?
 		})
 	}
 
@@ -160,6 +633,22 @@ impl LoadAvg {
 		read_to_string_mut(Self::path(), &mut self.raw)
 	}
 
+	/// Reads the file without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string_async(Self::path()).await?
+		})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(Self::path(), &mut self.raw).await
+	}
+
 	/// Get all key and values.
 	pub fn values<'a>(&'a self) -> impl Iterator<Item=&'a str> {
 		self.raw.split(' ')
@@ -175,6 +664,35 @@ impl LoadAvg {
 		Some((vals.next()??, vals.next()??, vals.next()??))
 	}
 
+	/// Get the average divided by the amount of online cpus (1 min, 5 min,
+	/// 15 min).
+	///
+	/// A value of `1.0` means every cpu was busy. The cpus are counted with
+	/// [`online_cpus`].
+	///
+	/// ```no_run
+	/// use linux_info::system::LoadAvg;
+	///
+	/// let (one, _, _) = LoadAvg::read().unwrap().normalized().unwrap();
+	/// println!("load per cpu {:.2}", one);
+	/// ```
+	pub fn normalized(&self) -> Option<(f32, f32, f32)> {
+		self.normalized_by(online_cpus().ok()?)
+	}
+
+	/// Get the average divided by `cores` (1 min, 5 min, 15 min).
+	///
+	/// Returns `None` if `cores` is zero.
+	pub fn normalized_by(&self, cores: usize) -> Option<(f32, f32, f32)> {
+		if cores == 0 {
+			return None
+		}
+
+		let cores = cores as f32;
+		let (one, five, fifteen) = self.average()?;
+		Some((one / cores, five / cores, fifteen / cores))
+	}
+
 	/// Returns two values (runnable threads, running threads).
 	pub fn threads(&self) -> Option<(usize, usize)> {
 		let mut vals = self.values()
@@ -189,9 +707,65 @@ impl LoadAvg {
 		self.values().last()?
 			.parse().ok()
 	}
+
+	fn try_nth(&self, nth: usize, field: &str) -> error::Result<&str> {
+		self.raw.split_whitespace()
+			.nth(nth)
+			.ok_or_else(|| Error::missing_field(Self::path(), field))
+	}
+
+	fn try_parse<T: std::str::FromStr>(
+		&self,
+		value: &str,
+		field: &str
+	) -> error::Result<T> {
+		value.parse()
+			.map_err(|_| Error::parse_field(
+				Self::path(),
+				Some(1),
+				field,
+				format!("{:?}", value)
+			))
+	}
+
+	/// Like [`LoadAvg::average`] but returns an error.
+	pub fn try_average(&self) -> error::Result<(f32, f32, f32)> {
+		let mut avg = [0.0; 3];
+		for (i, field) in ["load1", "load5", "load15"].iter().enumerate() {
+			avg[i] = self.try_parse(self.try_nth(i, field)?, field)?;
+		}
+		Ok((avg[0], avg[1], avg[2]))
+	}
+
+	/// Like [`LoadAvg::threads`] but returns an error.
+	pub fn try_threads(&self) -> error::Result<(usize, usize)> {
+		let value = self.try_nth(3, "threads")?;
+		let (runnable, total) = value.split_once('/')
+			.ok_or_else(|| Error::parse_field(
+				Self::path(),
+				Some(1),
+				"threads",
+				format!("{:?}", value)
+			))?;
+		Ok((
+			self.try_parse(runnable, "threads")?,
+			self.try_parse(total, "threads")?
+		))
+	}
+
+	/// Like [`LoadAvg::newest_pid`] but returns an error.
+	pub fn try_newest_pid(&self) -> error::Result<u32> {
+		let value = self.try_nth(4, "newest_pid")?;
+		self.try_parse(value, "newest_pid")
+	}
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
 pub struct CpuStat {
 	/// user: normal processes executing in user mode
 	pub user: usize,
@@ -238,15 +812,16 @@ impl CpuStat {
 impl Sub for CpuStat {
 	type Output = Self;
 
+	// saturating since iowait can decrease
 	fn sub(self, other: Self) -> Self {
 		Self {
-			user: self.user - other.user,
-			nice: self.nice - other.nice,
-			system: self.system - other.system,
-			idle: self.idle - other.idle,
-			iowait: self.iowait - other.iowait,
-			irq: self.irq - other.irq,
-			softirq: self.softirq - other.softirq,
+			user: self.user.saturating_sub(other.user),
+			nice: self.nice.saturating_sub(other.nice),
+			system: self.system.saturating_sub(other.system),
+			idle: self.idle.saturating_sub(other.idle),
+			iowait: self.iowait.saturating_sub(other.iowait),
+			irq: self.irq.saturating_sub(other.irq),
+			softirq: self.softirq.saturating_sub(other.softirq),
 		}
 	}
 }
@@ -268,7 +843,7 @@ impl FromIterator<usize> for CpuStat {
 	}
 }
 
-/// Read the load average from /proc/loadavg.
+/// Read kernel and cpu statistics from /proc/stat.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Stat {
 	raw: String
@@ -276,18 +851,18 @@ pub struct Stat {
 
 impl Stat {
 	fn path() -> &'static Path {
-		Path::new("/proc/loadavg")
+		Path::new("/proc/stat")
 	}
 
 	#[cfg(test)]
-	fn from_string(raw: String) -> Self {
+	pub(crate) fn from_string(raw: String) -> Self {
 		Self {raw}
 	}
 
-	/// Read load average from /proc/loadavg.
+	/// Read cpu statistics from /proc/stat.
 	pub fn read() -> io::Result<Self> {
 		Ok(Self {
-			raw: fs::read_to_string(Self::path())?
+			raw: read_to_string(Self::path())?
 		})
 	}
 
@@ -296,6 +871,22 @@ impl Stat {
 		read_to_string_mut(Self::path(), &mut self.raw)
 	}
 
+	/// Reads the file without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string_async(Self::path()).await?
+		})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(Self::path(), &mut self.raw).await
+	}
+
 	/// Get all key and values.
 	pub fn values<'a>(&'a self) -> impl Iterator<Item=(
 		&'a str,
@@ -310,6 +901,18 @@ impl Stat {
 			})
 	}
 
+	/// Returns every line which was added, removed or whose values changed
+	/// compared to the newer reading.
+	pub fn diff<'a>(
+		&'a self,
+		newer: &'a Self
+	) -> Vec<Change<&'a str, Vec<usize>>> {
+		diff_by_key(
+			self.values().map(|(k, v)| (k, v.collect())),
+			newer.values().map(|(k, v)| (k, v.collect()))
+		)
+	}
+
 	pub fn cpu(&self) -> Option<CpuStat> {
 		self.values().find(|(k, _)| *k == "cpu")
 			.map(|(_, v)| v.collect())
@@ -320,8 +923,442 @@ impl Stat {
 		self.values().find(|(k, _)| *k == nk)
 			.map(|(_, v)| v.collect())
 	}
+
+	/// Returns every core with its number in one pass.
+	///
+	/// Offline cores are not listed, so the number can be different from the
+	/// position.
+	pub fn cpus(&self) -> impl Iterator<Item=(usize, CpuStat)> + '_ {
+		self.values()
+			.filter_map(|(k, v)| {
+				let nth = k.strip_prefix("cpu")?.parse().ok()?;
+				Some((nth, v.collect()))
+			})
+	}
+
+	/// Returns the number of cores listed, which are the online cores.
+	pub fn cpu_count(&self) -> usize {
+		self.cpus().count()
+	}
+
+	/// Like [`Stat::cpu`] but returns an error if the line is missing or a
+	/// value can't be parsed.
+	pub fn try_cpu(&self) -> error::Result<CpuStat> {
+		self.try_cpu_key("cpu")
+	}
+
+	/// Like [`Stat::cpu_nth`] but returns an error.
+	pub fn try_cpu_nth(&self, nth: usize) -> error::Result<CpuStat> {
+		self.try_cpu_key(&format!("cpu{}", nth))
+	}
+
+	fn try_cpu_key(&self, key: &str) -> error::Result<CpuStat> {
+		let line = self.raw.lines()
+			.find(|l| l.split_whitespace().next() == Some(key))
+			.ok_or_else(|| Error::missing_field(Self::path(), key))?;
+		let line_nr = Some(line_of(&self.raw, line));
+
+		let values = line.split_whitespace()
+			.skip(1)
+			.map(|v| v.parse().map_err(|_| Error::parse_field(
+				Self::path(),
+				line_nr,
+				key,
+				format!("{:?}", v)
+			)))
+			.collect::<error::Result<Vec<usize>>>()?;
+
+		if values.len() < 7 {
+			return Err(Error::parse_field(
+				Self::path(),
+				line_nr,
+				key,
+				format!("{} values, expected at least 7", values.len())
+			))
+		}
+
+		Ok(values.into_iter().collect())
+	}
+}
+
+/// Read the interrupt counters of every cpu from /proc/interrupts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interrupts {
+	raw: String
+}
+
+impl Interrupts {
+	fn path() -> &'static Path {
+		Path::new("/proc/interrupts")
+	}
+
+	#[cfg(test)]
+	fn from_string(raw: String) -> Self {
+		Self {raw}
+	}
+
+	/// Read interrupt counters from /proc/interrupts.
+	pub fn read() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string(Self::path())?
+		})
+	}
+
+	/// Reloads information without allocating.
+	pub fn reload(&mut self) -> io::Result<()> {
+		read_to_string_mut(Self::path(), &mut self.raw)
+	}
+
+	/// Reads the file without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn read_async() -> io::Result<Self> {
+		Ok(Self {
+			raw: read_to_string_async(Self::path()).await?
+		})
+	}
+
+	/// Reloads information without blocking the async runtime.
+	#[cfg(feature = "async")]
+	#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+	pub async fn reload_async(&mut self) -> io::Result<()> {
+		read_to_string_mut_async(Self::path(), &mut self.raw).await
+	}
+
+	/// Returns the number of every cpu which has a column.
+	///
+	/// Offline cpus are not listed, so the number can be different from the
+	/// position in [`Interrupt::counts`].
+	pub fn cpus(&self) -> impl Iterator<Item=usize> + '_ {
+		self.raw.lines()
+			.next()
+			.unwrap_or("")
+			.split_whitespace()
+			.filter_map(|c| c.strip_prefix("CPU")?.parse().ok())
+	}
+
+	/// Returns every interrupt.
+	pub fn entries(&self) -> impl Iterator<Item=Interrupt> + '_ {
+		let cpus = self.cpus().count();
+		self.raw.lines()
+			.skip(1)
+			.filter_map(move |l| Interrupt::from_line(l, cpus))
+	}
+
+	/// Returns an interrupt by its id for example `24` or `NMI`.
+	pub fn by_id(&self, id: &str) -> Option<Interrupt> {
+		self.entries()
+			.find(|i| i.id == id)
+	}
+
+	/// Returns every interrupt which was added, removed or whose counters
+	/// changed compared to the newer reading.
+	pub fn diff(&self, newer: &Self) -> Vec<Change<String, Interrupt>> {
+		diff_by_key(
+			self.entries().map(|i| (i.id.clone(), i)),
+			newer.entries().map(|i| (i.id.clone(), i))
+		)
+	}
+}
+
+/// An interrupt with its counters from /proc/interrupts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct Interrupt {
+	/// The irq number or the name of an architecture specific interrupt,
+	/// for example `24` or `NMI`.
+	pub id: String,
+	/// How many times the interrupt was handled by every cpu, in the order
+	/// of [`Interrupts::cpus`].
+	///
+	/// Some interrupts like `ERR` only have a single count.
+	pub counts: Vec<u64>,
+	/// The interrupt controller for example `IO-APIC` or
+	/// `PCI-MSIX-0000:00:01.0`, `None` for architecture specific
+	/// interrupts.
+	pub controller: Option<String>,
+	/// The hardware irq and trigger, for example `5-edge` or `27 Level`.
+	pub hwirq: Option<String>,
+	/// The devices which use the interrupt separated by `, ` or the
+	/// description of an architecture specific interrupt.
+	pub label: String
+}
+
+impl Interrupt {
+	fn from_line(line: &str, cpus: usize) -> Option<Self> {
+		let (id, rest) = line.split_once(':')?;
+		let id = id.trim();
+		let mut words = rest.split_whitespace().peekable();
+
+		let mut counts = Vec::with_capacity(cpus);
+		while counts.len() < cpus {
+			match words.peek().and_then(|w| w.parse().ok()) {
+				Some(c) => counts.push(c),
+				None => break
+			}
+			words.next();
+		}
+
+		let (mut controller, mut hwirq) = (None, None);
+		if id.parse::<u32>().is_ok() {
+			controller = words.next().map(String::from);
+			// for example `5-edge`, `524288-edge` or `27 Level`
+			let hw: Vec<_> = std::iter::from_fn(|| words.next_if(|w| {
+				w.starts_with(|c: char| c.is_ascii_digit()) ||
+				matches!(*w, "Edge" | "Level" | "edge" | "level")
+			})).collect();
+			hwirq = (!hw.is_empty()).then(|| hw.join(" "));
+		}
+
+		Some(Self {
+			id: id.into(),
+			counts,
+			controller,
+			hwirq,
+			label: words.collect::<Vec<_>>().join(" ")
+		})
+	}
+
+	/// The irq number, `None` for architecture specific interrupts.
+	pub fn number(&self) -> Option<u32> {
+		self.id.parse().ok()
+	}
+
+	/// How many times the interrupt was handled by all cpus.
+	pub fn total(&self) -> u64 {
+		self.counts.iter().sum()
+	}
+
+	/// The devices which use the interrupt, empty for architecture
+	/// specific interrupts.
+	pub fn devices(&self) -> impl Iterator<Item=&str> {
+		self.number()
+			.and(Some(self.label.as_str()))
+			.unwrap_or("")
+			.split(", ")
+			.filter(|d| !d.is_empty())
+	}
+
+	/// The cpus the interrupt may be handled by, from
+	/// `/proc/irq/{number}/smp_affinity_list`.
+	///
+	/// Returns `None` for architecture specific interrupts.
+	pub fn affinity(&self) -> io::Result<Option<Vec<u32>>> {
+		let number = match self.number() {
+			Some(n) => n,
+			None => return Ok(None)
+		};
+		let path = format!("/proc/irq/{}/smp_affinity_list", number);
+		let raw = read_to_string(&path)?;
+		parse_cpu_list(&raw)
+			.map(Some)
+			.ok_or_else(|| {
+				Error::parse(&path, None, format!("{:?}", raw.trim())).into()
+			})
+	}
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Uptime {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where S: Serializer {
+		let mut s = serializer.serialize_struct("Uptime", 2)?;
+		s.serialize_field("uptime", &self.uptime())?;
+		s.serialize_field("idletime", &self.idletime())?;
+		s.end()
+	}
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Hostname {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where S: Serializer {
+		serializer.serialize_str(self.hostname())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for OsRelease {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where S: Serializer {
+		serializer.serialize_str(self.full_str())
+	}
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for LoadAvg {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where S: Serializer {
+		let mut s = serializer.serialize_struct("LoadAvg", 3)?;
+		s.serialize_field("average", &self.average())?;
+		s.serialize_field("threads", &self.threads())?;
+		s.serialize_field("newest_pid", &self.newest_pid())?;
+		s.end()
+	}
+}
+
+/// Serializes every key with its values.
+#[cfg(feature = "serde")]
+impl Serialize for Stat {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where S: Serializer {
+		serializer.collect_map(
+			self.values().map(|(k, v)| (k, v.collect::<Vec<_>>()))
+		)
+	}
+}
+
+/// The amount of clock ticks per second (`CLK_TCK`).
+///
+/// The cpu times in /proc/stat are counted in clock ticks, divide them by
+/// this value to get seconds.
+pub fn clock_ticks() -> io::Result<u64> {
+	sysconf(libc::_SC_CLK_TCK)
 }
 
+/// The size of a memory page in bytes.
+///
+/// Page counts like the ones in /proc/vmstat need to be multiplied by this
+/// value to get bytes.
+pub fn page_size() -> io::Result<u64> {
+	sysconf(libc::_SC_PAGESIZE)
+}
+
+/// The number of processors which are currently online.
+pub fn online_cpus() -> io::Result<usize> {
+	sysconf(libc::_SC_NPROCESSORS_ONLN)
+		.map(|n| n as usize)
+}
+
+/// The daemon which synchronizes the clock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub enum TimeSyncSource {
+	Timesyncd,
+	Chrony,
+	/// ntpd or openntpd.
+	Ntpd,
+	/// ptp4l or phc2sys.
+	Ptp
+}
+
+impl TimeSyncSource {
+	/// Returns the source from the name of a process.
+	fn from_process(name: &str) -> Option<Self> {
+		Some(match name {
+			// the name is truncated to 15 characters
+			"systemd-timesyn" => Self::Timesyncd,
+			"chronyd" => Self::Chrony,
+			"ntpd" | "openntpd" => Self::Ntpd,
+			"ptp4l" | "phc2sys" => Self::Ptp,
+			_ => return None
+		})
+	}
+}
+
+/// If the clock is synchronized and how accurate it is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct TimeSync {
+	/// True if a daemon told the kernel that the clock is synchronized.
+	pub synchronized: bool,
+	/// The maximum error of the clock, grows while it is not synchronized.
+	pub max_error: Duration,
+	/// The estimated error of the clock.
+	pub estimated_error: Duration,
+	/// The running daemon, `None` if no known daemon is running.
+	pub source: Option<TimeSyncSource>,
+	/// The server the daemon synchronizes with.
+	///
+	/// Is read from chrony using `chronyc` and from timesyncd over dbus,
+	/// which requires a feature that enables dbus (like `upower`).
+	pub server: Option<String>
+}
+
+/// Returns if the clock is synchronized, using `adjtimex` and the running
+/// sync daemon.
+pub fn time_sync() -> io::Result<TimeSync> {
+	let mut timex: libc::timex = unsafe { std::mem::zeroed() };
+	// modes is zero so nothing gets changed and no privileges are required
+	let state = unsafe { libc::adjtimex(&mut timex) };
+	if state == -1 {
+		return Err(io::Error::last_os_error())
+	}
+
+	let synchronized = state != libc::TIME_ERROR &&
+		timex.status & libc::STA_UNSYNC == 0;
+	let micros = |v: std::os::raw::c_long| {
+		Duration::from_micros(v.max(0) as u64)
+	};
+
+	let source = crate::process::Process::all()?
+		.into_iter()
+		.filter_map(|p| p.name().ok())
+		.find_map(|n| TimeSyncSource::from_process(&n));
+	let server = match source {
+		Some(TimeSyncSource::Chrony) => chrony_server(),
+		#[cfg(feature = "dbus")]
+		Some(TimeSyncSource::Timesyncd) => timesyncd_server(),
+		_ => None
+	};
+
+	Ok(TimeSync {
+		synchronized,
+		max_error: micros(timex.maxerror),
+		estimated_error: micros(timex.esterror),
+		source,
+		server
+	})
+}
+
+fn chrony_server() -> Option<String> {
+	let output = std::process::Command::new("chronyc")
+		.args(["-c", "tracking"])
+		.output().ok()?;
+	if !output.status.success() {
+		return None
+	}
+	parse_chrony_tracking(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// The second field of `chronyc -c tracking` is the name or the address of
+/// the reference.
+fn parse_chrony_tracking(out: &str) -> Option<String> {
+	out.trim().split(',')
+		.nth(1)
+		.filter(|s| !s.is_empty())
+		.map(Into::into)
+}
+
+#[cfg(feature = "dbus")]
+fn timesyncd_server() -> Option<String> {
+	use dbus::blocking::Connection;
+	use dbus::blocking::stdintf::org_freedesktop_dbus::Properties;
+
+	let conn = Connection::new_system().ok()?;
+	let proxy = conn.with_proxy(
+		"org.freedesktop.timesync1",
+		"/org/freedesktop/timesync1",
+		Duration::from_secs(2)
+	);
+	let name: String = proxy.get(
+		"org.freedesktop.timesync1.Manager",
+		"ServerName"
+	).ok()?;
+	(!name.is_empty()).then(|| name)
+}
 
 // TODO add https://www.idnt.net/en-US/kb/941772
 // /proc/stat
@@ -330,6 +1367,7 @@ impl Stat {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::diff::ChangeKind;
 
 	fn uptime() -> Uptime {
 		Uptime::from_string("220420.83 5275548.45\n".into())
@@ -341,6 +1379,135 @@ mod tests {
 		assert_eq!(uptime().uptime().unwrap().as_secs(), 220420);
 		// idle time
 		assert_eq!(uptime().idletime().unwrap().as_secs(), 5275548);
+		// booted at
+		let booted = uptime().booted_at().unwrap();
+		let since = SystemTime::now().duration_since(booted).unwrap();
+		assert!((220420..220430).contains(&since.as_secs()));
+	}
+
+	#[test]
+	fn uptime_human() {
+		assert_eq!(uptime().format_human().unwrap(), "2 days, 13:13");
+		let u = Uptime::from_string("87000.00 0.00\n".into());
+		assert_eq!(u.format_human().unwrap(), "1 day, 0:10");
+		let u = Uptime::from_string("59.99 0.00\n".into());
+		assert_eq!(u.format_human().unwrap(), "0:00");
+	}
+
+	#[test]
+	fn strict_uptime() {
+		assert_eq!(uptime().try_uptime().unwrap().as_secs(), 220420);
+		let e = Uptime::from_string("220420.83 nan\n".into())
+			.try_idletime()
+			.unwrap_err();
+		assert_eq!(
+			e.to_string(),
+			"/proc/uptime:1: failed to parse idletime from \"nan\""
+		);
+	}
+
+	#[test]
+	fn domainname() {
+		assert_eq!(parse_domainname("(none)\n"), None);
+		assert_eq!(parse_domainname("example.com\n").unwrap(), "example.com");
+	}
+
+	#[test]
+	fn time_sync_source() {
+		assert_eq!(
+			TimeSyncSource::from_process("systemd-timesyn"),
+			Some(TimeSyncSource::Timesyncd)
+		);
+		assert_eq!(TimeSyncSource::from_process("bash"), None);
+		let tracking = "A9FE A97B,169.254.169.123,4,1700000000.1,\
+			-0.000001,0.000002,0.000003,-3.9,0.001,0.05,0.0004,0.00002,\
+			64.2,Normal\n";
+		assert_eq!(
+			parse_chrony_tracking(tracking).as_deref(),
+			Some("169.254.169.123")
+		);
+	}
+
+	#[test]
+	fn fingerprint() {
+		assert_eq!(filter_dmi_value("To be filled by O.E.M.\n"), None);
+		assert_eq!(
+			filter_dmi_value("00000000-0000-0000-0000-000000000000\n"),
+			None
+		);
+		assert_eq!(filter_dmi_value("\n"), None);
+		assert_eq!(filter_dmi_value("PF2XZ3KA\n").as_deref(), Some("PF2XZ3KA"));
+
+		let a = MachineFingerprint::new(
+			Some("4d3c7e3b5f2a4c0e9a8b7c6d5e4f3a2b".into()),
+			None,
+			None,
+			Some("52:54:00:12:34:56".into())
+		).unwrap();
+		// running as root adds the product uuid
+		let b = MachineFingerprint::new(
+			Some("4d3c7e3b5f2a4c0e9a8b7c6d5e4f3a2b".into()),
+			Some("5c4a7e1e-2f1b-4b6e-9d3a-0c1b2a3d4e5f".into()),
+			None,
+			Some("52:54:00:12:34:57".into())
+		).unwrap();
+		assert_eq!(a.source, FingerprintSource::MachineId);
+		assert_eq!(a.hash.len(), 64);
+		assert_eq!(a.hash, b.hash);
+		assert_ne!(
+			a.component_hash(FingerprintSource::MacAddress),
+			b.component_hash(FingerprintSource::MacAddress)
+		);
+		assert_eq!(a.component_hash(FingerprintSource::ProductUuid), None);
+
+		let c = MachineFingerprint::new(
+			None,
+			None,
+			None,
+			Some("52:54:00:12:34:56".into())
+		).unwrap();
+		assert_eq!(c.source, FingerprintSource::MacAddress);
+		assert_eq!(
+			Some(c.hash),
+			a.component_hash(FingerprintSource::MacAddress)
+		);
+		assert!(MachineFingerprint::new(None, None, None, None).is_none());
+	}
+
+	#[test]
+	fn self_info_parsing() {
+		let status = "Name:\tcat\nVmRSS:\t    1024 kB\nThreads:\t1\n";
+		assert_eq!(
+			status_value(status, "VmRSS").and_then(DataSize::from_kernel_str),
+			Some(DataSize::from_kib(1024.0))
+		);
+
+		let v2 = "0::/system.slice/app.service\n";
+		assert_eq!(
+			cgroup_memory_file(v2).unwrap(),
+			Path::new("/sys/fs/cgroup/system.slice/app.service/memory.max")
+		);
+		let v1 = "5:cpu,cpuacct:/\n4:memory:/docker/abc\n0::/\n";
+		assert_eq!(
+			cgroup_memory_file(v1).unwrap(),
+			Path::new("/sys/fs/cgroup/memory/docker/abc/memory.limit_in_bytes")
+		);
+
+		assert_eq!(parse_memory_limit("max\n"), None);
+		assert_eq!(parse_memory_limit("9223372036854771712\n"), None);
+		assert_eq!(
+			parse_memory_limit("536870912\n").unwrap(),
+			DataSize::from_mib(512.0)
+		);
+
+		let info = SelfInfo {
+			open_fds: Some(10),
+			fd_soft_limit: Some(1024),
+			fd_hard_limit: None,
+			rss: None,
+			cgroup_memory_limit: None
+		};
+		assert_eq!(info.fds_available(), Some(1014));
 	}
 
 	#[test]
@@ -363,6 +1530,59 @@ mod tests {
 		assert_eq!(s.average().unwrap(), (13.37, 15.82, 16.64));
 		assert_eq!(s.threads().unwrap(), (14, 1444));
 		assert_eq!(s.newest_pid().unwrap(), 436826);
+		assert_eq!(
+			s.normalized_by(2).unwrap(),
+			(13.37 / 2.0, 15.82 / 2.0, 8.32)
+		);
+		assert!(s.normalized_by(0).is_none());
+		let cpus = online_cpus().unwrap() as f32;
+		assert_eq!(s.normalized().unwrap().0, 13.37 / cpus);
+	}
+
+	#[test]
+	fn strict_load_avg() {
+		let s = LoadAvg::from_string("13.37 15.82 16.64 14/1444 436826\n".into());
+		assert_eq!(s.try_average().unwrap(), (13.37, 15.82, 16.64));
+		assert_eq!(s.try_threads().unwrap(), (14, 1444));
+		assert_eq!(s.try_newest_pid().unwrap(), 436826);
+
+		let s = LoadAvg::from_string("13.37 x 16.64 14\n".into());
+		let e = s.try_average().unwrap_err();
+		assert_eq!(
+			e.to_string(),
+			"/proc/loadavg:1: failed to parse load5 from \"x\""
+		);
+		assert!(s.try_threads().is_err());
+		assert!(matches!(
+			s.try_newest_pid().unwrap_err(),
+			Error::MissingField { .. }
+		));
+	}
+
+	#[test]
+	fn strict_stat() {
+		let s = Stat::from_string("\
+cpu  47500 2396 21138 741776 6759 0 516 0 0 0
+cpu0 1657 25 649 31631 152 0 40 0 0 0
+cpu1 1895 140 -1 31335 197 0 9 0 0 0
+cpu2 1895 140
+".into());
+		assert_eq!(s.try_cpu().unwrap().softirq, 516);
+		assert_eq!(s.try_cpu_nth(0).unwrap().user, 1657);
+		let e = s.try_cpu_nth(1).unwrap_err();
+		assert_eq!(
+			e.to_string(),
+			"/proc/stat:3: failed to parse cpu1 from \"-1\""
+		);
+		let e = s.try_cpu_nth(2).unwrap_err();
+		assert_eq!(
+			e.to_string(),
+			"/proc/stat:4: failed to parse cpu2 from 2 values, expected at least 7"
+		);
+		assert!(matches!(
+			s.try_cpu_nth(3).unwrap_err(),
+			Error::MissingField { .. }
+		));
 	}
 
 	#[test]
@@ -403,6 +1623,10 @@ softirq 1572362 6570 73617 6 106501 103799 0 729 724985 18 556137\n\
 		".into());
 
 		assert!(first.cpu_nth(0).is_some());
+		assert_eq!(first.cpu_count(), 24);
+		let (nth, cpu23) = first.cpus().last().unwrap();
+		assert_eq!(nth, 23);
+		assert_eq!(Some(cpu23), first.cpu_nth(23));
 		assert_eq!(first.cpu().unwrap(), CpuStat {
 			user: 47500,
 			nice: 2396,
@@ -455,4 +1679,83 @@ softirq 19512683 120053 1138489 8 420631 143436 0 10350 10473743 18 7205955\n\
 		let usage = second_cpu.usage(&first_cpu);
 		assert_eq!(usage, 0.04514286735257322);
 	}
+
+	#[test]
+	fn interrupts() {
+		let ints = Interrupts::from_string("\
+           CPU0       CPU1       CPU3
+  0:         35          0          0   IO-APIC   2-edge      timer
+  8:          0          0          1   IO-APIC   8-edge      rtc0
+  9:          0       1242          0   IO-APIC   9-fasteoi   acpi, i8042
+ 11:       7810       2301          0     GICv3  27 Level     arch_timer
+124:          0        571          0  IR-PCI-MSI 524288-edge      nvme0q0
+ 30:          0          0          0  PCI-MSIX-0000:00:01.0   2-edge
+NMI:          4          2          3   Non-maskable interrupts
+LOC:    1510994    1304587    1288810   Local timer interrupts
+ERR:          0
+".into());
+
+		assert_eq!(ints.cpus().collect::<Vec<_>>(), [0, 1, 3]);
+		assert_eq!(ints.entries().count(), 9);
+
+		let timer = ints.by_id("0").unwrap();
+		assert_eq!(timer.number(), Some(0));
+		assert_eq!(timer.counts, [35, 0, 0]);
+		assert_eq!(timer.controller.as_deref(), Some("IO-APIC"));
+		assert_eq!(timer.hwirq.as_deref(), Some("2-edge"));
+		assert_eq!(timer.label, "timer");
+
+		let shared = ints.by_id("9").unwrap();
+		assert_eq!(shared.hwirq.as_deref(), Some("9-fasteoi"));
+		assert_eq!(shared.devices().collect::<Vec<_>>(), ["acpi", "i8042"]);
+
+		let arm = ints.by_id("11").unwrap();
+		assert_eq!(arm.hwirq.as_deref(), Some("27 Level"));
+		assert_eq!(arm.total(), 10111);
+
+		let nvme = ints.by_id("124").unwrap();
+		assert_eq!(nvme.controller.as_deref(), Some("IR-PCI-MSI"));
+		assert_eq!(nvme.hwirq.as_deref(), Some("524288-edge"));
+		assert_eq!(nvme.label, "nvme0q0");
+
+		let unused = ints.by_id("30").unwrap();
+		assert_eq!(unused.devices().count(), 0);
+
+		let nmi = ints.by_id("NMI").unwrap();
+		assert_eq!(nmi.number(), None);
+		assert_eq!(nmi.controller, None);
+		assert_eq!(nmi.label, "Non-maskable interrupts");
+		assert_eq!(nmi.devices().count(), 0);
+		assert_eq!(ints.by_id("ERR").unwrap().counts, [0]);
+	}
+
+	#[test]
+	fn stat_diff() {
+		let earlier = Stat::from_string("\
+cpu  47500 2396 21138 741776 6759 0 516 0 0 0
+cpu0 1657 25 649 31631 152 0 40 0 0 0
+cpu1 1895 140 624 31335 197 0 9 0 0 0
+ctxt 1000
+".into());
+		let stat = Stat::from_string("\
+cpu  47600 2396 21138 741776 6759 0 516 0 0 0
+cpu0 1757 25 649 31631 152 0 40 0 0 0
+ctxt 1000
+procs_running 2
+".into());
+		let diff = earlier.diff(&stat);
+		let kinds: Vec<_> = diff.iter()
+			.map(|c| (c.key, c.kind()))
+			.collect();
+		assert_eq!(kinds, [
+			("cpu", ChangeKind::Changed),
+			("cpu0", ChangeKind::Changed),
+			("procs_running", ChangeKind::Added),
+			("cpu1", ChangeKind::Removed)
+		]);
+		assert_eq!(diff[1].old.as_ref().unwrap()[0], 1657);
+		assert_eq!(diff[1].new.as_ref().unwrap()[0], 1757);
+		assert_eq!(diff[2].new, Some(vec![2]));
+		assert_eq!(diff[3].new, None);
+	}
 }
\ No newline at end of file
@@ -0,0 +1,201 @@
+//! Get information about the trusted platform module from `/sys/class/tpm`.
+//!
+//! The enabled, active and owned states as well as the capabilities are only
+//! exposed by the kernel for TPM 1.2 devices.
+
+use crate::util::read_sys_opt;
+
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+
+const TPM_PATH: &str = "/sys/class/tpm";
+
+/// A tpm device for example `/sys/class/tpm/tpm0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tpm {
+	path: PathBuf
+}
+
+impl Tpm {
+	/// Returns all tpm devices, an empty list means no tpm is present.
+	pub fn all() -> io::Result<Vec<Self>> {
+		let dir = match fs::read_dir(TPM_PATH) {
+			Ok(d) => d,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+			Err(e) => return Err(e)
+		};
+
+		let mut list = dir.map(|e| e.map(|e| Self::from_path(e.path())))
+			.collect::<io::Result<Vec<_>>>()?;
+		list.sort_by(|a, b| a.path.cmp(&b.path));
+		Ok(list)
+	}
+
+	/// Returns true if at least one tpm device is present.
+	pub fn is_present() -> io::Result<bool> {
+		Self::all()
+			.map(|l| !l.is_empty())
+	}
+
+	/// Creates a tpm from a path like `/sys/class/tpm/tpm0`.
+	pub fn from_path(path: impl Into<PathBuf>) -> Self {
+		Self { path: path.into() }
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// The name of the device for example `tpm0`.
+	pub fn name(&self) -> Option<&str> {
+		self.path.file_name()?.to_str()
+	}
+
+	fn device(&self) -> PathBuf {
+		self.path.join("device")
+	}
+
+	/// The tpm version.
+	///
+	/// Uses `tpm_version_major` (linux 5.7+) and falls back to the
+	/// capabilities and the existence of a tpmrm device.
+	pub fn version(&self) -> io::Result<TpmVersion> {
+		if let Some(major) = read_sys_opt::<u8>(
+			self.path.join("tpm_version_major")
+		)? {
+			return Ok(TpmVersion::from_major(major))
+		}
+
+		if self.caps()?.is_some() {
+			return Ok(TpmVersion::V1_2)
+		}
+
+		// only tpm 2.0 devices have a resource manager
+		let rm = self.name()
+			.map(|n| Path::new("/dev").join(n.replacen("tpm", "tpmrm", 1)));
+		match rm {
+			Some(rm) if rm.exists() => Ok(TpmVersion::V2_0),
+			_ => Ok(TpmVersion::Unknown)
+		}
+	}
+
+	/// Returns the capabilities (TPM 1.2 only).
+	pub fn caps(&self) -> io::Result<Option<TpmCaps>> {
+		match fs::read_to_string(self.device().join("caps")) {
+			Ok(raw) => Ok(Some(TpmCaps { raw })),
+			Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+			Err(e) => Err(e)
+		}
+	}
+
+	/// Returns the manufacturer for example `IFX` (TPM 1.2 only).
+	pub fn manufacturer(&self) -> io::Result<Option<String>> {
+		self.caps()
+			.map(|c| c.and_then(|c| c.manufacturer()))
+	}
+
+	/// Whether the tpm is enabled (TPM 1.2 only).
+	pub fn enabled(&self) -> io::Result<Option<bool>> {
+		self.flag("enabled")
+	}
+
+	/// Whether the tpm is active (TPM 1.2 only).
+	pub fn active(&self) -> io::Result<Option<bool>> {
+		self.flag("active")
+	}
+
+	/// Whether the tpm has an owner (TPM 1.2 only).
+	pub fn owned(&self) -> io::Result<Option<bool>> {
+		self.flag("owned")
+	}
+
+	fn flag(&self, name: &str) -> io::Result<Option<bool>> {
+		read_sys_opt::<u8>(self.device().join(name))
+			.map(|v| v.map(|v| v == 1))
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub enum TpmVersion {
+	V1_2,
+	V2_0,
+	Unknown
+}
+
+impl TpmVersion {
+	fn from_major(major: u8) -> Self {
+		match major {
+			1 => Self::V1_2,
+			2 => Self::V2_0,
+			_ => Self::Unknown
+		}
+	}
+}
+
+/// The capabilities of a TPM 1.2 device from `device/caps`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TpmCaps {
+	raw: String
+}
+
+impl TpmCaps {
+	#[cfg(test)]
+	fn from_string(raw: String) -> Self {
+		Self {raw}
+	}
+
+	/// Returns the value of the given key for example `TCG version`.
+	pub fn value(&self, key: &str) -> Option<&str> {
+		self.raw.lines()
+			.filter_map(|l| l.split_once(':'))
+			.find(|(k, _)| k.trim() == key)
+			.map(|(_, v)| v.trim())
+	}
+
+	/// The manufacturer id for example `0x49465800`.
+	pub fn manufacturer_id(&self) -> Option<u32> {
+		let id = self.value("Manufacturer")?;
+		u32::from_str_radix(id.trim_start_matches("0x"), 16).ok()
+	}
+
+	/// The manufacturer id decoded as ascii for example `IFX`.
+	pub fn manufacturer(&self) -> Option<String> {
+		let id = self.manufacturer_id()?.to_be_bytes();
+		let s: String = id.iter()
+			.take_while(|b| **b != 0)
+			.map(|b| *b as char)
+			.collect();
+		s.chars().all(|c| c.is_ascii_graphic()).then(|| s)
+	}
+
+	pub fn tcg_version(&self) -> Option<&str> {
+		self.value("TCG version")
+	}
+
+	pub fn firmware_version(&self) -> Option<&str> {
+		self.value("Firmware version")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn tpm_caps() {
+		let caps = TpmCaps::from_string("\
+Manufacturer: 0x49465800
+TCG version: 1.2
+Firmware version: 3.17
+".into());
+		assert_eq!(caps.manufacturer_id(), Some(0x49465800));
+		assert_eq!(caps.manufacturer().unwrap(), "IFX");
+		assert_eq!(caps.tcg_version(), Some("1.2"));
+		assert_eq!(caps.firmware_version(), Some("3.17"));
+	}
+}
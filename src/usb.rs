@@ -0,0 +1,211 @@
+//! List usb devices from `/sys/bus/usb/devices` with their runtime power
+//! management state.
+//!
+//! ## Example
+//! ```no_run
+//! use linux_info::usb::UsbDevice;
+//!
+//! for dev in UsbDevice::all().unwrap() {
+//!     let power = dev.power().unwrap();
+//!     println!(
+//!         "{:?} {:?} autosuspend {} {:?} max {:?} mA",
+//!         dev.name(), dev.product(), power.autosuspend,
+//!         power.runtime_status, power.max_power
+//!     );
+//! }
+//! ```
+
+use crate::util::{read_sys_string, read_sys_value, read_sys_opt};
+
+use std::{fs, io};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const USB_PATH: &str = "/sys/bus/usb/devices";
+
+/// A usb device for example `/sys/bus/usb/devices/1-2`, root hubs like
+/// `usb1` are included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsbDevice {
+	path: PathBuf
+}
+
+impl UsbDevice {
+	/// Returns all usb devices, the interfaces of the devices (like `1-2:1.0`)
+	/// are skipped.
+	pub fn all() -> io::Result<Vec<Self>> {
+		let dir = match fs::read_dir(USB_PATH) {
+			Ok(d) => d,
+			Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+			Err(e) => return Err(e)
+		};
+
+		let mut list = vec![];
+		for entry in dir {
+			let entry = entry?;
+			let is_interface = entry.file_name().to_str()
+				.map(|n| n.contains(':'))
+				.unwrap_or(true);
+			if !is_interface {
+				list.push(Self::from_path(entry.path()));
+			}
+		}
+		list.sort_by(|a, b| a.path.cmp(&b.path));
+		Ok(list)
+	}
+
+	/// Creates a usb device from a path like `/sys/bus/usb/devices/1-2`.
+	pub fn from_path(path: impl Into<PathBuf>) -> Self {
+		Self { path: path.into() }
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// The name of the device for example `1-2` or `usb1`.
+	pub fn name(&self) -> Option<&str> {
+		self.path.file_name()?.to_str()
+	}
+
+	pub fn bus_num(&self) -> io::Result<u32> {
+		read_sys_value(self.path.join("busnum"))
+	}
+
+	pub fn dev_num(&self) -> io::Result<u32> {
+		read_sys_value(self.path.join("devnum"))
+	}
+
+	/// The usb vendor id for example `0x046d`.
+	pub fn vendor_id(&self) -> io::Result<u16> {
+		read_hex(self.path.join("idVendor"))
+	}
+
+	/// The usb product id.
+	pub fn product_id(&self) -> io::Result<u16> {
+		read_hex(self.path.join("idProduct"))
+	}
+
+	/// The manufacturer reported by the device.
+	pub fn manufacturer(&self) -> Option<String> {
+		read_sys_string(self.path.join("manufacturer")).ok()
+	}
+
+	/// The product name reported by the device.
+	pub fn product(&self) -> Option<String> {
+		read_sys_string(self.path.join("product")).ok()
+	}
+
+	pub fn serial(&self) -> Option<String> {
+		read_sys_string(self.path.join("serial")).ok()
+	}
+
+	/// The negotiated speed in Mbit/s for example `480` or `5000`, low speed
+	/// devices report 1.5.
+	pub fn speed(&self) -> io::Result<f64> {
+		read_sys_value(self.path.join("speed"))
+	}
+
+	/// The usb version the device supports for example `2.00`.
+	pub fn version(&self) -> io::Result<String> {
+		read_sys_string(self.path.join("version"))
+	}
+
+	/// Returns true if the device is a hub.
+	pub fn is_hub(&self) -> io::Result<bool> {
+		read_hex(self.path.join("bDeviceClass")).map(|c| c == 0x09)
+	}
+
+	/// The runtime power management state of the device.
+	pub fn power(&self) -> io::Result<UsbPower> {
+		let power = self.path.join("power");
+		let millis = |name: &str| {
+			read_sys_opt::<i64>(power.join(name)).map(|ms| {
+				// a negative delay means the device never autosuspends
+				ms.and_then(|ms| u64::try_from(ms).ok())
+					.map(Duration::from_millis)
+			})
+		};
+
+		Ok(UsbPower {
+			autosuspend: read_sys_string(power.join("control"))? == "auto",
+			autosuspend_delay: millis("autosuspend_delay_ms")?,
+			runtime_status: read_sys_string(power.join("runtime_status"))?
+				.as_str().into(),
+			active_time: millis("runtime_active_time")?,
+			suspended_time: millis("runtime_suspended_time")?,
+			wakeup: read_sys_opt::<String>(power.join("wakeup"))?
+				.and_then(|w| match w.as_str() {
+					"enabled" => Some(true),
+					"disabled" => Some(false),
+					_ => None
+				}),
+			max_power: read_sys_opt::<String>(self.path.join("bMaxPower"))?
+				.and_then(|p| p.trim_end_matches("mA").parse().ok())
+		})
+	}
+}
+
+fn read_hex(path: impl AsRef<Path>) -> io::Result<u16> {
+	let s = read_sys_string(path)?;
+	u16::from_str_radix(s.trim_start_matches("0x"), 16)
+		.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// The power management state of a usb device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub struct UsbPower {
+	/// True if the kernel suspends the device when it is idle (`control` is
+	/// `auto`), false if it is always on.
+	pub autosuspend: bool,
+	/// How long the device needs to be idle before it is suspended, `None`
+	/// if it never is.
+	pub autosuspend_delay: Option<Duration>,
+	pub runtime_status: RuntimeStatus,
+	/// The time the device was active since boot.
+	pub active_time: Option<Duration>,
+	/// The time the device was suspended since boot.
+	pub suspended_time: Option<Duration>,
+	/// If the device is allowed to wake up the system, `None` if it cannot.
+	pub wakeup: Option<bool>,
+	/// The maximum power the device draws from the bus in mA as configured,
+	/// `None` if the device is not configured.
+	pub max_power: Option<u32>
+}
+
+/// The runtime power state of a device.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde1::Serialize, serde1::Deserialize),
+	serde(crate = "serde1")
+)]
+pub enum RuntimeStatus {
+	Active,
+	Suspended,
+	Suspending,
+	Resuming,
+	Error,
+	/// Runtime power management is disabled for the device.
+	Unsupported,
+	Unknown(String)
+}
+
+impl From<&str> for RuntimeStatus {
+	fn from(s: &str) -> Self {
+		match s {
+			"active" => Self::Active,
+			"suspended" => Self::Suspended,
+			"suspending" => Self::Suspending,
+			"resuming" => Self::Resuming,
+			"error" => Self::Error,
+			"unsupported" => Self::Unsupported,
+			o => Self::Unknown(o.into())
+		}
+	}
+}
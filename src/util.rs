@@ -1,12 +1,21 @@
 
+use crate::error::Error;
+
 use std::{fmt, io, mem};
+use std::borrow::Cow;
 use std::io::Read;
-use std::fs::File;
-use std::path::Path;
-use std::ffi::CString;
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::ffi::{CString, CStr};
+use std::ptr;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::os::unix::ffi::OsStrExt;
-use std::os::unix::prelude::AsRawFd;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::prelude::{AsRawFd, FromRawFd};
 use std::convert::TryInto;
+use std::str::FromStr;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::iter::Sum;
 
 use byte_parser::{StrParser, ParseIterator};
 
@@ -15,15 +24,30 @@ use libc::c_int;
 const DEF_PRECISION: usize = 2;
 
 /// Represents a size, for example `1024 kB`.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// `kb`, `mb`, `gb` and `tb` are 1024 based like in the kernel. Formatting
+/// uses them by default (`1 kb`), the alternate flag `{:#}` uses binary
+/// units (`1 kib`) and [`DataSize::display`] allows to use decimal units.
+///
+/// ```
+/// use linux_info::unit::DataSize;
+///
+/// let total: DataSize = "4 gb".parse().unwrap();
+/// let used = DataSize::from_gb(1.5);
+/// assert_eq!((total - used).as_gb(), 2.5);
+/// ```
+///
+/// With the `serde` feature a size is serialized as the amount of bytes,
+/// see [`human`](crate::unit::human) for a readable string. Before 0.1.17
+/// it was serialized as `{"bytes": N}`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct DataSize {
 	bytes: u128
 }
 
 impl DataSize {
 
-	// not implemeting FromStr because this is private.
-	pub(crate) fn from_str(s: &str) -> Option<Self> {
+	fn parse(s: &str) -> Option<Self> {
 		let mut iter = StrParser::new(s);
 		let float = parse_f64(&mut iter)?;
 		// now we need to parse the unit
@@ -37,30 +61,249 @@ impl DataSize {
 		})
 	}
 
+	/// Parses a size from a kernel file like /proc/meminfo where `kB` means
+	/// 1024 bytes.
+	pub(crate) fn from_kernel_str(s: &str) -> Option<Self> {
+		Self::parse(s)
+	}
+
 	pub(crate) fn from_size_bytes(bytes: impl TryInto<u128>) -> Option<Self> {
 		bytes.try_into().ok()
 			.map(|bytes| Self {bytes})
 	}
 
+	pub const fn from_bytes(bytes: u128) -> Self {
+		Self {bytes}
+	}
+
+	/// Creates a size from a value in the given unit.
+	///
+	/// Negative values result in zero.
+	pub fn from_unit(val: f64, unit: &DataSizeUnit) -> Self {
+		Self {
+			bytes: unit.to_byte(val)
+		}
+	}
+
+	pub fn from_kb(val: f64) -> Self {
+		Self::from_unit(val, &DataSizeUnit::Kb)
+	}
+
+	pub fn from_mb(val: f64) -> Self {
+		Self::from_unit(val, &DataSizeUnit::Mb)
+	}
+
+	pub fn from_gb(val: f64) -> Self {
+		Self::from_unit(val, &DataSizeUnit::Gb)
+	}
+
+	pub fn from_tb(val: f64) -> Self {
+		Self::from_unit(val, &DataSizeUnit::Tb)
+	}
+
+	pub fn from_kib(val: f64) -> Self {
+		Self::from_unit(val, &DataSizeUnit::Kib)
+	}
+
+	pub fn from_mib(val: f64) -> Self {
+		Self::from_unit(val, &DataSizeUnit::Mib)
+	}
+
+	pub fn from_gib(val: f64) -> Self {
+		Self::from_unit(val, &DataSizeUnit::Gib)
+	}
+
+	pub fn from_tib(val: f64) -> Self {
+		Self::from_unit(val, &DataSizeUnit::Tib)
+	}
+
+	pub fn from_si_kb(val: f64) -> Self {
+		Self::from_unit(val, &DataSizeUnit::SiKb)
+	}
+
+	pub fn from_si_mb(val: f64) -> Self {
+		Self::from_unit(val, &DataSizeUnit::SiMb)
+	}
+
+	pub fn from_si_gb(val: f64) -> Self {
+		Self::from_unit(val, &DataSizeUnit::SiGb)
+	}
+
+	pub fn from_si_tb(val: f64) -> Self {
+		Self::from_unit(val, &DataSizeUnit::SiTb)
+	}
+
 	/// Convert the data unit into a specific unit.
 	pub fn to(self, unit: &DataSizeUnit) -> f64 {
 		DataSizeUnit::convert(self.bytes, unit)
 	}
 
+	pub const fn as_bytes(&self) -> u128 {
+		self.bytes
+	}
+
+	pub fn as_kb(&self) -> f64 {
+		self.to(&DataSizeUnit::Kb)
+	}
+
+	pub fn as_mb(&self) -> f64 {
+		self.to(&DataSizeUnit::Mb)
+	}
+
+	pub fn as_gb(&self) -> f64 {
+		self.to(&DataSizeUnit::Gb)
+	}
+
+	pub fn as_tb(&self) -> f64 {
+		self.to(&DataSizeUnit::Tb)
+	}
+
+	pub fn as_kib(&self) -> f64 {
+		self.to(&DataSizeUnit::Kib)
+	}
+
+	pub fn as_mib(&self) -> f64 {
+		self.to(&DataSizeUnit::Mib)
+	}
+
+	pub fn as_gib(&self) -> f64 {
+		self.to(&DataSizeUnit::Gib)
+	}
+
+	pub fn as_tib(&self) -> f64 {
+		self.to(&DataSizeUnit::Tib)
+	}
+
+	pub fn as_si_kb(&self) -> f64 {
+		self.to(&DataSizeUnit::SiKb)
+	}
+
+	pub fn as_si_mb(&self) -> f64 {
+		self.to(&DataSizeUnit::SiMb)
+	}
+
+	pub fn as_si_gb(&self) -> f64 {
+		self.to(&DataSizeUnit::SiGb)
+	}
+
+	pub fn as_si_tb(&self) -> f64 {
+		self.to(&DataSizeUnit::SiTb)
+	}
+
+	/// Returns a value which formats the size with the given unit system.
+	///
+	/// The default `Display` implementation uses [`UnitSystem::Kernel`] and
+	/// the alternate flag (`{:#}`) binary units.
+	pub fn display(&self, system: UnitSystem) -> DataSizeDisplay {
+		DataSizeDisplay {
+			size: *self,
+			system
+		}
+	}
+
+	/// Returns `None` if other is bigger than self.
+	pub fn checked_sub(self, other: Self) -> Option<Self> {
+		self.bytes.checked_sub(other.bytes)
+			.map(Self::from_bytes)
+	}
+
 }
 
+/// Parses a size like `1024`, `10 kb`, `4.2 GB` or `1.5 GiB`.
+///
+/// `kb`, `mb`, `gb` and `tb` are 1024 based like `kib`, `mib`, `gib` and
+/// `tib`, decimal units can't be parsed.
+impl FromStr for DataSize {
+	type Err = ParseDataSizeError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Self::parse(s).ok_or(ParseDataSizeError)
+	}
+}
+
+/// The error returned when a [`DataSize`] could not be parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseDataSizeError;
+
+impl fmt::Display for ParseDataSizeError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("invalid data size")
+	}
+}
+
+impl std::error::Error for ParseDataSizeError {}
+
+impl Add for DataSize {
+	type Output = Self;
+
+	fn add(self, other: Self) -> Self {
+		Self::from_bytes(self.bytes + other.bytes)
+	}
+}
+
+impl AddAssign for DataSize {
+	fn add_assign(&mut self, other: Self) {
+		self.bytes += other.bytes;
+	}
+}
+
+/// Saturates at zero, use [`DataSize::checked_sub`] to detect underflows.
+impl Sub for DataSize {
+	type Output = Self;
+
+	fn sub(self, other: Self) -> Self {
+		Self::from_bytes(self.bytes.saturating_sub(other.bytes))
+	}
+}
+
+impl SubAssign for DataSize {
+	fn sub_assign(&mut self, other: Self) {
+		*self = *self - other;
+	}
+}
+
+impl Sum for DataSize {
+	fn sum<I>(iter: I) -> Self
+	where I: Iterator<Item=Self> {
+		iter.fold(Self::default(), Add::add)
+	}
+}
+
+/// A unit of a [`DataSize`].
+///
+/// `Kb`, `Mb`, `Gb` and `Tb` are 1024 based like the kernel uses them, the
+/// decimal units are prefixed with `Si`.
+///
+/// With the `serde` feature a unit is serialized as a string like `kib`,
+/// before 0.1.17 the variant name (`Kib`) was used.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DataSizeUnit {
 	/// Byte
 	B,
 	/// Kilobyte
-	Kb,// 1_000
+	Kb,// 1_024
 	/// Megabyte
-	Mb,// 1_000_000
+	Mb,// 1_024 * 1_024
 	/// Gigabyte
-	Gb,// 1_000_000_000
+	Gb,// 1_024 * 1_024 * 1_024
 	/// Terabyte
-	Tb // 1_000_000_000_000
+	Tb,// 1_024 * 1_024 * 1_024 * 1_024
+	/// Kibibyte
+	Kib,// 1_024
+	/// Mebibyte
+	Mib,// 1_024 * 1_024
+	/// Gibibyte
+	Gib,
+	/// Tebibyte
+	Tib,
+	/// Decimal kilobyte
+	SiKb,// 1_000
+	/// Decimal megabyte
+	SiMb,// 1_000_000
+	/// Decimal gigabyte
+	SiGb,// 1_000_000_000
+	/// Decimal terabyte
+	SiTb // 1_000_000_000_000
 }
 
 impl DataSizeUnit {
@@ -68,10 +311,14 @@ impl DataSizeUnit {
 	const fn val(&self) -> u128 {
 		match self {
 			Self::B => 1,
-			Self::Kb => 1_024,
-			Self::Mb => 1_024 * 1_024,
-			Self::Gb => 1_024 * 1_024 * 1_024,
-			Self::Tb => 1_024 * 1_024 * 1_024 * 1_024
+			Self::Kb | Self::Kib => 1_024,
+			Self::Mb | Self::Mib => 1_024 * 1_024,
+			Self::Gb | Self::Gib => 1_024 * 1_024 * 1_024,
+			Self::Tb | Self::Tib => 1_024 * 1_024 * 1_024 * 1_024,
+			Self::SiKb => 1_000,
+			Self::SiMb => 1_000_000,
+			Self::SiGb => 1_000_000_000,
+			Self::SiTb => 1_000_000_000_000
 		}
 	}
 
@@ -83,22 +330,55 @@ impl DataSizeUnit {
 			s if eqs(s, "mb") => Self::Mb,
 			s if eqs(s, "gb") => Self::Gb,
 			s if eqs(s, "tb") => Self::Tb,
+			s if eqs(s, "kib") => Self::Kib,
+			s if eqs(s, "mib") => Self::Mib,
+			s if eqs(s, "gib") => Self::Gib,
+			s if eqs(s, "tib") => Self::Tib,
 			_ => return None
 		})
 	}
 
+	#[cfg(feature = "serde")]
+	fn from_serde_str(s: &str) -> Option<Self> {
+		Some(match s {
+			"si_kb" => Self::SiKb,
+			"si_mb" => Self::SiMb,
+			"si_gb" => Self::SiGb,
+			"si_tb" => Self::SiTb,
+			s => return Self::from_str(s)
+		})
+	}
+
+	/// Returns true for the binary units (Kib, Mib, Gib, Tib).
+	pub const fn is_binary(&self) -> bool {
+		matches!(self, Self::Kib | Self::Mib | Self::Gib | Self::Tib)
+	}
+
+	/// Returns true for the decimal units (SiKb, SiMb, SiGb, SiTb).
+	pub const fn is_si(&self) -> bool {
+		matches!(self, Self::SiKb | Self::SiMb | Self::SiGb | Self::SiTb)
+	}
+
 	fn to_byte(&self, val: f64) -> u128 {
 		// TODO probably need fix this overflowing
 		(val * self.val() as f64) as u128
 	}
 
-	fn adjust_to(byte: u128) -> Self {
+	fn adjust_to(byte: u128, system: UnitSystem) -> Self {
+		let [kb, mb, gb, tb] = match system {
+			UnitSystem::Kernel => [Self::Kb, Self::Mb, Self::Gb, Self::Tb],
+			UnitSystem::Binary => [Self::Kib, Self::Mib, Self::Gib, Self::Tib],
+			UnitSystem::Decimal => {
+				[Self::SiKb, Self::SiMb, Self::SiGb, Self::SiTb]
+			}
+		};
+
 		match byte {
-			b if b < Self::Kb.val() => Self::B,
-			b if b < Self::Mb.val() => Self::Kb,
-			b if b < Self::Gb.val() => Self::Mb,
-			b if b < Self::Tb.val() => Self::Gb,
-			_ => Self::Tb
+			b if b < kb.val() => Self::B,
+			b if b < mb.val() => kb,
+			b if b < gb.val() => mb,
+			b if b < tb.val() => gb,
+			_ => tb
 		}
 	}
 
@@ -112,7 +392,26 @@ impl DataSizeUnit {
 			Self::Kb => "kb",
 			Self::Mb => "mb",
 			Self::Gb => "gb",
-			Self::Tb => "tb"
+			Self::Tb => "tb",
+			Self::Kib => "kib",
+			Self::Mib => "mib",
+			Self::Gib => "gib",
+			Self::Tib => "tib",
+			Self::SiKb => "kB",
+			Self::SiMb => "MB",
+			Self::SiGb => "GB",
+			Self::SiTb => "TB"
+		}
+	}
+
+	#[cfg(feature = "serde")]
+	const fn serde_str(&self) -> &'static str {
+		match self {
+			Self::SiKb => "si_kb",
+			Self::SiMb => "si_mb",
+			Self::SiGb => "si_gb",
+			Self::SiTb => "si_tb",
+			u => u.as_str()
 		}
 	}
 
@@ -165,14 +464,218 @@ fn eqs(a: &str, b: &str) -> bool {
 	a.eq_ignore_ascii_case(b)
 }
 
-impl fmt::Display for DataSize {
+/// Which units are used when formatting a [`DataSize`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitSystem {
+	/// Powers of 1024 written like the kernel does (kb, mb, gb, tb), this is
+	/// the default.
+	Kernel,
+	/// Powers of 1024 (kib, mib, gib, tib).
+	Binary,
+	/// Powers of 1000 (kB, MB, GB, TB).
+	Decimal
+}
+
+/// Formats a [`DataSize`] with a given [`UnitSystem`], see
+/// [`DataSize::display`].
+#[derive(Debug, Clone, Copy)]
+pub struct DataSizeDisplay {
+	size: DataSize,
+	system: UnitSystem
+}
+
+impl fmt::Display for DataSizeDisplay {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-		let unit = DataSizeUnit::adjust_to(self.bytes);
-		let val = DataSizeUnit::convert(self.bytes, &unit);
+		let bytes = self.size.bytes;
+		let unit = DataSizeUnit::adjust_to(bytes, self.system);
+		let val = DataSizeUnit::convert(bytes, &unit);
 		unit.fmt_val(val, f)
 	}
 }
 
+impl fmt::Display for DataSize {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let system = match f.alternate() {
+			true => UnitSystem::Binary,
+			false => UnitSystem::Kernel
+		};
+		self.display(system).fmt(f)
+	}
+}
+
+#[cfg(feature = "serde")]
+mod impl_serde {
+	use super::*;
+
+	use serde1::ser::{self, Serialize, Serializer};
+	use serde1::de::{self, Deserialize, Deserializer, Visitor};
+
+	impl Serialize for DataSize {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where S: Serializer {
+			let bytes = self.bytes.try_into();
+			match bytes {
+				Ok(b) => serializer.serialize_u64(b),
+				// not every format supports u128
+				Err(_) if serializer.is_human_readable() => {
+					serializer.serialize_u128(self.bytes)
+				},
+				// binary formats need to always use the same type
+				Err(_) => Err(ser::Error::custom(
+					"data size does not fit into an u64"
+				))
+			}
+		}
+	}
+
+	struct DataSizeVisitor;
+
+	impl<'de> Visitor<'de> for DataSizeVisitor {
+		type Value = DataSize;
+
+		fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+			f.write_str("an amount of bytes or a size like \"4.2 gib\"")
+		}
+
+		fn visit_u64<E: de::Error>(self, v: u64) -> Result<DataSize, E> {
+			Ok(DataSize::from_bytes(v.into()))
+		}
+
+		fn visit_u128<E: de::Error>(self, v: u128) -> Result<DataSize, E> {
+			Ok(DataSize::from_bytes(v))
+		}
+
+		fn visit_i64<E: de::Error>(self, v: i64) -> Result<DataSize, E> {
+			v.try_into()
+				.map(|v: u64| DataSize::from_bytes(v.into()))
+				.map_err(|_| E::invalid_value(de::Unexpected::Signed(v), &self))
+		}
+
+		fn visit_str<E: de::Error>(self, v: &str) -> Result<DataSize, E> {
+			v.parse()
+				.map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+		}
+	}
+
+	/// Accepts the amount of bytes or a string like `4.2 gib`.
+	///
+	/// Formats which are not human readable (like bincode) only accept the
+	/// amount of bytes as an u64.
+	impl<'de> Deserialize<'de> for DataSize {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where D: Deserializer<'de> {
+			if deserializer.is_human_readable() {
+				deserializer.deserialize_any(DataSizeVisitor)
+			} else {
+				deserializer.deserialize_u64(DataSizeVisitor)
+			}
+		}
+	}
+
+	impl Serialize for DataSizeUnit {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where S: Serializer {
+			serializer.serialize_str(self.serde_str())
+		}
+	}
+
+	impl<'de> Deserialize<'de> for DataSizeUnit {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where D: Deserializer<'de> {
+			let s: std::borrow::Cow<'de, str> = Deserialize::deserialize(deserializer)?;
+			DataSizeUnit::from_serde_str(&s)
+				.ok_or_else(|| de::Error::invalid_value(
+					de::Unexpected::Str(&s),
+					&"a unit like kb or kib"
+				))
+		}
+	}
+
+	/// Serialize a [`DataSize`] as a readable string like `4.22 gb`.
+	///
+	/// Use it with `#[serde(with = "linux_info::unit::human")]`,
+	/// deserializing accepts strings and the amount of bytes.
+	///
+	/// The string is rounded to two decimals so serializing loses precision.
+	pub mod human {
+		use super::*;
+
+		pub fn serialize<S>(size: &DataSize, serializer: S) -> Result<S::Ok, S::Error>
+		where S: Serializer {
+			serializer.collect_str(size)
+		}
+
+		pub fn deserialize<'de, D>(deserializer: D) -> Result<DataSize, D::Error>
+		where D: Deserializer<'de> {
+			if deserializer.is_human_readable() {
+				deserializer.deserialize_any(DataSizeVisitor)
+			} else {
+				deserializer.deserialize_str(DataSizeVisitor)
+			}
+		}
+
+		/// The same as [`human`](super::human) for an `Option<DataSize>`.
+		pub mod option {
+			use super::*;
+
+			// wrapping the size keeps Some and None distinguishable in
+			// binary formats
+			#[derive(serde1::Serialize, serde1::Deserialize)]
+			#[serde(crate = "serde1")]
+			struct Human(#[serde(with = "super")] DataSize);
+
+			pub fn serialize<S>(
+				size: &Option<DataSize>,
+				serializer: S
+			) -> Result<S::Ok, S::Error>
+			where S: Serializer {
+				size.map(Human).serialize(serializer)
+			}
+
+			pub fn deserialize<'de, D>(
+				deserializer: D
+			) -> Result<Option<DataSize>, D::Error>
+			where D: Deserializer<'de> {
+				Option::<Human>::deserialize(deserializer)
+					.map(|o| o.map(|h| h.0))
+			}
+		}
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use serde1::de::IntoDeserializer;
+		use serde1::de::value::Error;
+
+		#[test]
+		fn deserialize() {
+			let size = DataSize::deserialize(
+				IntoDeserializer::<Error>::into_deserializer(1536u64)
+			).unwrap();
+			assert_eq!(size.as_bytes(), 1536);
+			let size = DataSize::deserialize(
+				IntoDeserializer::<Error>::into_deserializer("1.5 kib")
+			).unwrap();
+			assert_eq!(size.as_bytes(), 1536);
+			assert!(DataSize::deserialize(
+				IntoDeserializer::<Error>::into_deserializer(-1i64)
+			).is_err());
+			let unit = DataSizeUnit::deserialize(
+				IntoDeserializer::<Error>::into_deserializer("MiB")
+			).unwrap();
+			assert_eq!(unit, DataSizeUnit::Mib);
+			let unit = DataSizeUnit::deserialize(
+				IntoDeserializer::<Error>::into_deserializer("si_kb")
+			).unwrap();
+			assert_eq!(unit, DataSizeUnit::SiKb);
+		}
+	}
+}
+
+#[cfg(feature = "serde")]
+pub use impl_serde::human;
+
 // parses a part of a slice
 // Panics if Iterator contains not valid utf8
 fn parse_f64<'s, I>(iter: &mut I) -> Option<f64>
@@ -199,15 +702,227 @@ where I: ParseIterator<'s> {
 		.parse().ok()
 }
 
+/// Returns the line number (starting at 1) of `part` which needs to be a
+/// slice of `raw`.
+pub fn line_of(raw: &str, part: &str) -> usize {
+	let offset = (part.as_ptr() as usize)
+		.saturating_sub(raw.as_ptr() as usize)
+		.min(raw.len());
+	raw.as_bytes()[..offset].iter()
+		.filter(|b| **b == b'\n')
+		.count() + 1
+}
+
+/// The highest number of cpus the kernel can be configured with
+/// (`CONFIG_NR_CPUS`).
+const MAX_CPUS: u32 = 8192;
+
+/// Parses a cpu list like `0-3,8,10-11` which the kernel uses in sysfs and
+/// cgroup files, an empty string is an empty list.
+///
+/// Returns `None` for cpu numbers the kernel cannot have, so a malformed
+/// range does not allocate a huge list.
+pub fn parse_cpu_list(s: &str) -> Option<Vec<u32>> {
+	let cpu = |n: &str| n.parse().ok().filter(|n| *n < MAX_CPUS);
+
+	let mut list = vec![];
+	for part in s.trim().split(',').filter(|p| !p.is_empty()) {
+		match part.split_once('-') {
+			Some((start, end)) => list.extend(cpu(start)?..=cpu(end)?),
+			None => list.push(cpu(part)?)
+		}
+	}
+	Some(list)
+}
+
+/// Replaces octal escapes like `\040` which the kernel uses for spaces,
+/// tabs, newlines and backslashes in paths.
+pub fn unescape_octal(s: &str) -> Cow<'_, str> {
+	if !s.contains('\\') {
+		return Cow::Borrowed(s)
+	}
+
+	let bytes = s.as_bytes();
+	let mut out = Vec::with_capacity(bytes.len());
+	let mut i = 0;
+	while i < bytes.len() {
+		let octal = bytes.get(i + 1..i + 4)
+			.filter(|_| bytes[i] == b'\\')
+			.filter(|o| o.iter().all(|b| (b'0'..=b'7').contains(b)))
+			.and_then(|o| {
+				o.iter().try_fold(0u8, |n, b| {
+					n.checked_mul(8)?.checked_add(b - b'0')
+				})
+			});
+
+		match octal {
+			Some(b) => {
+				out.push(b);
+				i += 4;
+			},
+			None => {
+				out.push(bytes[i]);
+				i += 1;
+			}
+		}
+	}
+
+	Cow::Owned(String::from_utf8_lossy(&out).into_owned())
+}
+
+/// Reads the entire file, errors contain the path.
+pub fn read_to_string(path: impl AsRef<Path>) -> io::Result<String> {
+	let path = path.as_ref();
+	fs::read_to_string(path)
+		.map_err(|e| Error::io(path, e).into())
+}
+
 /// Clears the string the writes the entire file to the string.  
 /// Does not allocate in advance like std::fs::read_to_string.
 pub fn read_to_string_mut(path: impl AsRef<Path>, s: &mut String) -> io::Result<()> {
+	let path = path.as_ref();
 	s.clear();
-	let mut file = File::open(path)?;
-	file.read_to_string(s)
+	File::open(path)
+		.and_then(|mut file| file.read_to_string(s))
 		.map(|_| ())
+		.map_err(|e| Error::io(path, e).into())
 }
 
+/// Like [`read_to_string`] but without blocking the async runtime.
+#[cfg(feature = "async")]
+pub async fn read_to_string_async(path: impl AsRef<Path>) -> io::Result<String> {
+	let path = path.as_ref();
+	tokio::fs::read_to_string(path).await
+		.map_err(|e| Error::io(path, e).into())
+}
+
+/// Like [`read_to_string_mut`] but without blocking the async runtime.
+#[cfg(feature = "async")]
+pub async fn read_to_string_mut_async(
+	path: impl AsRef<Path>,
+	s: &mut String
+) -> io::Result<()> {
+	use tokio::io::AsyncReadExt;
+
+	let path = path.as_ref();
+	s.clear();
+	let r = match tokio::fs::File::open(path).await {
+		Ok(mut file) => file.read_to_string(s).await,
+		Err(e) => Err(e)
+	};
+	r.map(|_| ())
+		.map_err(|e| Error::io(path, e).into())
+}
+
+/// Reads a sysfs attribute and removes the trailing newline.
+pub fn read_sys_string(path: impl AsRef<Path>) -> io::Result<String> {
+	let mut s = read_to_string(path)?;
+	let len = s.trim_end().len();
+	s.truncate(len);
+	Ok(s)
+}
+
+/// Reads a sysfs attribute and parses it.
+pub fn read_sys_value<T>(path: impl AsRef<Path>) -> io::Result<T>
+where T: FromStr {
+	let path = path.as_ref();
+	let s = read_sys_string(path)?;
+	s.parse()
+		.map_err(|_| Error::parse(path, None, format!("{:?}", s)).into())
+}
+
+/// Like [`read_sys_value`] but returns `None` if the attribute does not
+/// exist.
+pub fn read_sys_opt<T>(path: impl AsRef<Path>) -> io::Result<Option<T>>
+where T: FromStr {
+	match read_sys_value(path) {
+		Ok(v) => Ok(Some(v)),
+		Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+		Err(e) => Err(e)
+	}
+}
+
+/// A sysfs directory which stays open, its attributes are opened with
+/// `openat` relative to it instead of resolving the whole path every time.
+///
+/// The buffers are reused, so reading an attribute does not allocate once
+/// they are large enough. Use this for types which read many attributes of
+/// the same directory on every reload.
+#[derive(Debug)]
+pub struct SysDir {
+	path: PathBuf,
+	dir: File,
+	name: Vec<u8>,
+	buf: String
+}
+
+impl SysDir {
+	pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+		let path = path.into();
+		let dir = OpenOptions::new()
+			.read(true)
+			.custom_flags(libc::O_DIRECTORY)
+			.open(&path)
+			.map_err(|e| Error::io(&path, e))?;
+
+		Ok(Self {
+			path,
+			dir,
+			name: vec![],
+			buf: String::new()
+		})
+	}
+
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// Reads an attribute into the buffer.
+	fn read(&mut self, name: &str) -> io::Result<()> {
+		self.name.clear();
+		self.name.extend_from_slice(name.as_bytes());
+		self.name.push(0);
+		let c_name = CStr::from_bytes_with_nul(&self.name)
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+		let fd = unsafe {
+			libc::openat(
+				self.dir.as_raw_fd(),
+				c_name.as_ptr(),
+				libc::O_RDONLY | libc::O_CLOEXEC
+			)
+		};
+		if fd == -1 {
+			let e = io::Error::last_os_error();
+			return Err(Error::io(self.path.join(name), e).into())
+		}
+		// the fd was just opened and is not used anywhere else
+		let mut file = unsafe { File::from_raw_fd(fd) };
+
+		self.buf.clear();
+		file.read_to_string(&mut self.buf)
+			.map(|_| ())
+			.map_err(|e| Error::io(self.path.join(name), e).into())
+	}
+
+	/// Reads an attribute without the trailing newline, the returned string
+	/// is overwritten by the next read.
+	pub fn read_string(&mut self, name: &str) -> io::Result<&str> {
+		self.read(name)?;
+		Ok(self.buf.trim_end())
+	}
+
+	/// Reads an attribute and parses it.
+	pub fn read_value<T>(&mut self, name: &str) -> io::Result<T>
+	where T: FromStr {
+		self.read(name)?;
+		let s = self.buf.trim_end();
+		s.parse().map_err(|_| {
+			Error::parse(self.path.join(name), None, format!("{:?}", s))
+				.into()
+		})
+	}
+}
 
 fn cstr(path: impl AsRef<Path>) -> io::Result<CString> {
 	CString::new(path.as_ref().as_os_str().as_bytes())
@@ -228,6 +943,162 @@ pub fn statfs(path: impl AsRef<Path>) -> io::Result<libc::statfs> {
 	}
 }
 
+// see https://man7.org/linux/man-pages/man3/sysconf.3.html
+pub fn sysconf(name: c_int) -> io::Result<u64> {
+	let r = unsafe { libc::sysconf(name) };
+	match r {
+		-1 => Err(io::Error::last_os_error()),
+		r => r.try_into()
+			.map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+	}
+}
+
+/// Returns the canonical name of a host using getaddrinfo.
+///
+/// This might query dns.
+pub fn canonical_name(host: &str) -> Option<String> {
+	let host = CString::new(host).ok()?;
+	unsafe {
+		let mut hints: libc::addrinfo = mem::zeroed();
+		hints.ai_family = libc::AF_UNSPEC;
+		hints.ai_flags = libc::AI_CANONNAME;
+		let mut res = ptr::null_mut();
+		let r = libc::getaddrinfo(host.as_ptr(), ptr::null(), &hints, &mut res);
+		if r != 0 || res.is_null() {
+			return None
+		}
+
+		let name = (*res).ai_canonname;
+		let name = if name.is_null() {
+			None
+		} else {
+			CStr::from_ptr(name).to_str().ok().map(String::from)
+		};
+		libc::freeaddrinfo(res);
+		name
+	}
+}
+
+/// Returns the ip addresses of every interface as (interface, address).
+pub fn interface_addresses() -> io::Result<Vec<(String, IpAddr)>> {
+	let mut list = vec![];
+	unsafe {
+		let mut addrs = ptr::null_mut();
+		if libc::getifaddrs(&mut addrs) == -1 {
+			return Err(io::Error::last_os_error())
+		}
+
+		let mut cur = addrs;
+		while !cur.is_null() {
+			let ifa: &libc::ifaddrs = &*cur;
+			cur = ifa.ifa_next;
+			if ifa.ifa_addr.is_null() || ifa.ifa_name.is_null() {
+				continue
+			}
+
+			let ip: IpAddr = match (*ifa.ifa_addr).sa_family as c_int {
+				libc::AF_INET => {
+					let addr = &*(ifa.ifa_addr as *const libc::sockaddr_in);
+					Ipv4Addr::from(addr.sin_addr.s_addr.to_ne_bytes()).into()
+				},
+				libc::AF_INET6 => {
+					let addr = &*(ifa.ifa_addr as *const libc::sockaddr_in6);
+					Ipv6Addr::from(addr.sin6_addr.s6_addr).into()
+				},
+				_ => continue
+			};
+			let name = CStr::from_ptr(ifa.ifa_name).to_string_lossy();
+			list.push((name.into_owned(), ip));
+		}
+
+		libc::freeifaddrs(addrs);
+	}
+	Ok(list)
+}
+
+/// Computes the sha256 hash of data, see FIPS 180-4.
+///
+/// Only used to hash the few bytes of the machine fingerprint, so a short
+/// implementation is preferred over adding a crypto dependency for every
+/// user of the crate. It is checked against the FIPS test vectors.
+pub(crate) fn sha256(data: &[u8]) -> [u8; 32] {
+	const K: [u32; 64] = [
+		0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1,
+		0x923f82a4, 0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3,
+		0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786,
+		0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+		0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147,
+		0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13,
+		0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+		0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+		0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a,
+		0x5b9cca4f, 0x682e6ff3, 0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208,
+		0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2
+	];
+	let mut h: [u32; 8] = [
+		0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c,
+		0x1f83d9ab, 0x5be0cd19
+	];
+
+	// pad with a one bit, zeros and the length in bits
+	let mut msg = data.to_vec();
+	msg.push(0x80);
+	while msg.len() % 64 != 56 {
+		msg.push(0);
+	}
+	msg.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+	for block in msg.chunks(64) {
+		let mut w = [0u32; 64];
+		for (i, word) in block.chunks(4).enumerate() {
+			w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+		}
+		for i in 16..64 {
+			let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^
+				(w[i - 15] >> 3);
+			let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^
+				(w[i - 2] >> 10);
+			w[i] = w[i - 16].wrapping_add(s0)
+				.wrapping_add(w[i - 7])
+				.wrapping_add(s1);
+		}
+
+		let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+		for i in 0..64 {
+			let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^
+				e.rotate_right(25);
+			let ch = (e & f) ^ (!e & g);
+			let t1 = hh.wrapping_add(s1)
+				.wrapping_add(ch)
+				.wrapping_add(K[i])
+				.wrapping_add(w[i]);
+			let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^
+				a.rotate_right(22);
+			let maj = (a & b) ^ (a & c) ^ (b & c);
+			let t2 = s0.wrapping_add(maj);
+
+			hh = g;
+			g = f;
+			f = e;
+			e = d.wrapping_add(t1);
+			d = c;
+			c = b;
+			b = a;
+			a = t1.wrapping_add(t2);
+		}
+
+		for (h, v) in h.iter_mut().zip([a, b, c, d, e, f, g, hh].iter()) {
+			*h = h.wrapping_add(*v);
+		}
+	}
+
+	let mut out = [0u8; 32];
+	for (chunk, v) in out.chunks_mut(4).zip(h.iter()) {
+		chunk.copy_from_slice(&v.to_be_bytes());
+	}
+	out
+}
+
 // BLKSSZGET
 
 pub fn blkdev_sector_size(fd: impl AsRawFd) -> io::Result<u64> {
@@ -264,11 +1135,47 @@ mod tests {
 		assert_eq!(size.to(&DataSizeUnit::Kb), 24576.0);
 	}
 
+	#[test]
+	fn octal() {
+		assert_eq!(unescape_octal("/mnt/a\\040b"), "/mnt/a b");
+		assert_eq!(unescape_octal("a\\134\\011"), "a\\\t");
+		assert!(matches!(unescape_octal("/mnt"), Cow::Borrowed("/mnt")));
+		// not an escape
+		assert_eq!(unescape_octal("a\\9b\\04"), "a\\9b\\04");
+	}
+
+	#[test]
+	fn cpu_list() {
+		assert_eq!(
+			parse_cpu_list("0-3,8,10-11\n").unwrap(),
+			[0, 1, 2, 3, 8, 10, 11]
+		);
+		assert_eq!(parse_cpu_list("5").unwrap(), [5]);
+		assert!(parse_cpu_list("").unwrap().is_empty());
+		assert!(parse_cpu_list("0-a").is_none());
+		assert_eq!(parse_cpu_list("8191").unwrap(), [8191]);
+		assert!(parse_cpu_list("0-4294967295").is_none());
+		assert!(parse_cpu_list("8192").is_none());
+	}
+
+	#[test]
+	fn kernel_size() {
+		let size = DataSize::from_kernel_str("24576 kB").unwrap();
+		assert_eq!(size.to(&DataSizeUnit::Kib), 24576.0);
+		let size = DataSize::from_kernel_str("2 MiB").unwrap();
+		assert_eq!(size.as_bytes(), 2 * 1024 * 1024);
+	}
+
 	#[test]
 	fn size_str() {
-		// TODO update the formatter
 		let s = DataSize::from_str("1024").unwrap();
 		assert_eq!(s.to_string(), "1 kb");
+		assert_eq!(format!("{:#}", s), "1 kib");
+		assert_eq!(s.display(UnitSystem::Decimal).to_string(), "1.02 kB");
+		assert_eq!(s.display(UnitSystem::Binary).to_string(), "1 kib");
+		let s = DataSize::from_str("1.5 GiB").unwrap();
+		assert_eq!(s.as_bytes(), 1024 * 1024 * 1024 * 3 / 2);
+		assert_eq!(format!("{:#}", s), "1.5 gib");
 		let s = DataSize::from_str("10 kb").unwrap();
 		assert_eq!(s.to_string(), "10 kb");
 		let s = DataSize::from_str("42.1 mB").unwrap();
@@ -281,6 +1188,28 @@ mod tests {
 		assert_eq!(format!("{:.0}", DataSize::from_str("1.2 kb").unwrap()), "1 kb");
 	}
 
+	#[test]
+	fn size_math() {
+		let a = DataSize::from_kb(1.0);
+		assert_eq!(a.as_bytes(), 1024);
+		assert_eq!(DataSize::from_kib(1.0).as_bytes(), 1024);
+		assert_eq!(DataSize::from_si_kb(1.0).as_bytes(), 1000);
+		let b = DataSize::from_bytes(512);
+		assert_eq!((a + b).as_bytes(), 1536);
+		assert_eq!((b - a).as_bytes(), 0);
+		assert_eq!(b.checked_sub(a), None);
+		assert_eq!(a.checked_sub(b), Some(b));
+		let mut c = a;
+		c += b;
+		c -= a;
+		assert_eq!(c, b);
+		assert_eq!(vec![a, b, b].into_iter().sum::<DataSize>(), a + a);
+		assert!(a > b);
+		assert_eq!(DataSize::from_gb(1.5).as_mb(), 1536.0);
+		assert_eq!(DataSize::from_gib(1.5).as_mib(), 1536.0);
+		assert_eq!(DataSize::from_si_gb(1.5).as_si_mb(), 1500.0);
+	}
+
 	#[test]
 	fn test_precision() {
 		assert_eq!(calculate_precision(0.00005, 4), 4);
@@ -292,9 +1221,57 @@ mod tests {
 		assert_eq!(calculate_precision(0.0, 4), 0);
 	}
 
+	#[test]
+	fn sha256_vectors() {
+		let hex = |b: [u8; 32]| b.iter()
+			.map(|b| format!("{:02x}", b))
+			.collect::<String>();
+		assert_eq!(
+			hex(sha256(b"")),
+			"e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+		);
+		assert_eq!(
+			hex(sha256(b"abc")),
+			"ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+		);
+		assert_eq!(
+			hex(sha256(&[b'a'; 1000])),
+			"41edece42d63e8d9bf515a9ba6932e1c20cbc9f5a5d134645adb5db1b9737ea3"
+		);
+	}
+
+	#[test]
+	fn sys_dir() {
+		let path = std::env::temp_dir()
+			.join(format!("linux-info-sys-dir-{}", std::process::id()));
+		fs::create_dir_all(&path).unwrap();
+		fs::write(path.join("name"), "k10temp\n").unwrap();
+		fs::write(path.join("temp1_input"), "45250\n").unwrap();
+
+		let mut dir = SysDir::open(&path).unwrap();
+		assert_eq!(dir.read_value::<String>("name").unwrap(), "k10temp");
+		assert_eq!(dir.read_value::<i64>("temp1_input").unwrap(), 45250);
+		fs::write(path.join("temp1_input"), "46000\n").unwrap();
+		assert_eq!(dir.read_value::<i64>("temp1_input").unwrap(), 46000);
+		let e = dir.read_value::<i64>("temp2_input").unwrap_err();
+		assert_eq!(e.kind(), io::ErrorKind::NotFound);
+		assert!(dir.read_value::<i64>("name").is_err());
+
+		fs::remove_dir_all(&path).unwrap();
+	}
+
 	#[test]
 	fn run_statfs() {
 		statfs("/").unwrap();
 	}
 
+	#[test]
+	fn read_error() {
+		let path = "/proc/linux-info-does-not-exist";
+		let e = read_to_string(path).unwrap_err();
+		assert_eq!(e.kind(), io::ErrorKind::NotFound);
+		assert_eq!(Error::raw_os_error_of(&e), Some(libc::ENOENT));
+		assert_eq!(Error::from(e).path(), Some(Path::new(path)));
+	}
+
 }
\ No newline at end of file